@@ -0,0 +1,65 @@
+//! Builds the C shim over RenderDoc's replay API for the `replay` feature.
+
+#[cfg(feature = "replay")]
+extern crate cc;
+
+fn main() {
+    #[cfg(feature = "replay")]
+    replay::build();
+}
+
+#[cfg(feature = "replay")]
+mod replay {
+    use std::env;
+    use std::path::PathBuf;
+
+    use cc;
+
+    /// RenderDoc source checkout providing the replay headers.
+    const SOURCE_DIR_VAR: &str = "RENDERDOC_RS_SOURCE_DIR";
+    /// Directory containing the RenderDoc library to link.
+    const LINK_DIR_VAR: &str = "RENDERDOC_RS_LINK_DIR";
+
+    pub fn build() {
+        println!("cargo:rerun-if-changed=src/replay/shim.cpp");
+        println!("cargo:rerun-if-env-changed={}", SOURCE_DIR_VAR);
+        println!("cargo:rerun-if-env-changed={}", LINK_DIR_VAR);
+
+        // docs.rs has neither RenderDoc's sources nor its library.
+        if env::var_os("DOCS_RS").is_some() {
+            return;
+        }
+
+        let source = match env::var_os(SOURCE_DIR_VAR) {
+            Some(dir) => PathBuf::from(dir),
+            None => panic!(
+                "the `replay` feature requires {} to point to a RenderDoc source checkout \
+                 of the same release as the linked library",
+                SOURCE_DIR_VAR
+            ),
+        };
+
+        // The replay headers select their platform from these, which
+        // RenderDoc's own build system defines.
+        let platform = match env::var("CARGO_CFG_TARGET_OS").as_ref().map(String::as_str) {
+            Ok("windows") => "RENDERDOC_PLATFORM_WIN32",
+            Ok("android") => "RENDERDOC_PLATFORM_ANDROID",
+            Ok("macos") => "RENDERDOC_PLATFORM_APPLE",
+            _ => "RENDERDOC_PLATFORM_LINUX",
+        };
+
+        cc::Build::new()
+            .cpp(true)
+            .file("src/replay/shim.cpp")
+            .include(source.join("renderdoc").join("api").join("replay"))
+            .include(source.join("renderdoc"))
+            .define(platform, None)
+            .flag_if_supported("-std=c++14")
+            .compile("renderdoc_replay_shim");
+
+        if let Some(dir) = env::var_os(LINK_DIR_VAR) {
+            println!("cargo:rustc-link-search=native={}", PathBuf::from(dir).display());
+        }
+        println!("cargo:rustc-link-lib=dylib=renderdoc");
+    }
+}
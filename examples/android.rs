@@ -0,0 +1,35 @@
+//! Minimal Android application which checks for the RenderDoc layer.
+//!
+//! Build and install it with [`cargo-apk`]:
+//!
+//! ```text
+//! cargo apk run --example android --features android
+//! ```
+//!
+//! When the APK is launched from the RenderDoc UI, RenderDoc packages its
+//! `libVkLayer_GLES_RenderDoc.so` layer into the application's native library
+//! directory and this example attaches to it.
+//!
+//! [`cargo-apk`]: https://github.com/tomaka/android-rs-glue
+
+extern crate renderdoc;
+
+use renderdoc::{RenderDoc, V110};
+use renderdoc::prelude::*;
+
+pub fn main() {
+    if !renderdoc::is_library_present() {
+        println!("RenderDoc layer not found, launch this APK from RenderDoc.");
+        return;
+    }
+
+    match RenderDoc::<V110>::attach() {
+        Ok(rd) => {
+            match rd.get_api_version() {
+                Ok(version) => println!("Attached to RenderDoc API {}.", version),
+                Err(err) => println!("{}", err),
+            }
+        }
+        Err(err) => println!("{}", err),
+    }
+}
@@ -21,7 +21,7 @@ extern crate renderdoc;
 use gfx::traits::FactoryExt;
 use gfx::Device;
 use glutin::GlContext;
-use renderdoc::{OverlayBits, RenderDoc, V110};
+use renderdoc::{OverlayBits, RenderDoc, ReplayUiOptions, V110};
 use renderdoc::prelude::*;
 
 pub type ColorFormat = gfx::format::Rgba8;
@@ -57,7 +57,7 @@ const TRIANGLE: [Vertex; 3] = [
 const CLEAR_COLOR: [f32; 4] = [0.1, 0.2, 0.3, 1.0];
 
 pub fn main() {
-    let mut rd: RenderDoc<V110> = RenderDoc::new().unwrap();
+    let rd: RenderDoc<V110> = RenderDoc::new().unwrap();
 
     let mut events_loop = glutin::EventsLoop::new();
     let window_config = glutin::WindowBuilder::new()
@@ -80,10 +80,10 @@ pub fn main() {
         out: main_color,
     };
 
-    rd.set_active_window(window.context(), ::std::ptr::null());
-    rd.set_focus_toggle_keys(&[glutin::VirtualKeyCode::F]);
-    rd.set_capture_keys(&[glutin::VirtualKeyCode::C]);
-    rd.mask_overlay_bits(OverlayBits::DEFAULT, OverlayBits::DEFAULT);
+    rd.set_active_window(window.context(), ::std::ptr::null()).unwrap();
+    rd.set_focus_toggle_keys(&[glutin::VirtualKeyCode::F.into()]).unwrap();
+    rd.set_capture_keys(&[glutin::VirtualKeyCode::C.into()]).unwrap();
+    rd.mask_overlay_bits(OverlayBits::DEFAULT, OverlayBits::DEFAULT).unwrap();
 
     let mut running = true;
     while running {
@@ -100,9 +100,9 @@ pub fn main() {
                     },
                     ..
                 } => {
-                    match rd.launch_replay_ui(None) {
-                        Ok(pid) => println!("Launched replay UI ({}).", pid),
-                        Err(err) => println!("{:?}", err),
+                    match rd.launch_replay_ui(&ReplayUiOptions::new()) {
+                        Ok(ui) => println!("Launched replay UI ({}).", ui.id()),
+                        Err(err) => println!("{}", err),
                     }
                 }
                 glutin::WindowEvent::KeyboardInput {
@@ -0,0 +1,67 @@
+//! Procedural macros for the `renderdoc` crate.
+//!
+//! Use them through the `macros` feature of `renderdoc` rather than depending
+//! on this crate directly.
+
+#![deny(missing_docs)]
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+use proc_macro::TokenStream;
+use syn::{ItemFn, ReturnType};
+
+/// Captures the frames rendered by a test with RenderDoc, printing the path
+/// of the capture if the test fails.
+///
+/// Place the attribute above `#[test]`. The test body runs inside a
+/// `renderdoc::TestCapture`, see there for details. Tests may fail by
+/// panicking or by returning an `Err`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use renderdoc::renderdoc_capture;
+///
+/// #[renderdoc_capture]
+/// #[test]
+/// fn draws_triangle() {
+///     // Render and assert...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn renderdoc_capture(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        let args = proc_macro2::TokenStream::from(args);
+        return syn::Error::new_spanned(args, "#[renderdoc_capture] takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let ItemFn { attrs, vis, sig, block } = parse_macro_input!(input as ItemFn);
+    let name = sig.ident.to_string();
+    let output = match sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ref ty) => quote!(#ty),
+    };
+    let expanded = quote! {
+        #(#attrs)*
+        #vis #sig {
+            let mut __renderdoc_capture = ::renderdoc::TestCapture::start(
+                concat!(module_path!(), "::", #name),
+            );
+            // The body runs in a closure so that `return` and `?` yield its
+            // outcome here rather than leaving the test.
+            let __renderdoc_outcome = (|| -> #output #block)();
+            if ::renderdoc::TestOutcome::is_failure(&__renderdoc_outcome) {
+                __renderdoc_capture.fail();
+            }
+            __renderdoc_outcome
+        }
+    };
+    expanded.into()
+}
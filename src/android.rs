@@ -0,0 +1,253 @@
+//! Capturing Android applications from a host machine through `adb`.
+//!
+//! An `AndroidSession` does what the RenderDoc UI does when a device is
+//! selected as the remote host: it forwards the remote server port over `adb`,
+//! starts RenderDoc's remote server app on the device unless it's already
+//! running, and connects to it. The session then launches activities with
+//! capturing enabled and pulls the resulting captures to the host.
+//!
+//! Requires `adb` on the `PATH`, or passed with `Adb::with_program()`, and
+//! the RenderDoc APK for the device's ABI installed, as done by the RenderDoc
+//! UI on first connection.
+//!
+//! This module runs on the host and is unrelated to the `android` feature,
+//! which locates RenderDoc within an application running on the device.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use renderdoc::android::{Adb, AndroidSession};
+//! use renderdoc::remote::LaunchOptions;
+//!
+//! # fn run() -> Result<(), renderdoc::protocol::Error> {
+//! let mut session = AndroidSession::start(Adb::new())?;
+//! session.launch("com.example.game/.MainActivity", &LaunchOptions::default())?;
+//! // Trigger captures, e.g. through target control...
+//! let captures = session.pull_captures("/sdcard/RenderDoc", "captures", |_, _, _| {})?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use protocol::Error;
+use remote::{self, LaunchOptions, RemoteCapture, RemoteServer};
+
+/// Package of RenderDoc's remote server app on 64-bit ARM devices.
+pub const SERVER_PACKAGE_ARM64: &str = "org.renderdoc.renderdoccmd.arm64";
+
+/// Package of RenderDoc's remote server app on 32-bit ARM devices.
+pub const SERVER_PACKAGE_ARM32: &str = "org.renderdoc.renderdoccmd.arm32";
+
+/// Time to wait for the remote server app to accept connections after
+/// starting it.
+const SERVER_START_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns the remote server package for a device reporting `abi` as its
+/// `ro.product.cpu.abi`.
+fn server_package(abi: &str) -> &'static str {
+    if abi.starts_with("arm64") {
+        SERVER_PACKAGE_ARM64
+    } else {
+        SERVER_PACKAGE_ARM32
+    }
+}
+
+/// Returns the abstract socket RenderDoc listens on for `port` on Android.
+fn device_socket(port: u16) -> String {
+    format!("localabstract:renderdoc_{}", port)
+}
+
+/// Runs `adb` commands against one device.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Adb {
+    program: PathBuf,
+    serial: Option<String>,
+}
+
+impl Adb {
+    /// Uses `adb` from the `PATH` with the only connected device.
+    pub fn new() -> Self {
+        Adb::with_program("adb")
+    }
+
+    /// Uses the `adb` executable at `program`.
+    pub fn with_program<P: Into<PathBuf>>(program: P) -> Self {
+        Adb {
+            program: program.into(),
+            serial: None,
+        }
+    }
+
+    /// Selects the device with the given serial number, for hosts with several
+    /// devices connected.
+    pub fn device<S: Into<String>>(mut self, serial: S) -> Self {
+        self.serial = Some(serial.into());
+        self
+    }
+
+    fn args(&self, args: &[&str]) -> Vec<OsString> {
+        let mut all = Vec::new();
+        if let Some(ref serial) = self.serial {
+            all.push("-s".into());
+            all.push(serial.into());
+        }
+        all.extend(args.iter().map(OsString::from));
+        all
+    }
+
+    /// Runs `adb` with `args`, returning its standard output.
+    pub fn run(&self, args: &[&str]) -> io::Result<String> {
+        let output = process::Command::new(&self.program).args(self.args(args)).output()?;
+        if !output.status.success() {
+            let msg = format!(
+                "adb {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Err(io::Error::new(io::ErrorKind::Other, msg));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Runs a shell command on the device, returning its output.
+    pub fn shell(&self, command: &str) -> io::Result<String> {
+        self.run(&["shell", command])
+    }
+
+    /// Forwards the local TCP `port` to RenderDoc's socket for the same port on
+    /// the device.
+    pub fn forward(&self, port: u16) -> io::Result<()> {
+        self.run(&["forward", &format!("tcp:{}", port), &device_socket(port)]).map(|_| ())
+    }
+
+    /// Returns whether a process of `package` is running on the device.
+    pub fn is_running(&self, package: &str) -> io::Result<bool> {
+        // `pidof` exits with 1 if nothing matches. Its status is printed rather
+        // than passed on, so failures of `adb` itself stay errors.
+        let output = self.shell(&format!("pidof {}; echo $?", package))?;
+        pidof_status(&output)
+    }
+}
+
+/// Tells from the output of `pidof`, followed by its exit status, whether a
+/// process matched.
+fn pidof_status(output: &str) -> io::Result<bool> {
+    match output.lines().map(str::trim).rfind(|line| !line.is_empty()) {
+        Some("0") => Ok(true),
+        Some("1") => Ok(false),
+        _ => {
+            let msg = format!("pidof failed: {}", output.trim());
+            Err(io::Error::new(io::ErrorKind::Other, msg))
+        }
+    }
+}
+
+impl Default for Adb {
+    fn default() -> Self {
+        Adb::new()
+    }
+}
+
+/// A connection to RenderDoc's remote server on an Android device.
+#[derive(Debug)]
+pub struct AndroidSession {
+    adb: Adb,
+    server: RemoteServer,
+}
+
+impl AndroidSession {
+    /// Forwards the remote server port, starts the remote server app if it
+    /// isn't running yet, and connects to it.
+    pub fn start(adb: Adb) -> Result<AndroidSession, Error> {
+        adb.forward(remote::DEFAULT_PORT)?;
+
+        let abi = adb.shell("getprop ro.product.cpu.abi")?;
+        let package = server_package(abi.trim());
+        if !adb.is_running(package)? {
+            log_debug!("Starting RenderDoc remote server {}", package);
+            adb.shell(&format!(
+                "am start -n {}/.Loader -e renderdoccmd remoteserver",
+                package
+            ))?;
+        }
+
+        // adb accepts forwarded connections even before the server listens,
+        // so retry until the handshake goes through.
+        let deadline = Instant::now() + SERVER_START_TIMEOUT;
+        loop {
+            match RemoteServer::connect(("127.0.0.1", remote::DEFAULT_PORT)) {
+                Ok(server) => return Ok(AndroidSession { adb, server }),
+                Err(Error::Io(_)) if Instant::now() < deadline => thread::sleep(Duration::from_millis(250)),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns the `adb` connection to the device.
+    pub fn adb(&self) -> &Adb {
+        &self.adb
+    }
+
+    /// Returns the connection to the remote server.
+    pub fn server(&mut self) -> &mut RemoteServer {
+        &mut self.server
+    }
+
+    /// Launches `activity`, e.g. `com.example.game/.MainActivity`, with
+    /// RenderDoc injected.
+    ///
+    /// Returns the target control port of the launched process, which is
+    /// forwarded to the same local port, so `TargetControl::connect_local()`
+    /// can connect to it.
+    pub fn launch(&mut self, activity: &str, options: &LaunchOptions) -> Result<u16, Error> {
+        let port = self.server.launch(activity, options)?;
+        self.adb.forward(port)?;
+        Ok(port)
+    }
+
+    /// Copies all captures in `remote_dir` on the device into `local_dir`.
+    ///
+    /// `progress` is called as by `RemoteServer::pull_captures()`. Returns the
+    /// local paths of the copies.
+    pub fn pull_captures<P, F>(&mut self, remote_dir: &str, local_dir: P, progress: F) -> Result<Vec<PathBuf>, Error>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&RemoteCapture, u64, u64),
+    {
+        let captures = self.server.list_captures(remote_dir)?;
+        self.server.pull_captures(&captures, local_dir, progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adb_arguments() {
+        let adb = Adb::new().device("emulator-5554");
+        assert_eq!(adb.args(&["forward", "tcp:39920"]), vec!["-s", "emulator-5554", "forward", "tcp:39920"]);
+        assert_eq!(Adb::new().args(&["shell", "ls"]), vec!["shell", "ls"]);
+        assert_eq!(device_socket(38920), "localabstract:renderdoc_38920");
+    }
+
+    #[test]
+    fn server_packages() {
+        assert_eq!(server_package("arm64-v8a"), SERVER_PACKAGE_ARM64);
+        assert_eq!(server_package("armeabi-v7a"), SERVER_PACKAGE_ARM32);
+    }
+
+    #[test]
+    fn parse_pidof_status() {
+        assert!(pidof_status("4242\r\n0\r\n").unwrap());
+        assert!(!pidof_status("1\n").unwrap());
+        assert!(pidof_status("/system/bin/sh: pidof: not found\n127\n").is_err());
+    }
+}
@@ -1,16 +1,33 @@
 //! Traits providing compile-time API functionality.
+//!
+//! All methods take `&self`, since the Rust handle holds no state of its own
+//! and merely forwards to the RenderDoc in-application API. That API is not
+//! internally synchronized, however, so calls which mutate RenderDoc state
+//! (options, hotkeys, overlay, capture path, frame capture begin/end) must be
+//! serialized by the caller when a handle is shared between threads, e.g. by
+//! going through [`RenderDocHandle`](../type.RenderDocHandle.html).
 
-use {CaptureOption, DevicePointer, OverlayBits, InputButton, WindowHandle};
+use {ApiVersionNumber, CaptureDirError, CaptureDirOptions, CaptureOption, DevicePointer, Error, OverlayBits,
+     InputButton, ReplayUiOptions, ReplayUiProcess, WindowBinding, WindowHandle};
 use entry::{EntryV100, EntryV110};
+use capture_dir;
+use capture_metrics;
+use init_config;
+use instrument;
+use observer;
 
 use std::ffi::{CStr, CString};
-use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::ptr;
 
 /// Base implementation of API version 1.0.0.
+///
+/// Every call fails with `Error::ShutDown` once RenderDoc has been shut down
+/// through `EarlyShutdown`, rather than calling into the torn down library.
 pub trait RenderDocV100: Sized {
-    /// Returns the raw `EntryV100` entry point struct.
-    unsafe fn entry_v100(&self) -> &EntryV100;
+    /// Returns the raw `EntryV100` entry point struct, or `Error::ShutDown` if
+    /// RenderDoc has been shut down.
+    unsafe fn entry_v100(&self) -> Result<&EntryV100, Error>;
 
     /// Provides the major, minor, and patch version numbers of the RenderDoc
     /// API given to the application.
@@ -25,17 +42,17 @@ pub trait RenderDocV100: Sized {
     /// # use renderdoc::prelude::*;
     /// # fn init() -> Result<(), String> {
     /// # let renderdoc: RenderDoc<V100> = RenderDoc::new()?;
-    /// let (major, minor, patch) = renderdoc.get_api_version();
-    /// assert_eq!(major, 1u32);
-    /// assert_eq!(minor, 0u32);
+    /// let version = renderdoc.get_api_version()?;
+    /// assert_eq!(version.major, 1u32);
+    /// assert!(version >= (1, 0, 0));
     /// # Ok(())
     /// # }
     /// ```
-    fn get_api_version(&self) -> (u32, u32, u32) {
+    fn get_api_version(&self) -> Result<ApiVersionNumber, Error> {
         unsafe {
             let (mut major, mut minor, mut patch) = (0, 0, 0);
-            (self.entry_v100().get_api_version)(&mut major, &mut minor, &mut patch);
-            (major as u32, minor as u32, patch as u32)
+            (self.entry_v100()?.get_api_version)(&mut major, &mut minor, &mut patch);
+            Ok(ApiVersionNumber::new(major as u32, minor as u32, patch as u32))
         }
     }
 
@@ -44,9 +61,12 @@ pub trait RenderDocV100: Sized {
     /// # Panics
     ///
     /// This method will panic if the option and/or the value are invalid.
-    fn set_capture_option_f32(&mut self, opt: CaptureOption, val: f32) {
-        let err = unsafe { (self.entry_v100().set_capture_option_f32)(opt, val) };
+    fn set_capture_option_f32(&self, opt: CaptureOption, val: f32) -> Result<(), Error> {
+        log_debug!("Setting capture option {:?} to {}", opt, val);
+        let err = unsafe { (self.entry_v100()?.set_capture_option_f32)(opt, val) };
         assert_eq!(err, 1);
+        observer::options_changed(opt);
+        Ok(())
     }
 
     /// Sets the specified `CaptureOption` to the given `u32` value.
@@ -54,169 +74,291 @@ pub trait RenderDocV100: Sized {
     /// # Panics
     ///
     /// This method will panic if the option and/or the value are invalid.
-    fn set_capture_option_u32(&mut self, opt: CaptureOption, val: u32) {
-        let err = unsafe { (self.entry_v100().set_capture_option_u32)(opt, val) };
+    fn set_capture_option_u32(&self, opt: CaptureOption, val: u32) -> Result<(), Error> {
+        log_debug!("Setting capture option {:?} to {}", opt, val);
+        let err = unsafe { (self.entry_v100()?.set_capture_option_u32)(opt, val) };
         assert_eq!(err, 1);
+        observer::options_changed(opt);
+        Ok(())
     }
 
     #[allow(missing_docs)]
-    fn get_capture_option_f32(&self, opt: CaptureOption) -> f32 {
+    fn get_capture_option_f32(&self, opt: CaptureOption) -> Result<f32, Error> {
         use std::f32::MAX;
-        let val = unsafe { (self.entry_v100().get_capture_option_f32)(opt) };
+        let val = unsafe { (self.entry_v100()?.get_capture_option_f32)(opt) };
         assert_ne!(val, -MAX);
-        val
+        Ok(val)
     }
 
     #[allow(missing_docs)]
-    fn get_capture_option_u32(&self, opt: CaptureOption) -> u32 {
+    fn get_capture_option_u32(&self, opt: CaptureOption) -> Result<u32, Error> {
         use std::u32::MAX;
-        let val = unsafe { (self.entry_v100().get_capture_option_u32)(opt) };
+        let val = unsafe { (self.entry_v100()?.get_capture_option_u32)(opt) };
         assert_ne!(val, MAX);
-        val
+        Ok(val)
     }
 
-    #[allow(missing_docs)]
-    fn set_capture_keys<I: Into<InputButton> + Clone>(&mut self, keys: &[I]) {
-        unsafe {
-            let k: Vec<_> = keys.iter().cloned().map(|k| k.into()).collect();
-            (self.entry_v100().set_capture_keys)(k.as_ptr(), k.len() as i32)
-        }
-    }
-
-    #[allow(missing_docs)]
-    fn set_focus_toggle_keys<I: Into<InputButton> + Clone>(&mut self, keys: &[I]) {
-        unsafe {
-            let k: Vec<_> = keys.iter().cloned().map(|k| k.into()).collect();
-            (self.entry_v100().set_focus_toggle_keys)(k.as_ptr(), k.len() as i32)
-        }
+    /// Sets the keys which capture the next frame.
+    ///
+    /// The keys are passed to RenderDoc as they are, without allocating.
+    /// Convert other key codes with `InputButton::from()`.
+    fn set_capture_keys(&self, keys: &[InputButton]) -> Result<(), Error> {
+        log_trace!("Setting capture keys to {:?}", keys);
+        unsafe { (self.entry_v100()?.set_capture_keys)(keys.as_ptr(), keys.len() as i32) }
+        Ok(())
     }
 
-    /// Attempts to shut down RenderDoc.
+    /// Sets the keys which toggle focus between multiple windows.
     ///
-    /// # Safety
-    ///
-    /// Note that this will work correctly if done _immediately_ after the
-    /// dynamic library is loaded, before any API work happens. At that point,
-    /// RenderDoc will remove its injected hooks and shut down. Behavior is
-    /// undefined if this is called after any API functions have been called.
-    unsafe fn shutdown(self) {
-        (self.entry_v100().shutdown)();
+    /// The keys are passed to RenderDoc as they are, without allocating.
+    /// Convert other key codes with `InputButton::from()`.
+    fn set_focus_toggle_keys(&self, keys: &[InputButton]) -> Result<(), Error> {
+        log_trace!("Setting focus toggle keys to {:?}", keys);
+        unsafe { (self.entry_v100()?.set_focus_toggle_keys)(keys.as_ptr(), keys.len() as i32) }
+        Ok(())
     }
 
     #[allow(missing_docs)]
-    fn unload_crash_handler(&mut self) {
+    fn unload_crash_handler(&self) -> Result<(), Error> {
         unsafe {
-            (self.entry_v100().unload_crash_handler)();
+            (self.entry_v100()?.unload_crash_handler)();
         }
+        Ok(())
     }
 
     #[allow(missing_docs)]
-    fn get_overlay_bits(&self) -> OverlayBits {
-        unsafe { (self.entry_v100().get_overlay_bits)() }
+    fn get_overlay_bits(&self) -> Result<OverlayBits, Error> {
+        unsafe { Ok((self.entry_v100()?.get_overlay_bits)()) }
     }
 
     #[allow(missing_docs)]
-    fn mask_overlay_bits(&mut self, and: OverlayBits, or: OverlayBits) {
+    fn mask_overlay_bits(&self, and: OverlayBits, or: OverlayBits) -> Result<(), Error> {
+        log_trace!("Masking overlay bits with {:?} and {:?}", and, or);
         unsafe {
-            (self.entry_v100().mask_overlay_bits)(and, or);
+            (self.entry_v100()?.mask_overlay_bits)(and, or);
         }
+        Ok(())
     }
 
-    #[allow(missing_docs)]
-    fn get_log_file_path_template(&self) -> &str {
+    /// Returns the template for the paths of capture files.
+    ///
+    /// The template is copied out of RenderDoc, so the result stays valid
+    /// when the template is changed afterwards.
+    fn get_capture_file_path_template(&self) -> Result<PathBuf, Error> {
         unsafe {
-            let raw = (self.entry_v100().get_log_file_path_template)();
-            CStr::from_ptr(raw).to_str().unwrap()
+            let raw = (self.entry_v100()?.get_log_file_path_template)();
+            Ok(path_from_c(CStr::from_ptr(raw)))
         }
     }
 
-    #[allow(missing_docs)]
-    fn set_log_file_path_template<P: AsRef<Path>>(&mut self, path_template: P) {
+    /// Sets the template for the paths of capture files.
+    ///
+    /// RenderDoc appends a frame number and the `.rdc` extension to the
+    /// template, e.g. `captures/game` yields `captures/game_frame123.rdc`.
+    /// Templates containing NUL bytes are ignored.
+    fn set_capture_file_path_template<P: AsRef<Path>>(&self, path_template: P) -> Result<(), Error> {
+        let path_template = path_template.as_ref();
+        log_debug!("Setting capture path template to {}", path_template.display());
+        let entry = unsafe { self.entry_v100()? };
+        let cstr = match path_to_c(path_template) {
+            Some(cstr) => cstr,
+            None => return Ok(()),
+        };
         unsafe {
-            let bytes = mem::transmute(path_template.as_ref().as_os_str());
-            let cstr = CStr::from_bytes_with_nul_unchecked(bytes);
-            (self.entry_v100().set_log_file_path_template)(cstr.as_ptr());
+            (entry.set_log_file_path_template)(cstr.as_ptr());
         }
+        Ok(())
     }
 
-    #[allow(missing_docs)]
-    fn get_num_captures(&self) -> u32 {
-        unsafe { (self.entry_v100().get_num_captures)() }
+    /// Sets the template for the paths of capture files, after making sure
+    /// captures can be written to its directory.
+    ///
+    /// RenderDoc silently drops captures it can't write. This checks that the
+    /// directory exists, or creates it if `options.create` is set, that it's
+    /// writable, and that its volume has `options.min_free_bytes` free. The
+    /// template is left unchanged if any check fails, or with
+    /// `CaptureDirError::Api` if RenderDoc has been shut down.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::prelude::*;
+    /// # use renderdoc::{CaptureDirOptions, RenderDoc, V110};
+    /// # fn init() -> Result<(), Box<dyn std::error::Error>> {
+    /// let rd: RenderDoc<V110> = RenderDoc::new()?;
+    /// let options = CaptureDirOptions {
+    ///     create: true,
+    ///     ..CaptureDirOptions::default()
+    /// };
+    /// rd.try_set_capture_file_path_template("captures/game", &options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn try_set_capture_file_path_template<P: AsRef<Path>>(
+        &self,
+        path_template: P,
+        options: &CaptureDirOptions,
+    ) -> Result<(), CaptureDirError> {
+        unsafe { self.entry_v100()? };
+        capture_dir::prepare(path_template.as_ref(), options)?;
+        self.set_capture_file_path_template(path_template)?;
+        Ok(())
+    }
+
+    /// Returns the template for the paths of capture files.
+    #[deprecated(since = "0.3.0", note = "renamed to `get_capture_file_path_template`")]
+    fn get_log_file_path_template(&self) -> Result<PathBuf, Error> {
+        self.get_capture_file_path_template()
+    }
+
+    /// Sets the template for the paths of capture files.
+    #[deprecated(since = "0.3.0", note = "renamed to `set_capture_file_path_template`")]
+    fn set_log_file_path_template<P: AsRef<Path>>(&self, path_template: P) -> Result<(), Error> {
+        self.set_capture_file_path_template(path_template)
     }
 
     #[allow(missing_docs)]
-    fn get_capture(&self, index: u32) -> Option<(String, u64)> {
+    fn get_num_captures(&self) -> Result<u32, Error> {
+        unsafe { Ok((self.entry_v100()?.get_num_captures)()) }
+    }
+
+    /// Returns the path and the timestamp of the capture with the given
+    /// index, or `None` if there is no such capture.
+    ///
+    /// Paths which aren't valid UTF-8 are converted lossily.
+    fn get_capture(&self, index: u32) -> Result<Option<(String, u64)>, Error> {
+        let entry = unsafe { self.entry_v100()? };
         unsafe {
-            let mut len = self.get_log_file_path_template().len() as u32 + 128;
-            let mut path = Vec::with_capacity(len as usize);
+            // The first call only queries the length of the path, including
+            // the NUL terminator.
+            let mut len = 0u32;
             let mut time = 0u64;
+            if (entry.get_capture)(index, ptr::null_mut(), &mut len, &mut time) != 1 || len == 0 {
+                return Ok(None);
+            }
 
-            if (self.entry_v100().get_capture)(index, path.as_mut_ptr(), &mut len, &mut time) == 1 {
-                let raw_path = CString::from_raw(path.as_mut_ptr());
-                let mut path = raw_path.into_string().unwrap();
-                path.shrink_to_fit();
-
-                Some((path, time))
-            } else {
-                None
+            let mut path = vec![0u8; len as usize];
+            if (entry.get_capture)(index, path.as_mut_ptr() as *mut _, &mut len, &mut time) != 1 {
+                return Ok(None);
             }
+
+            let path = CStr::from_bytes_until_nul(&path).ok();
+            Ok(path.map(|path| (path.to_string_lossy().into_owned(), time)))
         }
     }
 
     /// Captures the next frame from the currently active window and API device.
     ///
-    /// Data is saved to a capture log file at the location specified via
-    /// `set_log_file_path_template()`.
-    fn trigger_capture(&mut self) {
+    /// Data is saved to a capture file at the location specified via
+    /// `set_capture_file_path_template()`.
+    fn trigger_capture(&self) -> Result<(), Error> {
+        log_debug!("Triggering capture of the next frame");
+        profile_scope!("renderdoc::trigger_capture");
         unsafe {
-            (self.entry_v100().trigger_capture)();
+            (self.entry_v100()?.trigger_capture)();
         }
+        instrument::capture_triggered(1);
+        capture_metrics::capture_triggered(1);
+        Ok(())
     }
 
-    #[allow(missing_docs)]
-    fn is_target_control_connected(&self) -> bool {
-        unsafe { (self.entry_v100().is_target_control_connected)() == 1 }
+    /// Returns whether the replay UI, or another target control client, is
+    /// connected to this application.
+    ///
+    /// Works with every API version: RenderDoc 1.0.x names this function
+    /// `IsRemoteAccessConnected`, but provides it in the same slot of the
+    /// function table.
+    fn is_target_control_connected(&self) -> Result<bool, Error> {
+        unsafe { Ok((self.entry_v100()?.is_target_control_connected)() == 1) }
     }
 
-    #[allow(missing_docs)]
-    fn launch_replay_ui<C>(&self, cmd_line: C) -> Result<u32, ()>
-    where
-        C: Into<Option<&'static str>>,
-    {
+    /// Launches the replay UI, returning a handle to the new process.
+    ///
+    /// Fails with `Error::ReplayUiLaunch` if the UI could not be launched or
+    /// an argument in `options` contains a NUL byte, and with
+    /// `Error::ShutDown` if RenderDoc has been shut down.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::prelude::*;
+    /// # use renderdoc::ReplayUiOptions;
+    /// # fn run(rd: &RenderDoc<V110>) {
+    /// if let Ok(ui) = rd.launch_replay_ui(&ReplayUiOptions::new()) {
+    ///     println!("Replay UI started with PID {}", ui.id());
+    ///     ui.wait().unwrap();
+    /// }
+    /// # }
+    /// ```
+    fn launch_replay_ui(&self, options: &ReplayUiOptions) -> Result<ReplayUiProcess, Error> {
+        let entry = unsafe { self.entry_v100()? };
+        let cmd_line = options
+            .to_c_command_line()
+            .ok_or_else(|| Error::ReplayUiLaunch("an argument contains a NUL byte".into()))?;
+        let connect = options.connects_target_control() as u32;
         unsafe {
-            let with_cmd = cmd_line.into();
-            let (enabled, text) = if let Some(ref cmd) = with_cmd {
-                let bytes = cmd.as_bytes();
-                (1, CStr::from_bytes_with_nul_unchecked(bytes))
-            } else {
-                (0, Default::default())
-            };
-
-            match (self.entry_v100().launch_replay_ui)(enabled, text.as_ptr()) {
-                0 => Err(()),
-                pid => Ok(pid),
+            match (entry.launch_replay_ui)(connect, cmd_line.as_ptr()) {
+                0 => Err(Error::ReplayUiLaunch("RenderDoc returned no process ID".into())),
+                pid => Ok(ReplayUiProcess::new(pid)),
             }
         }
     }
 
     #[allow(missing_docs)]
-    fn set_active_window<D>(&mut self, dev: D, win: WindowHandle)
+    fn set_active_window<D, W>(&self, dev: D, win: W) -> Result<(), Error>
     where
         D: Into<DevicePointer>,
+        W: Into<WindowHandle>,
     {
+        let entry = unsafe { self.entry_v100()? };
+        let dev = dev.into();
+        let win = win.into();
+        log_trace!("Setting active window to device {:?}, window {:?}", *dev, win);
+        init_config::device_seen();
         unsafe {
-            (self.entry_v100().set_active_window)(dev.into(), win);
+            (entry.set_active_window)(dev, win);
         }
+        Ok(())
+    }
+
+    /// Sets the device and window which hotkeys and `trigger_capture()` apply
+    /// to, given as a single binding.
+    ///
+    /// Accepts a `WindowBinding` or a `(device, window)` tuple.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::prelude::*;
+    /// # use renderdoc::WindowBinding;
+    /// # fn run(rd: &RenderDoc<V110>, binding: WindowBinding) {
+    /// rd.set_active_window_binding(binding).unwrap();
+    /// # }
+    /// ```
+    fn set_active_window_binding<B: Into<WindowBinding>>(&self, binding: B) -> Result<(), Error> {
+        let binding = binding.into();
+        self.set_active_window(binding.device, binding.window)
     }
 
     #[allow(missing_docs)]
-    fn start_frame_capture<D>(&mut self, dev: D, win: WindowHandle)
+    fn start_frame_capture<D, W>(&self, dev: D, win: W) -> Result<(), Error>
     where
         D: Into<DevicePointer>,
+        W: Into<WindowHandle>,
     {
+        let entry = unsafe { self.entry_v100()? };
+        let dev = dev.into();
+        let win = win.into();
+        log_debug!("Starting frame capture on device {:?}, window {:?}", *dev, win);
+        init_config::device_seen();
+        instrument::frame_capture_started(&dev, win);
+        observer::capture_started();
+        capture_metrics::frame_capture_started(&dev, win);
+        profile_scope!("renderdoc::start_frame_capture");
         unsafe {
-            (self.entry_v100().start_frame_capture)(dev.into(), win);
+            (entry.start_frame_capture)(dev, win);
         }
+        Ok(())
     }
 
     /// Returns whether or not a frame capture is currently ongoing anywhere.
@@ -228,7 +370,7 @@ pub trait RenderDocV100: Sized {
     /// # use renderdoc::prelude::*;
     /// # fn init() -> Result<(), String> {
     /// # let renderdoc: RenderDoc<V100> = RenderDoc::new()?;
-    /// if renderdoc.is_frame_capturing() {
+    /// if renderdoc.is_frame_capturing()? {
     ///     println!("Frames are being captured.");
     /// } else {
     ///     println!("No frame capture is occurring.");
@@ -236,34 +378,110 @@ pub trait RenderDocV100: Sized {
     /// # Ok(())
     /// # }
     /// ```
-    fn is_frame_capturing(&self) -> bool {
-        unsafe { (self.entry_v100().is_frame_capturing)() == 1 }
+    fn is_frame_capturing(&self) -> Result<bool, Error> {
+        unsafe { Ok((self.entry_v100()?.is_frame_capturing)() == 1) }
     }
 
     #[allow(missing_docs)]
-    fn end_frame_capture<D>(&mut self, dev: D, win: WindowHandle)
+    fn end_frame_capture<D, W>(&self, dev: D, win: W) -> Result<(), Error>
     where
         D: Into<DevicePointer>,
+        W: Into<WindowHandle>,
     {
-        unsafe {
-            (self.entry_v100().end_frame_capture)(dev.into(), win);
+        let entry = unsafe { self.entry_v100()? };
+        let dev = dev.into();
+        let win = win.into();
+        log_debug!("Ending frame capture on device {:?}, window {:?}", *dev, win);
+        let ok = {
+            // Writing out the capture happens here, causing the frame spike.
+            profile_scope!("renderdoc::end_frame_capture");
+            unsafe { (entry.end_frame_capture)(dev.clone(), win) == 1 }
+        };
+        let wants_path =
+            cfg!(any(feature = "log", feature = "tracing")) || observer::is_set() || capture_metrics::is_enabled();
+        let path = if ok && wants_path {
+            let num = self.get_num_captures()?;
+            num.checked_sub(1).map_or(Ok(None), |idx| self.get_capture(idx))?.map(|(path, _)| path)
+        } else {
+            None
+        };
+
+        match (ok, &path) {
+            (false, _) => log_debug!("Frame capture failed"),
+            (true, Some(path)) => log_debug!("Frame capture saved to {}", path),
+            (true, None) => {}
         }
+        instrument::frame_capture_ended(&dev, win, ok, path.as_deref());
+        observer::capture_ended(ok, path.as_deref());
+        capture_metrics::frame_capture_ended(&dev, win, ok, path.as_deref());
+        Ok(())
     }
 }
 
 /// Additional features for API version 1.1.0.
 pub trait RenderDocV110: RenderDocV100 {
-    /// Returns the raw `EntryV110` entry point struct.
-    unsafe fn entry_v110(&self) -> &EntryV110;
+    /// Returns the raw `EntryV110` entry point struct, or `Error::ShutDown` if
+    /// RenderDoc has been shut down.
+    unsafe fn entry_v110(&self) -> Result<&EntryV110, Error>;
 
     /// Captures the next _n_ frames from the currently active window and API
     /// device.
     ///
-    /// Data is saved to a capture log file at the location specified via
-    /// `set_log_file_path_template()`.
-    fn trigger_multi_frame_capture(&self, num_frames: u32) {
+    /// Data is saved to a capture file at the location specified via
+    /// `set_capture_file_path_template()`.
+    fn trigger_multi_frame_capture(&self, num_frames: u32) -> Result<(), Error> {
+        log_debug!("Triggering capture of the next {} frames", num_frames);
+        profile_scope!("renderdoc::trigger_multi_frame_capture");
         unsafe {
-            (self.entry_v110().trigger_multi_frame_capture)(num_frames);
+            (self.entry_v110()?.trigger_multi_frame_capture)(num_frames);
         }
+        instrument::capture_triggered(num_frames);
+        capture_metrics::capture_triggered(num_frames);
+        Ok(())
+    }
+}
+
+/// Converts a path for RenderDoc, which takes the bytes of paths as they are
+/// on Unix and UTF-8 elsewhere. Fails for paths containing NUL bytes.
+#[cfg(unix)]
+fn path_to_c(path: &Path) -> Option<CString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    CString::new(path.as_os_str().as_bytes()).ok()
+}
+
+#[cfg(not(unix))]
+fn path_to_c(path: &Path) -> Option<CString> {
+    CString::new(path.to_string_lossy().into_owned()).ok()
+}
+
+/// Converts a path returned by RenderDoc.
+#[cfg(unix)]
+fn path_from_c(path: &CStr) -> PathBuf {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    PathBuf::from(OsStr::from_bytes(path.to_bytes()))
+}
+
+#[cfg(not(unix))]
+fn path_from_c(path: &CStr) -> PathBuf {
+    PathBuf::from(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn keep_non_utf8_paths() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"captures/caf\xe9/game"));
+        let cstr = path_to_c(path).unwrap();
+        assert_eq!(path_from_c(&cstr), path);
+        assert!(path_to_c(Path::new("a\0b")).is_none());
     }
 }
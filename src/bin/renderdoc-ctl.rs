@@ -0,0 +1,117 @@
+//! Command line client for controlling RenderDoc capture targets.
+//!
+//! Build with `cargo build --features ctl --bin renderdoc-ctl`.
+
+extern crate renderdoc;
+
+use std::env;
+use std::error::Error;
+use std::process;
+
+use renderdoc::remote::{self, RemoteServer};
+use renderdoc::targetcontrol::{self, Message, TargetControl};
+
+const CLIENT_NAME: &str = "renderdoc-ctl";
+
+const USAGE: &str = "\
+Usage: renderdoc-ctl <command> [args]
+
+Commands:
+    list                            List capture targets on this machine
+    trigger [port] [frames]         Capture the next frames of a target and
+                                    wait for the captures to be written
+    queue <port> <frame>            Capture a specific frame of a target
+    cycle-window [port]             Make the next window of a target active
+    copy <host> <remote> <local>    Copy a capture from a remote server
+
+If no port is given, the first target found on this machine is used.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = match args.as_slice() {
+        ["list"] => list(),
+        ["trigger"] => trigger(None, 1),
+        ["trigger", port] => parse(port).and_then(|port| trigger(Some(port), 1)),
+        ["trigger", port, frames] => parse(port).and_then(|port| trigger(Some(port), parse(frames)?)),
+        ["queue", port, frame] => parse(port).and_then(|port| queue(port, parse(frame)?)),
+        ["cycle-window"] => cycle_window(None),
+        ["cycle-window", port] => parse(port).and_then(|port| cycle_window(Some(port))),
+        ["copy", host, remote_path, local_path] => copy(host, remote_path, local_path),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn parse<T: std::str::FromStr>(arg: &str) -> Result<T, Box<dyn Error>> {
+    arg.parse().map_err(|_| format!("invalid number: {}", arg).into())
+}
+
+fn list() -> Result<(), Box<dyn Error>> {
+    for target in targetcontrol::discover() {
+        match target.busy_with {
+            Some(client) => println!("{}\t(busy with {})", target.port, client),
+            None => println!(
+                "{}\t{}\t{}\t{}",
+                target.port,
+                target.pid.unwrap_or(0),
+                target.target.unwrap_or_default(),
+                target.apis.join(", ")
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn connect(port: Option<u16>) -> Result<TargetControl, Box<dyn Error>> {
+    let port = match port {
+        Some(port) => port,
+        None => targetcontrol::discover()
+            .into_iter()
+            .find(|target| target.busy_with.is_none())
+            .map(|target| target.port)
+            .ok_or("no capture targets found")?,
+    };
+    Ok(TargetControl::connect_local(port, CLIENT_NAME, false)?)
+}
+
+fn trigger(port: Option<u16>, frames: u32) -> Result<(), Box<dyn Error>> {
+    let mut target = connect(port)?;
+    target.trigger_capture(frames)?;
+
+    let mut remaining = frames;
+    while remaining > 0 {
+        if let Message::NewCapture(capture) = target.receive()? {
+            println!("{}", capture.path);
+            remaining -= 1;
+        }
+    }
+    Ok(())
+}
+
+fn queue(port: u16, frame: u32) -> Result<(), Box<dyn Error>> {
+    let mut target = connect(Some(port))?;
+    target.queue_capture(frame)?;
+    Ok(())
+}
+
+fn cycle_window(port: Option<u16>) -> Result<(), Box<dyn Error>> {
+    let mut target = connect(port)?;
+    target.cycle_active_window()?;
+    Ok(())
+}
+
+fn copy(host: &str, remote_path: &str, local_path: &str) -> Result<(), Box<dyn Error>> {
+    let mut server = RemoteServer::connect((host, remote::DEFAULT_PORT))?;
+    let size = server.copy_capture_from_remote(remote_path, local_path)?;
+    println!("copied {} bytes to {}", size, local_path);
+    Ok(())
+}
@@ -0,0 +1,213 @@
+//! One-shot initialization of the RenderDoc API.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use entry::loader;
+use error::Error;
+use global;
+use overrides::Overrides;
+use settings::{OptionValue, Settings};
+use {ApiVersion, CaptureOption, InputButton, OverlayBits, RenderDoc};
+
+/// Loads the RenderDoc API and applies an initial configuration.
+///
+/// Created with `RenderDoc::builder()`. All settings are applied by `build()`
+/// right after loading, in a fixed order: capture options, hotkeys, overlay,
+/// capture path template, and finally unloading the crash handler. The handle
+/// is only returned once everything has been applied.
+///
+/// Settings from the `RENDERDOC_RS_OPTIONS`, `RENDERDOC_RS_OVERLAY` and
+/// `RENDERDOC_RS_CAPTURE_PATH` environment variables are applied on top,
+/// right before unloading the crash handler, unless disabled with
+/// `ignore_env_overrides()`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use renderdoc::{CaptureOption, InputButton, OverlayBits, RenderDoc, V110};
+/// # fn init() -> Result<(), renderdoc::Error> {
+/// let rd = RenderDoc::<V110>::builder()
+///     .capture_option_u32(CaptureOption::CaptureCallstacks, 1)
+///     .capture_keys(&[InputButton::F11])
+///     .overlay_bits(OverlayBits::ENABLED | OverlayBits::FRAME_NUMBER)
+///     .capture_file_path_template("captures/game")
+///     .unload_crash_handler()
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Builder<V: ApiVersion> {
+    library_path: Option<PathBuf>,
+    options: Vec<(CaptureOption, OptionValue)>,
+    capture_keys: Option<Vec<InputButton>>,
+    focus_toggle_keys: Option<Vec<InputButton>>,
+    overlay_bits: Option<OverlayBits>,
+    path_template: Option<PathBuf>,
+    unload_crash_handler: bool,
+    env_overrides: bool,
+    _version: PhantomData<fn() -> V>,
+}
+
+impl<V: ApiVersion> Builder<V> {
+    /// Creates a builder which loads RenderDoc from the default location and
+    /// leaves every setting at RenderDoc's default.
+    pub fn new() -> Self {
+        Builder {
+            library_path: None,
+            options: Vec::new(),
+            capture_keys: None,
+            focus_toggle_keys: None,
+            overlay_bits: None,
+            path_template: None,
+            unload_crash_handler: false,
+            env_overrides: true,
+            _version: PhantomData,
+        }
+    }
+
+    /// Loads the RenderDoc library from `path`, as `RenderDoc::with_library_path()`.
+    pub fn library_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.library_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the specified `CaptureOption` to the given `f32` value.
+    pub fn capture_option_f32(mut self, opt: CaptureOption, val: f32) -> Self {
+        self.options.push((opt, OptionValue::F32(val)));
+        self
+    }
+
+    /// Sets the specified `CaptureOption` to the given `u32` value.
+    pub fn capture_option_u32(mut self, opt: CaptureOption, val: u32) -> Self {
+        self.options.push((opt, OptionValue::U32(val)));
+        self
+    }
+
+    /// Sets the keys which capture the next frame.
+    pub fn capture_keys<I: Into<InputButton> + Clone>(mut self, keys: &[I]) -> Self {
+        self.capture_keys = Some(keys.iter().cloned().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the keys which toggle focus between multiple windows.
+    pub fn focus_toggle_keys<I: Into<InputButton> + Clone>(mut self, keys: &[I]) -> Self {
+        self.focus_toggle_keys = Some(keys.iter().cloned().map(Into::into).collect());
+        self
+    }
+
+    /// Replaces the overlay configuration bits with `bits`.
+    pub fn overlay_bits(mut self, bits: OverlayBits) -> Self {
+        self.overlay_bits = Some(bits);
+        self
+    }
+
+    /// Sets the template for the paths of capture files.
+    pub fn capture_file_path_template<P: AsRef<Path>>(mut self, path_template: P) -> Self {
+        self.path_template = Some(path_template.as_ref().to_path_buf());
+        self
+    }
+
+    /// Unloads RenderDoc's crash handler after loading.
+    pub fn unload_crash_handler(mut self) -> Self {
+        self.unload_crash_handler = true;
+        self
+    }
+
+    /// Ignores the `RENDERDOC_RS_*` environment variables overriding capture
+    /// settings.
+    pub fn ignore_env_overrides(mut self) -> Self {
+        self.env_overrides = false;
+        self
+    }
+
+    /// Loads the API and applies the configuration.
+    ///
+    /// The same single-initialization rules as `RenderDoc::new()` apply.
+    /// Invalid environment overrides are reported before loading RenderDoc.
+    pub fn build(self) -> Result<RenderDoc<V>, Error> {
+        let overrides = if self.env_overrides {
+            Overrides::from_env().map_err(Error::InvalidEnvironment)?
+        } else {
+            Overrides::default()
+        };
+
+        let rd = match self.library_path {
+            Some(ref path) => global::init(|| loader::load_from_path::<V>(path))?,
+            None => global::init(V::load)?,
+        };
+
+        let mut settings = Settings {
+            options: self.options.clone(),
+            capture_keys: self.capture_keys.as_deref(),
+            focus_toggle_keys: self.focus_toggle_keys.as_deref(),
+            overlay_bits: self.overlay_bits,
+            capture_path_template: self.path_template.as_deref(),
+            unload_crash_handler: self.unload_crash_handler,
+        };
+        overrides.layer_onto(&mut settings);
+        settings.apply(&rd)?;
+
+        Ok(rd)
+    }
+}
+
+impl<V: ApiVersion> Default for Builder<V> {
+    fn default() -> Self {
+        Builder::new()
+    }
+}
+
+impl<V: ApiVersion> Clone for Builder<V> {
+    fn clone(&self) -> Self {
+        Builder {
+            library_path: self.library_path.clone(),
+            options: self.options.clone(),
+            capture_keys: self.capture_keys.clone(),
+            focus_toggle_keys: self.focus_toggle_keys.clone(),
+            overlay_bits: self.overlay_bits,
+            path_template: self.path_template.clone(),
+            unload_crash_handler: self.unload_crash_handler,
+            env_overrides: self.env_overrides,
+            _version: PhantomData,
+        }
+    }
+}
+
+impl<V: ApiVersion> fmt::Debug for Builder<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("library_path", &self.library_path)
+            .field("options", &self.options)
+            .field("capture_keys", &self.capture_keys)
+            .field("focus_toggle_keys", &self.focus_toggle_keys)
+            .field("overlay_bits", &self.overlay_bits)
+            .field("path_template", &self.path_template)
+            .field("unload_crash_handler", &self.unload_crash_handler)
+            .field("env_overrides", &self.env_overrides)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use V110;
+
+    #[test]
+    fn options_keep_call_order() {
+        let builder = Builder::<V110>::new()
+            .capture_option_u32(CaptureOption::AllowVSync, 0)
+            .capture_option_f32(CaptureOption::DelayForDebugger, 2.0)
+            .capture_option_u32(CaptureOption::AllowVSync, 1);
+        assert_eq!(
+            builder.options,
+            vec![
+                (CaptureOption::AllowVSync, OptionValue::U32(0)),
+                (CaptureOption::DelayForDebugger, OptionValue::F32(2.0)),
+                (CaptureOption::AllowVSync, OptionValue::U32(1)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Checking the capture output directory.
+
+use std::error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use error::Error;
+
+/// Free space required by default before capturing, 512 MiB.
+pub const DEFAULT_MIN_FREE_BYTES: u64 = 512 << 20;
+
+/// Settings for `RenderDocV100::try_set_capture_file_path_template()`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CaptureDirOptions {
+    /// Whether to create the directory, including its parents, if it doesn't
+    /// exist.
+    pub create: bool,
+    /// Free space required on the directory's volume, in bytes.
+    ///
+    /// Not checked on platforms without a way to query it.
+    pub min_free_bytes: u64,
+}
+
+impl Default for CaptureDirOptions {
+    fn default() -> Self {
+        CaptureDirOptions {
+            create: false,
+            min_free_bytes: DEFAULT_MIN_FREE_BYTES,
+        }
+    }
+}
+
+/// Error returned when the directory of a capture path template can't hold
+/// captures.
+#[derive(Debug)]
+pub enum CaptureDirError {
+    /// The directory doesn't exist, and creating it wasn't requested.
+    Missing(PathBuf),
+    /// The path exists, but isn't a directory.
+    NotADirectory(PathBuf),
+    /// The directory could not be created.
+    Create(PathBuf, io::Error),
+    /// No files can be written to the directory.
+    NotWritable(PathBuf, io::Error),
+    /// The volume of the directory is running out of space.
+    InsufficientSpace {
+        /// The capture directory.
+        path: PathBuf,
+        /// Free space on the volume, in bytes.
+        available: u64,
+        /// Free space required, in bytes.
+        required: u64,
+    },
+    /// The template could not be set, because RenderDoc has been shut down.
+    Api(Error),
+}
+
+impl fmt::Display for CaptureDirError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CaptureDirError::Missing(ref path) => {
+                write!(f, "Capture directory {} does not exist", path.display())
+            }
+            CaptureDirError::NotADirectory(ref path) => {
+                write!(f, "Capture directory {} is not a directory", path.display())
+            }
+            CaptureDirError::Create(ref path, ref err) => {
+                write!(f, "Unable to create capture directory {}: {}", path.display(), err)
+            }
+            CaptureDirError::NotWritable(ref path, ref err) => {
+                write!(f, "Capture directory {} is not writable: {}", path.display(), err)
+            }
+            CaptureDirError::InsufficientSpace {
+                ref path,
+                available,
+                required,
+            } => write!(
+                f,
+                "Capture directory {} has {} MiB free, but {} MiB are required",
+                path.display(),
+                available >> 20,
+                required >> 20
+            ),
+            CaptureDirError::Api(ref err) => write!(f, "Unable to set capture path template: {}", err),
+        }
+    }
+}
+
+impl error::Error for CaptureDirError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            CaptureDirError::Create(_, ref err) | CaptureDirError::NotWritable(_, ref err) => Some(err),
+            CaptureDirError::Api(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for CaptureDirError {
+    fn from(err: Error) -> CaptureDirError {
+        CaptureDirError::Api(err)
+    }
+}
+
+/// Returns the directory captures written with `path_template` end up in.
+fn template_dir(path_template: &Path) -> PathBuf {
+    match path_template.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Makes sure captures written with `path_template` can be stored.
+pub(crate) fn prepare(path_template: &Path, options: &CaptureDirOptions) -> Result<(), CaptureDirError> {
+    let dir = template_dir(path_template);
+    match fs::metadata(&dir) {
+        Ok(ref metadata) if metadata.is_dir() => {}
+        Ok(_) => return Err(CaptureDirError::NotADirectory(dir)),
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound && options.create => {
+            fs::create_dir_all(&dir).map_err(|err| CaptureDirError::Create(dir.clone(), err))?;
+        }
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Err(CaptureDirError::Missing(dir)),
+        Err(err) => return Err(CaptureDirError::NotWritable(dir, err)),
+    }
+
+    // Permission bits don't tell about ACLs or read-only mounts, so actually
+    // write a file.
+    let probe = dir.join(format!(".renderdoc-rs-write-test-{}", process::id()));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|err| CaptureDirError::NotWritable(dir.clone(), err))?;
+    let _ = fs::remove_file(&probe);
+
+    if let Some(available) = free_space(&dir) {
+        if available < options.min_free_bytes {
+            return Err(CaptureDirError::InsufficientSpace {
+                path: dir,
+                available,
+                required: options.min_free_bytes,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the space available to this process on the volume of `dir`.
+#[cfg(unix)]
+fn free_space(dir: &Path) -> Option<u64> {
+    use libc;
+    use std::ffi::CString;
+    use std::mem;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(dir.as_os_str().as_bytes()).ok()?;
+    unsafe {
+        let mut stat: libc::statvfs = mem::zeroed();
+        if libc::statvfs(path.as_ptr(), &mut stat) != 0 {
+            return None;
+        }
+        Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+}
+
+/// Returns the space available to this process on the volume of `dir`.
+#[cfg(windows)]
+fn free_space(dir: &Path) -> Option<u64> {
+    use kernel32;
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr;
+
+    let path: Vec<u16> = dir.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0;
+    let ret = unsafe { kernel32::GetDiskFreeSpaceExW(path.as_ptr(), &mut available, ptr::null_mut(), ptr::null_mut()) };
+    if ret == 0 {
+        None
+    } else {
+        Some(available)
+    }
+}
+
+/// Returns the space available to this process on the volume of `dir`.
+#[cfg(not(any(unix, windows)))]
+fn free_space(_dir: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn template_dirs() {
+        assert_eq!(template_dir(Path::new("captures/game")), Path::new("captures"));
+        assert_eq!(template_dir(Path::new("game")), Path::new("."));
+    }
+
+    #[test]
+    fn prepare_dirs() {
+        let base = env::temp_dir().join(format!("renderdoc-rs-capture-dir-{}", process::id()));
+        let template = base.join("nested/game");
+        let options = CaptureDirOptions {
+            min_free_bytes: 0,
+            ..CaptureDirOptions::default()
+        };
+
+        match prepare(&template, &options) {
+            Err(CaptureDirError::Missing(dir)) => assert_eq!(dir, base.join("nested")),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let create = CaptureDirOptions { create: true, ..options };
+        prepare(&template, &create).unwrap();
+        assert!(base.join("nested").is_dir());
+        assert_eq!(fs::read_dir(base.join("nested")).unwrap().count(), 0);
+
+        let full = CaptureDirOptions {
+            min_free_bytes: u64::MAX,
+            ..options
+        };
+        match prepare(&template, &full) {
+            Err(CaptureDirError::InsufficientSpace { required, .. }) => assert_eq!(required, u64::MAX),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        fs::write(base.join("file"), b"").unwrap();
+        match prepare(&base.join("file/game"), &create) {
+            Err(CaptureDirError::NotADirectory(_)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
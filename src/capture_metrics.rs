@@ -0,0 +1,255 @@
+//! Collecting statistics about the captures of this session.
+//!
+//! Recording is opt-in through `RenderDoc::enable_capture_metrics()`. With the
+//! `metrics` feature, every recorded capture is also reported through the
+//! `metrics` facade crate:
+//!
+//! * `renderdoc_captures_total`, a counter labeled with the trigger `source`.
+//! * `renderdoc_capture_duration_seconds`, a histogram of the time spent
+//!   between starting and ending frame captures.
+//! * `renderdoc_capture_file_size_bytes`, a histogram of capture file sizes.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use api::RenderDocV100;
+use {DevicePointer, WindowHandle};
+
+/// What caused a capture.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TriggerSource {
+    /// RenderDoc's capture hotkeys, or anything else outside of this crate,
+    /// such as the replay UI.
+    Hotkey,
+    /// A call such as `trigger_capture()` or `start_frame_capture()`.
+    Programmatic,
+    /// A capture scheduled ahead of time, e.g. with
+    /// `RenderDoc::schedule_capture()`.
+    Scheduled,
+}
+
+impl TriggerSource {
+    /// Returns the source in lowercase, as used for metric labels.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            TriggerSource::Hotkey => "hotkey",
+            TriggerSource::Programmatic => "programmatic",
+            TriggerSource::Scheduled => "scheduled",
+        }
+    }
+}
+
+/// A single capture taken this session.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CaptureRecord {
+    /// What caused the capture.
+    pub source: TriggerSource,
+    /// Path of the capture file, if RenderDoc reported it.
+    pub path: Option<PathBuf>,
+    /// Time spent between `start_frame_capture()` and the end of
+    /// `end_frame_capture()`, for captures made with these calls.
+    pub duration: Option<Duration>,
+    /// Size of the capture file, if it could be read when the capture was
+    /// recorded.
+    pub file_size: Option<u64>,
+}
+
+/// Statistics about the captures taken this session, returned by
+/// `RenderDoc::capture_metrics()`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CaptureMetrics {
+    /// All captures in the order they were recorded.
+    pub captures: Vec<CaptureRecord>,
+}
+
+impl CaptureMetrics {
+    /// Returns the number of captures.
+    pub fn count(&self) -> usize {
+        self.captures.len()
+    }
+
+    /// Returns the number of captures caused by `source`.
+    pub fn count_by(&self, source: TriggerSource) -> usize {
+        self.captures.iter().filter(|capture| capture.source == source).count()
+    }
+
+    /// Returns the total time spent inside frame captures.
+    pub fn total_duration(&self) -> Duration {
+        self.captures.iter().filter_map(|capture| capture.duration).sum()
+    }
+
+    /// Returns the total size of all capture files.
+    pub fn total_file_size(&self) -> u64 {
+        self.captures.iter().filter_map(|capture| capture.file_size).sum()
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    enabled: bool,
+    /// Sources of triggered captures which RenderDoc hasn't listed yet.
+    pending: VecDeque<TriggerSource>,
+    /// Start times of ongoing frame captures, by device and window.
+    started: HashMap<(usize, usize), Instant>,
+    /// Paths of captures which were already recorded when they ended.
+    recorded_paths: HashSet<String>,
+    /// Number of RenderDoc's captures which have been looked at.
+    seen: u32,
+    metrics: CaptureMetrics,
+}
+
+impl Recorder {
+    fn record(&mut self, source: TriggerSource, path: Option<&str>, duration: Option<Duration>) {
+        let file_size = path.and_then(|path| fs::metadata(path).ok()).map(|meta| meta.len());
+        let record = CaptureRecord {
+            source,
+            path: path.map(PathBuf::from),
+            duration,
+            file_size,
+        };
+        report(&record);
+        self.metrics.captures.push(record);
+    }
+}
+
+lazy_static! {
+    static ref RECORDER: Mutex<Recorder> = Mutex::new(Recorder::default());
+}
+
+thread_local! {
+    /// Whether captures triggered on this thread were scheduled.
+    static SCHEDULED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn lock() -> MutexGuard<'static, Recorder> {
+    RECORDER.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Starts recording captures.
+pub(crate) fn enable() {
+    lock().enabled = true;
+}
+
+/// Returns whether captures are recorded.
+pub(crate) fn is_enabled() -> bool {
+    lock().enabled
+}
+
+/// Runs `f`, attributing the captures it triggers to a schedule.
+pub(crate) fn scheduled<R, F: FnOnce() -> R>(f: F) -> R {
+    SCHEDULED.with(|scheduled| scheduled.set(true));
+    let result = f();
+    SCHEDULED.with(|scheduled| scheduled.set(false));
+    result
+}
+
+/// Called after a capture of the next `num_frames` frames was triggered.
+pub(crate) fn capture_triggered(num_frames: u32) {
+    let mut recorder = lock();
+    if recorder.enabled {
+        let source = if SCHEDULED.with(Cell::get) {
+            TriggerSource::Scheduled
+        } else {
+            TriggerSource::Programmatic
+        };
+        recorder.pending.extend((0..num_frames).map(|_| source));
+    }
+}
+
+/// Called before a frame capture is started.
+pub(crate) fn frame_capture_started(dev: &DevicePointer, win: WindowHandle) {
+    let mut recorder = lock();
+    if recorder.enabled {
+        let key = (**dev as usize, win.as_raw() as usize);
+        recorder.started.insert(key, Instant::now());
+    }
+}
+
+/// Called after a frame capture ended, with the path of the capture file if it
+/// succeeded and is known.
+pub(crate) fn frame_capture_ended(dev: &DevicePointer, win: WindowHandle, ok: bool, path: Option<&str>) {
+    let mut recorder = lock();
+    if !recorder.enabled {
+        return;
+    }
+
+    let key = (**dev as usize, win.as_raw() as usize);
+    let duration = recorder.started.remove(&key).map(|start| start.elapsed());
+    if ok {
+        if let Some(path) = path {
+            recorder.recorded_paths.insert(path.to_string());
+        }
+        recorder.record(TriggerSource::Programmatic, path, duration);
+    }
+}
+
+/// Records the captures RenderDoc made since the last call, and returns all
+/// captures recorded so far.
+///
+/// Captures neither started through this crate nor triggered by it are
+/// attributed to hotkeys.
+pub(crate) fn snapshot<R: RenderDocV100>(rd: &R) -> CaptureMetrics {
+    let mut recorder = lock();
+    // Once RenderDoc is shut down, there are no new captures to discover.
+    if let (true, Ok(num)) = (recorder.enabled, rd.get_num_captures()) {
+        for index in recorder.seen..num {
+            let path = rd.get_capture(index).ok().flatten().map(|(path, _)| path);
+            if let Some(ref path) = path {
+                if recorder.recorded_paths.remove(path) {
+                    continue;
+                }
+            }
+            let source = recorder.pending.pop_front().unwrap_or(TriggerSource::Hotkey);
+            recorder.record(source, path.as_deref(), None);
+        }
+        recorder.seen = recorder.seen.max(num);
+    }
+    recorder.metrics.clone()
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+fn report(record: &CaptureRecord) {
+    #[cfg(feature = "metrics")]
+    {
+        ::metrics::counter!("renderdoc_captures_total", "source" => record.source.as_str()).increment(1);
+        if let Some(duration) = record.duration {
+            ::metrics::histogram!("renderdoc_capture_duration_seconds").record(duration.as_secs_f64());
+        }
+        if let Some(size) = record.file_size {
+            ::metrics::histogram!("renderdoc_capture_file_size_bytes").record(size as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarize_captures() {
+        let capture = |source, duration: Option<u64>, file_size| CaptureRecord {
+            source,
+            path: None,
+            duration: duration.map(Duration::from_millis),
+            file_size,
+        };
+        let metrics = CaptureMetrics {
+            captures: vec![
+                capture(TriggerSource::Hotkey, None, Some(100)),
+                capture(TriggerSource::Programmatic, Some(20), Some(50)),
+                capture(TriggerSource::Programmatic, Some(30), None),
+            ],
+        };
+
+        assert_eq!(metrics.count(), 3);
+        assert_eq!(metrics.count_by(TriggerSource::Programmatic), 2);
+        assert_eq!(metrics.count_by(TriggerSource::Scheduled), 0);
+        assert_eq!(metrics.total_duration(), Duration::from_millis(50));
+        assert_eq!(metrics.total_file_size(), 150);
+        assert_eq!(TriggerSource::Hotkey.as_str(), "hotkey");
+    }
+}
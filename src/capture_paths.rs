@@ -0,0 +1,131 @@
+//! Directories for the capture files of a run.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use api::RenderDocV100;
+use error::Error;
+
+/// Capture file locations of a single run of the application.
+///
+/// Long running setups such as soak tests restart the application many times,
+/// and would pile up all captures in one directory with a fixed path template.
+/// `CapturePaths::per_run()` instead creates a directory for every run, named
+/// `<exe>-<date>-<pid>`, e.g. `game-20240131-154502-4711`, with the date and
+/// time in UTC.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use renderdoc::{CapturePaths, RenderDoc, V110};
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let rd: RenderDoc<V110> = RenderDoc::new()?;
+/// let paths = CapturePaths::per_run("captures")?;
+/// paths.apply(&rd)?;
+/// println!("Capturing to {}", paths.dir().display());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CapturePaths {
+    dir: PathBuf,
+    template: PathBuf,
+}
+
+impl CapturePaths {
+    /// Creates a directory for this run inside `base_dir`, creating
+    /// `base_dir` as well if needed.
+    pub fn per_run<P: AsRef<Path>>(base_dir: P) -> io::Result<CapturePaths> {
+        let exe = exe_name();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let dir = base_dir
+            .as_ref()
+            .join(format!("{}-{}-{}", exe, format_utc(now.as_secs()), process::id()));
+        fs::create_dir_all(&dir)?;
+
+        let template = dir.join(&exe);
+        Ok(CapturePaths { dir, template })
+    }
+
+    /// Returns the directory of this run.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the capture path template, which RenderDoc completes with the
+    /// frame number, e.g. to `<dir>/game_frame123.rdc`.
+    pub fn template(&self) -> &Path {
+        &self.template
+    }
+
+    /// Sets the capture path template of `rd` to this run's directory.
+    pub fn apply<R: RenderDocV100>(&self, rd: &R) -> Result<(), Error> {
+        rd.set_capture_file_path_template(&self.template)
+    }
+}
+
+/// Returns the file stem of the running executable, or `app` if unknown.
+pub(crate) fn exe_name() -> String {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "app".to_string())
+}
+
+/// Formats seconds since the Unix epoch as `YYYYMMDD-HHMMSS` in UTC.
+fn format_utc(secs: u64) -> String {
+    let (days, secs) = (secs / 86400, secs % 86400);
+
+    // Converts days to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_dates() {
+        assert_eq!(format_utc(0), "19700101-000000");
+        assert_eq!(format_utc(951_782_400), "20000229-000000");
+        assert_eq!(format_utc(1_706_715_902), "20240131-154502");
+        assert_eq!(format_utc(4_102_444_799), "20991231-235959");
+    }
+
+    #[test]
+    fn create_run_directory() {
+        let base = env::temp_dir().join(format!("renderdoc-rs-capture-paths-{}", process::id()));
+        let paths = CapturePaths::per_run(&base).unwrap();
+        assert!(paths.dir().is_dir());
+        assert_eq!(paths.dir().parent(), Some(base.as_path()));
+        assert_eq!(paths.template().parent(), Some(paths.dir()));
+
+        let name = paths.dir().file_name().unwrap().to_str().unwrap().to_string();
+        assert!(name.starts_with(&format!("{}-", exe_name())));
+        assert!(name.ends_with(&format!("-{}", process::id())));
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+//! Captures queued for the next run of an application.
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use capture_paths;
+use {ApiVersion, FrameClock, RenderDoc};
+
+/// Environment variable which, if set, overrides the location of the state file
+/// used by `CaptureQueue::for_current_exe()`.
+pub const CAPTURE_QUEUE_ENV_VAR: &str = "RENDERDOC_RS_CAPTURE_QUEUE";
+
+/// Frame captures queued for the next run of an application.
+///
+/// Frames early in a run, e.g. the third one after startup, are hard to capture
+/// by hand. Instead, tooling queues them in a small state file with `push()`,
+/// and the application arms them on its next start with `arm()`, which also
+/// empties the queue. The state file holds one frame index per line, with
+/// frames counted in presents since startup as by `FrameClock`.
+///
+/// # Examples
+///
+/// Queuing a capture of frame 3 from a tool:
+///
+/// ```rust,no_run
+/// # use renderdoc::CaptureQueue;
+/// # fn queue() -> std::io::Result<()> {
+/// CaptureQueue::for_app("my-game").push(3)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Arming the queued captures when `my-game` starts:
+///
+/// ```rust,no_run
+/// # use renderdoc::{CaptureQueue, FrameClock, RenderDoc, V110};
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let rd: RenderDoc<V110> = RenderDoc::new()?;
+/// let clock = FrameClock::new();
+/// CaptureQueue::for_current_exe().arm(&rd, &clock)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CaptureQueue {
+    path: PathBuf,
+}
+
+impl CaptureQueue {
+    /// Uses the state file at `path`.
+    pub fn at<P: Into<PathBuf>>(path: P) -> Self {
+        CaptureQueue { path: path.into() }
+    }
+
+    /// Uses the state file of the application named `name`, which is kept in
+    /// the temporary directory.
+    pub fn for_app(name: &str) -> Self {
+        CaptureQueue::at(env::temp_dir().join(format!("renderdoc-rs-{}.queue", name)))
+    }
+
+    /// Uses the state file named after the running executable, as by
+    /// `for_app()` with its file stem, or the one at `RENDERDOC_RS_CAPTURE_QUEUE`
+    /// if set.
+    pub fn for_current_exe() -> Self {
+        if let Some(path) = env::var_os(CAPTURE_QUEUE_ENV_VAR) {
+            return CaptureQueue::at(path);
+        }
+
+        CaptureQueue::for_app(&capture_paths::exe_name())
+    }
+
+    /// Returns the path of the state file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Queues a capture of `frame` for the next run.
+    pub fn push(&self, frame: u64) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Appending a whole line in one write keeps concurrent pushes intact.
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(format!("{}\n", frame).as_bytes())
+    }
+
+    /// Returns the queued frames in ascending order, without removing them.
+    pub fn pending(&self) -> io::Result<Vec<u64>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => parse(&contents),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Empties the queue, returning the queued frames in ascending order.
+    pub fn take(&self) -> io::Result<Vec<u64>> {
+        // The file is moved aside first, so frames pushed in the meantime are
+        // kept for the run after.
+        let taken = self.path.with_extension("taken");
+        match fs::rename(&self.path, &taken) {
+            Ok(()) => {}
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        }
+
+        let frames = fs::read_to_string(&taken).and_then(|contents| parse(&contents));
+        if frames.is_ok() {
+            let _ = fs::remove_file(&taken);
+        } else if !self.path.exists() {
+            // Put back a queue which can't be read, so it can be fixed by hand.
+            let _ = fs::rename(&taken, &self.path);
+        }
+        frames
+    }
+
+    /// Empties the queue and schedules a capture of each queued frame on
+    /// `clock`, see `RenderDoc::schedule_capture()`.
+    ///
+    /// Call this at startup, before the first frame is presented. Returns the
+    /// armed frames. Fails without emptying the queue if RenderDoc has been
+    /// shut down.
+    pub fn arm<V: ApiVersion>(&self, rd: &RenderDoc<V>, clock: &FrameClock) -> io::Result<Vec<u64>> {
+        rd.ensure_usable().map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        let frames = self.take()?;
+        for &frame in &frames {
+            log_debug!("Arming queued capture of frame {}", frame);
+            let _ = rd.schedule_capture(clock, frame);
+        }
+        Ok(frames)
+    }
+}
+
+/// Parses the contents of a state file, ignoring blank lines and `#` comments.
+fn parse(contents: &str) -> io::Result<Vec<u64>> {
+    let mut frames = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let frame = line.parse().map_err(|_| {
+            let msg = format!("invalid frame {:?} on line {} of the capture queue", line, index + 1);
+            io::Error::new(io::ErrorKind::InvalidData, msg)
+        })?;
+        frames.push(frame);
+    }
+
+    frames.sort_unstable();
+    frames.dedup();
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    #[test]
+    fn parse_state_file() {
+        assert_eq!(parse("# queued by tooling\n3\n\n 1 \n3\n").unwrap(), vec![1, 3]);
+        let err = parse("3\nthree\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "invalid frame \"three\" on line 2 of the capture queue");
+    }
+
+    #[test]
+    fn push_and_take() {
+        let queue = CaptureQueue::for_app(&format!("capture-queue-test-{}", process::id()));
+        assert_eq!(queue.take().unwrap(), Vec::<u64>::new());
+
+        queue.push(10).unwrap();
+        queue.push(3).unwrap();
+        assert_eq!(queue.pending().unwrap(), vec![3, 10]);
+        assert_eq!(queue.take().unwrap(), vec![3, 10]);
+        assert!(!queue.path().exists());
+        assert_eq!(queue.pending().unwrap(), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn keep_invalid_queue() {
+        let queue = CaptureQueue::for_app(&format!("capture-queue-invalid-{}", process::id()));
+        fs::write(queue.path(), "3\nthree\n").unwrap();
+        assert_eq!(queue.take().unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(fs::read_to_string(queue.path()).unwrap(), "3\nthree\n");
+        fs::remove_file(queue.path()).unwrap();
+    }
+}
@@ -0,0 +1,190 @@
+//! Command line flags controlling captures.
+//!
+//! `RenderDocArgs` can be flattened into an application's own `clap`
+//! arguments, so test binaries and samples share the same `--renderdoc-*`
+//! flags:
+//!
+//! * `--renderdoc-capture-path <TEMPLATE>` sets the capture path template.
+//! * `--renderdoc-capture-frame <FRAME>` captures the given frame.
+//! * `--renderdoc-options <PRESET>` selects a preset of capture options.
+//! * `--renderdoc-no-overlay` hides the in-application overlay.
+//!
+//! Requires the `clap` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate clap;
+//! # extern crate renderdoc;
+//! use clap::Parser;
+//! use renderdoc::cli::RenderDocArgs;
+//! use renderdoc::{FrameClock, RenderDoc, V110};
+//!
+//! #[derive(Parser)]
+//! struct Args {
+//!     #[command(flatten)]
+//!     renderdoc: RenderDocArgs,
+//! }
+//!
+//! # fn main() {
+//! let args = Args::parse();
+//! let rd: RenderDoc<V110> = RenderDoc::new().unwrap();
+//! let clock = FrameClock::new();
+//! args.renderdoc.apply(&rd).unwrap();
+//! args.renderdoc.schedule_capture(&rd, &clock).unwrap();
+//! # }
+//! ```
+
+use std::path::PathBuf;
+
+use clap;
+use clap::{Args, ValueEnum};
+
+use api::RenderDocV100;
+use command::CaptureOptions;
+use settings::Settings;
+use {ApiVersion, Error, FrameClock, OverlayBits, RenderDoc};
+
+/// Preset of capture options, selected with `--renderdoc-options`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, ValueEnum)]
+pub enum OptionsPreset {
+    /// RenderDoc's default options.
+    Default,
+    /// API validation with unmuted debug output, and CPU callstacks.
+    Debug,
+    /// Everything of `debug`, and all resources, initial states and command
+    /// lists included in captures.
+    Full,
+}
+
+impl OptionsPreset {
+    /// Returns the capture options of this preset.
+    pub fn options(self) -> CaptureOptions {
+        let defaults = CaptureOptions::default();
+        let debug = CaptureOptions {
+            api_validation: true,
+            debug_output_mute: false,
+            capture_callstacks: true,
+            ..defaults
+        };
+        match self {
+            OptionsPreset::Default => defaults,
+            OptionsPreset::Debug => debug,
+            OptionsPreset::Full => CaptureOptions {
+                ref_all_resources: true,
+                save_all_initials: true,
+                capture_all_cmd_lists: true,
+                ..debug
+            },
+        }
+    }
+}
+
+/// Capture flags for `clap` command lines.
+///
+/// Flags which aren't given leave the corresponding setting untouched.
+#[derive(Args, Clone, Debug, Default, Eq, PartialEq)]
+pub struct RenderDocArgs {
+    /// Template for the paths of RenderDoc capture files.
+    #[arg(long = "renderdoc-capture-path", value_name = "TEMPLATE")]
+    pub capture_path: Option<PathBuf>,
+    /// Frame to capture with RenderDoc, counted in presents since startup.
+    #[arg(long = "renderdoc-capture-frame", value_name = "FRAME")]
+    pub capture_frame: Option<u64>,
+    /// Preset of RenderDoc capture options.
+    #[arg(long = "renderdoc-options", value_enum, value_name = "PRESET")]
+    pub options: Option<OptionsPreset>,
+    /// Hide the RenderDoc overlay.
+    #[arg(long = "renderdoc-no-overlay")]
+    pub no_overlay: bool,
+}
+
+impl RenderDocArgs {
+    /// Applies the capture path, options and overlay flags to `rd`.
+    ///
+    /// `--renderdoc-capture-frame` needs a frame clock, and is applied by
+    /// `schedule_capture()` instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if RenderDoc rejects one of the preset's options.
+    pub fn apply<R: RenderDocV100>(&self, rd: &R) -> Result<(), Error> {
+        let mut settings = Settings {
+            overlay_bits: if self.no_overlay { Some(OverlayBits::NONE) } else { None },
+            capture_path_template: self.capture_path.as_deref(),
+            ..Settings::default()
+        };
+        if let Some(preset) = self.options {
+            settings.extend_u32_options(&preset.options().values());
+        }
+        settings.apply(rd)
+    }
+
+    /// Captures the frame given by `--renderdoc-capture-frame`, once `clock`
+    /// reaches it, see `RenderDoc::schedule_capture()`. Does nothing if the
+    /// flag wasn't given.
+    pub fn schedule_capture<V: ApiVersion>(&self, rd: &RenderDoc<V>, clock: &FrameClock) -> Result<(), Error> {
+        match self.capture_frame {
+            Some(frame) => rd.schedule_capture(clock, frame),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Debug, Parser)]
+    struct TestArgs {
+        #[command(flatten)]
+        renderdoc: RenderDocArgs,
+        #[arg(long)]
+        frames: Option<u32>,
+    }
+
+    fn parse(args: &[&str]) -> Result<RenderDocArgs, clap::Error> {
+        let args = Some("test").into_iter().chain(args.iter().cloned());
+        TestArgs::try_parse_from(args).map(|args| args.renderdoc)
+    }
+
+    #[test]
+    fn parse_flags() {
+        assert_eq!(parse(&[]).unwrap(), RenderDocArgs::default());
+        assert_eq!(
+            parse(&[
+                "--renderdoc-capture-path",
+                "captures/test",
+                "--renderdoc-capture-frame=3",
+                "--renderdoc-options",
+                "debug",
+                "--renderdoc-no-overlay",
+            ])
+            .unwrap(),
+            RenderDocArgs {
+                capture_path: Some(PathBuf::from("captures/test")),
+                capture_frame: Some(3),
+                options: Some(OptionsPreset::Debug),
+                no_overlay: true,
+            }
+        );
+        assert!(parse(&["--renderdoc-options", "bogus"]).is_err());
+    }
+
+    #[test]
+    fn presets_build_on_each_other() {
+        let debug = OptionsPreset::Debug.options();
+        assert!(debug.api_validation && !debug.debug_output_mute);
+        assert_eq!(
+            OptionsPreset::Full.options(),
+            CaptureOptions {
+                ref_all_resources: true,
+                save_all_initials: true,
+                capture_all_cmd_lists: true,
+                ..debug
+            }
+        );
+        assert_eq!(OptionsPreset::Default.options(), CaptureOptions::default());
+    }
+}
@@ -0,0 +1,490 @@
+//! Launching applications with RenderDoc capturing enabled.
+//!
+//! On Linux, the application is started directly with the RenderDoc library
+//! preloaded and the Vulkan layer enabled through the environment. On Windows,
+//! where RenderDoc has to be injected into the new process, launching is
+//! delegated to `renderdoccmd capture`.
+
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitStatus};
+
+use protocol;
+use targetcontrol::TargetControl;
+use CaptureOption;
+
+/// Options applied to captures made by a launched application.
+///
+/// These mirror `CaptureOption`, but have to be known before the application
+/// starts rather than being set through the in-application API.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CaptureOptions {
+    /// Allow the application to enable vertical synchronization.
+    pub allow_vsync: bool,
+    /// Allow the application to enter exclusive fullscreen mode.
+    pub allow_fullscreen: bool,
+    /// Record API debugging events and messages.
+    pub api_validation: bool,
+    /// Capture CPU callstacks for API events.
+    pub capture_callstacks: bool,
+    /// Only capture callstacks for draw calls.
+    pub capture_callstacks_only_draws: bool,
+    /// Seconds to wait for a debugger to attach after launching.
+    pub delay_for_debugger: u32,
+    /// Verify that buffer writes stay within mapped bounds.
+    pub verify_map_writes: bool,
+    /// Also hook into any child processes launched by the application.
+    pub hook_into_children: bool,
+    /// Include all live resources in captures, not just referenced ones.
+    pub ref_all_resources: bool,
+    /// Save the initial state of all resources at the start of a capture.
+    pub save_all_initials: bool,
+    /// Capture all command lists from the start of the application.
+    pub capture_all_cmd_lists: bool,
+    /// Mute API debug output when `api_validation` is enabled.
+    pub debug_output_mute: bool,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> CaptureOptions {
+        CaptureOptions {
+            allow_vsync: true,
+            allow_fullscreen: true,
+            api_validation: false,
+            capture_callstacks: false,
+            capture_callstacks_only_draws: false,
+            delay_for_debugger: 0,
+            verify_map_writes: false,
+            hook_into_children: false,
+            ref_all_resources: false,
+            save_all_initials: false,
+            capture_all_cmd_lists: false,
+            debug_output_mute: true,
+        }
+    }
+}
+
+impl CaptureOptions {
+    /// Returns the options as values for the in-application API.
+    pub(crate) fn values(&self) -> [(CaptureOption, u32); 12] {
+        [
+            (CaptureOption::AllowVSync, self.allow_vsync as u32),
+            (CaptureOption::AllowFullscreen, self.allow_fullscreen as u32),
+            (CaptureOption::ApiValidation, self.api_validation as u32),
+            (CaptureOption::CaptureCallstacks, self.capture_callstacks as u32),
+            (CaptureOption::CaptureCallstacksOnlyDraws, self.capture_callstacks_only_draws as u32),
+            (CaptureOption::DelayForDebugger, self.delay_for_debugger),
+            (CaptureOption::VerifyMapWrites, self.verify_map_writes as u32),
+            (CaptureOption::HookIntoChildren, self.hook_into_children as u32),
+            (CaptureOption::RefAllResources, self.ref_all_resources as u32),
+            (CaptureOption::SaveAllInitials, self.save_all_initials as u32),
+            (CaptureOption::CaptureAllCmdLists, self.capture_all_cmd_lists as u32),
+            (CaptureOption::DebugOutputMute, self.debug_output_mute as u32),
+        ]
+    }
+
+    /// Encodes the options like RenderDoc's `CaptureOptions::EncodeAsString()`,
+    /// which the library reads from the `RENDERDOC_CAPOPTS` variable.
+    ///
+    /// Each byte of the C struct becomes two letters from `a` to `p`.
+    #[cfg_attr(windows, allow(dead_code))]
+    fn encode(&self) -> String {
+        self.raw()
+            .iter()
+            .flat_map(|&b| vec![(b'a' + (b >> 4)) as char, (b'a' + (b & 0xf)) as char])
+            .collect()
+    }
+
+    /// Returns the bytes of RenderDoc's C `CaptureOptions` struct.
+    pub(crate) fn raw(&self) -> [u8; 20] {
+        let mut raw = [0u8; 20];
+        raw[0] = self.allow_vsync as u8;
+        raw[1] = self.allow_fullscreen as u8;
+        raw[2] = self.api_validation as u8;
+        raw[3] = self.capture_callstacks as u8;
+        raw[4] = self.capture_callstacks_only_draws as u8;
+        raw[8..12].copy_from_slice(&self.delay_for_debugger.to_le_bytes());
+        raw[12] = self.verify_map_writes as u8;
+        raw[13] = self.hook_into_children as u8;
+        raw[14] = self.ref_all_resources as u8;
+        raw[15] = self.save_all_initials as u8;
+        raw[16] = self.capture_all_cmd_lists as u8;
+        raw[17] = self.debug_output_mute as u8;
+        raw
+    }
+
+    /// Translates the options to `renderdoccmd capture` arguments.
+    #[cfg(windows)]
+    fn renderdoccmd_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        let flags = [
+            (!self.allow_vsync, "--opt-disallow-vsync"),
+            (!self.allow_fullscreen, "--opt-disallow-fullscreen"),
+            (self.api_validation, "--opt-api-validation"),
+            (self.api_validation && !self.debug_output_mute, "--opt-api-validation-unmute"),
+            (self.capture_callstacks, "--opt-capture-callstacks"),
+            (self.capture_callstacks_only_draws, "--opt-capture-callstacks-only-draws"),
+            (self.verify_map_writes, "--opt-verify-map-writes"),
+            (self.hook_into_children, "--opt-hook-children"),
+            (self.ref_all_resources, "--opt-ref-all-resources"),
+            (self.save_all_initials, "--opt-save-all-initials"),
+            (self.capture_all_cmd_lists, "--opt-capture-all-cmd-lists"),
+        ];
+        for &(enabled, flag) in flags.iter() {
+            if enabled {
+                args.push(flag.into());
+            }
+        }
+        if self.delay_for_debugger > 0 {
+            args.push("--opt-delay-for-debugger".into());
+            args.push(self.delay_for_debugger.to_string().into());
+        }
+        args
+    }
+}
+
+/// A process builder like `std::process::Command`, which launches the
+/// application with RenderDoc capturing enabled.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use renderdoc::Command;
+///
+/// # fn run() -> std::io::Result<()> {
+/// let mut child = Command::new("./target/debug/gpu-tests")
+///     .arg("--frames=3")
+///     .capture_template("/tmp/gpu-tests/capture")
+///     .spawn()?;
+/// child.wait()?;
+/// for capture in child.captures()? {
+///     println!("{}", capture.display());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Command {
+    program: OsString,
+    args: Vec<OsString>,
+    envs: Vec<(OsString, OsString)>,
+    current_dir: Option<PathBuf>,
+    capture_template: Option<PathBuf>,
+    log_file: Option<PathBuf>,
+    options: CaptureOptions,
+}
+
+impl Command {
+    /// Creates a builder for launching `program`.
+    pub fn new<S: AsRef<OsStr>>(program: S) -> Command {
+        Command {
+            program: program.as_ref().to_os_string(),
+            args: Vec::new(),
+            envs: Vec::new(),
+            current_dir: None,
+            capture_template: None,
+            log_file: None,
+            options: CaptureOptions::default(),
+        }
+    }
+
+    /// Adds an argument to pass to the application.
+    pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Command {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    /// Adds multiple arguments to pass to the application.
+    pub fn args<I, S>(&mut self, args: I) -> &mut Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args.extend(args.into_iter().map(|arg| arg.as_ref().to_os_string()));
+        self
+    }
+
+    /// Sets an environment variable for the application.
+    pub fn env<K: AsRef<OsStr>, V: AsRef<OsStr>>(&mut self, key: K, val: V) -> &mut Command {
+        self.envs.push((key.as_ref().to_os_string(), val.as_ref().to_os_string()));
+        self
+    }
+
+    /// Sets the working directory of the application.
+    pub fn current_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Command {
+        self.current_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the path template for captures, see
+    /// `RenderDocV100::set_capture_file_path_template()`.
+    ///
+    /// Defaults to `RenderDoc/<program name>` within the temporary directory.
+    pub fn capture_template<P: AsRef<Path>>(&mut self, template: P) -> &mut Command {
+        self.capture_template = Some(template.as_ref().to_path_buf());
+        self
+    }
+
+    /// Makes RenderDoc write its log to `path`.
+    pub fn log_file<P: AsRef<Path>>(&mut self, path: P) -> &mut Command {
+        self.log_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the options applied to captures.
+    pub fn options(&mut self, options: CaptureOptions) -> &mut Command {
+        self.options = options;
+        self
+    }
+
+    /// Launches the application.
+    ///
+    /// Fails with `io::ErrorKind::NotFound` if RenderDoc itself can't be
+    /// found.
+    pub fn spawn(&mut self) -> io::Result<CaptureChild> {
+        let capture_template = self.capture_template.clone().unwrap_or_else(|| {
+            let name = Path::new(&self.program).file_stem().unwrap_or_else(|| OsStr::new("capture"));
+            env::temp_dir().join("RenderDoc").join(name)
+        });
+        if let Some(dir) = capture_template.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut cmd = self.launcher(&capture_template)?;
+        if let Some(ref dir) = self.current_dir {
+            cmd.current_dir(dir);
+        }
+        if let Some(ref log_file) = self.log_file {
+            cmd.env("RENDERDOC_DEBUG_LOG_FILE", log_file);
+        }
+
+        Ok(CaptureChild {
+            child: cmd.spawn()?,
+            capture_template,
+        })
+    }
+
+    #[cfg(not(windows))]
+    fn launcher(&self, capture_template: &Path) -> io::Result<process::Command> {
+        use entry::loader;
+
+        let library = loader::find_library()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "RenderDoc library not found"))?;
+
+        // Keep anything the application preloads itself.
+        let mut preload = library.into_os_string();
+        let existing = self.envs.iter().rev().find(|(key, _)| key == "LD_PRELOAD");
+        if let Some(existing) = existing.map(|(_, val)| val.clone()).or_else(|| env::var_os("LD_PRELOAD")) {
+            preload.push(":");
+            preload.push(existing);
+        }
+
+        let mut cmd = process::Command::new(&self.program);
+        cmd.args(&self.args)
+            .envs(self.envs.iter().map(|(key, val)| (key, val)))
+            .env("LD_PRELOAD", preload)
+            .env("RENDERDOC_CAPFILE", capture_template)
+            .env("RENDERDOC_CAPOPTS", self.options.encode())
+            .env("ENABLE_VULKAN_RENDERDOC_CAPTURE", "1");
+        Ok(cmd)
+    }
+
+    #[cfg(windows)]
+    fn launcher(&self, capture_template: &Path) -> io::Result<process::Command> {
+        use discovery;
+
+        let renderdoccmd = discovery::find_renderdoccmd()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "renderdoccmd not found"))?;
+
+        // The application inherits the environment of renderdoccmd.
+        let mut cmd = process::Command::new(renderdoccmd);
+        cmd.envs(self.envs.iter().map(|(key, val)| (key, val)));
+        cmd.arg("capture").arg("--wait-for-exit");
+        cmd.arg("--capture-file").arg(capture_template);
+        if let Some(ref dir) = self.current_dir {
+            cmd.arg("--working-dir").arg(dir);
+        }
+        cmd.args(self.options.renderdoccmd_args());
+        cmd.arg(&self.program).args(&self.args);
+        Ok(cmd)
+    }
+}
+
+/// Injects RenderDoc into the already running process `pid` and connects to
+/// it over the target control protocol, identifying as `client`.
+///
+/// Injection is delegated to `renderdoccmd inject` and is only supported on
+/// Windows; elsewhere applications have to be launched with RenderDoc, e.g.
+/// through `Command`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use renderdoc::CaptureOptions;
+///
+/// # fn run() -> Result<(), renderdoc::protocol::Error> {
+/// let mut target = renderdoc::command::inject(4242, &CaptureOptions::default(), None, "attach-tool")?;
+/// target.trigger_capture(1)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn inject(
+    pid: u32,
+    options: &CaptureOptions,
+    capture_template: Option<&Path>,
+    client: &str,
+) -> Result<TargetControl, protocol::Error> {
+    let port = inject_process(pid, options, capture_template)?;
+    TargetControl::connect_local(port, client, false)
+}
+
+#[cfg(windows)]
+fn inject_process(pid: u32, options: &CaptureOptions, capture_template: Option<&Path>) -> io::Result<u16> {
+    use discovery;
+
+    let renderdoccmd = discovery::find_renderdoccmd()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "renderdoccmd not found"))?;
+
+    let mut cmd = process::Command::new(renderdoccmd);
+    cmd.arg("inject").arg(format!("--PID={}", pid));
+    if let Some(template) = capture_template {
+        cmd.arg("--capture-file").arg(template);
+    }
+    let output = cmd.args(options.renderdoccmd_args()).output()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_ident(&stdout).ok_or_else(|| {
+        let msg = format!("injecting into process {} failed: {}", pid, stdout.trim());
+        io::Error::new(io::ErrorKind::Other, msg)
+    })
+}
+
+#[cfg(not(windows))]
+fn inject_process(_pid: u32, _options: &CaptureOptions, _capture_template: Option<&Path>) -> io::Result<u16> {
+    let msg = "injecting into running processes is only supported on Windows";
+    Err(io::Error::new(io::ErrorKind::Other, msg))
+}
+
+/// Finds the target control port in the output of `renderdoccmd`, which
+/// reports it as e.g. `Launched as ID 38920`.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_ident(output: &str) -> Option<u16> {
+    let words: Vec<&str> = output.split_whitespace().collect();
+    words
+        .windows(2)
+        .filter(|pair| pair[0] == "ID")
+        .filter_map(|pair| pair[1].parse().ok())
+        .find(|&ident| ident != 0)
+}
+
+/// An application launched with RenderDoc capturing enabled.
+#[derive(Debug)]
+pub struct CaptureChild {
+    child: process::Child,
+    capture_template: PathBuf,
+}
+
+impl CaptureChild {
+    /// Returns the OS process ID.
+    ///
+    /// On Windows, this is the ID of the `renderdoccmd` process waiting for
+    /// the application to exit.
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Returns the underlying process handle.
+    pub fn child(&mut self) -> &mut process::Child {
+        &mut self.child
+    }
+
+    /// Waits for the application to exit.
+    pub fn wait(&mut self) -> io::Result<ExitStatus> {
+        self.child.wait()
+    }
+
+    /// Returns the path template captures are written with.
+    pub fn capture_template(&self) -> &Path {
+        &self.capture_template
+    }
+
+    /// Returns the captures written so far, oldest first.
+    ///
+    /// RenderDoc names captures after the template followed by a timestamp
+    /// and the frame number, e.g. `capture_2018.01.31_12.00.00_frame42.rdc`.
+    pub fn captures(&self) -> io::Result<Vec<PathBuf>> {
+        find_captures(&self.capture_template)
+    }
+}
+
+pub(crate) fn find_captures(template: &Path) -> io::Result<Vec<PathBuf>> {
+    let dir = match template.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = match template.file_name().and_then(|name| name.to_str()) {
+        Some(name) => format!("{}_", name),
+        None => return Ok(Vec::new()),
+    };
+
+    let mut captures = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let matches = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".rdc"));
+        if matches {
+            captures.push(path);
+        }
+    }
+
+    captures.sort();
+    Ok(captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_default_options() {
+        let encoded = CaptureOptions::default().encode();
+        assert_eq!(encoded.len(), 40);
+        assert!(encoded.starts_with("ababaa"));
+        assert_eq!(&encoded[34..36], "ab");
+    }
+
+    #[test]
+    fn option_values_cover_every_option() {
+        let options = CaptureOptions {
+            delay_for_debugger: 5,
+            ..CaptureOptions::default()
+        };
+        let values = options.values();
+        for (i, &(opt, _)) in values.iter().enumerate() {
+            assert_eq!(opt as usize, i);
+        }
+        assert_eq!(values[0], (CaptureOption::AllowVSync, 1));
+        assert_eq!(values[5], (CaptureOption::DelayForDebugger, 5));
+    }
+
+    #[test]
+    fn parse_injected_ident() {
+        let output = "Injecting into PID 4242\nLaunched as ID 38921\n";
+        assert_eq!(parse_ident(output), Some(38921));
+        assert_eq!(parse_ident("Failed to inject: ID 0\n"), None);
+    }
+
+    #[test]
+    fn encode_delay_for_debugger() {
+        let options = CaptureOptions {
+            delay_for_debugger: 0x1f,
+            ..CaptureOptions::default()
+        };
+        assert_eq!(&options.encode()[16..24], "bpaaaaaa");
+    }
+}
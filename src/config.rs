@@ -0,0 +1,316 @@
+//! Capture configuration files.
+//!
+//! A configuration file describes capture options, hotkeys, the overlay and
+//! the capture path template, so capture behavior can be tuned without
+//! changing code. Files ending in `.json` are read as JSON, all others as
+//! TOML:
+//!
+//! ```toml
+//! capture_path_template = "captures/game"
+//! capture_keys = ["F12", "PrtScrn"]
+//! focus_toggle_keys = ["F11"]
+//! overlay = ["enabled", "frame_number", "capture_list"]
+//!
+//! [options]
+//! api_validation = true
+//! capture_callstacks = true
+//! delay_for_debugger = 5
+//! ```
+//!
+//! Names of keys, options and overlay flags are case-insensitive, and ignore
+//! `_` and `-`. The overlay is given as a list of flags, or as one of the
+//! strings `"none"`, `"default"` and `"all"`.
+//!
+//! `load()` applies the `RENDERDOC_RS_OPTIONS`, `RENDERDOC_RS_OVERLAY` and
+//! `RENDERDOC_RS_CAPTURE_PATH` environment variables on top of the file.
+//!
+//! Requires the `config` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use renderdoc::{RenderDoc, V110};
+//! # fn run(rd: &RenderDoc<V110>) -> Result<(), renderdoc::config::Error> {
+//! let config = renderdoc::config::load("renderdoc.toml")?;
+//! config.apply(rd)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json;
+use toml;
+
+use api::RenderDocV100;
+use names;
+use overrides::Overrides;
+use settings::Settings;
+use {CaptureOption, InputButton, OverlayBits};
+
+/// Errors that can occur while loading a configuration.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the file failed.
+    Io(io::Error),
+    /// The file is not valid TOML, or doesn't have the expected structure.
+    Toml(toml::de::Error),
+    /// The file is not valid JSON, or doesn't have the expected structure.
+    Json(serde_json::Error),
+    /// The file names an unknown key, option or overlay flag, or an
+    /// environment variable overriding it is invalid.
+    Invalid(String),
+    /// The configuration could not be applied, because RenderDoc has been shut
+    /// down.
+    Api(::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+            Error::Toml(ref err) => write!(f, "Invalid TOML configuration: {}", err),
+            Error::Json(ref err) => write!(f, "Invalid JSON configuration: {}", err),
+            Error::Invalid(ref msg) => write!(f, "Invalid configuration: {}", msg),
+            Error::Api(ref err) => write!(f, "Unable to apply configuration: {}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Toml(ref err) => Some(err),
+            Error::Json(ref err) => Some(err),
+            Error::Invalid(_) => None,
+            Error::Api(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Error {
+        Error::Toml(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Error {
+        Error::Json(err)
+    }
+}
+
+impl From<::Error> for Error {
+    fn from(err: ::Error) -> Error {
+        Error::Api(err)
+    }
+}
+
+/// Capture settings, applied to a handle with `apply()`.
+///
+/// Settings left at `None`, and options not listed, keep RenderDoc's current
+/// values.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    /// Capture options and their values, in the order they are applied.
+    pub options: Vec<(CaptureOption, u32)>,
+    /// Keys which capture the next frame.
+    pub capture_keys: Option<Vec<InputButton>>,
+    /// Keys which toggle focus between multiple windows.
+    pub focus_toggle_keys: Option<Vec<InputButton>>,
+    /// Overlay configuration bits, replacing the current ones.
+    pub overlay: Option<OverlayBits>,
+    /// Template for the paths of capture files.
+    pub capture_path_template: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    #[serde(default)]
+    options: BTreeMap<String, RawValue>,
+    capture_keys: Option<Vec<String>>,
+    focus_toggle_keys: Option<Vec<String>>,
+    overlay: Option<RawOverlay>,
+    capture_path_template: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawValue {
+    Bool(bool),
+    Number(u32),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawOverlay {
+    Preset(String),
+    Flags(Vec<String>),
+}
+
+impl Config {
+    /// Parses a configuration in TOML format.
+    pub fn from_toml(source: &str) -> Result<Config, Error> {
+        Config::from_raw(toml::from_str(source)?)
+    }
+
+    /// Parses a configuration in JSON format.
+    pub fn from_json(source: &str) -> Result<Config, Error> {
+        Config::from_raw(serde_json::from_str(source)?)
+    }
+
+    /// Applies the `RENDERDOC_RS_*` environment variables on top of this
+    /// configuration.
+    ///
+    /// Overridden options are appended, so they take precedence when applied.
+    pub fn with_env_overrides(mut self) -> Result<Config, Error> {
+        let overrides = Overrides::from_env().map_err(Error::Invalid)?;
+        self.options.extend(overrides.options);
+        self.overlay = overrides.overlay.or(self.overlay);
+        self.capture_path_template = overrides.capture_path_template.or(self.capture_path_template);
+        Ok(self)
+    }
+
+    fn from_raw(raw: RawConfig) -> Result<Config, Error> {
+        let mut options = Vec::with_capacity(raw.options.len());
+        for (name, value) in raw.options {
+            let opt = names::capture_option(&name)
+                .ok_or_else(|| Error::Invalid(format!("unknown capture option `{}`", name)))?;
+            let value = match value {
+                RawValue::Bool(value) => value as u32,
+                RawValue::Number(value) => value,
+            };
+            options.push((opt, value));
+        }
+
+        let overlay = match raw.overlay {
+            Some(RawOverlay::Preset(name)) => Some(overlay_flag(&name)?),
+            Some(RawOverlay::Flags(names)) => {
+                let mut bits = OverlayBits::NONE;
+                for name in names {
+                    bits |= overlay_flag(&name)?;
+                }
+                Some(bits)
+            }
+            None => None,
+        };
+
+        Ok(Config {
+            options,
+            capture_keys: raw.capture_keys.map(|keys| input_buttons(&keys)).transpose()?,
+            focus_toggle_keys: raw.focus_toggle_keys.map(|keys| input_buttons(&keys)).transpose()?,
+            overlay,
+            capture_path_template: raw.capture_path_template,
+        })
+    }
+
+    /// Applies the configuration to `rd`.
+    ///
+    /// Options are applied first, followed by hotkeys, the overlay and the
+    /// capture path template. Fails with `Error::Api` if RenderDoc has been
+    /// shut down.
+    ///
+    /// # Panics
+    ///
+    /// Panics if RenderDoc rejects an option value.
+    pub fn apply<R: RenderDocV100>(&self, rd: &R) -> Result<(), Error> {
+        let mut settings = Settings {
+            capture_keys: self.capture_keys.as_deref(),
+            focus_toggle_keys: self.focus_toggle_keys.as_deref(),
+            overlay_bits: self.overlay,
+            capture_path_template: self.capture_path_template.as_deref(),
+            ..Settings::default()
+        };
+        settings.extend_u32_options(&self.options);
+        settings.apply(rd).map_err(Error::Api)
+    }
+}
+
+fn overlay_flag(name: &str) -> Result<OverlayBits, Error> {
+    names::overlay_flag(name).ok_or_else(|| Error::Invalid(format!("unknown overlay flag `{}`", name)))
+}
+
+fn input_buttons(names: &[String]) -> Result<Vec<InputButton>, Error> {
+    names
+        .iter()
+        .map(|name| {
+            names::input_button(name).ok_or_else(|| Error::Invalid(format!("unknown key `{}`", name)))
+        })
+        .collect()
+}
+
+/// Loads the configuration file at `path`.
+///
+/// Files with a `.json` extension are parsed as JSON, all others as TOML.
+/// Environment variable overrides are applied on top, as with
+/// `Config::with_env_overrides()`.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, Error> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)?;
+    let config = match path.extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("json") => Config::from_json(&source)?,
+        _ => Config::from_toml(&source)?,
+    };
+    config.with_env_overrides()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_toml_and_json() {
+        let toml = r#"
+            capture_path_template = "captures/game"
+            capture_keys = ["F12", "PrtScrn"]
+            overlay = ["enabled", "frame_number"]
+
+            [options]
+            api_validation = true
+            delay_for_debugger = 5
+        "#;
+        let json = r#"{
+            "capture_path_template": "captures/game",
+            "capture_keys": ["f12", "print_screen"],
+            "overlay": ["Enabled", "FrameNumber"],
+            "options": { "ApiValidation": true, "delay-for-debugger": 5 }
+        }"#;
+
+        let config = Config::from_toml(toml).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                options: vec![(CaptureOption::ApiValidation, 1), (CaptureOption::DelayForDebugger, 5)],
+                capture_keys: Some(vec![InputButton::F12, InputButton::PrtScrn]),
+                focus_toggle_keys: None,
+                overlay: Some(OverlayBits::ENABLED | OverlayBits::FRAME_NUMBER),
+                capture_path_template: Some(PathBuf::from("captures/game")),
+            }
+        );
+        assert_eq!(Config::from_json(json).unwrap(), config);
+    }
+
+    #[test]
+    fn reject_unknown_names() {
+        assert!(Config::from_toml("overlay = \"none\"").is_ok());
+        match Config::from_toml("capture_keys = [\"F13\"]") {
+            Err(Error::Invalid(msg)) => assert_eq!(msg, "unknown key `F13`"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(Config::from_toml("[options]\nvsnyc = false").is_err());
+        assert!(Config::from_toml("overlays = \"none\"").is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Looking up devices by name.
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+use api::RenderDocV100;
+use {DevicePointer, Error, WindowBinding, WindowHandle};
+
+/// Identifies a device registered with a `DeviceRegistry`.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DeviceKey {
+    /// A name chosen by the application, e.g. `"compute"` or `"display"`.
+    Label(String),
+    /// The locally unique identifier of the adapter, as reported by
+    /// `VkPhysicalDeviceIDProperties::deviceLUID` or, in memory order, by
+    /// `DXGI_ADAPTER_DESC::AdapterLuid`.
+    Luid([u8; 8]),
+}
+
+impl DeviceKey {
+    /// Creates a key from the two halves of a Windows `LUID`.
+    pub fn from_luid_parts(low_part: u32, high_part: i32) -> Self {
+        let mut luid = [0; 8];
+        luid[..4].copy_from_slice(&low_part.to_le_bytes());
+        luid[4..].copy_from_slice(&high_part.to_le_bytes());
+        DeviceKey::Luid(luid)
+    }
+}
+
+impl fmt::Display for DeviceKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DeviceKey::Label(ref label) => f.write_str(label),
+            DeviceKey::Luid(luid) => write!(f, "LUID {:#018x}", u64::from_le_bytes(luid)),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for DeviceKey {
+    fn from(label: &'a str) -> Self {
+        DeviceKey::Label(label.to_string())
+    }
+}
+
+impl From<String> for DeviceKey {
+    fn from(label: String) -> Self {
+        DeviceKey::Label(label)
+    }
+}
+
+impl From<[u8; 8]> for DeviceKey {
+    fn from(luid: [u8; 8]) -> Self {
+        DeviceKey::Luid(luid)
+    }
+}
+
+/// Error returned when a `DeviceRegistry` has no device under a key.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UnknownDevice(pub DeviceKey);
+
+impl fmt::Display for UnknownDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "No device registered as {}", self.0)
+    }
+}
+
+impl error::Error for UnknownDevice {}
+
+impl From<UnknownDevice> for Error {
+    fn from(err: UnknownDevice) -> Error {
+        Error::UnknownDevice(err.0)
+    }
+}
+
+/// Devices of the application, registered under labels or adapter LUIDs.
+///
+/// Applications with several devices, e.g. a compute device next to the one
+/// presenting, or one device per adapter, have to pass the right
+/// `DevicePointer` to every frame capture. The registry keeps these pointers
+/// in one place, so subsystems can capture their device by name instead of
+/// stashing raw pointers. Each device may be registered together with the
+/// window it presents to, which is otherwise the wildcard window.
+///
+/// The registry can be shared between threads.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use renderdoc::prelude::*;
+/// # use renderdoc::{DevicePointer, DeviceRegistry};
+/// # fn run(rd: &RenderDoc<V110>, compute: DevicePointer, display: DevicePointer) {
+/// let devices = DeviceRegistry::new();
+/// devices.register("compute", compute);
+/// devices.register("display", display);
+///
+/// devices.start_frame_capture(rd, "compute").unwrap();
+/// // Dispatch the compute work...
+/// devices.end_frame_capture(rd, "compute").unwrap();
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct DeviceRegistry {
+    devices: Mutex<HashMap<DeviceKey, WindowBinding>>,
+}
+
+// The pointers are only handed to RenderDoc and never dereferenced, which is
+// safe from any thread.
+unsafe impl Send for DeviceRegistry {}
+unsafe impl Sync for DeviceRegistry {}
+
+impl DeviceRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        DeviceRegistry::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<DeviceKey, WindowBinding>> {
+        self.devices.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Registers `device` under `key`, capturing it on any window.
+    ///
+    /// Returns the binding previously registered under `key`, if any.
+    pub fn register<K, D>(&self, key: K, device: D) -> Option<WindowBinding>
+    where
+        K: Into<DeviceKey>,
+        D: Into<DevicePointer>,
+    {
+        self.register_binding(key, WindowBinding::new(device, WindowHandle::null()))
+    }
+
+    /// Registers a device together with the window it presents to under
+    /// `key`.
+    ///
+    /// Returns the binding previously registered under `key`, if any.
+    pub fn register_binding<K, B>(&self, key: K, binding: B) -> Option<WindowBinding>
+    where
+        K: Into<DeviceKey>,
+        B: Into<WindowBinding>,
+    {
+        self.lock().insert(key.into(), binding.into())
+    }
+
+    /// Removes the device registered under `key`, returning its binding.
+    pub fn unregister<K: Into<DeviceKey>>(&self, key: K) -> Option<WindowBinding> {
+        self.lock().remove(&key.into())
+    }
+
+    /// Returns the device and window registered under `key`.
+    pub fn binding<K: Into<DeviceKey>>(&self, key: K) -> Result<WindowBinding, UnknownDevice> {
+        let key = key.into();
+        match self.lock().get(&key) {
+            Some(binding) => Ok(binding.clone()),
+            None => Err(UnknownDevice(key)),
+        }
+    }
+
+    /// Returns the device registered under `key`.
+    pub fn device<K: Into<DeviceKey>>(&self, key: K) -> Result<DevicePointer, UnknownDevice> {
+        self.binding(key).map(|binding| binding.device)
+    }
+
+    /// Returns the keys of all registered devices, in sorted order.
+    pub fn keys(&self) -> Vec<DeviceKey> {
+        let mut keys: Vec<_> = self.lock().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Makes the device registered under `key` the target of hotkeys and
+    /// `trigger_capture()`.
+    pub fn set_active<R, K>(&self, rd: &R, key: K) -> Result<(), Error>
+    where
+        R: RenderDocV100,
+        K: Into<DeviceKey>,
+    {
+        let binding = self.binding(key)?;
+        rd.set_active_window_binding(binding)
+    }
+
+    /// Starts a frame capture on the device registered under `key`.
+    pub fn start_frame_capture<R, K>(&self, rd: &R, key: K) -> Result<(), Error>
+    where
+        R: RenderDocV100,
+        K: Into<DeviceKey>,
+    {
+        let binding = self.binding(key)?;
+        rd.start_frame_capture(binding.device, binding.window)
+    }
+
+    /// Ends the frame capture on the device registered under `key`.
+    pub fn end_frame_capture<R, K>(&self, rd: &R, key: K) -> Result<(), Error>
+    where
+        R: RenderDocV100,
+        K: Into<DeviceKey>,
+    {
+        let binding = self.binding(key)?;
+        rd.end_frame_capture(binding.device, binding.window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::raw::c_void;
+
+    #[test]
+    fn register_and_look_up() {
+        let devices = DeviceRegistry::new();
+        let compute = DevicePointer::from_raw(0x10 as *const c_void);
+        let display = WindowBinding::new(0x20 as *const c_void, 0x30 as *const c_void);
+
+        assert!(devices.register("compute", compute.clone()).is_none());
+        devices.register_binding(DeviceKey::from_luid_parts(1, 2), display.clone());
+
+        assert_eq!(devices.device("compute"), Ok(compute.clone()));
+        assert_eq!(devices.binding("compute").unwrap().window, WindowHandle::null());
+        assert_eq!(devices.binding([1, 0, 0, 0, 2, 0, 0, 0]), Ok(display.clone()));
+        assert_eq!(
+            devices.keys(),
+            vec![DeviceKey::from("compute"), DeviceKey::Luid([1, 0, 0, 0, 2, 0, 0, 0])]
+        );
+
+        assert_eq!(devices.unregister("compute").map(|binding| binding.device), Some(compute));
+        let err = devices.device("compute").unwrap_err();
+        assert_eq!(err.to_string(), "No device registered as compute");
+        assert_eq!(DeviceKey::from_luid_parts(1, 2).to_string(), "LUID 0x0000000200000001");
+    }
+}
@@ -0,0 +1,143 @@
+//! Discovery of RenderDoc installations.
+//!
+//! RenderDoc installers rarely put anything on the `PATH`, so these helpers
+//! also look in the locations an installation is known to use, e.g. the
+//! Windows registry and `Program Files`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+#[cfg(windows)]
+const RENDERDOCCMD: &str = "renderdoccmd.exe";
+#[cfg(not(windows))]
+const RENDERDOCCMD: &str = "renderdoccmd";
+
+#[cfg(windows)]
+const QRENDERDOC: &str = "qrenderdoc.exe";
+#[cfg(not(windows))]
+const QRENDERDOC: &str = "qrenderdoc";
+
+/// Returns the directories of all RenderDoc installations found on this
+/// machine, most likely candidates first.
+///
+/// Directories are only returned if they exist, and each one only once.
+pub fn install_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for dir in candidate_dirs() {
+        if dir.is_dir() && !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Finds the `renderdoccmd` command line tool, looking on the `PATH` first.
+pub fn find_renderdoccmd() -> Option<PathBuf> {
+    find_binary(RENDERDOCCMD)
+}
+
+/// Finds the `qrenderdoc` replay UI, looking on the `PATH` first.
+pub fn find_replay_ui() -> Option<PathBuf> {
+    find_binary(QRENDERDOC)
+}
+
+fn find_binary(name: &str) -> Option<PathBuf> {
+    path_dirs()
+        .into_iter()
+        .chain(install_dirs())
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn path_dirs() -> Vec<PathBuf> {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(windows)]
+fn candidate_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = registry::install_dirs();
+    for var in &["ProgramW6432", "ProgramFiles", "ProgramFiles(x86)"] {
+        if let Some(dir) = env::var_os(var) {
+            dirs.push(Path::new(&dir).join("RenderDoc"));
+        }
+    }
+    dirs
+}
+
+#[cfg(not(windows))]
+fn candidate_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = path_dirs()
+        .into_iter()
+        .filter(|dir| dir.join(RENDERDOCCMD).is_file() || dir.join(QRENDERDOC).is_file())
+        .collect();
+    dirs.extend(
+        ["/usr/bin", "/usr/local/bin", "/opt/renderdoc/bin"]
+            .iter()
+            .map(PathBuf::from)
+            .filter(|dir| dir.join(RENDERDOCCMD).is_file()),
+    );
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(Path::new(&home).join(".local/bin"));
+    }
+    dirs
+}
+
+/// Reads installation paths from the file associations that the RenderDoc
+/// installer registers for `.rdc` files.
+#[cfg(windows)]
+mod registry {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+    use std::path::PathBuf;
+    use std::ptr;
+
+    use advapi32;
+    use winapi;
+
+    const CAPTURE_ICON_KEY: &str = r"SOFTWARE\Classes\RenderDoc.RDCCapture.1\DefaultIcon";
+
+    pub fn install_dirs() -> Vec<PathBuf> {
+        [winapi::HKEY_CURRENT_USER, winapi::HKEY_LOCAL_MACHINE]
+            .iter()
+            .filter_map(|&root| unsafe { read_default_value(root, CAPTURE_ICON_KEY) })
+            .filter_map(|icon| parse_icon_path(&icon))
+            .collect()
+    }
+
+    /// Turns a value like `"C:\Program Files\RenderDoc\qrenderdoc.exe",0` into
+    /// the directory containing the executable.
+    fn parse_icon_path(icon: &str) -> Option<PathBuf> {
+        let exe = icon.rsplitn(2, ',').last()?.trim().trim_matches('"');
+        PathBuf::from(exe).parent().map(PathBuf::from)
+    }
+
+    unsafe fn read_default_value(root: winapi::HKEY, subkey: &str) -> Option<String> {
+        let subkey: Vec<u16> = OsString::from(subkey).encode_wide().chain(Some(0)).collect();
+        let mut key = ptr::null_mut();
+        let status = advapi32::RegOpenKeyExW(root, subkey.as_ptr(), 0, winapi::KEY_READ, &mut key);
+        if status != winapi::ERROR_SUCCESS as i32 {
+            return None;
+        }
+
+        let mut buf = [0u16; 1024];
+        let mut len = (buf.len() * 2) as winapi::DWORD;
+        let status = advapi32::RegQueryValueExW(
+            key,
+            ptr::null(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            buf.as_mut_ptr() as *mut u8,
+            &mut len,
+        );
+        advapi32::RegCloseKey(key);
+        if status != winapi::ERROR_SUCCESS as i32 {
+            return None;
+        }
+
+        let chars = &buf[..(len as usize / 2)];
+        let chars = chars.split(|&c| c == 0).next().unwrap_or(chars);
+        OsString::from_wide(chars).into_string().ok()
+    }
+}
@@ -0,0 +1,206 @@
+//! Object-safe access to the common API functionality.
+//!
+//! The version traits in `api` have generic methods and require `Sized`, so
+//! they can't be used as trait objects. `RenderDocApi` covers the function
+//! table except for shutting down and launching the replay UI, and can be
+//! boxed or borrowed as `&dyn RenderDocApi`, allowing a mock implementation
+//! to be swapped in for unit tests.
+//!
+//! The methods share their names with those in `api`, so avoid importing both
+//! this trait and the `api` traits into the same scope.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use renderdoc::dynamic::RenderDocApi;
+//!
+//! fn capture_next_frame(rd: &dyn RenderDocApi) -> Result<bool, renderdoc::Error> {
+//!     if rd.is_frame_capturing()? {
+//!         return Ok(false);
+//!     }
+//!     rd.trigger_capture()?;
+//!     Ok(true)
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use api::RenderDocV100;
+use {ApiVersion, ApiVersionNumber, CaptureOption, DevicePointer, Error, InputButton, OverlayBits, RenderDoc,
+     WindowHandle};
+
+/// In-application API operations, usable as a trait object.
+///
+/// See the corresponding methods of `RenderDocV100` and `RenderDocV110` for
+/// details. Calls fail with `Error::ShutDown` once RenderDoc has been shut
+/// down.
+pub trait RenderDocApi {
+    /// Returns the major, minor, and patch version of the API.
+    fn get_api_version(&self) -> Result<ApiVersionNumber, Error>;
+
+    /// Sets the specified `CaptureOption` to the given `f32` value.
+    fn set_capture_option_f32(&self, opt: CaptureOption, val: f32) -> Result<(), Error>;
+
+    /// Sets the specified `CaptureOption` to the given `u32` value.
+    fn set_capture_option_u32(&self, opt: CaptureOption, val: u32) -> Result<(), Error>;
+
+    /// Returns the `f32` value of the specified `CaptureOption`.
+    fn get_capture_option_f32(&self, opt: CaptureOption) -> Result<f32, Error>;
+
+    /// Returns the `u32` value of the specified `CaptureOption`.
+    fn get_capture_option_u32(&self, opt: CaptureOption) -> Result<u32, Error>;
+
+    /// Sets the keys which toggle focus between multiple windows.
+    fn set_focus_toggle_keys(&self, keys: &[InputButton]) -> Result<(), Error>;
+
+    /// Sets the keys which capture the next frame.
+    fn set_capture_keys(&self, keys: &[InputButton]) -> Result<(), Error>;
+
+    /// Returns the overlay configuration bits.
+    fn get_overlay_bits(&self) -> Result<OverlayBits, Error>;
+
+    /// Updates the overlay configuration bits, first masking with `and` and
+    /// then setting `or`.
+    fn mask_overlay_bits(&self, and: OverlayBits, or: OverlayBits) -> Result<(), Error>;
+
+    /// Unloads RenderDoc's crash handler.
+    fn unload_crash_handler(&self) -> Result<(), Error>;
+
+    /// Returns the template for the paths of capture files.
+    fn get_capture_file_path_template(&self) -> Result<PathBuf, Error>;
+
+    /// Sets the template for the paths of capture files.
+    fn set_capture_file_path_template(&self, path_template: &Path) -> Result<(), Error>;
+
+    /// Returns the number of captures made so far.
+    fn get_num_captures(&self) -> Result<u32, Error>;
+
+    /// Returns the path and timestamp of the capture at `index`.
+    fn get_capture(&self, index: u32) -> Result<Option<(String, u64)>, Error>;
+
+    /// Captures the next frame from the currently active window and device.
+    fn trigger_capture(&self) -> Result<(), Error>;
+
+    /// Returns whether a target control client, e.g. the replay UI, is
+    /// connected.
+    fn is_target_control_connected(&self) -> Result<bool, Error>;
+
+    /// Makes the given device and window the active ones.
+    fn set_active_window(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error>;
+
+    /// Starts capturing a frame for the given device and window.
+    fn start_frame_capture(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error>;
+
+    /// Returns whether a frame capture is currently ongoing anywhere.
+    fn is_frame_capturing(&self) -> Result<bool, Error>;
+
+    /// Ends capturing a frame for the given device and window.
+    fn end_frame_capture(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error>;
+
+    /// Captures the next `num_frames` frames from the currently active window
+    /// and device.
+    ///
+    /// Handles without the 1.1.0 function table capture only the next frame.
+    fn trigger_multi_frame_capture(&self, num_frames: u32) -> Result<(), Error>;
+}
+
+impl<V: ApiVersion> RenderDocApi for RenderDoc<V> {
+    fn get_api_version(&self) -> Result<ApiVersionNumber, Error> {
+        RenderDocV100::get_api_version(self)
+    }
+
+    fn set_capture_option_f32(&self, opt: CaptureOption, val: f32) -> Result<(), Error> {
+        RenderDocV100::set_capture_option_f32(self, opt, val)
+    }
+
+    fn set_capture_option_u32(&self, opt: CaptureOption, val: u32) -> Result<(), Error> {
+        RenderDocV100::set_capture_option_u32(self, opt, val)
+    }
+
+    fn get_capture_option_f32(&self, opt: CaptureOption) -> Result<f32, Error> {
+        RenderDocV100::get_capture_option_f32(self, opt)
+    }
+
+    fn get_capture_option_u32(&self, opt: CaptureOption) -> Result<u32, Error> {
+        RenderDocV100::get_capture_option_u32(self, opt)
+    }
+
+    fn set_focus_toggle_keys(&self, keys: &[InputButton]) -> Result<(), Error> {
+        RenderDocV100::set_focus_toggle_keys(self, keys)
+    }
+
+    fn set_capture_keys(&self, keys: &[InputButton]) -> Result<(), Error> {
+        RenderDocV100::set_capture_keys(self, keys)
+    }
+
+    fn get_overlay_bits(&self) -> Result<OverlayBits, Error> {
+        RenderDocV100::get_overlay_bits(self)
+    }
+
+    fn mask_overlay_bits(&self, and: OverlayBits, or: OverlayBits) -> Result<(), Error> {
+        RenderDocV100::mask_overlay_bits(self, and, or)
+    }
+
+    fn unload_crash_handler(&self) -> Result<(), Error> {
+        RenderDocV100::unload_crash_handler(self)
+    }
+
+    fn get_capture_file_path_template(&self) -> Result<PathBuf, Error> {
+        RenderDocV100::get_capture_file_path_template(self)
+    }
+
+    fn set_capture_file_path_template(&self, path_template: &Path) -> Result<(), Error> {
+        RenderDocV100::set_capture_file_path_template(self, path_template)
+    }
+
+    fn get_num_captures(&self) -> Result<u32, Error> {
+        RenderDocV100::get_num_captures(self)
+    }
+
+    fn get_capture(&self, index: u32) -> Result<Option<(String, u64)>, Error> {
+        RenderDocV100::get_capture(self, index)
+    }
+
+    fn trigger_capture(&self) -> Result<(), Error> {
+        RenderDocV100::trigger_capture(self)
+    }
+
+    fn is_target_control_connected(&self) -> Result<bool, Error> {
+        RenderDocV100::is_target_control_connected(self)
+    }
+
+    fn set_active_window(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error> {
+        RenderDocV100::set_active_window(self, dev, win)
+    }
+
+    fn start_frame_capture(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error> {
+        RenderDocV100::start_frame_capture(self, dev, win)
+    }
+
+    fn is_frame_capturing(&self) -> Result<bool, Error> {
+        RenderDocV100::is_frame_capturing(self)
+    }
+
+    fn end_frame_capture(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error> {
+        RenderDocV100::end_frame_capture(self, dev, win)
+    }
+
+    fn trigger_multi_frame_capture(&self, num_frames: u32) -> Result<(), Error> {
+        if !self.try_trigger_multi_frame_capture(num_frames)? {
+            RenderDocV100::trigger_capture(self)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use V110;
+
+    #[test]
+    fn handles_are_object_safe() {
+        fn assert_object_safe(_: Option<&dyn RenderDocApi>) {}
+        assert_object_safe(None::<&RenderDoc<V110>>.map(|rd| rd as &dyn RenderDocApi));
+    }
+}
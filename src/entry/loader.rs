@@ -0,0 +1,318 @@
+//! Locating and loading the RenderDoc library.
+
+use std::env;
+use std::os::raw::{c_char, c_void};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[cfg(windows)]
+use kernel32;
+#[cfg(unix)]
+use libc;
+use shared_library::dynamic_library::DynamicLibrary;
+
+use super::version;
+#[cfg(windows)]
+use discovery;
+use error::Error;
+use ApiVersion;
+
+/// Environment variable which, if set, overrides the default location of the
+/// RenderDoc library.
+///
+/// This allows redirecting which RenderDoc build gets loaded without any code
+/// changes, e.g. when reproducing driver-specific capture bugs. It has no effect
+/// on `RenderDoc::with_library_path()`.
+pub const LIBRARY_ENV_VAR: &str = "RENDERDOC_RS_LIBRARY";
+
+fn default_path() -> PathBuf {
+    env::var_os(LIBRARY_ENV_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| get_path().to_path_buf())
+}
+
+/// Returns whether the RenderDoc library can be loaded from the default
+/// location.
+///
+/// Unless RenderDoc is already loaded, the library is opened and closed again
+/// the same way `RenderDoc::new()` opens it, so everything the platform loader
+/// searches counts, e.g. `ld.so.cache` and multiarch directories on Linux.
+/// Opening RenderDoc runs its initialization, so prefer `is_injected()` where
+/// only an already loaded RenderDoc is of interest.
+pub fn is_library_present() -> bool {
+    if is_injected() || RD_LIB.lock().unwrap().is_some() {
+        return true;
+    }
+
+    open_default().is_ok()
+}
+
+/// Returns the full path of the library at the default location, if it can
+/// be found.
+#[cfg(unix)]
+pub(crate) fn find_library() -> Option<PathBuf> {
+    let path = default_path();
+    if path.components().count() > 1 {
+        return Some(path).filter(|path| path.is_file());
+    }
+
+    search_dirs()
+        .into_iter()
+        .chain(fallback_dirs())
+        .map(|dir| dir.join(&path))
+        .find(|path| path.is_file())
+}
+
+/// Returns whether the RenderDoc library is already loaded into this process,
+/// e.g. because the application was launched or injected by RenderDoc.
+///
+/// Honors `RENDERDOC_RS_LIBRARY`, looking for the library it names instead.
+pub fn is_injected() -> bool {
+    find_loaded_symbol().is_some()
+}
+
+/// Directories searched by the platform loader for a bare library name.
+#[cfg(all(unix, not(target_os = "android")))]
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = env::var_os("LD_LIBRARY_PATH")
+        .map(|paths| env::split_paths(&paths).collect())
+        .unwrap_or_default();
+    dirs.extend(
+        ["/lib", "/usr/lib", "/usr/local/lib", "/lib64", "/usr/lib64"]
+            .iter()
+            .map(PathBuf::from),
+    );
+    dirs
+}
+
+/// Directories searched by the platform loader for a bare library name.
+///
+/// On Android, the RenderDoc layer is packaged inside the APK's native library
+/// directory, which is only known at runtime and requires the `android`
+/// feature to be located.
+#[cfg(target_os = "android")]
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = ["/system/lib64", "/system/lib", "/vendor/lib64", "/vendor/lib"]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+    #[cfg(feature = "android")]
+    dirs.extend(app_library_dir());
+    dirs
+}
+
+/// Finds the APK's native library directory from the libraries mapped into
+/// this process, e.g. `/data/app/com.example-1/lib/arm64`.
+#[cfg(all(target_os = "android", feature = "android"))]
+fn app_library_dir() -> Option<PathBuf> {
+    use std::fs;
+
+    let maps = fs::read_to_string("/proc/self/maps").ok()?;
+    maps.lines()
+        .filter_map(|line| line.split_whitespace().nth(5))
+        .filter(|path| path.starts_with("/data/app/") && path.ends_with(".so"))
+        .filter_map(|path| Path::new(path).parent())
+        .find(|dir| dir.parent().map_or(false, |p| p.ends_with("lib")))
+        .map(Path::to_path_buf)
+}
+
+#[cfg(windows)]
+fn get_path() -> &'static Path {
+    Path::new("renderdoc.dll")
+}
+
+#[cfg(all(unix, not(target_os = "android")))]
+fn get_path() -> &'static Path {
+    Path::new("librenderdoc.so")
+}
+
+#[cfg(target_os = "android")]
+fn get_path() -> &'static Path {
+    Path::new("libVkLayer_GLES_RenderDoc.so")
+}
+
+lazy_static! {
+    /// The RenderDoc library loaded from the default location, if any.
+    ///
+    /// Only successful loads are cached, so that a later attempt can still
+    /// succeed if RenderDoc is installed or injected in the meantime.
+    static ref RD_LIB: Mutex<Option<DynamicLibrary>> = Mutex::new(None);
+}
+
+/// Loads the entry point for `V` from the library at the default location,
+/// opening the library first if it isn't loaded yet.
+pub(crate) fn load_default<V: ApiVersion + ?Sized>() -> Result<V::Entry, Error> {
+    let mut lib = RD_LIB.lock().unwrap();
+    if lib.is_none() {
+        *lib = Some(open_default().map_err(Error::Library)?);
+    }
+
+    version::load_from::<V>(lib.as_ref().unwrap())
+}
+
+/// Closes the library loaded from the default location, if any.
+///
+/// The caller must ensure that no entry points loaded from it are still alive.
+pub(crate) fn unload_default() {
+    RD_LIB.lock().unwrap().take();
+}
+
+/// Opens the RenderDoc library from the default location, falling back to
+/// well-known install locations if the platform loader can't find it.
+///
+/// The returned error lists every location that was attempted.
+fn open_default() -> Result<DynamicLibrary, String> {
+    let path = default_path();
+    let mut attempts = Vec::new();
+
+    log_trace!("Loading RenderDoc library {}", path.display());
+    match DynamicLibrary::open(Some(&path)) {
+        Ok(lib) => return Ok(lib),
+        Err(err) => attempts.push(format!("{} ({})", path.display(), err)),
+    }
+
+    if path.components().count() == 1 {
+        for candidate in fallback_dirs().into_iter().map(|dir| dir.join(&path)) {
+            if !candidate.is_file() {
+                attempts.push(format!("{} (not found)", candidate.display()));
+                continue;
+            }
+
+            match DynamicLibrary::open(Some(&candidate)) {
+                Ok(lib) => {
+                    log_debug!("Loaded RenderDoc library from {}", candidate.display());
+                    return Ok(lib);
+                }
+                Err(err) => attempts.push(format!("{} ({})", candidate.display(), err)),
+            }
+        }
+    }
+
+    Err(format!("attempted {}", attempts.join(", ")))
+}
+
+/// Additional directories where RenderDoc is commonly installed, which the
+/// platform loader doesn't search by default.
+///
+/// RenderDoc has no official BSD builds, but self-built copies usually end up
+/// in the same places as on Linux.
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd"))]
+fn fallback_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/usr/lib/renderdoc"),
+        PathBuf::from("/usr/local/lib/renderdoc"),
+    ];
+
+    if let Some(home) = env::var_os("HOME").map(PathBuf::from) {
+        dirs.push(home.join(".local/lib"));
+        dirs.push(home.join(".local/lib/renderdoc"));
+    }
+
+    // Learn the install prefix from a `renderdoccmd` found on the `PATH`.
+    if let Some(paths) = env::var_os("PATH") {
+        for dir in env::split_paths(&paths) {
+            let cmd = dir.join("renderdoccmd");
+            if let Ok(cmd) = cmd.canonicalize() {
+                if let Some(prefix) = cmd.parent().and_then(Path::parent) {
+                    dirs.push(prefix.join("lib"));
+                    dirs.push(prefix.join("lib/renderdoc"));
+                }
+            }
+        }
+    }
+
+    if let Some(preload) = env::var_os("LD_PRELOAD") {
+        let preload = preload.to_string_lossy().into_owned();
+        dirs.extend(
+            preload
+                .split([':', ' '])
+                .filter_map(|lib| Path::new(lib).parent())
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .map(Path::to_path_buf),
+        );
+    }
+
+    dirs
+}
+
+/// Additional directories where RenderDoc is commonly installed, which the
+/// platform loader doesn't search by default.
+#[cfg(windows)]
+fn fallback_dirs() -> Vec<PathBuf> {
+    discovery::install_dirs()
+}
+
+/// Additional directories where RenderDoc is commonly installed, which the
+/// platform loader doesn't search by default.
+#[cfg(not(any(windows, target_os = "linux", target_os = "freebsd", target_os = "openbsd")))]
+fn fallback_dirs() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// Opens the RenderDoc library at `path` and loads the entry point for `V`.
+///
+/// The library is intentionally never closed, since the returned function
+/// pointers must stay valid for the remainder of the process.
+pub(crate) fn load_from_path<V: ApiVersion>(path: &Path) -> Result<V::Entry, Error> {
+    log_trace!("Loading RenderDoc library {}", path.display());
+    let lib = DynamicLibrary::open(Some(path)).map_err(Error::Library)?;
+    let lib: &'static DynamicLibrary = Box::leak(Box::new(lib));
+    version::load_from::<V>(lib)
+}
+
+/// Loads the entry point for `V` from a RenderDoc library which has already
+/// been loaded into this process, e.g. by injection from the RenderDoc UI.
+///
+/// This never loads the library itself.
+pub(crate) fn load_attached<V: ApiVersion>() -> Result<V::Entry, Error> {
+    match find_loaded_symbol() {
+        Some(get_api) => unsafe { version::get_api::<V>(get_api) },
+        None => Err(Error::NotLoaded),
+    }
+}
+
+/// Looks up `RENDERDOC_GetAPI` in the RenderDoc module, if already loaded.
+#[cfg(unix)]
+fn find_loaded_symbol() -> Option<*mut c_void> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let name = CString::new(default_path().as_os_str().as_bytes()).ok()?;
+    unsafe {
+        let handle = libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_NOLOAD);
+        if handle.is_null() {
+            return None;
+        }
+
+        // `RTLD_NOLOAD` still takes a reference on the module. Releasing it
+        // leaves the module loaded by whoever loaded it in the first place.
+        let sym = libc::dlsym(handle, b"RENDERDOC_GetAPI\0".as_ptr() as *const c_char);
+        libc::dlclose(handle);
+        if sym.is_null() {
+            None
+        } else {
+            Some(sym)
+        }
+    }
+}
+
+/// Looks up `RENDERDOC_GetAPI` in the RenderDoc module, if already loaded.
+#[cfg(windows)]
+fn find_loaded_symbol() -> Option<*mut c_void> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let name: Vec<u16> = default_path().as_os_str().encode_wide().chain(Some(0)).collect();
+    unsafe {
+        let module = kernel32::GetModuleHandleW(name.as_ptr());
+        if module.is_null() {
+            return None;
+        }
+
+        let sym = kernel32::GetProcAddress(module, b"RENDERDOC_GetAPI\0".as_ptr() as *const c_char);
+        if sym.is_null() {
+            None
+        } else {
+            Some(sym as *mut c_void)
+        }
+    }
+}
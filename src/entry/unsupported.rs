@@ -0,0 +1,50 @@
+//! Stand-in for the library loader on platforms RenderDoc doesn't support.
+//!
+//! RenderDoc is never present on these platforms, so probes report it as
+//! missing and initialization fails with `Error::PlatformUnsupported`. This
+//! keeps cross-platform applications compiling with a single code path,
+//! including on macOS and for the web on `wasm32-unknown-unknown`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+use error::Error;
+use ApiVersion;
+
+/// Environment variable which, if set, overrides the default location of the
+/// RenderDoc library.
+///
+/// Has no effect on this platform.
+pub const LIBRARY_ENV_VAR: &str = "RENDERDOC_RS_LIBRARY";
+
+/// Returns whether the RenderDoc library could likely be loaded.
+///
+/// Always `false` on this platform.
+pub fn is_library_present() -> bool {
+    false
+}
+
+/// Returns whether the RenderDoc library is already loaded into this process.
+///
+/// Always `false` on this platform.
+pub fn is_injected() -> bool {
+    false
+}
+
+pub(crate) fn find_library() -> Option<PathBuf> {
+    None
+}
+
+pub(crate) fn load_default<V: ApiVersion + ?Sized>() -> Result<V::Entry, Error> {
+    Err(Error::PlatformUnsupported(env::consts::OS))
+}
+
+pub(crate) fn unload_default() {}
+
+pub(crate) fn load_from_path<V: ApiVersion>(_path: &Path) -> Result<V::Entry, Error> {
+    Err(Error::PlatformUnsupported(env::consts::OS))
+}
+
+pub(crate) fn load_attached<V: ApiVersion>() -> Result<V::Entry, Error> {
+    Err(Error::PlatformUnsupported(env::consts::OS))
+}
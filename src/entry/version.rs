@@ -1,6 +1,20 @@
 //! API versioning.
 
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+#[cfg(any(windows, target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd"))]
+use std::os::raw::c_void;
+
+#[cfg(any(windows, target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd"))]
+use shared_library::dynamic_library::DynamicLibrary;
+
 use entry::{EntryV100, EntryV110};
+use error::Error;
+#[cfg(any(windows, target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd"))]
+use sys::GetApiFn;
+use sys::raw;
 
 /// Available versions of the RenderDoc API.
 #[repr(u32)]
@@ -18,13 +32,116 @@ pub enum Version {
     V111 = 10101,
 }
 
-/// Initializes a new instance of the RenderDoc API.
+// The version numbers have to match `renderdoc_app.h`.
+const _: () = assert!(Version::V100 as u32 == raw::eRENDERDOC_API_Version_1_0_0);
+const _: () = assert!(Version::V101 as u32 == raw::eRENDERDOC_API_Version_1_0_1);
+const _: () = assert!(Version::V102 as u32 == raw::eRENDERDOC_API_Version_1_0_2);
+const _: () = assert!(Version::V110 as u32 == raw::eRENDERDOC_API_Version_1_1_0);
+const _: () = assert!(Version::V111 as u32 == raw::eRENDERDOC_API_Version_1_1_1);
+
+impl Version {
+    /// Returns the version number this variant requests.
+    pub fn number(self) -> ApiVersionNumber {
+        ApiVersionNumber::try_from(self as u32).unwrap()
+    }
+}
+
+/// A RenderDoc API version number, as returned by `get_api_version()`.
 ///
-/// # Safety
+/// Version numbers are ordered semantically and compare against tuples, and
+/// can be decoded from the `10000 * major + 100 * minor + patch` encoding
+/// used by `RENDERDOC_GetAPI`.
+///
+/// # Examples
 ///
-/// This function is not thread-safe and should not be called on multiple
-/// threads at once.
-type GetApiFn<T> = unsafe extern "C" fn(ver: Version, out: *mut *mut T) -> i32;
+/// ```rust
+/// # use renderdoc::ApiVersionNumber;
+/// use std::convert::TryFrom;
+///
+/// let version = ApiVersionNumber::try_from(10401).unwrap();
+/// assert_eq!(version.to_string(), "1.4.1");
+/// assert!(version >= (1, 4, 0));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ApiVersionNumber {
+    /// Major version, incremented on breaking changes.
+    pub major: u32,
+    /// Minor version, incremented when functionality is added.
+    pub minor: u32,
+    /// Patch version.
+    pub patch: u32,
+}
+
+impl ApiVersionNumber {
+    /// Creates a version number from its components.
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        ApiVersionNumber { major, minor, patch }
+    }
+}
+
+impl fmt::Display for ApiVersionNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl From<(u32, u32, u32)> for ApiVersionNumber {
+    fn from((major, minor, patch): (u32, u32, u32)) -> Self {
+        ApiVersionNumber::new(major, minor, patch)
+    }
+}
+
+impl From<ApiVersionNumber> for (u32, u32, u32) {
+    fn from(version: ApiVersionNumber) -> Self {
+        (version.major, version.minor, version.patch)
+    }
+}
+
+impl From<Version> for ApiVersionNumber {
+    fn from(version: Version) -> Self {
+        version.number()
+    }
+}
+
+impl TryFrom<u32> for ApiVersionNumber {
+    type Error = InvalidVersionNumber;
+
+    /// Decodes the `10000 * major + 100 * minor + patch` encoding.
+    ///
+    /// Fails for values with a major version of zero, which RenderDoc never
+    /// uses.
+    fn try_from(raw: u32) -> Result<Self, Self::Error> {
+        if raw < 10000 {
+            return Err(InvalidVersionNumber(raw));
+        }
+
+        Ok(ApiVersionNumber::new(raw / 10000, raw / 100 % 100, raw % 100))
+    }
+}
+
+impl PartialEq<(u32, u32, u32)> for ApiVersionNumber {
+    fn eq(&self, other: &(u32, u32, u32)) -> bool {
+        *self == ApiVersionNumber::from(*other)
+    }
+}
+
+impl PartialOrd<(u32, u32, u32)> for ApiVersionNumber {
+    fn partial_cmp(&self, other: &(u32, u32, u32)) -> Option<Ordering> {
+        self.partial_cmp(&ApiVersionNumber::from(*other))
+    }
+}
+
+/// Error returned when decoding an invalid encoded API version number.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct InvalidVersionNumber(pub u32);
+
+impl fmt::Display for InvalidVersionNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid RenderDoc API version number: {}", self.0)
+    }
+}
+
+impl error::Error for InvalidVersionNumber {}
 
 /// Entry point into the RenderDoc API.
 pub trait ApiVersion {
@@ -32,7 +149,11 @@ pub trait ApiVersion {
     const VERSION: Version;
 
     /// Entry point struct.
-    type Entry: Clone;
+    ///
+    /// Every entry point begins with the 1.0.0 function table, which is what
+    /// allows the version traits to be implemented once for all handles. Entry
+    /// points only contain function pointers, so they are `Send` and `Sync`.
+    type Entry: AsRef<EntryV100> + Clone + fmt::Debug + Send + Sync + 'static;
 
     /// Initializes a new instance of the RenderDoc API.
     ///
@@ -40,43 +161,154 @@ pub trait ApiVersion {
     ///
     /// This function is not thread-safe and should not be called on multiple
     /// threads at once.
-    fn load() -> Result<Self::Entry, String> {
-        use std::{mem, ptr};
+    fn load() -> Result<Self::Entry, Error> {
+        super::loader::load_default::<Self>()
+    }
+
+    /// Returns the 1.1.0 function table, if the entry point provides it.
+    fn entry_v110(_entry: &Self::Entry) -> Option<&EntryV110> {
+        None
+    }
+}
+
+/// Retrieves the entry point for version `V` from an already opened library.
+#[cfg(any(windows, target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd"))]
+pub(crate) fn load_from<V: ApiVersion + ?Sized>(lib: &DynamicLibrary) -> Result<V::Entry, Error> {
+    unsafe {
+        let f = lib.symbol::<c_void>("RENDERDOC_GetAPI").map_err(Error::Symbol)?;
+        get_api::<V>(f)
+    }
+}
+
+/// Calls `RENDERDOC_GetAPI`, given its address, to retrieve the entry point
+/// for version `V`.
+///
+/// # Safety
+///
+/// `get_api` must point to the `RENDERDOC_GetAPI` function.
+#[cfg(any(windows, target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd"))]
+pub(crate) unsafe fn get_api<V: ApiVersion + ?Sized>(get_api: *mut c_void) -> Result<V::Entry, Error> {
+    use std::{mem, ptr};
+
+    let get_api = mem::transmute::<*mut c_void, GetApiFn>(get_api);
+    let mut obj = ptr::null_mut::<V::Entry>();
+    match get_api(V::VERSION as u32, &mut obj as *mut *mut V::Entry as *mut *mut c_void) {
+        1 => {
+            validate::<V>(obj)?;
+            Ok(ptr::read(obj))
+        }
+        _ => Err(Error::IncompatibleVersion(V::VERSION)),
+    }
+}
+
+/// Checks the function table returned by `RENDERDOC_GetAPI` before it is used.
+///
+/// Every function pointer has to be set, and the library has to report at
+/// least the requested version. Otherwise, the table doesn't have the layout
+/// this crate expects, and calling through it would jump to arbitrary
+/// addresses.
+///
+/// # Safety
+///
+/// `entry` must be null or point to at least `size_of::<V::Entry>()` readable
+/// bytes.
+#[cfg(any(windows, target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd"))]
+unsafe fn validate<V: ApiVersion + ?Sized>(entry: *const V::Entry) -> Result<(), Error> {
+    use std::{mem, slice};
 
-        let api = unsafe {
-            let get_api = match *super::RD_LIB {
-                Ok(ref lib) => {
-                    let f = lib.symbol::<()>("RENDERDOC_GetAPI")?;
-                    Ok(mem::transmute::<_, GetApiFn<Self::Entry>>(f))
-                }
-                Err(ref err) => Err(err.to_string()),
-            }?;
+    let requested = V::VERSION.number();
+    if entry.is_null() {
+        let reason = format!("no function table returned for version {}", requested);
+        return Err(Error::InvalidEntryPoint(reason));
+    }
 
-            let mut obj = ptr::null_mut();
-            match get_api(Self::VERSION, &mut obj) {
-                1 => ptr::read(obj),
-                _ => Err("Compatible API version not available.")?,
-            }
-        };
+    // The table is read as raw pointers first, as a null function pointer is
+    // invalid in Rust.
+    let len = mem::size_of::<V::Entry>() / mem::size_of::<*const c_void>();
+    let fns = slice::from_raw_parts(entry as *const *const c_void, len);
+    if let Some(index) = fns.iter().position(|f| f.is_null()) {
+        let reason = format!("function {} of the {} table is null", index, requested);
+        return Err(Error::InvalidEntryPoint(reason));
+    }
 
-        Ok(api)
+    // Every table starts with the 1.0.0 functions.
+    let table = &*(entry as *const EntryV100);
+    let (mut major, mut minor, mut patch) = (0, 0, 0);
+    (table.get_api_version)(&mut major, &mut minor, &mut patch);
+    let reported = ApiVersionNumber::new(major as u32, minor as u32, patch as u32);
+    if reported < requested {
+        let reason = format!("reports version {}, older than the requested {}", reported, requested);
+        return Err(Error::InvalidEntryPoint(reason));
     }
+
+    Ok(())
 }
 
-/// Requests a minimum version number of 1.0.0.
-pub enum V100 {}
+api_version! {
+    /// Requests a minimum version number of 1.0.0.
+    V100: V100 => EntryV100
+}
 
-impl ApiVersion for V100 {
-    const VERSION: Version = Version::V100;
+api_version! {
+    /// Requests a minimum version number of 1.1.0.
+    V110: V110 => EntryV110 + EntryV110
+}
 
-    type Entry = EntryV100;
+api_version! {
+    /// Requests a minimum version number of 1.1.1.
+    ///
+    /// Version 1.1.1 only renamed functions, so its handles provide the same
+    /// functionality as `V110` handles, but fail to load with older RenderDoc
+    /// builds.
+    V111: V111 => EntryV110 + EntryV110
 }
 
-/// Requests a minimum version number of 1.1.0.
-pub enum V110 {}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl ApiVersion for V110 {
-    const VERSION: Version = Version::V110;
+    #[test]
+    fn version_numbers() {
+        assert_eq!(Version::V111.number(), (1, 1, 1));
+        assert_eq!(ApiVersionNumber::try_from(10203).unwrap(), (1, 2, 3));
+        assert_eq!(ApiVersionNumber::try_from(999), Err(InvalidVersionNumber(999)));
 
-    type Entry = EntryV110;
+        let version = ApiVersionNumber::new(1, 10, 0);
+        assert!(version > (1, 9, 9));
+        assert!(version < (2, 0, 0));
+        assert_eq!(version.to_string(), "1.10.0");
+    }
+
+    #[cfg(any(windows, target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd"))]
+    #[test]
+    fn validate_function_table() {
+        use std::os::raw::c_int;
+        use std::{mem, ptr};
+
+        unsafe extern "C" fn version_1_0_2(major: *mut c_int, minor: *mut c_int, patch: *mut c_int) {
+            *major = 1;
+            *minor = 0;
+            *patch = 2;
+        }
+
+        let mut table = [version_1_0_2 as *const c_void; 23];
+        let entry = table.as_ptr() as *const EntryV110;
+        unsafe {
+            assert!(validate::<V100>(entry as *const EntryV100).is_ok());
+            assert_eq!(
+                validate::<V110>(entry),
+                Err(Error::InvalidEntryPoint(
+                    "reports version 1.0.2, older than the requested 1.1.0".to_string()
+                ))
+            );
+
+            table[3] = ptr::null();
+            let entry = table.as_ptr() as *const EntryV100;
+            assert_eq!(
+                validate::<V100>(entry),
+                Err(Error::InvalidEntryPoint("function 3 of the 1.0.0 table is null".to_string()))
+            );
+        }
+        assert_eq!(mem::size_of_val(&table), mem::size_of::<EntryV110>());
+    }
 }
@@ -0,0 +1,81 @@
+//! Error handling.
+
+use std::error;
+use std::fmt;
+
+use devices::DeviceKey;
+use entry::version::Version;
+use frame_capture::CaptureOverlap;
+
+/// Errors that can occur while initializing the RenderDoc API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The RenderDoc shared library could not be loaded.
+    Library(String),
+    /// The RenderDoc shared library does not export a required symbol.
+    Symbol(String),
+    /// The RenderDoc library is not loaded in this process.
+    ///
+    /// Only returned when attaching to an already injected RenderDoc.
+    NotLoaded,
+    /// RenderDoc does not provide an API compatible with the requested version.
+    IncompatibleVersion(Version),
+    /// RenderDoc returned a function table which doesn't match the requested
+    /// version, e.g. because of an unusual RenderDoc build.
+    InvalidEntryPoint(String),
+    /// A `RENDERDOC_RS_*` environment variable overriding capture settings
+    /// holds an invalid value.
+    InvalidEnvironment(String),
+    /// The RenderDoc API has already been initialized in this process.
+    ///
+    /// `RENDERDOC_GetAPI` should only be used once per process. Clone the
+    /// existing handle, or use `renderdoc::instance()`, instead.
+    AlreadyInitialized,
+    /// The RenderDoc library can't be unloaded while handles are still in use.
+    InUse,
+    /// RenderDoc does not support the platform this crate was compiled for,
+    /// e.g. macOS or the web.
+    ///
+    /// Holds the name of the operating system, as in `std::env::consts::OS`.
+    PlatformUnsupported(&'static str),
+    /// RenderDoc has been shut down and can't be used for the remainder of
+    /// the process.
+    ShutDown,
+    /// A `DeviceRegistry` has no device under the given key.
+    UnknownDevice(DeviceKey),
+    /// A guarded frame capture overlaps with one already in progress.
+    CaptureOverlap(CaptureOverlap),
+    /// The replay UI could not be launched, or RenderDoc didn't report the
+    /// process ID of the launched UI.
+    ReplayUiLaunch(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Library(ref err) => write!(f, "Unable to load RenderDoc library: {}", err),
+            Error::Symbol(ref err) => write!(f, "Unable to find RenderDoc symbol: {}", err),
+            Error::NotLoaded => write!(f, "RenderDoc is not loaded in this process"),
+            Error::IncompatibleVersion(ref ver) => {
+                write!(f, "Compatible API version not available: {:?}", ver)
+            }
+            Error::InvalidEntryPoint(ref err) => write!(f, "Invalid RenderDoc entry point: {}", err),
+            Error::InvalidEnvironment(ref err) => write!(f, "Invalid environment variable: {}", err),
+            Error::AlreadyInitialized => write!(f, "RenderDoc API was already initialized"),
+            Error::InUse => write!(f, "RenderDoc handles are still in use"),
+            Error::PlatformUnsupported(os) => write!(f, "RenderDoc does not support this platform ({})", os),
+            Error::ShutDown => write!(f, "RenderDoc has been shut down"),
+            Error::UnknownDevice(ref key) => write!(f, "No device registered as {}", key),
+            Error::CaptureOverlap(ref err) => err.fmt(f),
+            Error::ReplayUiLaunch(ref err) => write!(f, "Unable to launch the replay UI: {}", err),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<Error> for String {
+    fn from(err: Error) -> String {
+        err.to_string()
+    }
+}
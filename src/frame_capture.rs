@@ -0,0 +1,143 @@
+//! Frame captures ending when a guard is dropped.
+
+use std::error;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+use api::RenderDocV100;
+use {Error, RenderDoc, WindowBinding, V100};
+
+/// Device and window addresses of the captures held by guards.
+type Target = (usize, usize);
+
+lazy_static! {
+    static ref ACTIVE: Mutex<Vec<Target>> = Mutex::new(Vec::new());
+}
+
+fn active() -> MutexGuard<'static, Vec<Target>> {
+    ACTIVE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn target(binding: &WindowBinding) -> Target {
+    (*binding.device as usize, binding.window.as_raw() as usize)
+}
+
+/// Returns whether captures of `a` and `b` would record the same device and
+/// window, where null pointers match anything.
+fn overlaps(a: Target, b: Target) -> bool {
+    let matches = |a: usize, b: usize| a == 0 || b == 0 || a == b;
+    matches(a.0, b.0) && matches(a.1, b.1)
+}
+
+/// Error returned when starting a guarded frame capture which overlaps with
+/// one already in progress.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CaptureOverlap {
+    /// The device and window of the rejected capture.
+    pub requested: WindowBinding,
+    /// The device and window of the capture in progress.
+    pub active: WindowBinding,
+}
+
+impl fmt::Display for CaptureOverlap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Frame capture of {:?} overlaps with the capture of {:?} in progress",
+            self.requested, self.active
+        )
+    }
+}
+
+impl error::Error for CaptureOverlap {}
+
+// Like the guards, the error only reports the addresses and never dereferences
+// them, so it can travel with `Error` to other threads.
+unsafe impl Send for CaptureOverlap {}
+unsafe impl Sync for CaptureOverlap {}
+
+impl From<CaptureOverlap> for Error {
+    fn from(err: CaptureOverlap) -> Error {
+        Error::CaptureOverlap(err)
+    }
+}
+
+/// A frame capture in progress, ending when dropped.
+///
+/// Created with `RenderDoc::frame_capture()`. Guards for distinct devices or
+/// windows can be held at the same time, e.g. to capture several viewports of
+/// an editor which present independently.
+#[must_use = "the frame capture ends as soon as the guard is dropped"]
+pub struct FrameCaptureGuard {
+    rd: RenderDoc<V100>,
+    binding: WindowBinding,
+}
+
+// The device and window are only handed to RenderDoc and never dereferenced,
+// so captures may end on another thread than they started on.
+unsafe impl Send for FrameCaptureGuard {}
+
+impl FrameCaptureGuard {
+    pub(crate) fn start(rd: RenderDoc<V100>, binding: WindowBinding) -> Result<Self, Error> {
+        rd.ensure_usable()?;
+        {
+            let mut active = active();
+            let requested = target(&binding);
+            if let Some(&(device, window)) = active.iter().find(|&&other| overlaps(requested, other)) {
+                return Err(CaptureOverlap {
+                    requested: binding,
+                    active: WindowBinding::new(device as *const _, window as *const _),
+                }
+                .into());
+            }
+            active.push(requested);
+        }
+
+        // The guard is in place before starting, so the target is released
+        // again if RenderDoc has been shut down in the meantime.
+        let guard = FrameCaptureGuard { rd, binding };
+        guard.rd.start_frame_capture(guard.binding.device.clone(), guard.binding.window)?;
+        Ok(guard)
+    }
+
+    /// Returns the device and window being captured.
+    pub fn binding(&self) -> &WindowBinding {
+        &self.binding
+    }
+
+    /// Ends the frame capture, same as dropping the guard.
+    pub fn end(self) {}
+}
+
+impl fmt::Debug for FrameCaptureGuard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FrameCaptureGuard").field("binding", &self.binding).finish()
+    }
+}
+
+impl Drop for FrameCaptureGuard {
+    fn drop(&mut self) {
+        let _ = self.rd.end_frame_capture(self.binding.device.clone(), self.binding.window);
+        let mut active = active();
+        let target = target(&self.binding);
+        if let Some(index) = active.iter().position(|&other| other == target) {
+            active.swap_remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_overlaps() {
+        assert!(overlaps((1, 2), (1, 2)));
+        assert!(!overlaps((1, 2), (1, 3)));
+        assert!(!overlaps((1, 2), (4, 2)));
+        assert!(overlaps((0, 2), (1, 2)));
+        assert!(overlaps((1, 2), (1, 0)));
+        assert!(overlaps((0, 0), (5, 6)));
+        assert!(!overlaps((0, 2), (1, 3)));
+    }
+}
@@ -0,0 +1,190 @@
+//! Counting presented frames.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+type FrameHook = Box<dyn FnMut(u64) -> bool + Send>;
+
+/// Counts the frames presented by the application.
+///
+/// The application calls `tick()` once per present. RenderDoc numbers frames
+/// by presents as well, so a clock created at startup reports the same index
+/// RenderDoc shows in its overlay and capture list. Use
+/// `FrameClock::starting_at()` when the clock is created later on.
+///
+/// Hooks registered with `on_frame()` run on every tick, which is where
+/// per-frame capture logic such as scheduled captures lives. The clock can be
+/// shared between threads; ticks are expected to come from one of them.
+///
+/// Captures scheduled through the clock are labeled with why they were
+/// made, which `capture_label()` returns while the captured frame is being
+/// recorded, e.g. to mark it with debug groups.
+///
+/// # Examples
+///
+/// ```rust
+/// # use renderdoc::FrameClock;
+/// let clock = FrameClock::new();
+/// clock.on_frame(|frame| {
+///     println!("presented frame {}", frame);
+///     frame < 2
+/// });
+///
+/// clock.tick();
+/// assert_eq!(clock.frame(), 1);
+/// assert_eq!(clock.expand_template("captures/frame{frame}"), "captures/frame1");
+/// ```
+pub struct FrameClock {
+    frame: AtomicU64,
+    hooks: Mutex<Vec<FrameHook>>,
+    capture_labels: Mutex<BTreeMap<u64, String>>,
+}
+
+impl FrameClock {
+    /// Creates a clock at frame `0`.
+    pub fn new() -> Self {
+        FrameClock::starting_at(0)
+    }
+
+    /// Creates a clock at the given frame index.
+    pub fn starting_at(frame: u64) -> Self {
+        FrameClock {
+            frame: AtomicU64::new(frame),
+            hooks: Mutex::new(Vec::new()),
+            capture_labels: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the index of the current frame.
+    pub fn frame(&self) -> u64 {
+        self.frame.load(Ordering::SeqCst)
+    }
+
+    /// Advances to the next frame and runs all hooks, returning the new frame
+    /// index.
+    pub fn tick(&self) -> u64 {
+        let frame = self.frame.fetch_add(1, Ordering::SeqCst) + 1;
+        {
+            let mut labels = self.lock_capture_labels();
+            *labels = labels.split_off(&frame);
+        }
+
+        // Hooks run without holding the lock, so they may register new hooks.
+        let mut hooks = mem::take(&mut *self.lock_hooks());
+        hooks.retain_mut(|hook| hook(frame));
+
+        let mut registered = self.lock_hooks();
+        hooks.append(&mut registered);
+        *registered = hooks;
+
+        frame
+    }
+
+    /// Registers `hook` to run on every tick with the new frame index.
+    ///
+    /// The hook is removed once it returns `false`.
+    pub fn on_frame<F>(&self, hook: F)
+    where
+        F: FnMut(u64) -> bool + Send + 'static,
+    {
+        self.lock_hooks().push(Box::new(hook));
+    }
+
+    /// Replaces every `{frame}` placeholder in `template` with the current
+    /// frame index.
+    ///
+    /// Intended for capture path templates, so captures are named after the
+    /// frame they were requested in.
+    pub fn expand_template(&self, template: &str) -> String {
+        template.replace("{frame}", &self.frame().to_string())
+    }
+
+    /// Returns why the current frame is captured, if the capture was
+    /// scheduled through this clock.
+    ///
+    /// This is the label of the frame being recorded, i.e. of the frame
+    /// following the last `tick()`. RenderDoc starts a triggered capture at
+    /// the next present, so captures triggered during a frame label the frame
+    /// after it.
+    pub fn capture_label(&self) -> Option<String> {
+        self.lock_capture_labels().get(&self.frame()).cloned()
+    }
+
+    /// Labels the capture of `frame`, replacing any previous label.
+    pub(crate) fn label_capture(&self, frame: u64, label: String) {
+        self.lock_capture_labels().insert(frame, label);
+    }
+
+    fn lock_capture_labels(&self) -> MutexGuard<'_, BTreeMap<u64, String>> {
+        self.capture_labels.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_hooks(&self) -> MutexGuard<'_, Vec<FrameHook>> {
+        // A panicking hook shouldn't stop the clock for everyone else.
+        self.hooks.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        FrameClock::new()
+    }
+}
+
+impl fmt::Debug for FrameClock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FrameClock")
+            .field("frame", &self.frame())
+            .field("hooks", &self.lock_hooks().len())
+            .field("capture_labels", &*self.lock_capture_labels())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn hooks_run_until_removed() {
+        let clock = Arc::new(FrameClock::starting_at(10));
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let frames = seen.clone();
+        let inner = clock.clone();
+        clock.on_frame(move |frame| {
+            frames.lock().unwrap().push(frame);
+            if frame == 11 {
+                // Registering from within a hook must not deadlock.
+                inner.on_frame(|_| false);
+            }
+            frame < 13
+        });
+
+        for _ in 0..5 {
+            clock.tick();
+        }
+        assert_eq!(clock.frame(), 15);
+        assert_eq!(*seen.lock().unwrap(), vec![11, 12, 13]);
+        assert_eq!(clock.lock_hooks().len(), 0);
+    }
+
+    #[test]
+    fn capture_labels_expire() {
+        let clock = FrameClock::new();
+        clock.label_capture(0, "now".to_string());
+        clock.label_capture(2, "later".to_string());
+        assert_eq!(clock.capture_label().as_deref(), Some("now"));
+
+        clock.tick();
+        assert_eq!(clock.capture_label(), None);
+        clock.tick();
+        assert_eq!(clock.capture_label().as_deref(), Some("later"));
+        clock.tick();
+        assert!(clock.lock_capture_labels().is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+//! Process-wide RenderDoc instance.
+
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use entry::{loader, EntryV110, ShutdownFn};
+use error::Error;
+use init_config;
+use {ApiVersion, RenderDoc, V110};
+
+/// Shared, internally synchronized handle to the process-wide RenderDoc API.
+///
+/// The mutex serializes access to the otherwise unsynchronized RenderDoc state
+/// between the subsystems of an application.
+pub type RenderDocHandle = Mutex<RenderDoc<V110>>;
+
+/// Set once `RENDERDOC_GetAPI` has been successfully called in this process.
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Set once a `'static` reference to the instance has been handed out.
+static SHARED: AtomicBool = AtomicBool::new(false);
+
+/// Set once RenderDoc has been shut down, after which it must not be used.
+static POISONED: AtomicBool = AtomicBool::new(false);
+
+/// Number of live `RenderDoc` handles, including the global instance.
+static LIVE_HANDLES: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref INSTANCE: Mutex<Option<&'static RenderDocHandle>> = Mutex::new(None);
+}
+
+/// Returns the process-wide instance of the RenderDoc API.
+///
+/// If a handle with version 1.1.0 or newer was already created with
+/// `RenderDoc::new()`, that handle is returned. Otherwise, the API is loaded on
+/// first access and lives for the remainder of the program. Returns `None` if
+/// RenderDoc could not be loaded, if it does not provide at least API version
+/// 1.1.0, or if the API was already initialized with an older version.
+///
+/// # Examples
+///
+/// ```rust
+/// # use renderdoc::prelude::*;
+/// if let Some(rd) = renderdoc::instance() {
+///     rd.lock().unwrap().trigger_capture().unwrap();
+/// }
+/// ```
+pub fn instance() -> Option<&'static RenderDocHandle> {
+    if is_poisoned() {
+        return None;
+    }
+
+    let mut slot = INSTANCE.lock().unwrap();
+    if slot.is_none() && claim().is_ok() {
+        match V110::load() {
+            Ok(entry) => *slot = Some(leak(RenderDoc(Arc::new(entry), Lease::acquire()))),
+            Err(_) => release(),
+        }
+    }
+
+    if slot.is_some() {
+        SHARED.store(true, Ordering::SeqCst);
+    }
+
+    *slot
+}
+
+/// Unloads the RenderDoc library, so that it can later be loaded again with
+/// `RenderDoc::new()`.
+///
+/// This fails with `Error::InUse` while any `RenderDoc` handle is still alive,
+/// or once `instance()` has handed out its `'static` handle, since those would
+/// otherwise point into the unloaded library. Libraries opened through
+/// `RenderDoc::with_library_path()` or `RenderDoc::attach()` are never
+/// unloaded, but the API may still be initialized again afterwards.
+///
+/// # Safety
+///
+/// RenderDoc hooks into the graphics APIs as soon as it is loaded, and these
+/// hooks are not removed by unloading the library. This inherits the
+/// constraints of `EarlyShutdown`: it is only sound to call this immediately after
+/// the library was loaded, before any graphics API work happens, or after
+/// RenderDoc has been shut down in that window. Otherwise, calls into the
+/// graphics APIs will jump into unloaded code.
+pub unsafe fn unload() -> Result<(), Error> {
+    let mut slot = INSTANCE.lock().unwrap();
+    if SHARED.load(Ordering::SeqCst) {
+        return Err(Error::InUse);
+    }
+
+    let expected = if slot.is_some() { 1 } else { 0 };
+    if LIVE_HANDLES.load(Ordering::SeqCst) != expected {
+        return Err(Error::InUse);
+    }
+
+    if let Some(handle) = slot.take() {
+        drop(Box::from_raw(handle as *const RenderDocHandle as *mut RenderDocHandle));
+    }
+
+    loader::unload_default();
+    init_config::reset();
+    release();
+    Ok(())
+}
+
+/// Loads the API as `V` with the given loader, failing if it has been
+/// initialized before.
+pub(crate) fn init<V, F>(load: F) -> Result<RenderDoc<V>, Error>
+where
+    V: ApiVersion,
+    F: FnOnce() -> Result<V::Entry, Error>,
+{
+    claim()?;
+    let rd: RenderDoc<V> = match load() {
+        Ok(entry) => RenderDoc(Arc::new(entry), Lease::acquire()),
+        Err(err) => {
+            log_debug!("Failed to load the RenderDoc API: {}", err);
+            release();
+            return Err(err);
+        }
+    };
+    log_debug!("Loaded RenderDoc API {}", V::VERSION.number());
+
+    let any: Arc<dyn Any + Send + Sync> = rd.0.clone();
+    if let Ok(entry) = any.downcast::<EntryV110>() {
+        let mut slot = INSTANCE.lock().unwrap();
+        *slot = Some(leak(RenderDoc(entry, Lease::acquire())));
+    }
+
+    Ok(rd)
+}
+
+/// Permission to shut RenderDoc down right after loading it.
+///
+/// Shutting down only works correctly _immediately_ after the library is
+/// loaded, before any API or graphics work happens. This token is therefore
+/// only handed out together with a freshly loaded handle by
+/// `RenderDoc::new_with_shutdown()`, and not by `instance()`, `attach()`, or
+/// for handles of an injected RenderDoc.
+///
+/// After shutting down, every remaining handle is poisoned: initialization
+/// fails with `Error::ShutDown`, `instance()` returns `None`, and calls through
+/// cloned handles fail with `Error::ShutDown` instead of calling into
+/// RenderDoc.
+#[derive(Debug)]
+pub struct EarlyShutdown(ShutdownFn);
+
+impl EarlyShutdown {
+    pub(crate) fn new(shutdown: ShutdownFn) -> EarlyShutdown {
+        EarlyShutdown(shutdown)
+    }
+
+    /// Removes RenderDoc's hooks and shuts it down, consuming `rd`.
+    ///
+    /// # Safety
+    ///
+    /// No graphics API work may have happened since RenderDoc was loaded, and
+    /// no RenderDoc API functions may have been called.
+    pub unsafe fn shutdown<V: ApiVersion>(self, rd: RenderDoc<V>) {
+        drop(rd);
+        poison();
+        (self.0)();
+    }
+
+    /// Removes RenderDoc's hooks and shuts it down, as `shutdown()`.
+    ///
+    /// Newer RenderDoc versions call this function `RemoveHooks`.
+    ///
+    /// # Safety
+    ///
+    /// The same restrictions as for `shutdown()` apply.
+    pub unsafe fn remove_hooks<V: ApiVersion>(self, rd: RenderDoc<V>) {
+        self.shutdown(rd)
+    }
+}
+
+/// Keeps track of a live `RenderDoc` handle.
+#[derive(Debug)]
+pub(crate) struct Lease(());
+
+impl Lease {
+    pub(crate) fn acquire() -> Lease {
+        LIVE_HANDLES.fetch_add(1, Ordering::SeqCst);
+        Lease(())
+    }
+}
+
+impl Clone for Lease {
+    fn clone(&self) -> Lease {
+        Lease::acquire()
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        LIVE_HANDLES.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Marks RenderDoc as shut down for the remainder of the process.
+pub(crate) fn poison() {
+    POISONED.store(true, Ordering::SeqCst);
+}
+
+/// Returns whether RenderDoc has been shut down.
+pub(crate) fn is_poisoned() -> bool {
+    POISONED.load(Ordering::SeqCst)
+}
+
+/// Fails with `Error::ShutDown` if RenderDoc has been shut down.
+pub(crate) fn check_usable() -> Result<(), Error> {
+    if is_poisoned() {
+        Err(Error::ShutDown)
+    } else {
+        Ok(())
+    }
+}
+
+fn claim() -> Result<(), Error> {
+    if is_poisoned() {
+        Err(Error::ShutDown)
+    } else if INITIALIZED.swap(true, Ordering::SeqCst) {
+        Err(Error::AlreadyInitialized)
+    } else {
+        Ok(())
+    }
+}
+
+fn release() {
+    INITIALIZED.store(false, Ordering::SeqCst);
+}
+
+fn leak(rd: RenderDoc<V110>) -> &'static RenderDocHandle {
+    Box::leak(Box::new(Mutex::new(rd)))
+}
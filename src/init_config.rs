@@ -0,0 +1,85 @@
+//! Settings which have to be applied before graphics APIs initialize.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use api::RenderDocV100;
+use settings::Settings;
+use {CaptureOption, Error, InputButton};
+
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+static DEVICE_SEEN: AtomicBool = AtomicBool::new(false);
+
+/// Settings applied once by `RenderDoc::init()`, before any graphics device is
+/// created.
+///
+/// Several capture options, e.g. `CaptureOption::ApiValidation` or
+/// `CaptureOption::CaptureCallstacks`, are read by RenderDoc when a graphics
+/// API initializes, and only take effect for devices created afterwards.
+/// Gathering them here makes the point at which they are applied explicit.
+///
+/// Fields left at their defaults leave RenderDoc's settings untouched.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use renderdoc::{CaptureOption, InitConfig, InputButton, RenderDoc, V110};
+/// # fn init() -> Result<(), renderdoc::Error> {
+/// let rd: RenderDoc<V110> = RenderDoc::new()?;
+/// rd.init(InitConfig {
+///     options: vec![(CaptureOption::ApiValidation, 1)],
+///     unload_crash_handler: true,
+///     capture_keys: Some(vec![InputButton::F11]),
+///     capture_path_template: Some("captures/game".into()),
+///     ..InitConfig::default()
+/// })?;
+/// // Create the graphics device afterwards...
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InitConfig {
+    /// Capture options, set in order.
+    pub options: Vec<(CaptureOption, u32)>,
+    /// Whether to unload RenderDoc's crash handler.
+    pub unload_crash_handler: bool,
+    /// Keys which capture the next frame.
+    pub capture_keys: Option<Vec<InputButton>>,
+    /// Keys which toggle focus between multiple windows.
+    pub focus_toggle_keys: Option<Vec<InputButton>>,
+    /// Template for the paths of capture files.
+    pub capture_path_template: Option<PathBuf>,
+}
+
+impl InitConfig {
+    /// Applies the settings to `rd`, unloading the crash handler last.
+    pub(crate) fn apply<R: RenderDocV100>(&self, rd: &R) -> Result<(), Error> {
+        let first = !INITIALIZED.swap(true, Ordering::SeqCst);
+        debug_assert!(first, "RenderDoc::init() must only be called once");
+        let before_devices = !DEVICE_SEEN.load(Ordering::SeqCst);
+        debug_assert!(before_devices, "RenderDoc::init() must be called before creating graphics devices");
+
+        let mut settings = Settings {
+            capture_keys: self.capture_keys.as_deref(),
+            focus_toggle_keys: self.focus_toggle_keys.as_deref(),
+            capture_path_template: self.capture_path_template.as_deref(),
+            unload_crash_handler: self.unload_crash_handler,
+            ..Settings::default()
+        };
+        settings.extend_u32_options(&self.options);
+        settings.apply(rd)
+    }
+}
+
+/// Forgets about earlier calls of `RenderDoc::init()` and devices handed to
+/// RenderDoc, once the library has been unloaded and may be loaded again.
+pub(crate) fn reset() {
+    INITIALIZED.store(false, Ordering::SeqCst);
+    DEVICE_SEEN.store(false, Ordering::SeqCst);
+}
+
+/// Called whenever a device is handed to RenderDoc, which means a graphics API
+/// has been initialized.
+pub(crate) fn device_seen() {
+    DEVICE_SEEN.store(true, Ordering::SeqCst);
+}
@@ -0,0 +1,69 @@
+//! Reporting capture activity to instrumentation crates.
+//!
+//! With the `tracing` feature, frame captures are wrapped in a
+//! `renderdoc.frame_capture` span from `start_frame_capture()` to
+//! `end_frame_capture()`, and triggers and completed captures are emitted as
+//! events, all with the `renderdoc` target.
+//!
+//! With the `profiling` feature, the calls triggering, starting, and ending
+//! captures show up as `renderdoc::*` scopes in whichever profiler backend of
+//! the `profiling` crate the application enables.
+
+#[cfg(feature = "tracing")]
+use std::cell::RefCell;
+#[cfg(feature = "tracing")]
+use std::collections::HashMap;
+
+#[cfg(feature = "tracing")]
+use tracing::span::EnteredSpan;
+
+use {DevicePointer, WindowHandle};
+
+#[cfg(feature = "tracing")]
+thread_local! {
+    /// Spans of frame captures started on this thread, by device and window.
+    ///
+    /// Entered spans can't leave their thread, so a capture ended on another
+    /// thread only emits its completion event.
+    static CAPTURE_SPANS: RefCell<HashMap<(usize, usize), EnteredSpan>> = RefCell::new(HashMap::new());
+}
+
+/// Called after a capture of the next `num_frames` frames was triggered.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn capture_triggered(num_frames: u32) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(target: "renderdoc", num_frames, "capture triggered");
+}
+
+/// Called before a frame capture is started.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn frame_capture_started(dev: &DevicePointer, win: WindowHandle) {
+    #[cfg(feature = "tracing")]
+    {
+        let span = tracing::info_span!(
+            target: "renderdoc",
+            "renderdoc.frame_capture",
+            device = ?**dev,
+            window = ?win
+        );
+        let key = (**dev as usize, win.as_raw() as usize);
+        CAPTURE_SPANS.with(|spans| spans.borrow_mut().insert(key, span.entered()));
+    }
+}
+
+/// Called after a frame capture ended, with the path of the capture file if it
+/// succeeded and is known.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn frame_capture_ended(dev: &DevicePointer, win: WindowHandle, ok: bool, path: Option<&str>) {
+    #[cfg(feature = "tracing")]
+    {
+        let key = (**dev as usize, win.as_raw() as usize);
+        let span = CAPTURE_SPANS.with(|spans| spans.borrow_mut().remove(&key));
+        match (ok, path) {
+            (true, Some(path)) => tracing::info!(target: "renderdoc", path, "capture completed"),
+            (true, None) => tracing::info!(target: "renderdoc", "capture completed"),
+            (false, _) => tracing::warn!(target: "renderdoc", "capture failed"),
+        }
+        drop(span);
+    }
+}
@@ -2,207 +2,204 @@
 
 #![deny(missing_docs)]
 
-#[cfg(any(target_os = "macos", target_os = "ios"))]
-compile_error!("RenderDoc does not support this platform.");
-
 #[macro_use]
 extern crate bitflags;
 #[macro_use]
 extern crate lazy_static;
+#[cfg(any(windows, target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd"))]
 extern crate shared_library;
 
-#[cfg(feature = "glutin")]
+#[cfg(feature = "async")]
+extern crate bytes;
+#[cfg(feature = "clap")]
+extern crate clap;
+#[cfg(feature = "async")]
+extern crate futures;
+#[cfg(all(feature = "glutin", not(target_arch = "wasm32")))]
 extern crate glutin;
 #[cfg(target_os = "windows")]
+extern crate advapi32;
+#[cfg(feature = "ash")]
+extern crate ash;
+#[cfg(target_os = "windows")]
+extern crate kernel32;
+#[cfg(unix)]
+extern crate libc;
+#[cfg(feature = "lz4")]
+extern crate lz4_flex;
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
+#[cfg(feature = "metrics")]
+extern crate metrics;
+#[cfg(feature = "testing")]
+extern crate png;
+#[cfg(feature = "profiling")]
+extern crate profiling;
+#[cfg(feature = "raw-window-handle")]
+extern crate raw_window_handle;
+#[cfg(feature = "macros")]
+extern crate renderdoc_macros;
+#[cfg(feature = "zstd")]
+extern crate ruzstd;
+#[cfg(any(feature = "replay", feature = "config"))]
+extern crate serde;
+#[cfg(any(feature = "replay", feature = "config"))]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(any(feature = "replay", feature = "config"))]
+extern crate serde_json;
+#[cfg(feature = "async")]
+extern crate tokio;
+#[cfg(feature = "async")]
+extern crate tokio_util;
+#[cfg(feature = "config")]
+extern crate toml;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "wgpu")]
+extern crate wgpu;
+#[cfg(target_os = "windows")]
 extern crate winapi;
 #[cfg(target_os = "windows")]
 extern crate wio;
 
-pub use self::entry::version::{ApiVersion, V100, V110};
-
-use std::ops;
-use std::os::raw::{c_ulonglong, c_void};
+pub use self::builder::Builder;
+pub use self::capture_dir::{CaptureDirError, CaptureDirOptions, DEFAULT_MIN_FREE_BYTES};
+pub use self::capture_metrics::{CaptureMetrics, CaptureRecord, TriggerSource};
+pub use self::capture_paths::CapturePaths;
+pub use self::capture_queue::{CaptureQueue, CAPTURE_QUEUE_ENV_VAR};
+pub use self::command::{CaptureChild, CaptureOptions, Command};
+pub use self::devices::{DeviceKey, DeviceRegistry, UnknownDevice};
+pub use self::entry::{is_injected, is_library_present};
+pub use self::entry::version::{ApiVersion, ApiVersionNumber, V100, V110, V111};
+pub use self::error::Error;
+pub use self::frame_capture::{CaptureOverlap, FrameCaptureGuard};
+pub use self::frame_clock::FrameClock;
+pub use self::global::{instance, unload, EarlyShutdown, RenderDocHandle};
+pub use self::init_config::InitConfig;
+pub use self::observer::CaptureObserver;
+pub use self::overrides::{CAPTURE_PATH_ENV_VAR, OPTIONS_ENV_VAR, OVERLAY_ENV_VAR};
+pub use self::replay_ui::{ReplayUiOptions, ReplayUiProcess};
+pub use self::sys::{CaptureOption, DevicePointer, InputButton, OverlayBits, WindowHandle};
+#[cfg(windows)]
+pub use self::sys::SHADER_MAGIC_DEBUG_VALUE_STRUCT;
+pub use self::sys::{SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY, SHADER_MAGIC_DEBUG_VALUE_TRUNCATED};
+#[doc(hidden)]
+pub use self::test_capture::TestOutcome;
+pub use self::test_capture::TestCapture;
+pub use self::window::WindowBinding;
+#[cfg(feature = "macros")]
+pub use renderdoc_macros::renderdoc_capture;
+
+use std::fmt;
+#[cfg(windows)]
+use std::os::raw::c_void;
+use std::path::Path;
+use std::sync::Arc;
 use std::u32;
 
-#[cfg(feature = "glutin")]
+#[cfg(all(feature = "glutin", not(target_arch = "wasm32")))]
 use glutin::VirtualKeyCode;
 #[cfg(windows)]
-use winapi::guiddef::GUID;
-#[cfg(windows)]
 use wio::com::ComPtr;
 
+#[macro_use]
+mod macros;
+
+pub mod android;
 pub mod api;
+#[cfg(feature = "clap")]
+pub mod cli;
+pub mod command;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod discovery;
+pub mod dynamic;
 pub mod entry;
+#[cfg(any(feature = "ash", feature = "gl", feature = "wgpu"))]
+pub mod markers;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod prelude;
-
-/// Magic value used for when applications pass a path where shader debug
-/// information can be found to match up with a stripped shader.
-///
-/// Windows GUID representation intended for consumption by D3D.
-#[cfg(windows)]
-pub const SHADER_MAGIC_DEBUG_VALUE_STRUCT: GUID = GUID {
-    Data1: 0xeab25520,
-    Data2: 0x6670,
-    Data3: 0x4865,
-    Data4: [0x84, 0x29, 0x6c, 0x8, 0x51, 0x54, 0x00, 0xff],
-};
-
-/// Magic value used for when applications pass a path where shader debug
-/// information can be found to match up with a stripped shader.
-///
-/// Raw byte array representation (assuming x86 endianness).
-pub const SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY: &[u8] = &[
-    0x20,
-    0x55,
-    0xb2,
-    0xea,
-    0x70,
-    0x66,
-    0x65,
-    0x48,
-    0x84,
-    0x29,
-    0x6c,
-    0x8,
-    0x51,
-    0x54,
-    0x00,
-    0xff,
-];
-
-/// Magic value used for when applications pass a path where shader debug
-/// information can be found to match up with a stripped shader.
-///
-/// Truncated version when only a `uint64_t` is available (e.g. Vulkan tags).
-pub const SHADER_MAGIC_DEBUG_VALUE_TRUNCATED: c_ulonglong = 0x4856670eab25520;
-
-/// RenderDoc capture options.
-#[repr(u32)]
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum CaptureOption {
-    /// Let the application enable vertical synchronization.
-    AllowVSync = 0,
-    /// Let the application enter fullscreen mode.
-    AllowFullscreen = 1,
-    /// Record API debugging events and messages.
-    ///
-    /// This option also goes by the deprecated name of `DebugDeviceMode`.
-    ApiValidation = 2,
-    /// Capture CPU callstacks for API events.
-    CaptureCallstacks = 3,
-    /// When capturing CPU callstacks, only capture them from drawcalls.
-    ///
-    /// This option does nothing without the above option being enabled.
-    CaptureCallstacksOnlyDraws = 4,
-    /// Specify a delay, measured in seconds, to wait for a debugger to attach
-    /// to the application after being injected.
-    DelayForDebugger = 5,
-    /// Verify any writes to mapped buffers by checking the memory after the
-    /// bounds of the returned pointer to detect any modification.
-    VerifyMapWrites = 6,
-    /// Hooks any system API calls that create child processes and injects
-    /// RenderDoc into them recursively with the same options.
-    HookIntoChildren = 7,
-    /// Reference all resources available at the time of capture.
-    ///
-    /// By default, RenderDoc only includes resources in the final capture file
-    /// necessary for that frame. This option allows you to override that
-    /// behavior.
-    RefAllResources = 8,
-    /// Save the initial state for all resources, regardless of usage.
-    ///
-    /// By default, RenderDoc skips saving initial states for resources where
-    /// the previous contents don't appear to be used (assuming that writes
-    /// before reads indicate the previous contents aren't used).
-    SaveAllInitials = 9,
-    /// Capture all command lists generated from the start of the application.
-    ///
-    /// In APIs that allow for recording of command lists to be replayed later,
-    /// RenderDoc may choose to not capture command lists before a frame capture
-    /// is triggered to reduce overhead. This means any command lists that are
-    /// recorded one and replayed many times will not be available, potentially
-    /// causing a failure to capture.
-    ///
-    /// Note that this is only true for APIs where multithreading is difficult
-    /// or otherwise discouraged. Newer APIs, e.g. Vulkan and D3D12, will ignore
-    /// this option and always capture all command lists since they are heavily
-    /// oriented around them and the associated overhead is mostly reduced due
-    /// to superior API design.
-    CaptureAllCmdLists = 10,
-    /// Mute API debug output when `CaptureOption::ApiValidation` is enabled.
-    DebugOutputMute = 11,
-}
-
-/// Raw mutable pointer to the API's root handle.
-///
-/// For example, this could be a pointer to an `ID3D11Device`,
-/// `HGLRC`/`GLXContext`, `ID3D12Device`, etc.
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
-pub struct DevicePointer(*const c_void);
-
-impl ops::Deref for DevicePointer {
-    type Target = *const c_void;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl From<*const c_void> for DevicePointer {
-    fn from(ptr: *const c_void) -> Self {
-        DevicePointer(ptr)
-    }
-}
-
-impl From<*mut c_void> for DevicePointer {
-    fn from(ptr: *mut c_void) -> Self {
-        DevicePointer(ptr)
-    }
-}
+pub mod protocol;
+pub mod rdcfile;
+#[cfg(feature = "record")]
+pub mod record;
+pub mod remote;
+#[cfg(feature = "replay")]
+pub mod replay;
+pub mod sys;
+pub mod targetcontrol;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod vulkan_layer;
+
+mod builder;
+mod capture_dir;
+mod capture_metrics;
+mod capture_paths;
+mod capture_queue;
+mod devices;
+mod error;
+mod frame_capture;
+mod frame_clock;
+mod global;
+mod init_config;
+mod instrument;
+mod names;
+mod observer;
+mod overrides;
+mod replay_ui;
+mod settings;
+mod test_capture;
+mod window;
+
+// Conversions from window system and graphics API types live here rather than
+// in `sys`, which keeps to the raw ABI.
 
 #[cfg(windows)]
 impl From<winapi::windef::HGLRC> for DevicePointer {
     fn from(ctx: winapi::windef::HGLRC) -> Self {
-        DevicePointer(ctx as *mut _ as *const c_void)
+        DevicePointer::from_raw(ctx as *mut _ as *const c_void)
     }
 }
 
 #[cfg(windows)]
 impl From<*mut winapi::ID3D11Device> for DevicePointer {
     fn from(ctx: *mut winapi::ID3D11Device) -> Self {
-        DevicePointer(ctx as *mut _ as *const c_void)
+        DevicePointer::from_raw(ctx as *mut _ as *const c_void)
     }
 }
 
 #[cfg(windows)]
 impl From<ComPtr<winapi::ID3D11Device>> for DevicePointer {
     fn from(ctx: ComPtr<winapi::ID3D11Device>) -> Self {
-        unsafe { DevicePointer(ctx.as_mut() as *mut _ as *const c_void) }
+        unsafe { DevicePointer::from_raw(ctx.as_mut() as *mut _ as *const c_void) }
     }
 }
 
 #[cfg(windows)]
 impl From<*mut winapi::ID3D12Device> for DevicePointer {
     fn from(ctx: *mut winapi::ID3D12Device) -> Self {
-        DevicePointer(ctx as *mut _ as *const c_void)
+        DevicePointer::from_raw(ctx as *mut _ as *const c_void)
     }
 }
 
 #[cfg(windows)]
 impl From<ComPtr<winapi::ID3D12Device>> for DevicePointer {
     fn from(ctx: ComPtr<winapi::ID3D12Device>) -> Self {
-        unsafe { DevicePointer(ctx.as_mut() as *mut _ as *const c_void) }
+        unsafe { DevicePointer::from_raw(ctx.as_mut() as *mut _ as *const c_void) }
     }
 }
 
-#[cfg(feature = "glutin")]
+#[cfg(all(feature = "glutin", not(target_arch = "wasm32")))]
 impl<'a> From<&'a glutin::Context> for DevicePointer {
     fn from(ctx: &'a glutin::Context) -> Self {
-        use glutin::os::GlContextExt;
-
-        #[cfg(unix)]
+        #[cfg(all(unix, not(any(target_os = "android", target_os = "macos", target_os = "ios"))))]
         unsafe {
             use glutin::os::unix::RawHandle;
+            use glutin::os::GlContextExt;
             match ctx.raw_handle() {
                 RawHandle::Glx(glx) => DevicePointer::from(glx),
                 _ => panic!("RenderDoc only supports GLX contexts on Unix!"),
@@ -212,109 +209,24 @@ impl<'a> From<&'a glutin::Context> for DevicePointer {
         #[cfg(windows)]
         unsafe {
             use glutin::os::windows::RawHandle;
+            use glutin::os::GlContextExt;
             match ctx.raw_handle() {
                 RawHandle::Wgl(wgl) => DevicePointer::from(wgl),
                 _ => panic!("RenderDoc only supports WGL contexts on Windows!"),
             }
         }
-    }
-}
 
-/// User input key codes.
-#[allow(missing_docs)]
-#[repr(u32)]
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum InputButton {
-    /// The '0' key over the letters.
-    Key0 = 0x30,
-    /// The '1' key over the letters.
-    Key1 = 0x31,
-    /// The '2' key over the letters.
-    Key2 = 0x32,
-    /// The '3' key over the letters.
-    Key3 = 0x33,
-    /// The '4' key over the letters.
-    Key4 = 0x34,
-    /// The '5' key over the letters.
-    Key5 = 0x35,
-    /// The '6' key over the letters.
-    Key6 = 0x36,
-    /// The '7' key over the letters.
-    Key7 = 0x37,
-    /// The '8' key over the letters.
-    Key8 = 0x38,
-    /// The '9' key over the letters.
-    Key9 = 0x39,
-
-    A = 0x41,
-    B = 0x42,
-    C = 0x43,
-    D = 0x44,
-    E = 0x45,
-    F = 0x46,
-    G = 0x47,
-    H = 0x48,
-    I = 0x49,
-    J = 0x4A,
-    K = 0x4B,
-    L = 0x4C,
-    M = 0x4D,
-    N = 0x4E,
-    O = 0x4F,
-    P = 0x50,
-    Q = 0x51,
-    R = 0x52,
-    S = 0x53,
-    T = 0x54,
-    U = 0x55,
-    V = 0x56,
-    W = 0x57,
-    X = 0x58,
-    Y = 0x59,
-    Z = 0x5A,
-
-    /// Leave the rest of the ASCII range free, in case the RenderDoc developers
-    /// decide to use it later.
-    NonPrintable = 0x100,
-
-    /// Division key on the numpad.
-    Divide,
-    /// Multiplication key on the numpad.
-    Multiply,
-    /// Subtraction key on the numpad.
-    Subtract,
-    /// Addition key on the numpad.
-    Plus,
-
-    F1,
-    F2,
-    F3,
-    F4,
-    F5,
-    F6,
-    F7,
-    F8,
-    F9,
-    F10,
-    F11,
-    F12,
-
-    Home,
-    End,
-    Insert,
-    Delete,
-    PageUp,
-    PageDn,
-
-    Backspace,
-    Tab,
-    PrtScrn,
-    Pause,
-
-    Max,
+        // Without GLX or WGL contexts, the wildcard device is the best match.
+        // RenderDoc doesn't run on macOS anyway.
+        #[cfg(any(target_os = "android", target_os = "macos", target_os = "ios"))]
+        {
+            let _ = ctx;
+            DevicePointer::null()
+        }
+    }
 }
 
-#[cfg(feature = "glutin")]
+#[cfg(all(feature = "glutin", not(target_arch = "wasm32")))]
 impl From<glutin::VirtualKeyCode> for InputButton {
     fn from(code: glutin::VirtualKeyCode) -> InputButton {
         match code {
@@ -385,38 +297,391 @@ impl From<glutin::VirtualKeyCode> for InputButton {
     }
 }
 
-bitflags! {
-    /// Bit flags for customizing the RenderDoc overlay.
-    pub struct OverlayBits: u32 {
-        /// Controls whether the overlay is enabled or disabled globally.
-        const ENABLED = 0x1;
-        /// Shows the average, minimum, and maximum sampled frame rate.
-        const FRAME_RATE = 0x2;
-        /// Shows the current frame number.
-        const FRAME_NUMBER = 0x4;
-        /// Shows a list of recent captures, out of the total captures made.
-        const CAPTURE_LIST = 0x8;
-        /// Sets the default configuration for the overlay.
-        const DEFAULT = (0x1 | 0x2 | 0x4 | 0x8);
-        /// Enables all overlay configuration bits.
-        const ALL = u32::MAX;
-        /// Disables all overlay configuration bits.
-        const NONE = u32::MIN;
-    }
-}
-
-/// Raw mutable pointer to the OS-provided window handle.
-pub type WindowHandle = *const c_void;
-
 /// An instance of the RenderDoc API with baseline version `V`.
-#[derive(Clone, Debug)]
-pub struct RenderDoc<V: ApiVersion>(V::Entry);
+///
+/// Handles are cheap to clone, since they only share a reference-counted
+/// pointer to the entry point table, and they are both `Send` and `Sync` so
+/// they can be moved to a render thread or stored in a global.
+///
+/// # Thread safety
+///
+/// The RenderDoc in-application API itself is not internally synchronized.
+/// Calls which change global RenderDoc state, e.g. setting capture options,
+/// hotkeys, the overlay, or the capture path template, as well as starting and
+/// ending frame captures, must not race with each other and should be
+/// serialized by the application.
+pub struct RenderDoc<V: ApiVersion>(Arc<V::Entry>, global::Lease);
 
 impl<V: ApiVersion> RenderDoc<V> {
     /// Initializes a new instance of the RenderDoc API.
-    pub fn new() -> Result<RenderDoc<V>, String> {
-        let api = V::load()?;
-        Ok(RenderDoc(api))
+    ///
+    /// The API may only be initialized once per process. Subsequent calls
+    /// return `Error::AlreadyInitialized`; clone the existing handle instead, or
+    /// use `renderdoc::instance()` to reach it from anywhere.
+    pub fn new() -> Result<RenderDoc<V>, Error> {
+        global::init(V::load)
+    }
+
+    /// Initializes a new instance of the RenderDoc API, together with a token
+    /// which allows shutting RenderDoc down again before it is used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::{RenderDoc, V110};
+    /// # fn init(capture_requested: bool) -> Result<(), renderdoc::Error> {
+    /// let (rd, shutdown) = RenderDoc::<V110>::new_with_shutdown()?;
+    /// if !capture_requested {
+    ///     unsafe { shutdown.shutdown(rd) };
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_shutdown() -> Result<(RenderDoc<V>, EarlyShutdown), Error> {
+        let rd = RenderDoc::new()?;
+        let shutdown = EarlyShutdown::new(AsRef::<entry::EntryV100>::as_ref(&*rd.0).shutdown);
+        Ok((rd, shutdown))
+    }
+
+    /// Returns whether RenderDoc has been shut down, in which case this handle
+    /// must no longer be used.
+    pub fn is_shut_down(&self) -> bool {
+        global::is_poisoned()
+    }
+
+    /// Fails with `Error::ShutDown` if RenderDoc has been shut down.
+    ///
+    /// Every call through a handle fails the same way once RenderDoc is shut
+    /// down. This allows checking up front, e.g. before preparing work which
+    /// only matters for a capture.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::prelude::*;
+    /// # fn run(rd: &RenderDoc<V110>) -> Result<(), renderdoc::Error> {
+    /// rd.ensure_usable()?;
+    /// // Label the resources of the next frame...
+    /// rd.trigger_capture()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ensure_usable(&self) -> Result<(), Error> {
+        global::check_usable()
+    }
+
+    /// Registers `observer` to be notified about captures, replacing any
+    /// previously registered observer.
+    ///
+    /// There is a single observer per process, notified about calls made
+    /// through any handle.
+    pub fn set_observer<O: CaptureObserver + 'static>(&self, observer: O) {
+        observer::set(Some(Arc::new(observer)));
+    }
+
+    /// Removes the registered observer, if any.
+    pub fn clear_observer(&self) {
+        observer::set(None);
+    }
+
+    /// Starts a frame capture of `binding`, which ends when the returned guard
+    /// is dropped.
+    ///
+    /// Accepts a `WindowBinding` or a `(device, window)` tuple. Guards of
+    /// distinct devices or windows may be held at the same time, but a capture
+    /// overlapping with one held by another guard is rejected with
+    /// `Error::CaptureOverlap`, as overlapping captures of the same device and
+    /// window are undefined behavior in RenderDoc. Null devices or windows
+    /// match every device or window, so a wildcard capture overlaps with all
+    /// captures it covers. Captures started through `start_frame_capture()`
+    /// aren't tracked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::{RenderDoc, V110, WindowBinding};
+    /// # fn run(rd: &RenderDoc<V110>, scene_view: WindowBinding, game_view: WindowBinding) {
+    /// let scene = rd.frame_capture(scene_view.clone()).unwrap();
+    /// let game = rd.frame_capture(game_view).unwrap();
+    /// assert!(rd.frame_capture(scene_view).is_err());
+    /// // Render and present both views...
+    /// drop(scene);
+    /// drop(game);
+    /// # }
+    /// ```
+    pub fn frame_capture<B: Into<WindowBinding>>(&self, binding: B) -> Result<FrameCaptureGuard, Error> {
+        FrameCaptureGuard::start(self.to_v100(), binding.into())
+    }
+
+    /// Starts recording statistics about the captures of this session.
+    ///
+    /// Recording is process-wide and can't be stopped again. Captures made
+    /// before enabling it are neither counted nor attributed.
+    pub fn enable_capture_metrics(&self) {
+        capture_metrics::enable();
+        capture_metrics::snapshot(self);
+    }
+
+    /// Returns statistics about the captures recorded since
+    /// `enable_capture_metrics()`.
+    ///
+    /// RenderDoc is asked for new captures on each call, which is how captures
+    /// triggered by hotkeys are found. These are only reported through the
+    /// `metrics` feature once discovered here, so call this regularly, e.g.
+    /// once per frame, when exporting metrics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::prelude::*;
+    /// # use renderdoc::TriggerSource;
+    /// # fn run(rd: &RenderDoc<V110>) -> Result<(), renderdoc::Error> {
+    /// rd.enable_capture_metrics();
+    /// rd.trigger_capture()?;
+    /// // Render a few frames...
+    /// let metrics = rd.capture_metrics();
+    /// println!(
+    ///     "{} captures, {} from hotkeys, {} bytes",
+    ///     metrics.count(),
+    ///     metrics.count_by(TriggerSource::Hotkey),
+    ///     metrics.total_file_size()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn capture_metrics(&self) -> CaptureMetrics {
+        capture_metrics::snapshot(self)
+    }
+
+    /// Initializes a new instance of the RenderDoc API, returning `None` if
+    /// that fails for any reason.
+    ///
+    /// This suits tooling that is only enabled when RenderDoc happens to be
+    /// available. With the `log` feature, the reason for a failure is logged at
+    /// debug level.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use renderdoc::{RenderDoc, V110};
+    /// let rd: Option<RenderDoc<V110>> = RenderDoc::try_new();
+    /// if rd.is_some() {
+    ///     println!("RenderDoc capture enabled");
+    /// }
+    /// ```
+    pub fn try_new() -> Option<RenderDoc<V>> {
+        match RenderDoc::new() {
+            Ok(rd) => Some(rd),
+            Err(err) => {
+                log_debug!("RenderDoc is unavailable: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Initializes a new instance of the RenderDoc API, loading the RenderDoc
+    /// library from a specific location instead of the default search path.
+    ///
+    /// This is useful for portable or custom builds of RenderDoc, or copies
+    /// vendored alongside a project. The same single-initialization rules as
+    /// `new()` apply.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::{RenderDoc, V110};
+    /// # fn init() -> Result<(), renderdoc::Error> {
+    /// let rd: RenderDoc<V110> = RenderDoc::with_library_path("vendor/renderdoc/librenderdoc.so")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_library_path<P: AsRef<Path>>(path: P) -> Result<RenderDoc<V>, Error> {
+        global::init(|| entry::loader::load_from_path::<V>(path.as_ref()))
+    }
+
+    /// Attaches to a RenderDoc instance that is already loaded in this process,
+    /// e.g. because the application was launched or injected by RenderDoc.
+    ///
+    /// Unlike `new()`, this never loads the RenderDoc library itself and fails
+    /// with `Error::NotLoaded` if it isn't present. This allows shipping builds
+    /// to opportunistically expose capture functionality when running under
+    /// RenderDoc, without ever pulling the library in otherwise.
+    pub fn attach() -> Result<RenderDoc<V>, Error> {
+        global::init(entry::loader::load_attached::<V>)
+    }
+
+    /// Initializes a new instance of the RenderDoc API and applies capture
+    /// options, capture keys and overlay bits in a single pass.
+    ///
+    /// The entry point is resolved once for the whole configuration, rather
+    /// than by every setter. Options rejected by RenderDoc are skipped, and
+    /// logged with the `log` feature. Use `builder()` for finer control.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::{CaptureOptions, InputButton, OverlayBits, RenderDoc, V110};
+    /// # fn init() -> Result<(), renderdoc::Error> {
+    /// let options = CaptureOptions {
+    ///     capture_callstacks: true,
+    ///     ..CaptureOptions::default()
+    /// };
+    /// let rd: RenderDoc<V110> =
+    ///     RenderDoc::init_with(&options, &[InputButton::F12], OverlayBits::DEFAULT)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn init_with(
+        options: &CaptureOptions,
+        keys: &[InputButton],
+        overlay: OverlayBits,
+    ) -> Result<RenderDoc<V>, Error> {
+        let rd = RenderDoc::new()?;
+        let entry = AsRef::<entry::EntryV100>::as_ref(&*rd.0);
+        unsafe {
+            for &(opt, val) in options.values().iter() {
+                if (entry.set_capture_option_u32)(opt, val) != 1 {
+                    log_debug!("RenderDoc rejected capture option {:?} = {}", opt, val);
+                }
+            }
+            (entry.set_capture_keys)(keys.as_ptr(), keys.len() as i32);
+            (entry.mask_overlay_bits)(OverlayBits::NONE, overlay);
+        }
+        log_debug!("Applied capture options, capture keys {:?} and overlay {:?}", keys, overlay);
+        Ok(rd)
+    }
+
+    /// Captures the next `num_frames` frames, even with a version 1.0.0
+    /// handle.
+    ///
+    /// Handles providing API version 1.1.0 forward to
+    /// `trigger_multi_frame_capture()`. Otherwise, this is emulated on a best
+    /// effort basis: the next frame is captured as by `trigger_capture()`, and
+    /// another capture is triggered on each of the following `num_frames - 1`
+    /// ticks of `clock`. Unlike the real call, this yields one capture file per
+    /// frame, and frames are skipped if `clock` isn't ticked on every present.
+    /// Emulated captures stop once RenderDoc is shut down.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::{FrameClock, RenderDoc, V100};
+    /// # fn run(rd: &RenderDoc<V100>, clock: &FrameClock) -> Result<(), renderdoc::Error> {
+    /// rd.trigger_multi_frame_capture_with(clock, 3)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn trigger_multi_frame_capture_with(&self, clock: &FrameClock, num_frames: u32) -> Result<(), Error> {
+        global::check_usable()?;
+        let first = clock.frame() + 1;
+        for i in 0..u64::from(num_frames) {
+            let label = format!("renderdoc-rs multi-frame capture {}/{} frame {}", i + 1, num_frames, first + i);
+            clock.label_capture(first + i, label);
+        }
+
+        if self.try_trigger_multi_frame_capture(num_frames)? {
+            return Ok(());
+        }
+
+        if num_frames == 0 {
+            return Ok(());
+        }
+
+        log_debug!("Emulating capture of the next {} frames", num_frames);
+        api::RenderDocV100::trigger_capture(self)?;
+        let rd = self.to_v100();
+        let mut remaining = num_frames - 1;
+        clock.on_frame(move |_| {
+            if remaining == 0 || api::RenderDocV100::trigger_capture(&rd).is_err() {
+                return false;
+            }
+            remaining -= 1;
+            remaining > 0
+        });
+        Ok(())
+    }
+
+    /// Captures `frame` once `clock` reaches it.
+    ///
+    /// The capture is triggered during the preceding frame, since RenderDoc
+    /// starts triggered captures at the next present, and right away if the
+    /// clock is already past that point. The captured frame is labeled as
+    /// scheduled, see `FrameClock::capture_label()`. Scheduled captures are
+    /// dropped if RenderDoc is shut down in the meantime.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::{FrameClock, RenderDoc, V110};
+    /// # fn run(rd: &RenderDoc<V110>) -> Result<(), renderdoc::Error> {
+    /// let clock = FrameClock::new();
+    /// rd.schedule_capture(&clock, 3)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn schedule_capture(&self, clock: &FrameClock, frame: u64) -> Result<(), Error> {
+        global::check_usable()?;
+        let rd = self.to_v100();
+        let trigger_at = frame.saturating_sub(1);
+        if clock.frame() >= trigger_at {
+            let frame = clock.frame() + 1;
+            clock.label_capture(frame, format!("renderdoc-rs scheduled capture frame {}", frame));
+            return capture_metrics::scheduled(|| api::RenderDocV100::trigger_capture(&rd));
+        }
+
+        clock.label_capture(frame, format!("renderdoc-rs scheduled capture frame {}", frame));
+        clock.on_frame(move |current| {
+            if current < trigger_at {
+                return !rd.is_shut_down();
+            }
+            let _ = capture_metrics::scheduled(|| api::RenderDocV100::trigger_capture(&rd));
+            false
+        });
+        Ok(())
+    }
+
+    /// Calls `trigger_multi_frame_capture()` if the handle provides the 1.1.0
+    /// function table, returning whether it does.
+    pub(crate) fn try_trigger_multi_frame_capture(&self, num_frames: u32) -> Result<bool, Error> {
+        let entry = match V::entry_v110(&self.0) {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+
+        global::check_usable()?;
+        log_debug!("Triggering capture of the next {} frames", num_frames);
+        profile_scope!("renderdoc::trigger_multi_frame_capture");
+        unsafe {
+            (entry.trigger_multi_frame_capture)(num_frames);
+        }
+        instrument::capture_triggered(num_frames);
+        capture_metrics::capture_triggered(num_frames);
+        Ok(true)
+    }
+
+    /// Returns a 1.0 handle sharing this handle's lease, e.g. for use in
+    /// `'static` frame hooks regardless of `V`.
+    pub(crate) fn to_v100(&self) -> RenderDoc<V100> {
+        let entry = AsRef::<entry::EntryV100>::as_ref(&*self.0).clone();
+        RenderDoc(Arc::new(entry), self.1.clone())
+    }
+
+    /// Applies settings which have to be in place before the graphics API
+    /// initializes.
+    ///
+    /// Call this once, right after loading RenderDoc and before creating any
+    /// graphics device, as RenderDoc only picks up several capture options when
+    /// a device is created. Debug builds panic if it's called twice, or after a
+    /// device was passed to RenderDoc, e.g. by `set_active_window()` or
+    /// `start_frame_capture()`. See `InitConfig` for an example.
+    pub fn init(&self, config: InitConfig) -> Result<(), Error> {
+        config.apply(self)
+    }
+
+    /// Returns a builder which loads the API and applies an initial
+    /// configuration in one step.
+    pub fn builder() -> Builder<V> {
+        Builder::new()
     }
 
     /// Returns the raw entry point of the API.
@@ -424,90 +689,112 @@ impl<V: ApiVersion> RenderDoc<V> {
     /// # Safety
     ///
     /// Using the entry point structure directly will discard any thread safety
-    /// provided by default with this library.
+    /// provided by default with this library. Its function pointers are also
+    /// not guarded against use after RenderDoc has been shut down.
     pub unsafe fn raw_api(&self) -> V::Entry {
-        self.0.clone()
+        (*self.0).clone()
     }
 }
 
-impl From<RenderDoc<V110>> for RenderDoc<V100> {
-    fn from(newer: RenderDoc<V110>) -> RenderDoc<V100> {
-        RenderDoc(newer.0.entry_v100)
+impl<V: ApiVersion> Clone for RenderDoc<V> {
+    fn clone(&self) -> Self {
+        RenderDoc(self.0.clone(), self.1.clone())
     }
 }
 
-impl api::RenderDocV100 for RenderDoc<V100> {
-    unsafe fn entry_v100(&self) -> &self::entry::EntryV100 {
-        &self.0
+impl<V: ApiVersion> fmt::Debug for RenderDoc<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RenderDoc").field(&self.0).finish()
     }
 }
 
-impl api::RenderDocV100 for RenderDoc<V110> {
-    unsafe fn entry_v100(&self) -> &self::entry::EntryV100 {
-        &self.0.entry_v100
-    }
-}
+impl_downgrade!(V110 => V100);
+impl_downgrade!(V111 => V110, V100);
 
-impl api::RenderDocV110 for RenderDoc<V110> {
-    unsafe fn entry_v110(&self) -> &self::entry::EntryV110 {
-        &self.0
+// Every handle provides the base API, while the 1.1.0 additions are available
+// to handles whose entry point includes them, i.e. `V110` and `V111`.
+impl<V: ApiVersion> api::RenderDocV100 for RenderDoc<V> {
+    unsafe fn entry_v100(&self) -> Result<&entry::EntryV100, Error> {
+        global::check_usable()?;
+        Ok(AsRef::<entry::EntryV100>::as_ref(&*self.0))
     }
 }
 
+impl_version_trait!(RenderDocV110, entry_v110, EntryV110);
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use super::api::*;
 
+    #[test]
+    fn handles_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<RenderDoc<V100>>();
+        assert_send_sync::<RenderDoc<V110>>();
+        assert_send_sync::<RenderDoc<V111>>();
+    }
+
+    #[test]
+    fn handles_implement_version_traits() {
+        fn assert_v100<T: RenderDocV100>() {}
+        fn assert_v110<T: RenderDocV110>() {}
+        assert_v100::<RenderDoc<V100>>();
+        assert_v100::<RenderDoc<V110>>();
+        assert_v100::<RenderDoc<V111>>();
+        assert_v110::<RenderDoc<V110>>();
+        assert_v110::<RenderDoc<V111>>();
+    }
+
     #[test]
     fn get_set_capture_option_f32() {
-        let mut rd: RenderDoc<V110> = RenderDoc::new().expect("Failed to init");
+        let rd = instance().expect("Failed to init").lock().unwrap();
 
-        let delay = rd.get_capture_option_f32(CaptureOption::DelayForDebugger);
+        let delay = rd.get_capture_option_f32(CaptureOption::DelayForDebugger).unwrap();
         assert_eq!(delay, 0.0f32);
 
-        rd.set_capture_option_f32(CaptureOption::DelayForDebugger, 2.5f32);
-        let delay = rd.get_capture_option_f32(CaptureOption::DelayForDebugger);
+        rd.set_capture_option_f32(CaptureOption::DelayForDebugger, 2.5f32).unwrap();
+        let delay = rd.get_capture_option_f32(CaptureOption::DelayForDebugger).unwrap();
         assert_eq!(delay, 2.0f32);
     }
 
     #[test]
     fn get_set_capture_option_u32() {
-        let rd: RenderDoc<V110> = RenderDoc::new().expect("Failed to init");
+        let rd = instance().expect("Failed to init").lock().unwrap();
 
-        let vsync = rd.get_capture_option_u32(CaptureOption::AllowVSync);
+        let vsync = rd.get_capture_option_u32(CaptureOption::AllowVSync).unwrap();
         assert_eq!(vsync, 1u32);
 
-        let is_full = rd.get_capture_option_u32(CaptureOption::AllowFullscreen);
+        let is_full = rd.get_capture_option_u32(CaptureOption::AllowFullscreen).unwrap();
         assert_eq!(is_full, 1u32);
 
-        let api_val_mode = rd.get_capture_option_u32(CaptureOption::ApiValidation);
-        let debug_mode = rd.get_capture_option_u32(CaptureOption::ApiValidation);
+        let api_val_mode = rd.get_capture_option_u32(CaptureOption::ApiValidation).unwrap();
+        let debug_mode = rd.get_capture_option_u32(CaptureOption::ApiValidation).unwrap();
         assert_eq!(api_val_mode, 0u32);
         assert_eq!(api_val_mode, debug_mode);
 
-        let cc = rd.get_capture_option_u32(CaptureOption::CaptureCallstacks);
+        let cc = rd.get_capture_option_u32(CaptureOption::CaptureCallstacks).unwrap();
         assert_eq!(cc, 0u32);
 
-        let cc_draw = rd.get_capture_option_u32(CaptureOption::CaptureCallstacksOnlyDraws);
+        let cc_draw = rd.get_capture_option_u32(CaptureOption::CaptureCallstacksOnlyDraws).unwrap();
         assert_eq!(cc_draw, 0u32);
 
-        let ver_map = rd.get_capture_option_u32(CaptureOption::VerifyMapWrites);
+        let ver_map = rd.get_capture_option_u32(CaptureOption::VerifyMapWrites).unwrap();
         assert_eq!(ver_map, 0u32);
 
-        let hook_in = rd.get_capture_option_u32(CaptureOption::HookIntoChildren);
+        let hook_in = rd.get_capture_option_u32(CaptureOption::HookIntoChildren).unwrap();
         assert_eq!(hook_in, 0u32);
 
-        let ref_all = rd.get_capture_option_u32(CaptureOption::RefAllResources);
+        let ref_all = rd.get_capture_option_u32(CaptureOption::RefAllResources).unwrap();
         assert_eq!(ref_all, 0u32);
 
-        let intls = rd.get_capture_option_u32(CaptureOption::SaveAllInitials);
+        let intls = rd.get_capture_option_u32(CaptureOption::SaveAllInitials).unwrap();
         assert_eq!(intls, 0u32);
 
-        let cmds = rd.get_capture_option_u32(CaptureOption::CaptureAllCmdLists);
+        let cmds = rd.get_capture_option_u32(CaptureOption::CaptureAllCmdLists).unwrap();
         assert_eq!(cmds, 0u32);
 
-        let is_muted = rd.get_capture_option_u32(CaptureOption::DebugOutputMute);
+        let is_muted = rd.get_capture_option_u32(CaptureOption::DebugOutputMute).unwrap();
         assert_eq!(is_muted, 1u32);
     }
 }
@@ -0,0 +1,144 @@
+//! Internal macros.
+//!
+//! The logging macros forward to the `log` crate with the `log` feature, and
+//! otherwise only type-check their arguments so that logging never affects the
+//! build.
+//!
+//! The remaining macros generate the boilerplate of each API version. Adding a
+//! version takes an `entry_point!` for its function table, an `api_version!`
+//! marker type, its trait in `api` together with `impl_version_trait!`, the
+//! `impl_downgrade!` conversions from its handles to older ones, and its new
+//! fields in an `assert_entry_layout!` check in `sys`.
+
+macro_rules! log_debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        debug!($($arg)*);
+        #[cfg(not(feature = "log"))]
+        {
+            if false {
+                let _ = format_args!($($arg)*);
+            }
+        }
+    }};
+}
+
+macro_rules! log_trace {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        trace!($($arg)*);
+        #[cfg(not(feature = "log"))]
+        {
+            if false {
+                let _ = format_args!($($arg)*);
+            }
+        }
+    }};
+}
+
+/// Opens a profiler scope lasting until the end of the enclosing block, with
+/// the `profiling` feature.
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "profiling")]
+        profiling::scope!($name);
+    };
+}
+
+/// Declares the function table of an API version which extends `$base`,
+/// stored in the field `$base_field`.
+///
+/// The table converts with `AsRef` to itself and to every table listed in
+/// brackets, which must be `$base` and all tables `$base` converts to.
+macro_rules! entry_point {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident: $base_field:ident: $base:ident [$($older:ident),*] {
+            $(pub $field:ident: $ty:ty,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[allow(missing_docs)]
+        #[repr(C)]
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        pub struct $name {
+            pub $base_field: $base,
+            $(pub $field: $ty,)*
+        }
+
+        impl AsRef<$name> for $name {
+            fn as_ref(&self) -> &$name {
+                self
+            }
+        }
+
+        $(
+            impl AsRef<$older> for $name {
+                fn as_ref(&self) -> &$older {
+                    AsRef::<$older>::as_ref(&self.$base_field)
+                }
+            }
+        )*
+    };
+}
+
+/// Declares a marker type requesting API version `$version` with the function
+/// table `$entry`.
+///
+/// Tables including the 1.1.0 functions are marked with `+ EntryV110`.
+macro_rules! api_version {
+    ($(#[$attr:meta])* $name:ident: $version:ident => $entry:ident) => {
+        api_version!(@marker $(#[$attr])* $name: $version => $entry {});
+    };
+    ($(#[$attr:meta])* $name:ident: $version:ident => $entry:ident + EntryV110) => {
+        api_version!(@marker $(#[$attr])* $name: $version => $entry {
+            fn entry_v110(entry: &$entry) -> Option<&$crate::entry::EntryV110> {
+                Some(entry)
+            }
+        });
+    };
+    (@marker $(#[$attr:meta])* $name:ident: $version:ident => $entry:ident { $($body:tt)* }) => {
+        $(#[$attr])*
+        pub enum $name {}
+
+        impl ApiVersion for $name {
+            const VERSION: Version = Version::$version;
+
+            type Entry = $entry;
+
+            $($body)*
+        }
+    };
+}
+
+/// Implements the version trait `$trait` for all `RenderDoc` handles whose
+/// function table includes `$entry`.
+macro_rules! impl_version_trait {
+    ($trait:ident, $method:ident, $entry:ident) => {
+        impl<V> $crate::api::$trait for RenderDoc<V>
+        where
+            V: ApiVersion,
+            V::Entry: AsRef<$crate::entry::$entry>,
+        {
+            unsafe fn $method(&self) -> Result<&$crate::entry::$entry, Error> {
+                global::check_usable()?;
+                Ok(AsRef::<$crate::entry::$entry>::as_ref(&*self.0))
+            }
+        }
+    };
+}
+
+/// Implements conversions from handles of version `$from` to handles of each
+/// of the older versions `$to`.
+macro_rules! impl_downgrade {
+    ($from:ident => $($to:ident),*) => {
+        $(
+            impl From<RenderDoc<$from>> for RenderDoc<$to> {
+                fn from(newer: RenderDoc<$from>) -> RenderDoc<$to> {
+                    let entry = AsRef::<<$to as ApiVersion>::Entry>::as_ref(&*newer.0).clone();
+                    RenderDoc(Arc::new(entry), newer.1)
+                }
+            }
+        )*
+    };
+}
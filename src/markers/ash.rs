@@ -0,0 +1,216 @@
+//! Vulkan labels and object names through `VK_EXT_debug_utils`, for `ash`.
+//!
+//! Requires the `ash` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate renderdoc;
+//! # extern crate ash;
+//! # use ash::vk;
+//! use renderdoc::markers::ash::Markers;
+//!
+//! # fn record(instance: &ash::Instance, device: &ash::Device, cmd: vk::CommandBuffer, image: vk::Image) {
+//! let markers = Markers::new(instance, device);
+//! unsafe { markers.set_object_name(image, "Shadow map").unwrap() };
+//!
+//! let labels = unsafe { markers.commands(cmd) };
+//! label_scope!(labels, "Shadow pass");
+//! // Record the pass...
+//! # }
+//! # fn main() {}
+//! ```
+
+use std::ffi::CString;
+
+use ash::ext::debug_utils;
+use ash::prelude::VkResult;
+use ash::vk;
+
+use markers::NameObject;
+
+/// Emits labels and object names on a Vulkan device.
+///
+/// The device must have been created with `VK_EXT_debug_utils` enabled, or
+/// the markers created with `Markers::disabled()`, which turns every call into
+/// a no-op.
+#[derive(Clone)]
+pub struct Markers {
+    debug_utils: Option<debug_utils::Device>,
+}
+
+impl Markers {
+    /// Loads the `VK_EXT_debug_utils` functions of `device`.
+    pub fn new(instance: &ash::Instance, device: &ash::Device) -> Self {
+        Markers {
+            debug_utils: Some(debug_utils::Device::new(instance, device)),
+        }
+    }
+
+    /// Creates markers which do nothing, for devices without
+    /// `VK_EXT_debug_utils`.
+    pub fn disabled() -> Self {
+        Markers { debug_utils: None }
+    }
+
+    /// Returns whether labels and names are emitted.
+    pub fn is_enabled(&self) -> bool {
+        self.debug_utils.is_some()
+    }
+
+    /// Returns labels recorded into `cmd`.
+    ///
+    /// # Safety
+    ///
+    /// `cmd` must be a command buffer of this device, which stays in the
+    /// recording state while the labels are used. Access to it must be
+    /// externally synchronized, as for any command recording.
+    pub unsafe fn commands(&self, cmd: vk::CommandBuffer) -> Labels<'_> {
+        Labels {
+            markers: self,
+            target: Target::Commands(cmd),
+        }
+    }
+
+    /// Returns labels inserted into `queue`.
+    ///
+    /// # Safety
+    ///
+    /// `queue` must be a queue of this device. Access to it must be externally
+    /// synchronized, as for queue submissions.
+    pub unsafe fn queue(&self, queue: vk::Queue) -> Labels<'_> {
+        Labels {
+            markers: self,
+            target: Target::Queue(queue),
+        }
+    }
+
+    /// Names `handle`, so RenderDoc shows `name` instead of a generic
+    /// identifier.
+    ///
+    /// Names are truncated at the first NUL byte.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid object of this device.
+    pub unsafe fn set_object_name<H: vk::Handle>(&self, handle: H, name: &str) -> VkResult<()> {
+        let debug_utils = match self.debug_utils {
+            Some(ref debug_utils) => debug_utils,
+            None => return Ok(()),
+        };
+        let name = c_label(name);
+        let info = vk::DebugUtilsObjectNameInfoEXT::default()
+            .object_handle(handle)
+            .object_name(&name);
+        debug_utils.set_debug_utils_object_name(&info)
+    }
+}
+
+impl<H: vk::Handle> NameObject<H> for Markers {
+    type Error = vk::Result;
+
+    unsafe fn name_object(&self, handle: H, name: &str) -> VkResult<()> {
+        self.set_object_name(handle, name)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Target {
+    Commands(vk::CommandBuffer),
+    Queue(vk::Queue),
+}
+
+/// Labels on a command buffer or queue, created with `Markers::commands()` or
+/// `Markers::queue()`.
+#[derive(Clone, Copy)]
+pub struct Labels<'a> {
+    markers: &'a Markers,
+    target: Target,
+}
+
+impl<'a> Labels<'a> {
+    /// Opens a labeled region, which ends when the returned guard is dropped.
+    ///
+    /// Regions nest, and must be closed in reverse order of opening.
+    pub fn scope(&self, name: &str) -> Scope<'a> {
+        self.scope_with_color(name, [0.0; 4])
+    }
+
+    /// Opens a labeled region drawn in `color`, given as RGBA.
+    ///
+    /// A color of all zeros leaves the choice of color to the tool.
+    pub fn scope_with_color(&self, name: &str, color: [f32; 4]) -> Scope<'a> {
+        self.emit(name, color, true);
+        Scope { labels: *self }
+    }
+
+    /// Inserts a single label, which marks a point rather than a region.
+    pub fn insert(&self, name: &str) {
+        self.emit(name, [0.0; 4], false);
+    }
+
+    fn emit(&self, name: &str, color: [f32; 4], begin: bool) {
+        let debug_utils = match self.markers.debug_utils {
+            Some(ref debug_utils) => debug_utils,
+            None => return,
+        };
+        let name = c_label(name);
+        let label = vk::DebugUtilsLabelEXT::default().label_name(&name).color(color);
+        // The target's validity is guaranteed by the creator of the labels.
+        unsafe {
+            match (self.target, begin) {
+                (Target::Commands(cmd), true) => debug_utils.cmd_begin_debug_utils_label(cmd, &label),
+                (Target::Commands(cmd), false) => debug_utils.cmd_insert_debug_utils_label(cmd, &label),
+                (Target::Queue(queue), true) => debug_utils.queue_begin_debug_utils_label(queue, &label),
+                (Target::Queue(queue), false) => debug_utils.queue_insert_debug_utils_label(queue, &label),
+            }
+        }
+    }
+}
+
+/// A labeled region, ending when dropped.
+#[must_use = "the labeled region ends as soon as the scope is dropped"]
+pub struct Scope<'a> {
+    labels: Labels<'a>,
+}
+
+impl<'a> Drop for Scope<'a> {
+    fn drop(&mut self) {
+        let debug_utils = match self.labels.markers.debug_utils {
+            Some(ref debug_utils) => debug_utils,
+            None => return,
+        };
+        unsafe {
+            match self.labels.target {
+                Target::Commands(cmd) => debug_utils.cmd_end_debug_utils_label(cmd),
+                Target::Queue(queue) => debug_utils.queue_end_debug_utils_label(queue),
+            }
+        }
+    }
+}
+
+/// Converts `name` to a C string, truncating it at the first NUL byte.
+fn c_label(name: &str) -> CString {
+    let end = name.find('\0').unwrap_or(name.len());
+    CString::new(&name[..end]).expect("label contains no NUL bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_markers_do_nothing() {
+        let markers = Markers::disabled();
+        assert!(!markers.is_enabled());
+
+        let labels = unsafe { markers.commands(vk::CommandBuffer::null()) };
+        label_scope!(labels, "Pass {}", 1);
+        labels.insert("Draw");
+        unsafe { markers.set_object_name(vk::Image::null(), "Image").unwrap() };
+        unsafe { ::markers::name_object(&markers, vk::Buffer::null(), "Buffer").unwrap() };
+
+        assert_eq!(c_label("Shadow pass").to_bytes(), b"Shadow pass");
+        assert_eq!(c_label("a\0b").to_bytes(), b"a");
+    }
+}
@@ -0,0 +1,192 @@
+//! OpenGL object labels through `KHR_debug`.
+//!
+//! The functions are loaded through the application's GL loader, so no GL
+//! bindings crate is needed.
+//!
+//! Requires the `gl` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use std::os::raw::c_void;
+//! use renderdoc::markers::gl::{GlLabels, GlObject};
+//! use renderdoc::markers::name_object;
+//!
+//! # unsafe fn run(get_proc_address: &dyn Fn(&str) -> *const c_void, texture: u32) {
+//! if let Some(labels) = GlLabels::load(|symbol| get_proc_address(symbol)) {
+//!     name_object(&labels, GlObject::texture(texture), "GBuffer.Albedo").unwrap();
+//! }
+//! # }
+//! ```
+
+use std::convert::Infallible;
+use std::fmt;
+use std::mem;
+use std::os::raw::{c_char, c_void};
+
+use markers::NameObject;
+
+type ObjectLabelFn = unsafe extern "system" fn(identifier: u32, name: u32, length: i32, label: *const c_char);
+
+/// An OpenGL object, identified by its type and name.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GlObject {
+    /// Type of the object, e.g. `GL_TEXTURE`.
+    pub identifier: u32,
+    /// Name of the object, as returned by `glGen*()` or `glCreate*()`.
+    pub name: u32,
+}
+
+impl GlObject {
+    /// A buffer object.
+    pub fn buffer(name: u32) -> Self {
+        GlObject { identifier: 0x82E0, name }
+    }
+
+    /// A shader object.
+    pub fn shader(name: u32) -> Self {
+        GlObject { identifier: 0x82E1, name }
+    }
+
+    /// A program object.
+    pub fn program(name: u32) -> Self {
+        GlObject { identifier: 0x82E2, name }
+    }
+
+    /// A vertex array object.
+    pub fn vertex_array(name: u32) -> Self {
+        GlObject { identifier: 0x8074, name }
+    }
+
+    /// A query object.
+    pub fn query(name: u32) -> Self {
+        GlObject { identifier: 0x82E3, name }
+    }
+
+    /// A program pipeline object.
+    pub fn program_pipeline(name: u32) -> Self {
+        GlObject { identifier: 0x82E4, name }
+    }
+
+    /// A sampler object.
+    pub fn sampler(name: u32) -> Self {
+        GlObject { identifier: 0x82E6, name }
+    }
+
+    /// A texture object.
+    pub fn texture(name: u32) -> Self {
+        GlObject { identifier: 0x1702, name }
+    }
+
+    /// A renderbuffer object.
+    pub fn renderbuffer(name: u32) -> Self {
+        GlObject { identifier: 0x8D41, name }
+    }
+
+    /// A framebuffer object.
+    pub fn framebuffer(name: u32) -> Self {
+        GlObject { identifier: 0x8D40, name }
+    }
+
+    /// A transform feedback object.
+    pub fn transform_feedback(name: u32) -> Self {
+        GlObject { identifier: 0x8E22, name }
+    }
+}
+
+/// Labels OpenGL objects with `glObjectLabel()`.
+#[derive(Clone, Copy)]
+pub struct GlLabels {
+    object_label: ObjectLabelFn,
+}
+
+impl GlLabels {
+    /// Loads `glObjectLabel()`, falling back to `glObjectLabelKHR()` for
+    /// OpenGL ES.
+    ///
+    /// Returns `None` if the context supports neither.
+    ///
+    /// # Safety
+    ///
+    /// `loader` must return the addresses of GL functions for the current
+    /// context, or null, as e.g. `wglGetProcAddress()` or `eglGetProcAddress()`
+    /// do.
+    pub unsafe fn load<F>(mut loader: F) -> Option<Self>
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        ["glObjectLabel", "glObjectLabelKHR"]
+            .iter()
+            .map(|symbol| loader(symbol))
+            .find(|ptr| !ptr.is_null())
+            .map(|ptr| GlLabels {
+                object_label: mem::transmute::<*const c_void, ObjectLabelFn>(ptr),
+            })
+    }
+
+    /// Labels `object` with `name`.
+    ///
+    /// Names longer than the context's `GL_MAX_LABEL_LENGTH` are rejected by
+    /// the driver.
+    ///
+    /// # Safety
+    ///
+    /// The context the labels were loaded for must be current on this thread.
+    pub unsafe fn object_label(&self, object: GlObject, name: &str) {
+        let len = name.len().min(i32::MAX as usize);
+        (self.object_label)(object.identifier, object.name, len as i32, name.as_ptr() as *const c_char);
+    }
+}
+
+impl fmt::Debug for GlLabels {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GlLabels")
+            .field("object_label", &(self.object_label as *const c_void))
+            .finish()
+    }
+}
+
+impl NameObject<GlObject> for GlLabels {
+    type Error = Infallible;
+
+    unsafe fn name_object(&self, object: GlObject, name: &str) -> Result<(), Infallible> {
+        self.object_label(object, name);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::ptr;
+    use std::slice;
+
+    thread_local! {
+        static LABELS: RefCell<Vec<(u32, u32, String)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    unsafe extern "system" fn object_label(identifier: u32, name: u32, length: i32, label: *const c_char) {
+        let label = slice::from_raw_parts(label as *const u8, length as usize);
+        let label = String::from_utf8_lossy(label).into_owned();
+        LABELS.with(|labels| labels.borrow_mut().push((identifier, name, label)));
+    }
+
+    #[test]
+    fn load_and_label() {
+        unsafe {
+            assert!(GlLabels::load(|_| ptr::null()).is_none());
+
+            let labels = GlLabels::load(|symbol| match symbol {
+                "glObjectLabelKHR" => object_label as ObjectLabelFn as *const c_void,
+                _ => ptr::null(),
+            })
+            .unwrap();
+            labels.name_object(GlObject::texture(7), "GBuffer.Albedo").unwrap();
+        }
+
+        LABELS.with(|labels| {
+            assert_eq!(*labels.borrow(), vec![(0x1702, 7, "GBuffer.Albedo".to_string())]);
+        });
+    }
+}
@@ -0,0 +1,79 @@
+//! Labeling regions of captured frames.
+//!
+//! Captures are hard to navigate without markers: RenderDoc shows labeled
+//! regions as a tree in its event browser, and uses object names throughout
+//! its UI. The submodules emit labels and names through the graphics APIs'
+//! own debug extensions, which RenderDoc records:
+//!
+//! * `ash`, with the `ash` feature, for Vulkan through `VK_EXT_debug_utils`.
+//! * `gl`, with the `gl` feature, for OpenGL through `KHR_debug`.
+//! * `wgpu`, with the `wgpu` feature, marking captured frames with debug
+//!   groups.
+//!
+//! Labels are scoped with the `label_scope!` macro, which ends the label at
+//! the end of the enclosing block. Objects are named through `name_object()`,
+//! regardless of the API.
+//!
+//! `wgpu` can't rename objects after creating them. Give their descriptors a
+//! `label` instead, which `wgpu` forwards to the underlying API.
+
+/// Devices which can name their objects of type `H`, such as images or
+/// buffers.
+pub trait NameObject<H> {
+    /// Error returned if naming fails.
+    type Error;
+
+    /// Names `handle`, so RenderDoc shows `name` instead of a generic
+    /// identifier.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid object of this device, under the additional
+    /// conditions of the implementation.
+    unsafe fn name_object(&self, handle: H, name: &str) -> Result<(), Self::Error>;
+}
+
+/// Names `handle` on `device`, e.g. `name_object(&markers, image,
+/// "GBuffer.Albedo")`.
+///
+/// # Safety
+///
+/// See `NameObject::name_object()`.
+pub unsafe fn name_object<D, H>(device: &D, handle: H, name: &str) -> Result<(), D::Error>
+where
+    D: NameObject<H>,
+{
+    device.name_object(handle, name)
+}
+
+/// Opens a labeled region lasting until the end of the enclosing block.
+///
+/// The first argument is anything with a `scope(&str)` method returning a
+/// guard, such as `markers::ash::Labels`. The label is formatted from the
+/// remaining arguments like `format!()`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let labels = unsafe { markers.commands(cmd) };
+/// {
+///     label_scope!(labels, "Shadow pass");
+///     for (i, cascade) in cascades.iter().enumerate() {
+///         label_scope!(labels, "Cascade {}", i);
+///         draw_cascade(cascade);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! label_scope {
+    ($labels:expr, $($arg:tt)+) => {
+        let _label_scope = $labels.scope(&format!($($arg)+));
+    };
+}
+
+#[cfg(feature = "ash")]
+pub mod ash;
+#[cfg(feature = "gl")]
+pub mod gl;
+#[cfg(feature = "wgpu")]
+pub mod wgpu;
@@ -0,0 +1,168 @@
+//! Debug groups marking captured frames, for `wgpu`.
+//!
+//! Captures scheduled through a `FrameClock`, e.g. with
+//! `RenderDoc::trigger_multi_frame_capture_with()` or
+//! `RenderDocArgs::schedule_capture()`, are labeled with why they were made.
+//! `capture_group()` wraps the frame's commands in a debug group carrying that
+//! label, such as `renderdoc-rs scheduled capture frame 1234`, so the capture
+//! shows why and when it was taken.
+//!
+//! Requires the `wgpu` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # extern crate renderdoc;
+//! # extern crate wgpu;
+//! use renderdoc::markers::wgpu::capture_group;
+//! use renderdoc::FrameClock;
+//!
+//! # fn record(clock: &FrameClock, encoder: &mut wgpu::CommandEncoder) {
+//! let mut encoder = capture_group(clock, encoder);
+//! encoder.insert_debug_marker("Frame start");
+//! // Record the frame...
+//! # }
+//! # fn main() {}
+//! ```
+
+use std::ops::{Deref, DerefMut};
+
+use wgpu::{CommandEncoder, ComputePass, RenderPass};
+
+use FrameClock;
+
+/// Encoders and passes which support debug groups.
+pub trait DebugGroups {
+    /// Starts a debug group named `label`.
+    fn push_debug_group(&mut self, label: &str);
+    /// Ends the most recently started debug group.
+    fn pop_debug_group(&mut self);
+}
+
+impl DebugGroups for CommandEncoder {
+    fn push_debug_group(&mut self, label: &str) {
+        CommandEncoder::push_debug_group(self, label);
+    }
+
+    fn pop_debug_group(&mut self) {
+        CommandEncoder::pop_debug_group(self);
+    }
+}
+
+impl DebugGroups for RenderPass<'_> {
+    fn push_debug_group(&mut self, label: &str) {
+        RenderPass::push_debug_group(self, label);
+    }
+
+    fn pop_debug_group(&mut self) {
+        RenderPass::pop_debug_group(self);
+    }
+}
+
+impl DebugGroups for ComputePass<'_> {
+    fn push_debug_group(&mut self, label: &str) {
+        ComputePass::push_debug_group(self, label);
+    }
+
+    fn pop_debug_group(&mut self) {
+        ComputePass::pop_debug_group(self);
+    }
+}
+
+/// Wraps `encoder` in a debug group labeling the current frame's capture.
+///
+/// Nothing is pushed unless a capture of the current frame was scheduled
+/// through `clock`. The group ends when the returned guard is dropped; use the
+/// guard in place of `encoder` until then.
+pub fn capture_group<'a, E: DebugGroups>(clock: &FrameClock, encoder: &'a mut E) -> CaptureGroup<'a, E> {
+    let pushed = match clock.capture_label() {
+        Some(label) => {
+            encoder.push_debug_group(&label);
+            true
+        }
+        None => false,
+    };
+    CaptureGroup { encoder, pushed }
+}
+
+/// A debug group opened by `capture_group()`, ending when dropped.
+#[must_use = "the debug group ends as soon as the guard is dropped"]
+pub struct CaptureGroup<'a, E: DebugGroups + 'a> {
+    encoder: &'a mut E,
+    pushed: bool,
+}
+
+impl<'a, E: DebugGroups> CaptureGroup<'a, E> {
+    /// Returns whether a debug group was opened.
+    pub fn is_capturing(&self) -> bool {
+        self.pushed
+    }
+}
+
+impl<'a, E: DebugGroups> Deref for CaptureGroup<'a, E> {
+    type Target = E;
+
+    fn deref(&self) -> &E {
+        self.encoder
+    }
+}
+
+impl<'a, E: DebugGroups> DerefMut for CaptureGroup<'a, E> {
+    fn deref_mut(&mut self) -> &mut E {
+        self.encoder
+    }
+}
+
+impl<'a, E: DebugGroups> Drop for CaptureGroup<'a, E> {
+    fn drop(&mut self) {
+        if self.pushed {
+            self.encoder.pop_debug_group();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Groups(Vec<String>);
+
+    impl DebugGroups for Groups {
+        fn push_debug_group(&mut self, label: &str) {
+            self.0.push(format!("push {}", label));
+        }
+
+        fn pop_debug_group(&mut self) {
+            self.0.push("pop".to_string());
+        }
+    }
+
+    #[test]
+    fn group_labeled_frames() {
+        let clock = FrameClock::new();
+        clock.label_capture(1, "renderdoc-rs scheduled capture frame 1".to_string());
+        let mut groups = Groups::default();
+
+        assert!(!capture_group(&clock, &mut groups).is_capturing());
+        clock.tick();
+        {
+            let mut group = capture_group(&clock, &mut groups);
+            assert!(group.is_capturing());
+            group.push_debug_group("Shadow pass");
+            group.pop_debug_group();
+        }
+        clock.tick();
+        assert!(!capture_group(&clock, &mut groups).is_capturing());
+
+        assert_eq!(
+            groups.0,
+            vec![
+                "push renderdoc-rs scheduled capture frame 1",
+                "push Shadow pass",
+                "pop",
+                "pop",
+            ]
+        );
+    }
+}
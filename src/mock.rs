@@ -0,0 +1,277 @@
+//! In-memory stand-in for the RenderDoc API.
+//!
+//! `MockRenderDoc` implements `dynamic::RenderDocApi` without touching the
+//! RenderDoc library. Every call is appended to an inspectable log, and the
+//! handle keeps enough state (capture options, overlay bits, captures) to
+//! behave plausibly, so code managing captures can be tested on machines
+//! without a GPU or a RenderDoc install.
+//!
+//! Requires the `mock` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use renderdoc::dynamic::RenderDocApi;
+//! use renderdoc::mock::{Call, MockRenderDoc};
+//!
+//! let rd = MockRenderDoc::new();
+//! rd.trigger_capture().unwrap();
+//!
+//! assert_eq!(rd.calls(), vec![Call::TriggerCapture]);
+//! assert_eq!(rd.get_num_captures(), Ok(1));
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use dynamic::RenderDocApi;
+pub use record::Call;
+use {ApiVersionNumber, CaptureOption, DevicePointer, Error, InputButton, OverlayBits, WindowHandle};
+
+#[derive(Debug)]
+struct State {
+    calls: Vec<Call>,
+    options: HashMap<CaptureOption, f32>,
+    focus_toggle_keys: Vec<InputButton>,
+    capture_keys: Vec<InputButton>,
+    overlay: OverlayBits,
+    template: PathBuf,
+    capturing: bool,
+    captures: Vec<(String, u64)>,
+}
+
+/// A recording implementation of `RenderDocApi`.
+///
+/// Capture options start out with RenderDoc's defaults. Triggered and
+/// completed frame captures are added to the capture list with a path derived
+/// from the capture template.
+#[derive(Debug)]
+pub struct MockRenderDoc {
+    version: ApiVersionNumber,
+    state: Mutex<State>,
+}
+
+impl MockRenderDoc {
+    /// Creates a mock reporting API version 1.1.0.
+    pub fn new() -> Self {
+        MockRenderDoc::with_version(ApiVersionNumber::new(1, 1, 0))
+    }
+
+    /// Creates a mock reporting the given API version.
+    pub fn with_version(version: ApiVersionNumber) -> Self {
+        let mut options = HashMap::new();
+        options.insert(CaptureOption::AllowVSync, 1.0);
+        options.insert(CaptureOption::AllowFullscreen, 1.0);
+        options.insert(CaptureOption::DebugOutputMute, 1.0);
+
+        MockRenderDoc {
+            version,
+            state: Mutex::new(State {
+                calls: Vec::new(),
+                options,
+                focus_toggle_keys: vec![InputButton::F11],
+                capture_keys: vec![InputButton::F12, InputButton::PrtScrn],
+                overlay: OverlayBits::DEFAULT,
+                template: PathBuf::from("mock_capture"),
+                capturing: false,
+                captures: Vec::new(),
+            }),
+        }
+    }
+
+    /// Returns all calls made so far, in order.
+    pub fn calls(&self) -> Vec<Call> {
+        self.state().calls.clone()
+    }
+
+    /// Returns and clears all calls made so far.
+    pub fn take_calls(&self) -> Vec<Call> {
+        let mut state = self.state();
+        state.calls.drain(..).collect()
+    }
+
+    /// Returns how many recorded calls match `pred`.
+    pub fn count<F: Fn(&Call) -> bool>(&self, pred: F) -> usize {
+        self.state().calls.iter().filter(|call| pred(call)).count()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // A panicking test shouldn't poison the log for the remaining ones.
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn record(&self, call: Call) -> MutexGuard<'_, State> {
+        let mut state = self.state();
+        state.calls.push(call);
+        state
+    }
+}
+
+impl Default for MockRenderDoc {
+    fn default() -> Self {
+        MockRenderDoc::new()
+    }
+}
+
+impl State {
+    fn add_capture(&mut self) {
+        let path = format!("{}_frame{}.rdc", self.template.display(), self.captures.len() + 1);
+        let timestamp = self.captures.len() as u64;
+        self.captures.push((path, timestamp));
+    }
+}
+
+impl RenderDocApi for MockRenderDoc {
+    fn get_api_version(&self) -> Result<ApiVersionNumber, Error> {
+        drop(self.record(Call::GetApiVersion));
+        Ok(self.version)
+    }
+
+    fn set_capture_option_f32(&self, opt: CaptureOption, val: f32) -> Result<(), Error> {
+        let mut state = self.record(Call::SetCaptureOptionF32(opt, val));
+        state.options.insert(opt, val);
+        Ok(())
+    }
+
+    fn set_capture_option_u32(&self, opt: CaptureOption, val: u32) -> Result<(), Error> {
+        let mut state = self.record(Call::SetCaptureOptionU32(opt, val));
+        state.options.insert(opt, val as f32);
+        Ok(())
+    }
+
+    fn get_capture_option_f32(&self, opt: CaptureOption) -> Result<f32, Error> {
+        let state = self.record(Call::GetCaptureOptionF32(opt));
+        Ok(state.options.get(&opt).cloned().unwrap_or(0.0))
+    }
+
+    fn get_capture_option_u32(&self, opt: CaptureOption) -> Result<u32, Error> {
+        let state = self.record(Call::GetCaptureOptionU32(opt));
+        Ok(state.options.get(&opt).cloned().unwrap_or(0.0) as u32)
+    }
+
+    fn set_focus_toggle_keys(&self, keys: &[InputButton]) -> Result<(), Error> {
+        self.record(Call::SetFocusToggleKeys(keys.to_vec())).focus_toggle_keys = keys.to_vec();
+        Ok(())
+    }
+
+    fn set_capture_keys(&self, keys: &[InputButton]) -> Result<(), Error> {
+        self.record(Call::SetCaptureKeys(keys.to_vec())).capture_keys = keys.to_vec();
+        Ok(())
+    }
+
+    fn get_overlay_bits(&self) -> Result<OverlayBits, Error> {
+        Ok(self.record(Call::GetOverlayBits).overlay)
+    }
+
+    fn mask_overlay_bits(&self, and: OverlayBits, or: OverlayBits) -> Result<(), Error> {
+        let mut state = self.record(Call::MaskOverlayBits(and, or));
+        state.overlay = (state.overlay & and) | or;
+        Ok(())
+    }
+
+    fn unload_crash_handler(&self) -> Result<(), Error> {
+        drop(self.record(Call::UnloadCrashHandler));
+        Ok(())
+    }
+
+    fn get_capture_file_path_template(&self) -> Result<PathBuf, Error> {
+        Ok(self.record(Call::GetCaptureFilePathTemplate).template.clone())
+    }
+
+    fn set_capture_file_path_template(&self, path_template: &Path) -> Result<(), Error> {
+        let call = Call::SetCaptureFilePathTemplate(path_template.to_path_buf());
+        self.record(call).template = path_template.to_path_buf();
+        Ok(())
+    }
+
+    fn get_num_captures(&self) -> Result<u32, Error> {
+        Ok(self.record(Call::GetNumCaptures).captures.len() as u32)
+    }
+
+    fn get_capture(&self, index: u32) -> Result<Option<(String, u64)>, Error> {
+        let state = self.record(Call::GetCapture(index));
+        Ok(state.captures.get(index as usize).cloned())
+    }
+
+    fn trigger_capture(&self) -> Result<(), Error> {
+        self.record(Call::TriggerCapture).add_capture();
+        Ok(())
+    }
+
+    fn is_target_control_connected(&self) -> Result<bool, Error> {
+        drop(self.record(Call::IsTargetControlConnected));
+        Ok(false)
+    }
+
+    fn set_active_window(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error> {
+        drop(self.record(Call::SetActiveWindow {
+            device: *dev as usize,
+            window: win.as_raw() as usize,
+        }));
+        Ok(())
+    }
+
+    fn start_frame_capture(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error> {
+        let mut state = self.record(Call::StartFrameCapture {
+            device: *dev as usize,
+            window: win.as_raw() as usize,
+        });
+        state.capturing = true;
+        Ok(())
+    }
+
+    fn is_frame_capturing(&self) -> Result<bool, Error> {
+        Ok(self.record(Call::IsFrameCapturing).capturing)
+    }
+
+    fn end_frame_capture(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error> {
+        let mut state = self.record(Call::EndFrameCapture {
+            device: *dev as usize,
+            window: win.as_raw() as usize,
+        });
+        if state.capturing {
+            state.capturing = false;
+            state.add_capture();
+        }
+        Ok(())
+    }
+
+    fn trigger_multi_frame_capture(&self, num_frames: u32) -> Result<(), Error> {
+        let mut state = self.record(Call::TriggerMultiFrameCapture(num_frames));
+        for _ in 0..num_frames {
+            state.add_capture();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_calls_and_state() {
+        let rd = MockRenderDoc::new();
+        let api: &dyn RenderDocApi = &rd;
+
+        assert_eq!(api.get_capture_option_u32(CaptureOption::AllowVSync), Ok(1));
+        api.set_capture_option_u32(CaptureOption::AllowVSync, 0).unwrap();
+        assert_eq!(api.get_capture_option_u32(CaptureOption::AllowVSync), Ok(0));
+
+        api.mask_overlay_bits(OverlayBits::NONE, OverlayBits::ENABLED).unwrap();
+        assert_eq!(api.get_overlay_bits(), Ok(OverlayBits::ENABLED));
+
+        let device = DevicePointer::null();
+        api.start_frame_capture(device.clone(), WindowHandle::null()).unwrap();
+        assert_eq!(api.is_frame_capturing(), Ok(true));
+        api.end_frame_capture(device, WindowHandle::null()).unwrap();
+        assert_eq!(api.is_frame_capturing(), Ok(false));
+
+        assert_eq!(api.get_num_captures(), Ok(1));
+        assert_eq!(api.get_capture(0).unwrap().unwrap().0, "mock_capture_frame1.rdc");
+        assert_eq!(rd.count(|call| *call == Call::IsFrameCapturing), 2);
+        assert_eq!(rd.take_calls().len(), 11);
+        assert!(rd.calls().is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+//! Parsing the names of keys, capture options and overlay bits.
+//!
+//! Names are matched case-insensitively, ignoring `_` and `-`, so that
+//! `CaptureCallstacks`, `capture_callstacks` and `capture-callstacks` are all
+//! accepted.
+
+use {CaptureOption, InputButton, OverlayBits};
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !['_', '-'].contains(c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Parses a key name such as `F12`, `A`, `1` or `PrtScrn`.
+#[cfg_attr(not(any(feature = "config", feature = "record")), allow(dead_code))]
+pub(crate) fn input_button(name: &str) -> Option<InputButton> {
+    use InputButton::*;
+
+    let name = normalize(name);
+    let name = name.trim_start_matches("key");
+    let button = match name {
+        "0" => Key0,
+        "1" => Key1,
+        "2" => Key2,
+        "3" => Key3,
+        "4" => Key4,
+        "5" => Key5,
+        "6" => Key6,
+        "7" => Key7,
+        "8" => Key8,
+        "9" => Key9,
+        "a" => A,
+        "b" => B,
+        "c" => C,
+        "d" => D,
+        "e" => E,
+        "f" => F,
+        "g" => G,
+        "h" => H,
+        "i" => I,
+        "j" => J,
+        "k" => K,
+        "l" => L,
+        "m" => M,
+        "n" => N,
+        "o" => O,
+        "p" => P,
+        "q" => Q,
+        "r" => R,
+        "s" => S,
+        "t" => T,
+        "u" => U,
+        "v" => V,
+        "w" => W,
+        "x" => X,
+        "y" => Y,
+        "z" => Z,
+        "nonprintable" => NonPrintable,
+        "divide" => Divide,
+        "multiply" => Multiply,
+        "subtract" => Subtract,
+        "plus" => Plus,
+        "f1" => F1,
+        "f2" => F2,
+        "f3" => F3,
+        "f4" => F4,
+        "f5" => F5,
+        "f6" => F6,
+        "f7" => F7,
+        "f8" => F8,
+        "f9" => F9,
+        "f10" => F10,
+        "f11" => F11,
+        "f12" => F12,
+        "home" => Home,
+        "end" => End,
+        "insert" => Insert,
+        "delete" => Delete,
+        "pageup" => PageUp,
+        "pagedn" | "pagedown" => PageDn,
+        "backspace" => Backspace,
+        "tab" => Tab,
+        "prtscrn" | "printscreen" => PrtScrn,
+        "pause" => Pause,
+        _ => return None,
+    };
+    Some(button)
+}
+
+/// Parses a capture option name such as `ApiValidation` or `vsync`.
+pub(crate) fn capture_option(name: &str) -> Option<CaptureOption> {
+    use CaptureOption::*;
+
+    let option = match normalize(name).as_str() {
+        "allowvsync" | "vsync" => AllowVSync,
+        "allowfullscreen" | "fullscreen" => AllowFullscreen,
+        "apivalidation" | "debugdevicemode" => ApiValidation,
+        "capturecallstacks" | "callstacks" => CaptureCallstacks,
+        "capturecallstacksonlydraws" | "callstacksonlydraws" => CaptureCallstacksOnlyDraws,
+        "delayfordebugger" => DelayForDebugger,
+        "verifymapwrites" => VerifyMapWrites,
+        "hookintochildren" => HookIntoChildren,
+        "refallresources" => RefAllResources,
+        "saveallinitials" => SaveAllInitials,
+        "captureallcmdlists" => CaptureAllCmdLists,
+        "debugoutputmute" => DebugOutputMute,
+        _ => return None,
+    };
+    Some(option)
+}
+
+/// Parses a single overlay flag such as `frame_rate`, or one of `none`,
+/// `default` and `all`.
+pub(crate) fn overlay_flag(name: &str) -> Option<OverlayBits> {
+    let bits = match normalize(name).as_str() {
+        "none" => OverlayBits::NONE,
+        "default" => OverlayBits::DEFAULT,
+        "all" => OverlayBits::ALL,
+        "enabled" => OverlayBits::ENABLED,
+        "framerate" => OverlayBits::FRAME_RATE,
+        "framenumber" => OverlayBits::FRAME_NUMBER,
+        "capturelist" => OverlayBits::CAPTURE_LIST,
+        _ => return None,
+    };
+    Some(bits)
+}
+
+/// Parses overlay flags separated by `|` or `,`, e.g. `enabled|frame_rate`.
+pub(crate) fn overlay_bits(spec: &str) -> Option<OverlayBits> {
+    spec.split(['|', ','])
+        .map(str::trim)
+        .try_fold(OverlayBits::NONE, |bits, name| overlay_flag(name).map(|flag| bits | flag))
+}
+
+/// Parses an option value, which is either a number or a boolean.
+pub(crate) fn option_value(value: &str) -> Option<u32> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "on" | "yes" => Some(1),
+        "false" | "off" | "no" => Some(0),
+        value => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(input_button("F12"), Some(InputButton::F12));
+        assert_eq!(input_button("key_1"), Some(InputButton::Key1));
+        assert_eq!(input_button("Print-Screen"), Some(InputButton::PrtScrn));
+        assert_eq!(input_button("F13"), None);
+
+        assert_eq!(capture_option("api_validation"), Some(CaptureOption::ApiValidation));
+        assert_eq!(capture_option("VSync"), Some(CaptureOption::AllowVSync));
+        assert_eq!(capture_option("vsnyc"), None);
+
+        assert_eq!(overlay_flag("Frame_Rate"), Some(OverlayBits::FRAME_RATE));
+        assert_eq!(overlay_flag("all"), Some(OverlayBits::ALL));
+        assert_eq!(overlay_flag("bogus"), None);
+
+        assert_eq!(
+            overlay_bits("enabled | frame_rate"),
+            Some(OverlayBits::ENABLED | OverlayBits::FRAME_RATE)
+        );
+        assert_eq!(overlay_bits("none"), Some(OverlayBits::NONE));
+        assert_eq!(overlay_bits("enabled,bogus"), None);
+
+        assert_eq!(option_value("On"), Some(1));
+        assert_eq!(option_value("5"), Some(5));
+        assert_eq!(option_value("maybe"), None);
+    }
+}
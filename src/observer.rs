@@ -0,0 +1,149 @@
+//! Notifying the application of capture activity.
+
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use CaptureOption;
+
+/// Receives notifications about captures made through this crate.
+///
+/// Registered with `RenderDoc::set_observer()`, so engine subsystems such as
+/// asset streaming or telemetry can react to captures without polling. All
+/// methods do nothing by default.
+///
+/// Notifications are sent for calls made through any handle, from the thread
+/// making the call. Only frame captures started and ended through this crate
+/// are reported; captures triggered with `trigger_capture()` or by hotkeys
+/// are made by RenderDoc itself on the next present.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use std::path::Path;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// # use renderdoc::{CaptureObserver, RenderDoc, V110};
+/// struct PauseStreaming(AtomicBool);
+///
+/// impl CaptureObserver for PauseStreaming {
+///     fn on_capture_started(&self) {
+///         self.0.store(true, Ordering::SeqCst);
+///     }
+///
+///     fn on_capture_ended(&self, _path: Option<&Path>) {
+///         self.0.store(false, Ordering::SeqCst);
+///     }
+///
+///     fn on_capture_discarded(&self) {
+///         self.0.store(false, Ordering::SeqCst);
+///     }
+/// }
+///
+/// # fn init() -> Result<(), renderdoc::Error> {
+/// let rd: RenderDoc<V110> = RenderDoc::new()?;
+/// rd.set_observer(PauseStreaming(AtomicBool::new(false)));
+/// # Ok(())
+/// # }
+/// ```
+pub trait CaptureObserver: Send + Sync {
+    /// Called right before a frame capture is started.
+    fn on_capture_started(&self) {}
+
+    /// Called after a frame capture was written, with the path of the capture
+    /// file if RenderDoc reports it.
+    fn on_capture_ended(&self, _path: Option<&Path>) {}
+
+    /// Called after a frame capture was ended without writing a capture file.
+    fn on_capture_discarded(&self) {}
+
+    /// Called after a capture option was changed.
+    fn on_options_changed(&self, _opt: CaptureOption) {}
+}
+
+lazy_static! {
+    static ref OBSERVER: RwLock<Option<Arc<dyn CaptureObserver>>> = RwLock::new(None);
+}
+
+/// Replaces the registered observer.
+pub(crate) fn set(observer: Option<Arc<dyn CaptureObserver>>) {
+    *OBSERVER.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = observer;
+}
+
+/// Returns whether an observer is registered.
+pub(crate) fn is_set() -> bool {
+    OBSERVER.read().unwrap_or_else(|poisoned| poisoned.into_inner()).is_some()
+}
+
+/// Calls `f` with the registered observer, if any.
+///
+/// The lock is released before calling `f`, so observers may replace
+/// themselves.
+fn notify<F: FnOnce(&dyn CaptureObserver)>(f: F) {
+    let observer = OBSERVER.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    if let Some(observer) = observer {
+        f(&*observer);
+    }
+}
+
+pub(crate) fn capture_started() {
+    notify(|observer| observer.on_capture_started());
+}
+
+pub(crate) fn capture_ended(ok: bool, path: Option<&str>) {
+    if ok {
+        notify(|observer| observer.on_capture_ended(path.map(Path::new)));
+    } else {
+        notify(|observer| observer.on_capture_discarded());
+    }
+}
+
+pub(crate) fn options_changed(opt: CaptureOption) {
+    notify(|observer| observer.on_options_changed(opt));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Events(Mutex<Vec<String>>);
+
+    impl CaptureObserver for Arc<Events> {
+        fn on_capture_started(&self) {
+            self.0.lock().unwrap().push("started".into());
+        }
+
+        fn on_capture_ended(&self, path: Option<&Path>) {
+            self.0.lock().unwrap().push(format!("ended {:?}", path));
+        }
+
+        fn on_options_changed(&self, opt: CaptureOption) {
+            // Replacing the observer from a callback must not deadlock.
+            set(None);
+            self.0.lock().unwrap().push(format!("changed {:?}", opt));
+        }
+    }
+
+    #[test]
+    fn notify_registered_observer() {
+        let events = Arc::new(Events::default());
+        set(Some(Arc::new(events.clone())));
+        assert!(is_set());
+
+        capture_started();
+        capture_ended(true, Some("captures/a.rdc"));
+        capture_ended(false, None);
+        options_changed(CaptureOption::AllowVSync);
+        options_changed(CaptureOption::AllowVSync);
+
+        assert!(!is_set());
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            vec![
+                "started".to_string(),
+                "ended Some(\"captures/a.rdc\")".to_string(),
+                "changed AllowVSync".to_string(),
+            ]
+        );
+    }
+}
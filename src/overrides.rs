@@ -0,0 +1,135 @@
+//! Overriding capture settings through environment variables.
+
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use names;
+use settings::Settings;
+use {CaptureOption, OverlayBits};
+
+/// Environment variable which, if set, overrides the capture path template.
+///
+/// Like the other `RENDERDOC_RS_*` variables, it is applied on top of the
+/// settings of `Builder` and `config::load()`, so e.g. CI jobs can change
+/// capture behavior without rebuilding.
+pub const CAPTURE_PATH_ENV_VAR: &str = "RENDERDOC_RS_CAPTURE_PATH";
+
+/// Environment variable which, if set, overrides capture options.
+///
+/// Holds a comma-separated list of `name=value` pairs, such as
+/// `apivalidation=1,vsync=0`. Option names are matched case-insensitively and
+/// ignore `_` and `-`, and values are numbers or booleans.
+pub const OPTIONS_ENV_VAR: &str = "RENDERDOC_RS_OPTIONS";
+
+/// Environment variable which, if set, overrides the overlay configuration
+/// bits.
+///
+/// Holds overlay flags separated by `|` or `,`, such as
+/// `enabled|frame_number`, or one of `none`, `default` and `all`.
+pub const OVERLAY_ENV_VAR: &str = "RENDERDOC_RS_OVERLAY";
+
+/// Capture settings read from the `RENDERDOC_RS_*` environment variables.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Overrides {
+    pub(crate) options: Vec<(CaptureOption, u32)>,
+    pub(crate) overlay: Option<OverlayBits>,
+    pub(crate) capture_path_template: Option<PathBuf>,
+}
+
+impl Overrides {
+    /// Reads the overrides of this process' environment.
+    ///
+    /// Returns a description of the first invalid variable, if any.
+    pub(crate) fn from_env() -> Result<Overrides, String> {
+        Overrides::from_vars(|name| env::var_os(name))
+    }
+
+    fn from_vars<F>(var: F) -> Result<Overrides, String>
+    where
+        F: Fn(&str) -> Option<OsString>,
+    {
+        let string_var = |name: &str| match var(name) {
+            Some(value) => value
+                .into_string()
+                .map(Some)
+                .map_err(|_| format!("{} is not valid Unicode", name)),
+            None => Ok(None),
+        };
+
+        let mut options = Vec::new();
+        if let Some(spec) = string_var(OPTIONS_ENV_VAR)? {
+            for pair in spec.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+                let invalid = || format!("{}: invalid option `{}`", OPTIONS_ENV_VAR, pair);
+                let mut parts = pair.splitn(2, '=');
+                let opt = parts.next().and_then(names::capture_option).ok_or_else(invalid)?;
+                let val = parts.next().and_then(names::option_value).ok_or_else(invalid)?;
+                options.push((opt, val));
+            }
+        }
+
+        let overlay = match string_var(OVERLAY_ENV_VAR)? {
+            Some(spec) => Some(
+                names::overlay_bits(&spec)
+                    .ok_or_else(|| format!("{}: invalid overlay flags `{}`", OVERLAY_ENV_VAR, spec))?,
+            ),
+            None => None,
+        };
+
+        Ok(Overrides {
+            options,
+            overlay,
+            capture_path_template: var(CAPTURE_PATH_ENV_VAR).map(PathBuf::from),
+        })
+    }
+
+    /// Layers the overrides on top of `settings`, setting their capture
+    /// options last and replacing the overlay and capture path template.
+    pub(crate) fn layer_onto<'a>(&'a self, settings: &mut Settings<'a>) {
+        for &(opt, _) in &self.options {
+            log_debug!("Overriding capture option {:?} from the environment", opt);
+        }
+        settings.extend_u32_options(&self.options);
+        if self.overlay.is_some() {
+            settings.overlay_bits = self.overlay;
+        }
+        if let Some(ref path_template) = self.capture_path_template {
+            settings.capture_path_template = Some(path_template);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn parse(vars: &[(&str, &str)]) -> Result<Overrides, String> {
+        let vars: HashMap<_, _> = vars.iter().cloned().collect();
+        Overrides::from_vars(|name| vars.get(name).map(OsString::from))
+    }
+
+    #[test]
+    fn parse_overrides() {
+        assert_eq!(parse(&[]), Ok(Overrides::default()));
+        assert_eq!(
+            parse(&[
+                (OPTIONS_ENV_VAR, "apivalidation=1, vsync=off,"),
+                (OVERLAY_ENV_VAR, "none"),
+                (CAPTURE_PATH_ENV_VAR, "ci/captures/job"),
+            ]),
+            Ok(Overrides {
+                options: vec![(CaptureOption::ApiValidation, 1), (CaptureOption::AllowVSync, 0)],
+                overlay: Some(OverlayBits::NONE),
+                capture_path_template: Some(PathBuf::from("ci/captures/job")),
+            })
+        );
+
+        assert_eq!(
+            parse(&[(OPTIONS_ENV_VAR, "vsync")]),
+            Err("RENDERDOC_RS_OPTIONS: invalid option `vsync`".to_string())
+        );
+        assert!(parse(&[(OPTIONS_ENV_VAR, "vsnyc=0")]).is_err());
+        assert!(parse(&[(OVERLAY_ENV_VAR, "enabled|bogus")]).is_err());
+    }
+}
@@ -1,4 +1,26 @@
 //! Contains common types that can be glob-imported (`*`) for convenience.
+//!
+//! Besides the handle and option types, this brings the version traits into
+//! scope, both under their own names and as `MinV100`/`MinV110`. The latter
+//! read naturally as bounds of functions which only need some minimum API
+//! version, and accept any handle providing it:
+//!
+//! ```rust
+//! use renderdoc::prelude::*;
+//!
+//! fn debug_tools<R: MinV110>(rd: &R) -> Result<(), renderdoc::Error> {
+//!     rd.mask_overlay_bits(OverlayBits::ALL, OverlayBits::CAPTURE_LIST)?;
+//!     rd.trigger_multi_frame_capture(3)
+//! }
+//!
+//! # fn run(rd: &RenderDoc<V110>) -> Result<(), renderdoc::Error> {
+//! debug_tools(rd)?;
+//! # Ok(())
+//! # }
+//! ```
 
 pub use api::{RenderDocV100, RenderDocV110};
-pub use entry::version::ApiVersion;
+pub use api::{RenderDocV100 as MinV100, RenderDocV110 as MinV110};
+pub use entry::version::{ApiVersion, V100, V110, V111};
+pub use {CaptureOption, DevicePointer, InputButton, OverlayBits, RenderDoc, RenderDocHandle,
+         WindowHandle};
@@ -0,0 +1,224 @@
+//! Wire format shared by RenderDoc's network protocols.
+//!
+//! Both the target control and the remote server protocols exchange packets
+//! following RenderDoc's chunk serialization: each packet starts with a `u32`
+//! packet type and a `u32` payload length. Payload fields are little-endian,
+//! strings are `u32` length-prefixed UTF-8 and byte arrays are `u64`
+//! length-prefixed.
+
+use std::error;
+use std::fmt;
+use std::io::{self, Read};
+
+/// Largest packet payload that will be accepted, to guard against garbage.
+pub(crate) const MAX_PAYLOAD_LEN: u32 = 512 * 1024 * 1024;
+
+/// Errors that can occur while talking to RenderDoc over the network.
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error occurred on the underlying connection.
+    Io(io::Error),
+    /// The remote end is already in use by the named client.
+    Busy(String),
+    /// The remote end speaks an incompatible protocol version.
+    VersionMismatch(u32),
+    /// An unexpected packet type was received.
+    UnexpectedPacket(u32),
+    /// A packet could not be decoded.
+    Malformed(&'static str),
+    /// The remote end reported a failure with the given status code.
+    Remote(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+            Error::Busy(ref client) => write!(f, "Target is busy with client: {}", client),
+            Error::VersionMismatch(ver) => write!(f, "Unsupported protocol version: {}", ver),
+            Error::UnexpectedPacket(ty) => write!(f, "Unexpected packet type: {}", ty),
+            Error::Malformed(what) => write!(f, "Malformed packet: {}", what),
+            Error::Remote(status) => write!(f, "Remote operation failed with status: {}", status),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+/// Builds the payload of a single packet.
+#[derive(Debug, Default)]
+pub(crate) struct PacketWriter {
+    buf: Vec<u8>,
+}
+
+impl PacketWriter {
+    pub fn new() -> PacketWriter {
+        PacketWriter::default()
+    }
+
+    pub fn u32(mut self, val: u32) -> Self {
+        self.buf.extend_from_slice(&val.to_le_bytes());
+        self
+    }
+
+    pub fn u64(mut self, val: u64) -> Self {
+        self.buf.extend_from_slice(&val.to_le_bytes());
+        self
+    }
+
+    pub fn bool(mut self, val: bool) -> Self {
+        self.buf.push(val as u8);
+        self
+    }
+
+    pub fn str(self, val: &str) -> Self {
+        let mut this = self.u32(val.len() as u32);
+        this.buf.extend_from_slice(val.as_bytes());
+        this
+    }
+
+    pub fn bytes(self, val: &[u8]) -> Self {
+        let mut this = self.u64(val.len() as u64);
+        this.buf.extend_from_slice(val);
+        this
+    }
+
+    /// Encodes the packet with its header, ready to be sent.
+    pub fn finish(self, ty: u32) -> Vec<u8> {
+        let mut packet = Vec::with_capacity(8 + self.buf.len());
+        packet.extend_from_slice(&ty.to_le_bytes());
+        packet.extend_from_slice(&(self.buf.len() as u32).to_le_bytes());
+        packet.extend_from_slice(&self.buf);
+        packet
+    }
+}
+
+/// Decodes the payload of a single packet.
+#[derive(Debug)]
+pub(crate) struct PacketReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> PacketReader<'a> {
+    pub fn new(data: &'a [u8]) -> PacketReader<'a> {
+        PacketReader { data }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::Malformed("unexpected end of packet"));
+        }
+
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        let mut raw = [0u8; 4];
+        raw.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(raw))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        let mut raw = [0u8; 8];
+        raw.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(raw))
+    }
+
+    pub fn f32(&mut self) -> Result<f32, Error> {
+        self.u32().map(f32::from_bits)
+    }
+
+    pub fn bool(&mut self) -> Result<bool, Error> {
+        Ok(self.take(1)?[0] != 0)
+    }
+
+    pub fn bytes(&mut self) -> Result<Vec<u8>, Error> {
+        let len = self.u64()?;
+        if len > self.data.len() as u64 {
+            return Err(Error::Malformed("unexpected end of packet"));
+        }
+        self.take(len as usize).map(<[u8]>::to_vec)
+    }
+
+    pub fn string(&mut self) -> Result<String, Error> {
+        let len = self.u32()? as usize;
+        let raw = self.take(len)?;
+        String::from_utf8(raw.to_vec()).map_err(|_| Error::Malformed("invalid UTF-8 string"))
+    }
+}
+
+/// Reads a single packet, returning its type and payload.
+pub(crate) fn read_packet<R: Read>(stream: &mut R) -> Result<(u32, Vec<u8>), Error> {
+    let (ty, len) = read_packet_header(stream)?;
+    let payload = read_payload(stream, len)?;
+    Ok((ty, payload))
+}
+
+/// Reads the header of a packet, returning its type and payload length.
+pub(crate) fn read_packet_header<R: Read>(stream: &mut R) -> Result<(u32, u32), Error> {
+    let mut header = [0u8; 8];
+    stream.read_exact(&mut header)?;
+
+    let mut reader = PacketReader::new(&header);
+    Ok((reader.u32()?, reader.u32()?))
+}
+
+/// Reads a payload of `len` bytes following a packet header.
+pub(crate) fn read_payload<R: Read>(stream: &mut R, len: u32) -> Result<Vec<u8>, Error> {
+    if len > MAX_PAYLOAD_LEN {
+        return Err(Error::Malformed("payload too large"));
+    }
+
+    let mut payload = vec![0u8; len as usize];
+    stream.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packet_round_trip() {
+        let packet = PacketWriter::new()
+            .u32(7)
+            .u64(1 << 40)
+            .bool(true)
+            .str("capture.rdc")
+            .finish(42);
+
+        let (ty, payload) = read_packet(&mut &packet[..]).unwrap();
+        assert_eq!(ty, 42);
+
+        let mut reader = PacketReader::new(&payload);
+        assert_eq!(reader.u32().unwrap(), 7);
+        assert_eq!(reader.u64().unwrap(), 1 << 40);
+        assert!(reader.bool().unwrap());
+        assert_eq!(reader.string().unwrap(), "capture.rdc");
+    }
+
+    #[test]
+    fn truncated_packet_is_malformed() {
+        let packet = PacketWriter::new().str("truncated").finish(1);
+        let mut reader = PacketReader::new(&packet[8..12]);
+        match reader.string() {
+            Err(Error::Malformed(_)) => (),
+            other => panic!("expected malformed packet, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Decompression of section data.
+//!
+//! RenderDoc compresses sections in blocks, each stored as its compressed
+//! size followed by the compressed bytes. LZ4 blocks decompress to at most
+//! 64 KiB and each uses the previous block as its dictionary, while every
+//! Zstandard block is a complete frame of its own.
+
+use std::cmp;
+use std::io::{self, Read};
+
+#[cfg(feature = "lz4")]
+use lz4_flex;
+#[cfg(feature = "zstd")]
+use ruzstd;
+
+use super::{read_u32, Error, Section, SectionFlags};
+
+/// Size of the data compressed into a single LZ4 block.
+#[cfg(feature = "lz4")]
+const LZ4_BLOCK_SIZE: u64 = 64 * 1024;
+
+#[derive(Clone, Copy, Debug)]
+enum Codec {
+    Stored,
+    #[cfg(feature = "lz4")]
+    Lz4,
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+/// Reader decompressing the data of a section.
+///
+/// LZ4-compressed sections require the `lz4` feature and Zstandard-compressed
+/// ones the `zstd` feature. Both decoders are written in pure Rust.
+#[derive(Debug)]
+pub struct Decompressor<R> {
+    inner: R,
+    codec: Codec,
+    block: Vec<u8>,
+    pos: usize,
+    remaining: u64,
+}
+
+impl<R: Read> Decompressor<R> {
+    /// Decompresses the data of `section`, read from `inner` as it is stored
+    /// in the file.
+    ///
+    /// Fails with `Error::UnsupportedCompression` if the section is compressed
+    /// with an algorithm whose feature isn't enabled.
+    pub fn new(inner: R, section: &Section) -> Result<Self, Error> {
+        let codec = if section.flags.contains(SectionFlags::LZ4_COMPRESSED) {
+            lz4_codec()?
+        } else if section.flags.contains(SectionFlags::ZSTD_COMPRESSED) {
+            zstd_codec()?
+        } else {
+            Codec::Stored
+        };
+
+        Ok(Decompressor {
+            inner,
+            codec,
+            block: Vec::new(),
+            pos: 0,
+            remaining: section.uncompressed_len,
+        })
+    }
+
+    /// Returns the number of decompressed bytes left to read.
+    pub fn remaining(&self) -> u64 {
+        self.remaining + (self.block.len() - self.pos) as u64
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Decompresses the next block, replacing the current one.
+    ///
+    /// The section data is already known to lie within the file, so
+    /// compressed data ending early means it is corrupt, not truncated.
+    fn next_block(&mut self) -> io::Result<()> {
+        let ended_early = || corrupt("compressed data ends early");
+        let compressed_len = read_u32(&mut self.inner).map_err(|err| match err.kind() {
+            io::ErrorKind::UnexpectedEof => ended_early(),
+            _ => err,
+        })?;
+        let mut compressed = Vec::new();
+        (&mut self.inner)
+            .take(u64::from(compressed_len))
+            .read_to_end(&mut compressed)?;
+        if compressed.len() != compressed_len as usize {
+            return Err(ended_early());
+        }
+
+        let block = match self.codec {
+            // Stored data is read directly instead.
+            Codec::Stored => Vec::new(),
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => {
+                let len = cmp::min(self.remaining, LZ4_BLOCK_SIZE) as usize;
+                lz4_flex::block::decompress_with_dict(&compressed, len, &self.block).map_err(corrupt)?
+            }
+            #[cfg(feature = "zstd")]
+            Codec::Zstd => {
+                let mut block = Vec::new();
+                ruzstd::decoding::StreamingDecoder::new(&compressed[..])
+                    .map_err(corrupt)?
+                    .read_to_end(&mut block)
+                    .map_err(corrupt)?;
+                block
+            }
+        };
+
+        if block.is_empty() || block.len() as u64 > self.remaining {
+            return Err(corrupt("block size does not match the section size"));
+        }
+        self.remaining -= block.len() as u64;
+        self.block = block;
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decompressor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if matches!(self.codec, Codec::Stored) {
+            let limit = cmp::min(buf.len() as u64, self.remaining) as usize;
+            let read = self.inner.read(&mut buf[..limit])?;
+            self.remaining -= read as u64;
+            return Ok(read);
+        }
+
+        if self.pos == self.block.len() {
+            if self.remaining == 0 {
+                return Ok(0);
+            }
+            self.next_block()?;
+        }
+
+        let len = cmp::min(buf.len(), self.block.len() - self.pos);
+        buf[..len].copy_from_slice(&self.block[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "lz4")]
+fn lz4_codec() -> Result<Codec, Error> {
+    Ok(Codec::Lz4)
+}
+
+#[cfg(not(feature = "lz4"))]
+fn lz4_codec() -> Result<Codec, Error> {
+    Err(Error::UnsupportedCompression("LZ4"))
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_codec() -> Result<Codec, Error> {
+    Ok(Codec::Zstd)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_codec() -> Result<Codec, Error> {
+    Err(Error::UnsupportedCompression("Zstandard"))
+}
+
+fn corrupt<E: ToString>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SectionType;
+    use super::*;
+    use std::io::Cursor;
+
+    fn section(flags: SectionFlags, uncompressed_len: u64) -> Section {
+        Section {
+            kind: SectionType::FrameCapture,
+            name: "renderdoc/internal/framecapture".into(),
+            version: 1,
+            flags,
+            offset: 0,
+            compressed_len: 0,
+            uncompressed_len,
+        }
+    }
+
+    #[cfg(any(feature = "lz4", feature = "zstd"))]
+    fn sample_data() -> Vec<u8> {
+        (0..150_000u32).map(|i| (i % 251) as u8 ^ (i / 1000) as u8).collect()
+    }
+
+    #[cfg(any(feature = "lz4", feature = "zstd"))]
+    fn push_block(stream: &mut Vec<u8>, block: &[u8]) {
+        stream.extend_from_slice(&(block.len() as u32).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+
+    #[test]
+    fn stored_data_is_passed_through() {
+        let mut reader = Decompressor::new(Cursor::new(b"{}trailing"), &section(SectionFlags::ASCII_STORED, 2)).unwrap();
+        let mut data = String::new();
+        reader.read_to_string(&mut data).unwrap();
+        assert_eq!(data, "{}");
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn decompress_linked_lz4_blocks() {
+        let data = sample_data();
+        let mut stream = Vec::new();
+        let mut previous: &[u8] = &[];
+        for block in data.chunks(LZ4_BLOCK_SIZE as usize) {
+            push_block(&mut stream, &lz4_flex::block::compress_with_dict(block, previous));
+            previous = block;
+        }
+
+        let section = section(SectionFlags::LZ4_COMPRESSED, data.len() as u64);
+        let mut reader = Decompressor::new(Cursor::new(stream), &section).unwrap();
+        let mut decompressed = Vec::new();
+        reader.read_to_end(&mut decompressed).unwrap();
+        assert!(decompressed == data);
+        assert_eq!(reader.remaining(), 0);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn decompress_zstd_frames() {
+        use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+
+        let data = sample_data();
+        let mut stream = Vec::new();
+        for block in data.chunks(128 * 1024) {
+            push_block(&mut stream, &compress_to_vec(block, CompressionLevel::Fastest));
+        }
+
+        let section = section(SectionFlags::ZSTD_COMPRESSED, data.len() as u64);
+        let mut reader = Decompressor::new(Cursor::new(stream), &section).unwrap();
+        let mut decompressed = Vec::new();
+        reader.read_to_end(&mut decompressed).unwrap();
+        assert!(decompressed == data);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn reject_corrupt_block() {
+        let mut stream = Vec::new();
+        push_block(&mut stream, b"not a zstd frame");
+        let section = section(SectionFlags::ZSTD_COMPRESSED, 100);
+        let mut reader = Decompressor::new(Cursor::new(stream), &section).unwrap();
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[cfg(not(feature = "lz4"))]
+    #[test]
+    fn require_lz4_feature() {
+        match Decompressor::new(Cursor::new(Vec::new()), &section(SectionFlags::LZ4_COMPRESSED, 1)) {
+            Err(Error::UnsupportedCompression("LZ4")) => {}
+            other => panic!("expected unsupported compression, got {:?}", other),
+        }
+    }
+}
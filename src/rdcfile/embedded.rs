@@ -0,0 +1,301 @@
+//! Data embedded in capture files besides the frame itself.
+
+use std::io::{self, Read};
+use std::path::Path;
+
+use super::{Error, RdcReader, SectionType};
+use {SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY, SHADER_MAGIC_DEBUG_VALUE_TRUNCATED};
+
+/// Size of the chunks the frame capture is scanned in.
+const SCAN_CHUNK_LEN: usize = 64 * 1024;
+
+/// Number of bytes after a magic value in which the path has to start.
+const PATH_SEARCH_LEN: usize = 128;
+
+/// Longest path recognized after a magic value.
+const MAX_PATH_LEN: usize = 1024;
+
+/// Shortest run of text accepted as a path.
+const MIN_PATH_LEN: usize = 3;
+
+/// Reads the comments entered in the RenderDoc UI from the capture file at
+/// `path`.
+///
+/// Returns `None` if the capture has no notes or no comments.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # fn run() -> Result<(), renderdoc::rdcfile::Error> {
+/// if let Some(comments) = renderdoc::rdcfile::read_comments("frame12.rdc")? {
+///     println!("{}", comments);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_comments<P: AsRef<Path>>(path: P) -> Result<Option<String>, Error> {
+    let mut reader = RdcReader::open(path)?;
+    while let Some(section) = reader.next().transpose()? {
+        if section.kind == SectionType::Notes {
+            let notes = reader.read_section(&section)?;
+            let notes = String::from_utf8(notes).map_err(|_| Error::Malformed("invalid UTF-8 notes"))?;
+            let fields = json_string_fields(&notes).ok_or(Error::Malformed("invalid notes"))?;
+            return Ok(fields.into_iter().find(|(key, _)| key == "comments").map(|(_, value)| value));
+        }
+    }
+    Ok(None)
+}
+
+/// Lists the shader debug information paths recorded in the capture file at
+/// `path`.
+///
+/// Applications pass RenderDoc the path of the debug information of stripped
+/// shaders by attaching it to the shader with `SHADER_MAGIC_DEBUG_VALUE_*`,
+/// e.g. as D3D private data or a Vulkan object tag. This scans the frame
+/// capture for the magic values and returns the text following each, in
+/// order of appearance and without duplicates, so the files can be copied to
+/// a symbol server.
+///
+/// The scan doesn't decode the captured API calls, so unrelated data
+/// happening to contain a magic value may yield a bogus path. Compressed
+/// captures require the `lz4` or `zstd` feature matching the compression.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::fs;
+/// use std::path::Path;
+///
+/// # fn run() -> Result<(), renderdoc::rdcfile::Error> {
+/// for path in renderdoc::rdcfile::shader_debug_paths("frame12.rdc")? {
+///     let path = Path::new(&path);
+///     if let Some(name) = path.file_name() {
+///         fs::copy(path, Path::new("//symbols/shaders").join(name))?;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn shader_debug_paths<P: AsRef<Path>>(path: P) -> Result<Vec<String>, Error> {
+    let mut reader = RdcReader::open(path)?;
+    while let Some(section) = reader.next().transpose()? {
+        if section.kind == SectionType::FrameCapture {
+            let contents = reader.section_contents(&section)?;
+            return scan_shader_debug_paths(contents).map_err(Error::from);
+        }
+    }
+    Ok(Vec::new())
+}
+
+fn scan_shader_debug_paths<R: Read>(mut reader: R) -> io::Result<Vec<String>> {
+    let truncated = SHADER_MAGIC_DEBUG_VALUE_TRUNCATED.to_le_bytes();
+    let magics: [&[u8]; 2] = [SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY, &truncated];
+    let lookahead = SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY.len() + PATH_SEARCH_LEN + MAX_PATH_LEN;
+
+    let mut paths = Vec::new();
+    let mut window = Vec::new();
+    let mut chunk = vec![0; SCAN_CHUNK_LEN];
+    let mut eof = false;
+    while !eof {
+        let read = reader.read(&mut chunk)?;
+        eof = read == 0;
+        window.extend_from_slice(&chunk[..read]);
+
+        // Magic values too close to the end are scanned with the next chunk,
+        // once the path following them has been read.
+        let scan_end = if eof { window.len() } else { window.len().saturating_sub(lookahead) };
+        for start in 0..scan_end {
+            let magic = magics.iter().find(|magic| window[start..].starts_with(magic));
+            if let Some(path) = magic.and_then(|magic| path_after(&window[start + magic.len()..])) {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        window.drain(..scan_end);
+    }
+    Ok(paths)
+}
+
+/// Returns the first run of text starting within `PATH_SEARCH_LEN` bytes,
+/// skipping the length and padding RenderDoc serializes before the path.
+fn path_after(data: &[u8]) -> Option<String> {
+    let is_text = |b: &u8| *b >= 0x20 && *b != 0x7f;
+    let mut start = 0;
+    while start < data.len().min(PATH_SEARCH_LEN) {
+        let len = data[start..].iter().take(MAX_PATH_LEN).take_while(|b| is_text(b)).count();
+        if len >= MIN_PATH_LEN {
+            return String::from_utf8(data[start..start + len].to_vec()).ok();
+        }
+        start += len + 1;
+    }
+    None
+}
+
+/// Returns the string-valued fields of a JSON object, skipping all others.
+fn json_string_fields(json: &str) -> Option<Vec<(String, String)>> {
+    let mut parser = JsonParser {
+        chars: json.chars().peekable(),
+    };
+    let mut fields = Vec::new();
+    parser.expect('{')?;
+    if parser.peek()? == '}' {
+        return Some(fields);
+    }
+    loop {
+        let key = parser.string()?;
+        parser.expect(':')?;
+        if parser.peek()? == '"' {
+            fields.push((key, parser.string()?));
+        } else {
+            parser.skip_value()?;
+        }
+        match parser.next()? {
+            ',' => continue,
+            '}' => return Some(fields),
+            _ => return None,
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    chars: ::std::iter::Peekable<::std::str::Chars<'a>>,
+}
+
+impl<'a> JsonParser<'a> {
+    /// Returns the next character which isn't whitespace, without consuming
+    /// it.
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.peek()?.is_whitespace() {
+            self.chars.next();
+        }
+        self.chars.peek().cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        self.peek()?;
+        self.chars.next()
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        if self.next()? == expected {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(string),
+                '\\' => match self.chars.next()? {
+                    'b' => string.push('\u{8}'),
+                    'f' => string.push('\u{c}'),
+                    'n' => string.push('\n'),
+                    'r' => string.push('\r'),
+                    't' => string.push('\t'),
+                    'u' => {
+                        let unit = self.hex4()?;
+                        let code = if (0xd800..0xdc00).contains(&unit) {
+                            if self.chars.next()? != '\\' || self.chars.next()? != 'u' {
+                                return None;
+                            }
+                            let low = self.hex4()?;
+                            0x10000 + ((unit - 0xd800) << 10) + low.checked_sub(0xdc00)?
+                        } else {
+                            unit
+                        };
+                        string.push(::std::char::from_u32(code)?);
+                    }
+                    escaped => string.push(escaped),
+                },
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..4 {
+            value = value * 16 + self.chars.next()?.to_digit(16)?;
+        }
+        Some(value)
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        match self.peek()? {
+            '"' => self.string().map(|_| ()),
+            open @ '{' | open @ '[' => {
+                let close = if open == '{' { '}' } else { ']' };
+                self.chars.next();
+                if self.peek()? == close {
+                    self.chars.next();
+                    return Some(());
+                }
+                loop {
+                    if open == '{' {
+                        self.string()?;
+                        self.expect(':')?;
+                    }
+                    self.skip_value()?;
+                    match self.next()? {
+                        ',' => continue,
+                        c if c == close => return Some(()),
+                        _ => return None,
+                    }
+                }
+            }
+            _ => {
+                // Numbers, booleans and null.
+                let mut len = 0;
+                while let Some(&c) = self.chars.peek() {
+                    if c == ',' || c == '}' || c == ']' || c.is_whitespace() {
+                        break;
+                    }
+                    self.chars.next();
+                    len += 1;
+                }
+                if len > 0 {
+                    Some(())
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn parse_notes() {
+        let notes = r#"{"hwinfo": {"gpu": ["A", 1, true]}, "comments": "Shadow \"acne\"\n\u00e9\ud83d\ude00", "n": null}"#;
+        let fields = json_string_fields(notes).unwrap();
+        assert_eq!(fields, vec![("comments".to_string(), "Shadow \"acne\"\n\u{e9}\u{1f600}".to_string())]);
+        assert_eq!(json_string_fields(" { } ").unwrap(), vec![]);
+        assert!(json_string_fields(r#"{"comments": "unterminated}"#).is_none());
+    }
+
+    #[test]
+    fn find_shader_debug_paths() {
+        let mut frame = vec![0xaa; SCAN_CHUNK_LEN - 20];
+        frame.extend_from_slice(SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY);
+        frame.extend_from_slice(&[27, 0, 0, 0, 27, 0, 0, 0, 0, 0, 0, 0]);
+        frame.extend_from_slice(b"C:\\shaders\\lighting_ps.pdb\0");
+        frame.extend_from_slice(&SHADER_MAGIC_DEBUG_VALUE_TRUNCATED.to_le_bytes());
+        frame.extend_from_slice(&[0; 8]);
+        frame.extend_from_slice(b"/build/shaders/shadow.vs.dbg\x01\x02");
+        frame.extend_from_slice(SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY);
+        frame.extend_from_slice(b"\x1b\0C:\\shaders\\lighting_ps.pdb");
+        frame.extend_from_slice(SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY);
+
+        let paths = scan_shader_debug_paths(Cursor::new(frame)).unwrap();
+        assert_eq!(paths, vec!["C:\\shaders\\lighting_ps.pdb", "/build/shaders/shadow.vs.dbg"]);
+    }
+}
@@ -0,0 +1,680 @@
+//! Parser for the RDC capture container format.
+//!
+//! Capture files start with a fixed header holding the RenderDoc version that
+//! wrote them, a JPEG thumbnail and the graphics driver used. The rest of the
+//! file is a sequence of sections, each with its own header, e.g. the frame
+//! capture itself, UI notes, or bookmarks. This module reads that structure
+//! without librenderdoc, so it works on machines without RenderDoc.
+//!
+//! `RdcFile` reads all section headers up front, while `RdcReader` visits
+//! them one at a time and streams section data, for scanning large captures.
+//!
+//! Sections may be compressed with LZ4 or Zstandard, which are decompressed
+//! with the `lz4` and `zstd` features respectively.
+//!
+//! `read_comments()` and `shader_debug_paths()` pull embedded data out of
+//! captures, e.g. to populate a symbol server with the shader debug
+//! information a capture refers to.
+//!
+//! `validate()` checks captures for truncation and corruption, and
+//! `salvage()` copies the readable sections of damaged captures.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use renderdoc::rdcfile::RdcFile;
+//!
+//! # fn run() -> Result<(), renderdoc::rdcfile::Error> {
+//! let rdc = RdcFile::open("frame12.rdc")?;
+//! println!("{} capture from RenderDoc {}", rdc.driver_name, rdc.program_version);
+//! for section in rdc.sections() {
+//!     println!("{}: {} bytes", section.name, section.uncompressed_len);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+pub use self::compression::Decompressor;
+pub use self::embedded::{read_comments, shader_debug_paths};
+pub use self::stream::{RdcReader, SectionData};
+pub use self::validate::{salvage, validate, Damage, Validation};
+pub use self::writer::{remove_section, write_section, NewSection};
+
+mod compression;
+mod embedded;
+mod stream;
+mod validate;
+mod writer;
+
+/// Magic value at the start of every capture file.
+pub const MAGIC: [u8; 8] = *b"RDOC\0\0\0\0";
+
+/// Oldest container version this parser understands.
+pub const MIN_VERSION: u32 = 0x100;
+
+/// Newest container version this parser understands.
+pub const MAX_VERSION: u32 = 0x102;
+
+/// Size of the fixed part of the file header.
+const FILE_HEADER_LEN: u64 = 32;
+
+/// Errors that can occur while reading a capture file.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the file failed.
+    Io(io::Error),
+    /// The file is not a RenderDoc capture.
+    InvalidMagic,
+    /// The file uses a container version this parser does not understand.
+    UnsupportedVersion(u32),
+    /// The file ends before the data it announces, as it does when the
+    /// application writing it crashed or was killed.
+    Truncated(&'static str),
+    /// The file is corrupted.
+    Malformed(&'static str),
+    /// A section is compressed with the given algorithm, whose feature isn't
+    /// enabled.
+    UnsupportedCompression(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+            Error::InvalidMagic => write!(f, "Not a RenderDoc capture file"),
+            Error::UnsupportedVersion(ver) => write!(f, "Unsupported capture file version: {:#x}", ver),
+            Error::Truncated(what) => write!(f, "Truncated capture file: {}", what),
+            Error::Malformed(what) => write!(f, "Malformed capture file: {}", what),
+            Error::UnsupportedCompression(algorithm) => {
+                write!(f, "Section compressed with unsupported algorithm: {}", algorithm)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => Error::Truncated("unexpected end of file"),
+            io::ErrorKind::InvalidData => Error::Malformed("corrupt compressed data"),
+            _ => Error::Io(err),
+        }
+    }
+}
+
+/// Graphics API a capture was made with.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Driver {
+    /// Direct3D 11.
+    D3D11,
+    /// OpenGL.
+    OpenGL,
+    /// Direct3D 12.
+    D3D12,
+    /// Direct3D 10.
+    D3D10,
+    /// Direct3D 9.
+    D3D9,
+    /// A plain image file opened as a capture.
+    Image,
+    /// Vulkan.
+    Vulkan,
+    /// OpenGL ES.
+    OpenGLES,
+    /// Direct3D 8.
+    D3D8,
+    /// Any other driver, identified by its raw ID.
+    Other(u32),
+}
+
+impl Driver {
+    fn from_raw(raw: u32) -> Driver {
+        match raw {
+            1 => Driver::D3D11,
+            2 => Driver::OpenGL,
+            4 => Driver::D3D12,
+            5 => Driver::D3D10,
+            6 => Driver::D3D9,
+            7 => Driver::Image,
+            8 => Driver::Vulkan,
+            9 => Driver::OpenGLES,
+            10 => Driver::D3D8,
+            raw => Driver::Other(raw),
+        }
+    }
+}
+
+/// Kind of data stored in a section.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SectionType {
+    /// The serialized frame capture.
+    FrameCapture,
+    /// Cached callstack symbol resolution.
+    ResolveDatabase,
+    /// Event bookmarks set in the UI.
+    Bookmarks,
+    /// Notes and comments entered in the UI.
+    Notes,
+    /// Resource names assigned in the UI.
+    ResourceRenames,
+    /// An AMD Radeon GPU Profiler profile.
+    AmdRgpProfile,
+    /// A thumbnail with higher resolution than the one in the file header.
+    ExtendedThumbnail,
+    /// The log of the captured application.
+    EmbeddedLogfile,
+    /// Shaders edited in the UI.
+    EditedShaders,
+    /// The D3D12 core runtime used by the application.
+    D3D12Core,
+    /// The D3D12 SDK layers used by the application.
+    D3D12SdkLayers,
+    /// Any other section, e.g. one added by a third-party tool.
+    Other(u32),
+}
+
+impl SectionType {
+    fn to_raw(self) -> u32 {
+        match self {
+            SectionType::FrameCapture => 1,
+            SectionType::ResolveDatabase => 2,
+            SectionType::Bookmarks => 3,
+            SectionType::Notes => 4,
+            SectionType::ResourceRenames => 5,
+            SectionType::AmdRgpProfile => 6,
+            SectionType::ExtendedThumbnail => 7,
+            SectionType::EmbeddedLogfile => 8,
+            SectionType::EditedShaders => 9,
+            SectionType::D3D12Core => 10,
+            SectionType::D3D12SdkLayers => 11,
+            SectionType::Other(raw) => raw,
+        }
+    }
+
+    fn from_raw(raw: u32) -> SectionType {
+        match raw {
+            1 => SectionType::FrameCapture,
+            2 => SectionType::ResolveDatabase,
+            3 => SectionType::Bookmarks,
+            4 => SectionType::Notes,
+            5 => SectionType::ResourceRenames,
+            6 => SectionType::AmdRgpProfile,
+            7 => SectionType::ExtendedThumbnail,
+            8 => SectionType::EmbeddedLogfile,
+            9 => SectionType::EditedShaders,
+            10 => SectionType::D3D12Core,
+            11 => SectionType::D3D12SdkLayers,
+            raw => SectionType::Other(raw),
+        }
+    }
+}
+
+bitflags! {
+    /// How the data of a section is stored.
+    pub struct SectionFlags: u32 {
+        /// The data is stored as text.
+        const ASCII_STORED = 0x1;
+        /// The data is compressed with LZ4.
+        const LZ4_COMPRESSED = 0x2;
+        /// The data is compressed with Zstandard.
+        const ZSTD_COMPRESSED = 0x4;
+    }
+}
+
+/// Header of a section within a capture file.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Section {
+    /// Kind of data stored in the section.
+    pub kind: SectionType,
+    /// Unique name of the section, e.g. `renderdoc/ui/notes`.
+    pub name: String,
+    /// Version of the section's data format.
+    pub version: u64,
+    /// How the data is stored.
+    pub flags: SectionFlags,
+    /// Offset of the section data from the start of the file.
+    pub offset: u64,
+    /// Size of the section data as stored in the file.
+    pub compressed_len: u64,
+    /// Size of the section data once decompressed.
+    pub uncompressed_len: u64,
+}
+
+impl Section {
+    /// Returns the offset just past the section data.
+    fn end(&self) -> Result<u64, Error> {
+        self.offset
+            .checked_add(self.compressed_len)
+            .ok_or(Error::Malformed("invalid section length"))
+    }
+}
+
+/// Preview image stored in the file header.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Thumbnail {
+    /// Width of the image in pixels.
+    pub width: u16,
+    /// Height of the image in pixels.
+    pub height: u16,
+    /// The image, encoded as a JPEG file.
+    pub jpeg: Vec<u8>,
+}
+
+/// Image file format of an extended thumbnail.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ThumbnailFormat {
+    /// DirectDraw Surface.
+    Dds,
+    /// PNG.
+    Png,
+    /// JPEG.
+    Jpeg,
+    /// Windows bitmap.
+    Bmp,
+    /// Truevision TGA.
+    Tga,
+    /// Radiance HDR.
+    Hdr,
+    /// OpenEXR.
+    Exr,
+    /// Raw pixel data.
+    Raw,
+    /// Any other format, identified by its raw ID.
+    Other(u32),
+}
+
+impl ThumbnailFormat {
+    fn from_raw(raw: u32) -> ThumbnailFormat {
+        match raw {
+            0 => ThumbnailFormat::Dds,
+            1 => ThumbnailFormat::Png,
+            2 => ThumbnailFormat::Jpeg,
+            3 => ThumbnailFormat::Bmp,
+            4 => ThumbnailFormat::Tga,
+            5 => ThumbnailFormat::Hdr,
+            6 => ThumbnailFormat::Exr,
+            7 => ThumbnailFormat::Raw,
+            raw => ThumbnailFormat::Other(raw),
+        }
+    }
+}
+
+/// Preview image stored in the `ExtendedThumbnail` section, usually with a
+/// higher resolution than the one in the file header.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ExtendedThumbnail {
+    /// Width of the image in pixels.
+    pub width: u16,
+    /// Height of the image in pixels.
+    pub height: u16,
+    /// Format of `data`.
+    pub format: ThumbnailFormat,
+    /// The image, encoded in `format`.
+    pub data: Vec<u8>,
+}
+
+impl ExtendedThumbnail {
+    /// Decodes the decompressed data of an `ExtendedThumbnail` section.
+    pub fn parse(section_data: &[u8]) -> Result<ExtendedThumbnail, Error> {
+        let reader = &mut io::Cursor::new(section_data);
+        let width = read_u16(reader)?;
+        let height = read_u16(reader)?;
+        let len = read_u32(reader)?;
+        let format = ThumbnailFormat::from_raw(read_u32(reader)?);
+        let data = read_bytes(reader, u64::from(len), section_data.len() as u64)?;
+        Ok(ExtendedThumbnail {
+            width,
+            height,
+            format,
+            data,
+        })
+    }
+}
+
+/// Structure of a capture file.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RdcFile {
+    /// Version of the container format.
+    pub version: u32,
+    /// Version of RenderDoc that wrote the file.
+    pub program_version: String,
+    /// Preview image of the captured frame, if any.
+    pub thumbnail: Option<Thumbnail>,
+    /// Identifies the kind of machine the capture was made on.
+    pub machine_ident: u64,
+    /// Graphics API the capture was made with.
+    pub driver: Driver,
+    /// Name of the driver as recorded by RenderDoc.
+    pub driver_name: String,
+    header_len: u64,
+    sections: Vec<Section>,
+}
+
+impl RdcFile {
+    /// Reads the structure of the capture file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<RdcFile, Error> {
+        let file = File::open(path)?;
+        RdcFile::parse(&mut BufReader::new(file))
+    }
+
+    /// Reads the structure of a capture file from `reader`.
+    ///
+    /// Section data is skipped over, so only the headers are read.
+    pub fn parse<R: Read + Seek>(reader: &mut R) -> Result<RdcFile, Error> {
+        let mut stream = RdcReader::new(reader)?;
+        let sections = stream.by_ref().collect::<Result<Vec<_>, _>>()?;
+        let mut rdc = stream.into_header();
+        rdc.sections = sections;
+        Ok(rdc)
+    }
+
+    /// Reads the file header, leaving the list of sections empty.
+    fn parse_header<R: Read + Seek>(reader: &mut R) -> Result<RdcFile, Error> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(Error::InvalidMagic);
+        }
+
+        let version = read_u32(reader)?;
+        if !(MIN_VERSION..=MAX_VERSION).contains(&version) {
+            return Err(Error::UnsupportedVersion(version));
+        }
+
+        let header_len = u64::from(read_u32(reader)?);
+        let mut program_version = [0u8; 16];
+        reader.read_exact(&mut program_version)?;
+        let program_version = c_string(&program_version);
+
+        let width = read_u16(reader)?;
+        let height = read_u16(reader)?;
+        let thumb_len = read_u32(reader)?;
+        let jpeg = read_bytes(reader, u64::from(thumb_len), file_len)?;
+        let thumbnail = if jpeg.is_empty() {
+            None
+        } else {
+            Some(Thumbnail { width, height, jpeg })
+        };
+
+        let machine_ident = read_u64(reader)?;
+        let driver = Driver::from_raw(read_u32(reader)?);
+        let name_len = read_u8(reader)?;
+        let driver_name = c_string(&read_bytes(reader, u64::from(name_len), file_len)?);
+
+        if header_len < FILE_HEADER_LEN {
+            return Err(Error::Malformed("invalid header length"));
+        } else if header_len > file_len {
+            return Err(Error::Truncated("file header extends past end of file"));
+        }
+
+        Ok(RdcFile {
+            version,
+            program_version,
+            thumbnail,
+            machine_ident,
+            driver,
+            driver_name,
+            header_len,
+            sections: Vec::new(),
+        })
+    }
+
+    /// Returns all sections in the order they are stored in the file.
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Returns the first section of the given kind, if any.
+    pub fn section(&self, kind: SectionType) -> Option<&Section> {
+        self.sections.iter().find(|section| section.kind == kind)
+    }
+
+    /// Returns the section with the given name, if any.
+    pub fn section_by_name(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+}
+
+/// Reads only the thumbnail from the header of the capture file at `path`.
+///
+/// This skips the sections entirely, which makes it cheap enough for listing
+/// large numbers of captures. Returns `None` if the capture has no thumbnail.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::fs;
+///
+/// # fn run() -> Result<(), renderdoc::rdcfile::Error> {
+/// if let Some(thumb) = renderdoc::rdcfile::read_thumbnail("frame12.rdc")? {
+///     fs::write("frame12.jpg", &thumb.jpeg)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_thumbnail<P: AsRef<Path>>(path: P) -> Result<Option<Thumbnail>, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    RdcFile::parse_header(&mut reader).map(|rdc| rdc.thumbnail)
+}
+
+/// Reads the `ExtendedThumbnail` section of the capture file at `path`.
+///
+/// Returns `None` if the capture has no such section. The section is
+/// usually compressed, so this requires the `lz4` or `zstd` feature matching
+/// the compression.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::fs;
+/// use renderdoc::rdcfile::ThumbnailFormat;
+///
+/// # fn run() -> Result<(), renderdoc::rdcfile::Error> {
+/// if let Some(thumb) = renderdoc::rdcfile::read_extended_thumbnail("frame12.rdc")? {
+///     if thumb.format == ThumbnailFormat::Png {
+///         fs::write("frame12.png", &thumb.data)?;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_extended_thumbnail<P: AsRef<Path>>(path: P) -> Result<Option<ExtendedThumbnail>, Error> {
+    let mut reader = RdcReader::open(path)?;
+    while let Some(section) = reader.next().transpose()? {
+        if section.kind == SectionType::ExtendedThumbnail {
+            let data = reader.read_section(&section)?;
+            return ExtendedThumbnail::parse(&data).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+fn read_section<R: Read + Seek>(reader: &mut R, file_len: u64) -> Result<Section, Error> {
+    let mut is_ascii = [0u8; 4];
+    reader.read_exact(&mut is_ascii)?;
+    if is_ascii[0] != 0 {
+        return Err(Error::Malformed("text section headers are not supported"));
+    }
+
+    let kind = SectionType::from_raw(read_u32(reader)?);
+    let compressed_len = read_u64(reader)?;
+    let uncompressed_len = read_u64(reader)?;
+    let version = read_u64(reader)?;
+    let flags = SectionFlags::from_bits_truncate(read_u32(reader)?);
+    let name_len = read_u32(reader)?;
+    let name = String::from_utf8(read_bytes(reader, u64::from(name_len), file_len)?)
+        .map_err(|_| Error::Malformed("invalid UTF-8 section name"))?;
+
+    let offset = reader.stream_position()?;
+    let section = Section {
+        kind,
+        name,
+        version,
+        flags,
+        offset,
+        compressed_len,
+        uncompressed_len,
+    };
+    if section.end()? > file_len {
+        return Err(Error::Truncated("section extends past end of file"));
+    }
+    Ok(section)
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut raw = [0u8; 1];
+    reader.read_exact(&mut raw)?;
+    Ok(raw[0])
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> io::Result<u16> {
+    let mut raw = [0u8; 2];
+    reader.read_exact(&mut raw)?;
+    Ok(u16::from_le_bytes(raw))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut raw = [0u8; 4];
+    reader.read_exact(&mut raw)?;
+    Ok(u32::from_le_bytes(raw))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut raw = [0u8; 8];
+    reader.read_exact(&mut raw)?;
+    Ok(u64::from_le_bytes(raw))
+}
+
+/// Reads `len` bytes, refusing lengths which can't possibly fit the file.
+fn read_bytes<R: Read>(reader: &mut R, len: u64, file_len: u64) -> Result<Vec<u8>, Error> {
+    if len > file_len {
+        return Err(Error::Truncated("length exceeds file size"));
+    }
+
+    let mut buf = vec![0u8; len as usize];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Decodes a NUL-padded string.
+fn c_string(raw: &[u8]) -> String {
+    let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+    String::from_utf8_lossy(&raw[..end]).into_owned()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    pub fn sample_file() -> Vec<u8> {
+        let thumb = b"\xff\xd8jpeg\xff\xd9";
+        let driver = b"Vulkan";
+        let header_len = FILE_HEADER_LEN as usize + 8 + thumb.len() + 13 + driver.len();
+
+        let mut file = MAGIC.to_vec();
+        file.extend_from_slice(&0x102u32.to_le_bytes());
+        file.extend_from_slice(&(header_len as u32).to_le_bytes());
+        file.extend_from_slice(b"v1.25\0\0\0\0\0\0\0\0\0\0\0");
+        file.extend_from_slice(&64u16.to_le_bytes());
+        file.extend_from_slice(&36u16.to_le_bytes());
+        file.extend_from_slice(&(thumb.len() as u32).to_le_bytes());
+        file.extend_from_slice(thumb);
+        file.extend_from_slice(&0x2au64.to_le_bytes());
+        file.extend_from_slice(&8u32.to_le_bytes());
+        file.push(driver.len() as u8);
+        file.extend_from_slice(driver);
+
+        let name = b"renderdoc/ui/notes";
+        let data = b"{}";
+        file.extend_from_slice(&[0, 0, 0, 0]);
+        file.extend_from_slice(&4u32.to_le_bytes());
+        file.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        file.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        file.extend_from_slice(&1u64.to_le_bytes());
+        file.extend_from_slice(&1u32.to_le_bytes());
+        file.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        file.extend_from_slice(name);
+        file.extend_from_slice(data);
+        file
+    }
+
+    #[test]
+    fn parse_header_and_sections() {
+        let file = sample_file();
+        let rdc = RdcFile::parse(&mut Cursor::new(&file)).unwrap();
+        assert_eq!(rdc.program_version, "v1.25");
+        assert_eq!(rdc.driver, Driver::Vulkan);
+        assert_eq!(rdc.driver_name, "Vulkan");
+        assert_eq!(rdc.thumbnail.as_ref().unwrap().width, 64);
+
+        let notes = rdc.section(SectionType::Notes).unwrap();
+        assert_eq!(notes.name, "renderdoc/ui/notes");
+        assert_eq!(notes.flags, SectionFlags::ASCII_STORED);
+        assert_eq!(&file[notes.offset as usize..], b"{}");
+    }
+
+    #[test]
+    fn thumbnail_ignores_sections() {
+        let mut file = sample_file();
+        file.truncate(file.len() - 4);
+        let rdc = RdcFile::parse_header(&mut Cursor::new(&file)).unwrap();
+        assert_eq!(rdc.thumbnail.unwrap().jpeg, b"\xff\xd8jpeg\xff\xd9");
+    }
+
+    #[test]
+    fn parse_extended_thumbnail() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&1280u16.to_le_bytes());
+        data.extend_from_slice(&720u16.to_le_bytes());
+        data.extend_from_slice(&4u32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(b"\x89PNG");
+        let thumb = ExtendedThumbnail::parse(&data).unwrap();
+        assert_eq!((thumb.width, thumb.height), (1280, 720));
+        assert_eq!(thumb.format, ThumbnailFormat::Png);
+        assert_eq!(thumb.data, b"\x89PNG");
+
+        data.pop();
+        assert!(ExtendedThumbnail::parse(&data).is_err());
+    }
+
+    #[test]
+    fn reject_overflowing_section_length() {
+        let mut file = sample_file();
+        let len_offset = file.len() - 2 - 18 - 4 - 4 - 8 - 8 - 8;
+        file[len_offset..len_offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        match RdcFile::parse(&mut Cursor::new(&file)) {
+            Err(Error::Malformed(_)) => (),
+            other => panic!("expected malformed file, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reject_truncated_section() {
+        let mut file = sample_file();
+        file.pop();
+        match RdcFile::parse(&mut Cursor::new(&file)) {
+            Err(Error::Truncated(_)) => (),
+            other => panic!("expected truncated file, got {:?}", other),
+        }
+    }
+}
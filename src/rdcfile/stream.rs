@@ -0,0 +1,198 @@
+//! Reading capture files section by section.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use super::{read_section, Decompressor, Error, RdcFile, Section};
+
+/// Reads the sections of a capture file one at a time.
+///
+/// Iterating yields the section headers in file order, seeking over the data
+/// in between, so even multi-gigabyte captures are scanned with a few small
+/// reads. The data of a section is only read when asked for with
+/// `section_contents()`, or `section_data()` for the data as stored.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::io::Read;
+/// use renderdoc::rdcfile::{RdcReader, SectionType};
+///
+/// # fn run() -> Result<(), renderdoc::rdcfile::Error> {
+/// let mut reader = RdcReader::open("frame12.rdc")?;
+/// while let Some(section) = reader.next().transpose()? {
+///     if section.kind == SectionType::Notes {
+///         let mut notes = String::new();
+///         reader.section_contents(&section)?.read_to_string(&mut notes)?;
+///         println!("{}", notes);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RdcReader<R> {
+    reader: R,
+    header: RdcFile,
+    file_len: u64,
+    next_offset: u64,
+    failed: bool,
+}
+
+impl RdcReader<BufReader<File>> {
+    /// Opens the capture file at `path` and reads its header.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        RdcReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> RdcReader<R> {
+    /// Reads the file header from `reader`.
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let header = RdcFile::parse_header(&mut reader)?;
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        let next_offset = header.header_len;
+        Ok(RdcReader {
+            reader,
+            header,
+            file_len,
+            next_offset,
+            failed: false,
+        })
+    }
+
+    /// Returns the file header. Its list of sections is empty.
+    pub fn header(&self) -> &RdcFile {
+        &self.header
+    }
+
+    /// Returns the size of the file in bytes.
+    pub fn file_len(&self) -> u64 {
+        self.file_len
+    }
+
+    /// Returns a reader over the data of `section` as stored in the file,
+    /// i.e. still compressed if the section is.
+    ///
+    /// Iteration continues after `section` regardless of how much data is
+    /// read.
+    pub fn section_data(&mut self, section: &Section) -> Result<SectionData<'_, R>, Error> {
+        self.reader.seek(SeekFrom::Start(section.offset))?;
+        Ok(SectionData {
+            inner: (&mut self.reader).take(section.compressed_len),
+        })
+    }
+
+    /// Returns a reader over the decompressed data of `section`.
+    ///
+    /// Fails with `Error::UnsupportedCompression` if decompressing the
+    /// section requires a feature which isn't enabled.
+    pub fn section_contents(&mut self, section: &Section) -> Result<Decompressor<SectionData<'_, R>>, Error> {
+        let data = self.section_data(section)?;
+        Decompressor::new(data, section)
+    }
+
+    /// Reads the decompressed data of `section` into memory.
+    pub fn read_section(&mut self, section: &Section) -> Result<Vec<u8>, Error> {
+        let mut contents = Vec::new();
+        self.section_contents(section)?.read_to_end(&mut contents)?;
+        if contents.len() as u64 != section.uncompressed_len {
+            return Err(Error::Malformed("section data shorter than its uncompressed size"));
+        }
+        Ok(contents)
+    }
+
+    /// Returns the file header, consuming the reader.
+    pub(super) fn into_header(self) -> RdcFile {
+        self.header
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read + Seek> Iterator for RdcReader<R> {
+    type Item = Result<Section, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.next_offset >= self.file_len {
+            return None;
+        }
+
+        let section = self
+            .reader
+            .seek(SeekFrom::Start(self.next_offset))
+            .map_err(Error::from)
+            .and_then(|_| read_section(&mut self.reader, self.file_len));
+        match section.and_then(|section| section.end().map(|end| (section, end))) {
+            Ok((section, end)) => {
+                self.next_offset = end;
+                Some(Ok(section))
+            }
+            Err(err) => {
+                // The position of any further section is unknown.
+                self.failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Reader over the stored data of a single section.
+#[derive(Debug)]
+pub struct SectionData<'a, R: 'a> {
+    inner: io::Take<&'a mut R>,
+}
+
+impl<'a, R: Read> SectionData<'a, R> {
+    /// Returns the number of bytes left to read.
+    pub fn remaining(&self) -> u64 {
+        self.inner.limit()
+    }
+}
+
+impl<'a, R: Read> Read for SectionData<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::sample_file;
+    use super::super::SectionType;
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn stream_sections() {
+        let file = sample_file();
+        let mut reader = RdcReader::new(Cursor::new(&file)).unwrap();
+        assert_eq!(reader.header().driver_name, "Vulkan");
+
+        let notes = reader.next().unwrap().unwrap();
+        assert_eq!(notes.kind, SectionType::Notes);
+        let mut data = reader.section_data(&notes).unwrap();
+        assert_eq!(data.remaining(), 2);
+        let mut contents = String::new();
+        data.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "{}");
+        assert_eq!(reader.read_section(&notes).unwrap(), b"{}");
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn stop_after_error() {
+        let mut file = sample_file();
+        file.pop();
+        let mut reader = RdcReader::new(Cursor::new(&file)).unwrap();
+        match reader.next() {
+            Some(Err(Error::Truncated(_))) => {}
+            other => panic!("expected truncated file, got {:?}", other),
+        }
+        assert!(reader.next().is_none());
+    }
+}
@@ -0,0 +1,310 @@
+//! Integrity checks of capture files.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::writer::write_section_header;
+use super::{read_section, Decompressor, Error, RdcFile, Section, SectionFlags};
+
+/// Damage found by `validate()`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Damage {
+    /// The file ends early, as it does when the application writing it
+    /// crashed or was killed.
+    Truncated(&'static str),
+    /// The file is complete, but its contents are inconsistent.
+    Corrupt(&'static str),
+}
+
+/// Result of validating a capture file.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Validation {
+    /// The first damage found, if any.
+    pub damage: Option<Damage>,
+    /// Sections whose data could be read completely, including
+    /// `unchecked_sections`.
+    pub readable_sections: Vec<Section>,
+    /// Sections within the file whose data is damaged.
+    pub damaged_sections: Vec<Section>,
+    /// Compressed sections which weren't decompressed because the `lz4` or
+    /// `zstd` feature isn't enabled.
+    pub unchecked_sections: Vec<Section>,
+}
+
+impl Validation {
+    /// Returns whether no damage was found.
+    pub fn is_valid(&self) -> bool {
+        self.damage.is_none()
+    }
+}
+
+/// Checks the integrity of the capture file at `path`.
+///
+/// The file header, the section table and the data of every compressed
+/// section are checked. Truncation is told apart from corruption by whether
+/// the file ends before the data it announces, so capture pipelines can e.g.
+/// retry captures whose writing was interrupted, but report corrupt ones.
+///
+/// Fails only if the file can't be read, or if it uses a container version
+/// newer than this parser understands.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::fs;
+/// use renderdoc::rdcfile::{self, Damage};
+///
+/// # fn run() -> Result<(), renderdoc::rdcfile::Error> {
+/// match rdcfile::validate("frame12.rdc")?.damage {
+///     None => println!("capture is intact"),
+///     Some(Damage::Truncated(what)) => println!("capture was cut off: {}", what),
+///     Some(Damage::Corrupt(what)) => {
+///         println!("capture is corrupt: {}", what);
+///         fs::remove_file("frame12.rdc")?;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn validate<P: AsRef<Path>>(path: P) -> Result<Validation, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    validate_reader(&mut reader).map(|(_, validation)| validation)
+}
+
+/// Writes the readable sections of the capture file at `path` to a new
+/// capture file at `output`.
+///
+/// Damaged sections and everything after a damaged section table are
+/// dropped. Fails with `Error::Malformed` if the file header itself is
+/// damaged, in which case nothing is written.
+pub fn salvage<P, Q>(path: P, output: Q) -> Result<Validation, Error>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut input = BufReader::new(File::open(path)?);
+    let (header_len, validation) = validate_reader(&mut input)?;
+    let header_len = header_len.ok_or(Error::Malformed("file header is damaged"))?;
+
+    let output = output.as_ref();
+    let result = (|| {
+        let mut out = BufWriter::new(File::create(output)?);
+        input.seek(SeekFrom::Start(0))?;
+        io::copy(&mut (&mut input).take(header_len), &mut out)?;
+        for section in &validation.readable_sections {
+            write_section_header(
+                &mut out,
+                section.kind,
+                &section.name,
+                section.version,
+                section.flags,
+                section.compressed_len,
+                section.uncompressed_len,
+            )?;
+            input.seek(SeekFrom::Start(section.offset))?;
+            io::copy(&mut (&mut input).take(section.compressed_len), &mut out)?;
+        }
+        out.flush()
+    })();
+
+    match result {
+        Ok(()) => Ok(validation),
+        Err(err) => {
+            let _ = fs::remove_file(output);
+            Err(Error::from(err))
+        }
+    }
+}
+
+/// Validates a capture file, returning the length of its header if the
+/// header is intact.
+fn validate_reader<R: Read + Seek>(reader: &mut R) -> Result<(Option<u64>, Validation), Error> {
+    let mut validation = Validation {
+        damage: None,
+        readable_sections: Vec::new(),
+        damaged_sections: Vec::new(),
+        unchecked_sections: Vec::new(),
+    };
+
+    let header_len = match RdcFile::parse_header(reader) {
+        Ok(header) => header.header_len,
+        Err(Error::Truncated(_)) => {
+            validation.damage = Some(Damage::Truncated("file header is incomplete"));
+            return Ok((None, validation));
+        }
+        Err(Error::Malformed(_)) => {
+            validation.damage = Some(Damage::Corrupt("invalid file header"));
+            return Ok((None, validation));
+        }
+        Err(Error::InvalidMagic) => {
+            validation.damage = Some(Damage::Corrupt("not a RenderDoc capture file"));
+            return Ok((None, validation));
+        }
+        Err(err) => return Err(err),
+    };
+
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    let mut offset = header_len;
+    while offset < file_len {
+        reader.seek(SeekFrom::Start(offset))?;
+        let section = match read_section(reader, file_len)
+            .and_then(|section| section.end().map(|end| (section, end)))
+        {
+            Ok((section, end)) => {
+                offset = end;
+                section
+            }
+            Err(err) => {
+                // The position of any further section is unknown.
+                validation.damage.get_or_insert(section_table_damage(err)?);
+                break;
+            }
+        };
+
+        if validation.readable_sections.iter().any(|s| s.name == section.name) {
+            validation.damage.get_or_insert(Damage::Corrupt("duplicate section name"));
+            validation.damaged_sections.push(section);
+            continue;
+        }
+
+        match check_section_data(reader, &section) {
+            Ok(()) => validation.readable_sections.push(section),
+            Err(Error::UnsupportedCompression(_)) => {
+                validation.unchecked_sections.push(section.clone());
+                validation.readable_sections.push(section);
+            }
+            Err(Error::Malformed(what)) => {
+                validation.damage.get_or_insert(Damage::Corrupt(what));
+                validation.damaged_sections.push(section);
+            }
+            Err(Error::Truncated(what)) => {
+                validation.damage.get_or_insert(Damage::Truncated(what));
+                validation.damaged_sections.push(section);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok((Some(header_len), validation))
+}
+
+/// Classifies an error reading a section header by whether the file ends
+/// early.
+fn section_table_damage(err: Error) -> Result<Damage, Error> {
+    match err {
+        Error::Truncated(what) => Ok(Damage::Truncated(what)),
+        Error::Malformed(what) => Ok(Damage::Corrupt(what)),
+        err => Err(err),
+    }
+}
+
+/// Decompresses the data of `section`, checking that it matches the size
+/// recorded in the section header.
+fn check_section_data<R: Read + Seek>(reader: &mut R, section: &Section) -> Result<(), Error> {
+    let compressed = SectionFlags::LZ4_COMPRESSED | SectionFlags::ZSTD_COMPRESSED;
+    if !section.flags.intersects(compressed) {
+        if section.compressed_len != section.uncompressed_len {
+            return Err(Error::Malformed("stored section sizes differ"));
+        }
+        return Ok(());
+    }
+
+    reader.seek(SeekFrom::Start(section.offset))?;
+    let data = (&mut *reader).take(section.compressed_len);
+    let mut contents = Decompressor::new(data, section)?;
+    let len = io::copy(&mut contents, &mut io::sink())?;
+    if len != section.uncompressed_len {
+        return Err(Error::Malformed("compressed data ends early"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::sample_file;
+    use super::super::SectionType;
+    use super::*;
+    use std::env;
+    use std::io::Cursor;
+    use std::process;
+
+    fn validate_bytes(file: &[u8]) -> Validation {
+        validate_reader(&mut Cursor::new(file)).unwrap().1
+    }
+
+    fn push_section(file: &mut Vec<u8>, name: &str, flags: SectionFlags, uncompressed_len: u64, data: &[u8]) {
+        let len = data.len() as u64;
+        write_section_header(file, SectionType::Other(0), name, 1, flags, len, uncompressed_len).unwrap();
+        file.extend_from_slice(data);
+    }
+
+    #[test]
+    fn accept_intact_file() {
+        let validation = validate_bytes(&sample_file());
+        assert!(validation.is_valid());
+        assert_eq!(validation.readable_sections.len(), 1);
+    }
+
+    #[test]
+    fn detect_truncation() {
+        let mut file = sample_file();
+        push_section(&mut file, "ci/build", SectionFlags::empty(), 10, b"commit=abc");
+        file.truncate(file.len() - 3);
+        let validation = validate_bytes(&file);
+        assert_eq!(validation.damage, Some(Damage::Truncated("section extends past end of file")));
+        assert_eq!(validation.readable_sections.len(), 1);
+
+        let len = sample_file().len();
+        file.truncate(len + 10);
+        assert_eq!(validate_bytes(&file).damage, Some(Damage::Truncated("unexpected end of file")));
+        file.truncate(20);
+        assert_eq!(validate_bytes(&file).damage, Some(Damage::Truncated("file header is incomplete")));
+
+        let mut file = sample_file();
+        push_section(&mut file, "ci/build", SectionFlags::empty(), 10, b"commit=abc");
+        file[len + 36..len + 40].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(validate_bytes(&file).damage, Some(Damage::Truncated("length exceeds file size")));
+    }
+
+    #[test]
+    fn detect_corruption() {
+        let mut file = sample_file();
+        file[0] = b'X';
+        assert_eq!(validate_bytes(&file).damage, Some(Damage::Corrupt("not a RenderDoc capture file")));
+
+        let mut file = sample_file();
+        push_section(&mut file, "ci/build", SectionFlags::empty(), 99, b"commit=abc");
+        push_section(&mut file, "ci/host", SectionFlags::empty(), 4, b"ci-7");
+        let validation = validate_bytes(&file);
+        assert_eq!(validation.damage, Some(Damage::Corrupt("stored section sizes differ")));
+        assert_eq!(validation.damaged_sections[0].name, "ci/build");
+        assert_eq!(validation.readable_sections.len(), 2);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn detect_corrupt_compressed_data() {
+        let mut data = 8u32.to_le_bytes().to_vec();
+        data.extend_from_slice(b"not zstd");
+        let mut file = sample_file();
+        push_section(&mut file, "ci/log", SectionFlags::ZSTD_COMPRESSED, 100, &data);
+        assert_eq!(validate_bytes(&file).damage, Some(Damage::Corrupt("corrupt compressed data")));
+    }
+
+    #[test]
+    fn salvage_readable_sections() {
+        let dir = env::temp_dir().join(format!("renderdoc-rs-validate-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut file = sample_file();
+        push_section(&mut file, "ci/build", SectionFlags::empty(), 10, b"commit=abc");
+        file.truncate(file.len() - 1);
+        fs::write(dir.join("damaged.rdc"), &file).unwrap();
+
+        let validation = salvage(dir.join("damaged.rdc"), dir.join("salvaged.rdc")).unwrap();
+        assert!(!validation.is_valid());
+        assert!(validate(dir.join("salvaged.rdc")).unwrap().is_valid());
+        assert_eq!(fs::read(dir.join("salvaged.rdc")).unwrap(), sample_file());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+//! Adding, replacing and removing sections of existing capture files.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use super::{Error, RdcFile, Section, SectionFlags, SectionType};
+
+/// A section to be stored in a capture file.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct NewSection<'a> {
+    /// Kind of data stored in the section, usually `SectionType::Other(0)`
+    /// for sections specific to a tool.
+    pub kind: SectionType,
+    /// Unique name of the section, e.g. `mystudio/ci/build-info`.
+    pub name: &'a str,
+    /// Version of the section's data format.
+    pub version: u64,
+    /// How `data` is stored. Set the compression flags only if `data` has
+    /// already been compressed accordingly.
+    pub flags: SectionFlags,
+    /// Contents of the section.
+    pub data: &'a [u8],
+    /// Size of `data` once decompressed. Required if `flags` has a
+    /// compression flag set, `None` for data stored as is.
+    pub uncompressed_len: Option<u64>,
+}
+
+/// Stores `section` in the capture file at `path`.
+///
+/// A section with the same name is replaced, otherwise the section is
+/// appended to the end of the file. Fails with an `InvalidInput` I/O error if
+/// `section` is compressed but lacks its uncompressed size.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use renderdoc::rdcfile::{self, NewSection, SectionFlags, SectionType};
+///
+/// # fn run() -> Result<(), renderdoc::rdcfile::Error> {
+/// let section = NewSection {
+///     kind: SectionType::Other(0),
+///     name: "mystudio/ci/build-info",
+///     version: 1,
+///     flags: SectionFlags::ASCII_STORED,
+///     data: b"commit=3f2a9c1 pipeline=nightly",
+///     uncompressed_len: None,
+/// };
+/// rdcfile::write_section("frame12.rdc", &section)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_section<P: AsRef<Path>>(path: P, section: &NewSection) -> Result<(), Error> {
+    let compressed = SectionFlags::LZ4_COMPRESSED | SectionFlags::ZSTD_COMPRESSED;
+    if section.flags.intersects(compressed) && section.uncompressed_len.is_none() {
+        let msg = "compressed section without uncompressed size";
+        return Err(Error::Io(io::Error::new(io::ErrorKind::InvalidInput, msg)));
+    }
+
+    let path = path.as_ref();
+    let rdc = RdcFile::open(path)?;
+    if rdc.section_by_name(section.name).is_none() {
+        let mut file = BufWriter::new(OpenOptions::new().append(true).open(path)?);
+        write_new_section(&mut file, section)?;
+        return file.flush().map_err(Error::from);
+    }
+
+    rewrite(path, &rdc, |existing, out| {
+        if existing.name == section.name {
+            write_new_section(out, section).map(|_| false)
+        } else {
+            Ok(true)
+        }
+    })
+}
+
+/// Removes the section called `name` from the capture file at `path`.
+///
+/// Returns whether the section existed.
+pub fn remove_section<P: AsRef<Path>>(path: P, name: &str) -> Result<bool, Error> {
+    let path = path.as_ref();
+    let rdc = RdcFile::open(path)?;
+    if rdc.section_by_name(name).is_none() {
+        return Ok(false);
+    }
+
+    rewrite(path, &rdc, |existing, _| Ok(existing.name != name))?;
+    Ok(true)
+}
+
+/// Rewrites the file section by section through a temporary file.
+///
+/// `filter` may write a replacement for a section and returns whether the
+/// original section should be kept.
+fn rewrite<F>(path: &Path, rdc: &RdcFile, mut filter: F) -> Result<(), Error>
+where
+    F: FnMut(&Section, &mut BufWriter<File>) -> io::Result<bool>,
+{
+    let tmp_path = temp_path(path);
+    let result = (|| {
+        let mut input = BufReader::new(File::open(path)?);
+        let mut out = BufWriter::new(File::create(&tmp_path)?);
+
+        io::copy(&mut (&mut input).take(rdc.header_len), &mut out)?;
+        for section in rdc.sections() {
+            if filter(section, &mut out)? {
+                write_section_header(
+                    &mut out,
+                    section.kind,
+                    &section.name,
+                    section.version,
+                    section.flags,
+                    section.compressed_len,
+                    section.uncompressed_len,
+                )?;
+                input.seek(SeekFrom::Start(section.offset))?;
+                io::copy(&mut (&mut input).take(section.compressed_len), &mut out)?;
+            }
+        }
+
+        out.flush()
+    })();
+
+    match result {
+        Ok(()) => fs::rename(&tmp_path, path).map_err(Error::from),
+        Err(err) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(Error::from(err))
+        }
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+fn write_new_section<W: Write>(out: &mut W, section: &NewSection) -> io::Result<()> {
+    let len = section.data.len() as u64;
+    let uncompressed_len = section.uncompressed_len.unwrap_or(len);
+    write_section_header(out, section.kind, section.name, section.version, section.flags, len, uncompressed_len)?;
+    out.write_all(section.data)
+}
+
+pub(super) fn write_section_header<W: Write>(
+    out: &mut W,
+    kind: SectionType,
+    name: &str,
+    version: u64,
+    flags: SectionFlags,
+    compressed_len: u64,
+    uncompressed_len: u64,
+) -> io::Result<()> {
+    out.write_all(&[0, 0, 0, 0])?;
+    out.write_all(&kind.to_raw().to_le_bytes())?;
+    out.write_all(&compressed_len.to_le_bytes())?;
+    out.write_all(&uncompressed_len.to_le_bytes())?;
+    out.write_all(&version.to_le_bytes())?;
+    out.write_all(&flags.bits().to_le_bytes())?;
+    out.write_all(&(name.len() as u32).to_le_bytes())?;
+    out.write_all(name.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdcfile::tests::sample_file;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn append_replace_and_remove() {
+        let path = env::temp_dir().join(format!("renderdoc-rs-writer-{}.rdc", process::id()));
+        fs::write(&path, sample_file()).unwrap();
+
+        let mut section = NewSection {
+            kind: SectionType::Other(0),
+            name: "ci/build",
+            version: 1,
+            flags: SectionFlags::ASCII_STORED,
+            data: b"commit=abc",
+            uncompressed_len: None,
+        };
+        write_section(&path, &section).unwrap();
+        section.data = b"commit=def";
+        write_section(&path, &section).unwrap();
+
+        let rdc = RdcFile::open(&path).unwrap();
+        let names: Vec<_> = rdc.sections().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["renderdoc/ui/notes", "ci/build"]);
+        let file = fs::read(&path).unwrap();
+        let build = rdc.section_by_name("ci/build").unwrap();
+        assert_eq!(&file[build.offset as usize..], b"commit=def");
+
+        assert!(remove_section(&path, "renderdoc/ui/notes").unwrap());
+        assert!(!remove_section(&path, "renderdoc/ui/notes").unwrap());
+        assert_eq!(RdcFile::open(&path).unwrap().sections().len(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn require_uncompressed_len() {
+        let section = NewSection {
+            kind: SectionType::Other(0),
+            name: "ci/log",
+            version: 1,
+            flags: SectionFlags::LZ4_COMPRESSED,
+            data: b"\x04\0\0\0\x40ci-7",
+            uncompressed_len: None,
+        };
+        match write_section("does-not-exist.rdc", &section) {
+            Err(Error::Io(ref err)) if err.kind() == io::ErrorKind::InvalidInput => {}
+            other => panic!("expected invalid input, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn read_back_compressed_section() {
+        use rdcfile::RdcReader;
+        use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+
+        let path = env::temp_dir().join(format!("renderdoc-rs-writer-zstd-{}.rdc", process::id()));
+        fs::write(&path, sample_file()).unwrap();
+
+        let log = b"frame 12 captured\n".repeat(100);
+        let frame = compress_to_vec(&log[..], CompressionLevel::Fastest);
+        let mut data = (frame.len() as u32).to_le_bytes().to_vec();
+        data.extend_from_slice(&frame);
+        let section = NewSection {
+            kind: SectionType::EmbeddedLogfile,
+            name: "renderdoc/internal/logfile",
+            version: 1,
+            flags: SectionFlags::ZSTD_COMPRESSED,
+            data: &data,
+            uncompressed_len: Some(log.len() as u64),
+        };
+        write_section(&path, &section).unwrap();
+
+        let mut reader = RdcReader::open(&path).unwrap();
+        let written = reader.by_ref().last().unwrap().unwrap();
+        assert_eq!(written.uncompressed_len, log.len() as u64);
+        assert_eq!(reader.read_section(&written).unwrap(), log);
+        fs::remove_file(&path).unwrap();
+    }
+}
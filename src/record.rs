@@ -0,0 +1,1269 @@
+//! Recording and replaying sequences of API calls.
+//!
+//! Calls can be recorded at two levels:
+//!
+//! * `Recorder` wraps any `RenderDocApi` implementation, including a real
+//!   `RenderDoc` handle, and logs every call made through it.
+//! * `load()` loads the RenderDoc API with every function of its function
+//!   table interposed, logging all calls made through any handle, including
+//!   shutting RenderDoc down and launching the replay UI. The log is read with
+//!   `records()`.
+//!
+//! Each `Record` holds a call together with its return value, and prints as a
+//! single line such as `get_num_captures() -> 2`. A log printed one record per
+//! line, e.g. into a bug report from a user's machine, is read back with
+//! `parse_log()` and can be replayed with `replay()` against a `MockRenderDoc`
+//! or another handle to find where the behavior diverges.
+//!
+//! Requires the `record` feature, which the `mock` feature enables.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use std::fs;
+//! use renderdoc::dynamic::RenderDocApi;
+//! use renderdoc::record::{self, Recorder};
+//! use renderdoc::{RenderDoc, V110};
+//!
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let rd = Recorder::new(RenderDoc::<V110>::new()?);
+//! rd.trigger_capture()?;
+//! let log: String = rd.records().iter().map(|record| format!("{}\n", record)).collect();
+//! fs::write("renderdoc-calls.log", log)?;
+//!
+//! // Later, e.g. in a test replaying the calls against a `MockRenderDoc`.
+//! let records = record::parse_log(&fs::read_to_string("renderdoc-calls.log")?)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::path::{Path, PathBuf};
+use std::slice;
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard};
+
+use dynamic::RenderDocApi;
+use entry::{EntryV100, EntryV110, TriggerMultiFrameCaptureFn};
+use {global, names};
+use {ApiVersion, ApiVersionNumber, CaptureOption, DevicePointer, Error, InputButton, OverlayBits, RenderDoc,
+     WindowHandle};
+
+/// A call of a function of the RenderDoc API.
+///
+/// Device and window handles are recorded by address.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Call {
+    /// `get_api_version()`
+    GetApiVersion,
+    /// `set_capture_option_f32()`
+    SetCaptureOptionF32(CaptureOption, f32),
+    /// `set_capture_option_u32()`
+    SetCaptureOptionU32(CaptureOption, u32),
+    /// `get_capture_option_f32()`
+    GetCaptureOptionF32(CaptureOption),
+    /// `get_capture_option_u32()`
+    GetCaptureOptionU32(CaptureOption),
+    /// `set_focus_toggle_keys()`
+    SetFocusToggleKeys(Vec<InputButton>),
+    /// `set_capture_keys()`
+    SetCaptureKeys(Vec<InputButton>),
+    /// `get_overlay_bits()`
+    GetOverlayBits,
+    /// `mask_overlay_bits()`
+    MaskOverlayBits(OverlayBits, OverlayBits),
+    /// `EarlyShutdown::shutdown()`, only recorded by `load()`.
+    Shutdown,
+    /// `unload_crash_handler()`
+    UnloadCrashHandler,
+    /// `set_capture_file_path_template()`
+    SetCaptureFilePathTemplate(PathBuf),
+    /// `get_capture_file_path_template()`
+    GetCaptureFilePathTemplate,
+    /// `get_num_captures()`
+    GetNumCaptures,
+    /// `get_capture()`
+    GetCapture(u32),
+    /// `trigger_capture()`
+    TriggerCapture,
+    /// `is_target_control_connected()`
+    IsTargetControlConnected,
+    /// `launch_replay_ui()`, only recorded by `load()`.
+    LaunchReplayUi {
+        /// Whether the replay UI connects to this application.
+        connect_target_control: bool,
+        /// Command line passed to the replay UI.
+        cmd_line: String,
+    },
+    /// `set_active_window()`
+    SetActiveWindow {
+        /// Address of the device.
+        device: usize,
+        /// Address of the window.
+        window: usize,
+    },
+    /// `start_frame_capture()`
+    StartFrameCapture {
+        /// Address of the device.
+        device: usize,
+        /// Address of the window.
+        window: usize,
+    },
+    /// `is_frame_capturing()`
+    IsFrameCapturing,
+    /// `end_frame_capture()`
+    EndFrameCapture {
+        /// Address of the device.
+        device: usize,
+        /// Address of the window.
+        window: usize,
+    },
+    /// `trigger_multi_frame_capture()`
+    TriggerMultiFrameCapture(u32),
+}
+
+/// Value returned by a recorded call.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// The call returned nothing.
+    Unit,
+    /// A boolean.
+    Bool(bool),
+    /// An unsigned integer.
+    U32(u32),
+    /// A floating point number.
+    F32(f32),
+    /// An API version.
+    Version(ApiVersionNumber),
+    /// Overlay configuration bits.
+    Overlay(OverlayBits),
+    /// A path.
+    Path(PathBuf),
+    /// Path and timestamp of a capture, if it exists.
+    Capture(Option<(String, u64)>),
+}
+
+/// A call together with the value it returned.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    /// The call made.
+    pub call: Call,
+    /// The value it returned.
+    pub result: Value,
+}
+
+/// A replayed call which returned a different value than recorded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// Index of the call in the replayed sequence.
+    pub index: usize,
+    /// The call made.
+    pub call: Call,
+    /// The recorded return value.
+    pub expected: Value,
+    /// The return value during replay.
+    pub actual: Value,
+}
+
+impl Call {
+    /// Makes this call on `api`, returning its result.
+    ///
+    /// Returns `None` for `Shutdown` and `LaunchReplayUi`, which are logged
+    /// but not replayed, and fails if the call fails on `api`.
+    ///
+    /// Device and window handles are passed on by their recorded address, so
+    /// replaying against a real handle is only meaningful within the process
+    /// the calls were recorded in.
+    pub fn apply(&self, api: &dyn RenderDocApi) -> Result<Option<Value>, Error> {
+        let value = match *self {
+            Call::GetApiVersion => Value::Version(api.get_api_version()?),
+            Call::SetCaptureOptionF32(opt, val) => {
+                api.set_capture_option_f32(opt, val)?;
+                Value::Unit
+            }
+            Call::SetCaptureOptionU32(opt, val) => {
+                api.set_capture_option_u32(opt, val)?;
+                Value::Unit
+            }
+            Call::GetCaptureOptionF32(opt) => Value::F32(api.get_capture_option_f32(opt)?),
+            Call::GetCaptureOptionU32(opt) => Value::U32(api.get_capture_option_u32(opt)?),
+            Call::SetFocusToggleKeys(ref keys) => {
+                api.set_focus_toggle_keys(keys)?;
+                Value::Unit
+            }
+            Call::SetCaptureKeys(ref keys) => {
+                api.set_capture_keys(keys)?;
+                Value::Unit
+            }
+            Call::GetOverlayBits => Value::Overlay(api.get_overlay_bits()?),
+            Call::MaskOverlayBits(and, or) => {
+                api.mask_overlay_bits(and, or)?;
+                Value::Unit
+            }
+            Call::Shutdown | Call::LaunchReplayUi { .. } => return Ok(None),
+            Call::UnloadCrashHandler => {
+                api.unload_crash_handler()?;
+                Value::Unit
+            }
+            Call::SetCaptureFilePathTemplate(ref path_template) => {
+                api.set_capture_file_path_template(path_template)?;
+                Value::Unit
+            }
+            Call::GetCaptureFilePathTemplate => Value::Path(api.get_capture_file_path_template()?),
+            Call::GetNumCaptures => Value::U32(api.get_num_captures()?),
+            Call::GetCapture(index) => Value::Capture(api.get_capture(index)?),
+            Call::TriggerCapture => {
+                api.trigger_capture()?;
+                Value::Unit
+            }
+            Call::IsTargetControlConnected => Value::Bool(api.is_target_control_connected()?),
+            Call::SetActiveWindow { device, window } => {
+                api.set_active_window(device_pointer(device), window_handle(window))?;
+                Value::Unit
+            }
+            Call::StartFrameCapture { device, window } => {
+                api.start_frame_capture(device_pointer(device), window_handle(window))?;
+                Value::Unit
+            }
+            Call::IsFrameCapturing => Value::Bool(api.is_frame_capturing()?),
+            Call::EndFrameCapture { device, window } => {
+                api.end_frame_capture(device_pointer(device), window_handle(window))?;
+                Value::Unit
+            }
+            Call::TriggerMultiFrameCapture(num_frames) => {
+                api.trigger_multi_frame_capture(num_frames)?;
+                Value::Unit
+            }
+        };
+        Ok(Some(value))
+    }
+}
+
+fn device_pointer(address: usize) -> DevicePointer {
+    DevicePointer::from(address as *const c_void)
+}
+
+fn window_handle(address: usize) -> WindowHandle {
+    WindowHandle::from(address as *const c_void)
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Call::GetApiVersion => write!(f, "get_api_version()"),
+            Call::SetCaptureOptionF32(opt, val) => write!(f, "set_capture_option_f32({:?}, {})", opt, val),
+            Call::SetCaptureOptionU32(opt, val) => write!(f, "set_capture_option_u32({:?}, {})", opt, val),
+            Call::GetCaptureOptionF32(opt) => write!(f, "get_capture_option_f32({:?})", opt),
+            Call::GetCaptureOptionU32(opt) => write!(f, "get_capture_option_u32({:?})", opt),
+            Call::SetFocusToggleKeys(ref keys) => write!(f, "set_focus_toggle_keys({:?})", keys),
+            Call::SetCaptureKeys(ref keys) => write!(f, "set_capture_keys({:?})", keys),
+            Call::GetOverlayBits => write!(f, "get_overlay_bits()"),
+            Call::MaskOverlayBits(and, or) => write!(f, "mask_overlay_bits({:#x}, {:#x})", and.bits(), or.bits()),
+            Call::Shutdown => write!(f, "shutdown()"),
+            Call::UnloadCrashHandler => write!(f, "unload_crash_handler()"),
+            Call::SetCaptureFilePathTemplate(ref path_template) => {
+                write!(f, "set_capture_file_path_template({:?})", path_template.to_string_lossy())
+            }
+            Call::GetCaptureFilePathTemplate => write!(f, "get_capture_file_path_template()"),
+            Call::GetNumCaptures => write!(f, "get_num_captures()"),
+            Call::GetCapture(index) => write!(f, "get_capture({})", index),
+            Call::TriggerCapture => write!(f, "trigger_capture()"),
+            Call::IsTargetControlConnected => write!(f, "is_target_control_connected()"),
+            Call::LaunchReplayUi {
+                connect_target_control,
+                ref cmd_line,
+            } => write!(f, "launch_replay_ui({}, {:?})", connect_target_control, cmd_line),
+            Call::SetActiveWindow { device, window } => {
+                write!(f, "set_active_window({:#x}, {:#x})", device, window)
+            }
+            Call::StartFrameCapture { device, window } => {
+                write!(f, "start_frame_capture({:#x}, {:#x})", device, window)
+            }
+            Call::IsFrameCapturing => write!(f, "is_frame_capturing()"),
+            Call::EndFrameCapture { device, window } => {
+                write!(f, "end_frame_capture({:#x}, {:#x})", device, window)
+            }
+            Call::TriggerMultiFrameCapture(num_frames) => write!(f, "trigger_multi_frame_capture({})", num_frames),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Unit => write!(f, "()"),
+            Value::Bool(val) => write!(f, "{}", val),
+            Value::U32(val) => write!(f, "{}", val),
+            Value::F32(val) => write!(f, "{}", val),
+            Value::Version(version) => write!(f, "{}", version),
+            Value::Overlay(bits) => write!(f, "{:#x}", bits.bits()),
+            Value::Path(ref path) => write!(f, "{:?}", path.to_string_lossy()),
+            Value::Capture(Some((ref path, timestamp))) => write!(f, "Some({:?}, {})", path, timestamp),
+            Value::Capture(None) => write!(f, "None"),
+        }
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} -> {}", self.call, self.result)
+    }
+}
+
+/// Error parsing a recorded call.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    /// Line of the log the error occurred on, starting at 1.
+    pub line: usize,
+    /// Description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for ParseError {}
+
+impl FromStr for Record {
+    type Err = ParseError;
+
+    /// Parses a record in the format it is displayed in.
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser { rest: s.trim() };
+        parser.record().map_err(|message| ParseError { line: 1, message })
+    }
+}
+
+/// Parses a log of records, one per line, as printed with `Display`.
+///
+/// Blank lines are skipped.
+pub fn parse_log(log: &str) -> Result<Vec<Record>, ParseError> {
+    log.lines()
+        .enumerate()
+        .filter(|&(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            line.parse().map_err(|err: ParseError| ParseError {
+                line: index + 1,
+                message: err.message,
+            })
+        })
+        .collect()
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn record(&mut self) -> Result<Record, String> {
+        let call = self.call()?;
+        self.eat(" -> ")?;
+        let result = self.value(&call)?;
+        if !self.rest.is_empty() {
+            return Err(format!("unexpected `{}`", self.rest));
+        }
+        Ok(Record { call, result })
+    }
+
+    fn call(&mut self) -> Result<Call, String> {
+        let name = self.word();
+        self.eat("(")?;
+        let call = match name {
+            "get_api_version" => Call::GetApiVersion,
+            "set_capture_option_f32" => {
+                let opt = self.option()?;
+                self.eat(", ")?;
+                Call::SetCaptureOptionF32(opt, self.number("value")?)
+            }
+            "set_capture_option_u32" => {
+                let opt = self.option()?;
+                self.eat(", ")?;
+                Call::SetCaptureOptionU32(opt, self.number("value")?)
+            }
+            "get_capture_option_f32" => Call::GetCaptureOptionF32(self.option()?),
+            "get_capture_option_u32" => Call::GetCaptureOptionU32(self.option()?),
+            "set_focus_toggle_keys" => Call::SetFocusToggleKeys(self.keys()?),
+            "set_capture_keys" => Call::SetCaptureKeys(self.keys()?),
+            "get_overlay_bits" => Call::GetOverlayBits,
+            "mask_overlay_bits" => {
+                let and = self.overlay()?;
+                self.eat(", ")?;
+                Call::MaskOverlayBits(and, self.overlay()?)
+            }
+            "shutdown" => Call::Shutdown,
+            "unload_crash_handler" => Call::UnloadCrashHandler,
+            "set_capture_file_path_template" => Call::SetCaptureFilePathTemplate(PathBuf::from(self.string()?)),
+            "get_capture_file_path_template" => Call::GetCaptureFilePathTemplate,
+            "get_num_captures" => Call::GetNumCaptures,
+            "get_capture" => Call::GetCapture(self.number("index")?),
+            "trigger_capture" => Call::TriggerCapture,
+            "is_target_control_connected" => Call::IsTargetControlConnected,
+            "launch_replay_ui" => {
+                let connect_target_control = self.bool()?;
+                self.eat(", ")?;
+                Call::LaunchReplayUi {
+                    connect_target_control,
+                    cmd_line: self.string()?,
+                }
+            }
+            "set_active_window" => {
+                let (device, window) = self.handles()?;
+                Call::SetActiveWindow { device, window }
+            }
+            "start_frame_capture" => {
+                let (device, window) = self.handles()?;
+                Call::StartFrameCapture { device, window }
+            }
+            "is_frame_capturing" => Call::IsFrameCapturing,
+            "end_frame_capture" => {
+                let (device, window) = self.handles()?;
+                Call::EndFrameCapture { device, window }
+            }
+            "trigger_multi_frame_capture" => Call::TriggerMultiFrameCapture(self.number("frame count")?),
+            name => return Err(format!("unknown function `{}`", name)),
+        };
+        self.eat(")")?;
+        Ok(call)
+    }
+
+    /// Parses the value returned by `call`.
+    fn value(&mut self, call: &Call) -> Result<Value, String> {
+        let value = match *call {
+            Call::GetApiVersion => {
+                let version = self.word();
+                let numbers: Vec<u32> = version.split('.').filter_map(|n| n.parse().ok()).collect();
+                match numbers[..] {
+                    [major, minor, patch] => Value::Version(ApiVersionNumber::new(major, minor, patch)),
+                    _ => return Err(format!("invalid version `{}`", version)),
+                }
+            }
+            Call::GetCaptureOptionF32(_) => Value::F32(self.number("value")?),
+            Call::GetCaptureOptionU32(_) | Call::GetNumCaptures | Call::LaunchReplayUi { .. } => {
+                Value::U32(self.number("value")?)
+            }
+            Call::GetOverlayBits => Value::Overlay(self.overlay()?),
+            Call::GetCaptureFilePathTemplate => Value::Path(PathBuf::from(self.string()?)),
+            Call::GetCapture(_) => {
+                if self.try_eat("None") {
+                    Value::Capture(None)
+                } else {
+                    self.eat("Some(")?;
+                    let path = self.string()?;
+                    self.eat(", ")?;
+                    let timestamp = self.number("timestamp")?;
+                    self.eat(")")?;
+                    Value::Capture(Some((path, timestamp)))
+                }
+            }
+            Call::IsTargetControlConnected | Call::IsFrameCapturing => Value::Bool(self.bool()?),
+            _ => {
+                self.eat("()")?;
+                Value::Unit
+            }
+        };
+        Ok(value)
+    }
+
+    fn try_eat(&mut self, token: &str) -> bool {
+        if self.rest.starts_with(token) {
+            self.rest = &self.rest[token.len()..];
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> Result<(), String> {
+        if self.try_eat(token) {
+            Ok(())
+        } else {
+            Err(format!("expected `{}` at `{}`", token.trim(), self.rest))
+        }
+    }
+
+    /// Returns the next name, number or version.
+    fn word(&mut self) -> &'a str {
+        let end = self
+            .rest
+            .find(|c: char| !(c.is_alphanumeric() || ['_', '.', '-', '+'].contains(&c)))
+            .unwrap_or(self.rest.len());
+        let (word, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        word
+    }
+
+    fn number<T: FromStr>(&mut self, what: &str) -> Result<T, String> {
+        let word = self.word();
+        word.parse().map_err(|_| format!("invalid {} `{}`", what, word))
+    }
+
+    fn bool(&mut self) -> Result<bool, String> {
+        self.number("boolean")
+    }
+
+    fn hex(&mut self, what: &str) -> Result<usize, String> {
+        let word = self.word();
+        word.strip_prefix("0x")
+            .and_then(|digits| usize::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| format!("invalid {} `{}`", what, word))
+    }
+
+    fn overlay(&mut self) -> Result<OverlayBits, String> {
+        let bits = self.hex("overlay bits")?;
+        Ok(OverlayBits::from_bits_truncate(bits as u32))
+    }
+
+    fn handles(&mut self) -> Result<(usize, usize), String> {
+        let device = self.hex("device")?;
+        self.eat(", ")?;
+        Ok((device, self.hex("window")?))
+    }
+
+    fn option(&mut self) -> Result<CaptureOption, String> {
+        let word = self.word();
+        names::capture_option(word).ok_or_else(|| format!("unknown capture option `{}`", word))
+    }
+
+    fn keys(&mut self) -> Result<Vec<InputButton>, String> {
+        self.eat("[")?;
+        let mut keys = Vec::new();
+        if self.try_eat("]") {
+            return Ok(keys);
+        }
+        loop {
+            let word = self.word();
+            keys.push(names::input_button(word).ok_or_else(|| format!("unknown key `{}`", word))?);
+            if self.try_eat("]") {
+                return Ok(keys);
+            }
+            self.eat(", ")?;
+        }
+    }
+
+    /// Parses a string quoted and escaped as by `Debug`.
+    fn string(&mut self) -> Result<String, String> {
+        self.eat("\"")?;
+        let mut string = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[i + 1..];
+                    return Ok(string);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('\\') => '\\',
+                        Some('"') => '"',
+                        Some('\'') => '\'',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some('u') => {
+                            let rest = chars.as_str();
+                            let code = rest
+                                .strip_prefix('{')
+                                .and_then(|rest| rest.find('}').map(|end| &rest[..end]))
+                                .ok_or("invalid unicode escape")?;
+                            let c = u32::from_str_radix(code, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or("invalid unicode escape")?;
+                            for _ in 0..code.len() + 2 {
+                                chars.next();
+                            }
+                            c
+                        }
+                        _ => return Err("invalid escape in string".to_string()),
+                    };
+                    string.push(escaped);
+                }
+                c => string.push(c),
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+}
+
+/// Logs all calls made through the wrapped `RenderDocApi`.
+#[derive(Debug)]
+pub struct Recorder<A> {
+    inner: A,
+    records: Mutex<Vec<Record>>,
+}
+
+impl<A: RenderDocApi> Recorder<A> {
+    /// Wraps `inner`, starting with an empty log.
+    pub fn new(inner: A) -> Self {
+        Recorder {
+            inner,
+            records: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns all calls made so far, in order.
+    pub fn records(&self) -> Vec<Record> {
+        self.records.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Returns the wrapped implementation.
+    pub fn get_ref(&self) -> &A {
+        &self.inner
+    }
+
+    /// Unwraps this recorder, returning the wrapped implementation and the log.
+    pub fn into_parts(self) -> (A, Vec<Record>) {
+        let records = self.records.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+        (self.inner, records)
+    }
+
+    /// Makes `call` on the wrapped implementation, logging it if it succeeds.
+    fn record(&self, call: Call) -> Result<Value, Error> {
+        let result = call.apply(&self.inner)?.expect("every trait method is replayable");
+        let mut records = self.records.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        records.push(Record {
+            call,
+            result: result.clone(),
+        });
+        Ok(result)
+    }
+}
+
+impl<A: RenderDocApi> RenderDocApi for Recorder<A> {
+    fn get_api_version(&self) -> Result<ApiVersionNumber, Error> {
+        match self.record(Call::GetApiVersion)? {
+            Value::Version(version) => Ok(version),
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_capture_option_f32(&self, opt: CaptureOption, val: f32) -> Result<(), Error> {
+        self.record(Call::SetCaptureOptionF32(opt, val)).map(drop)
+    }
+
+    fn set_capture_option_u32(&self, opt: CaptureOption, val: u32) -> Result<(), Error> {
+        self.record(Call::SetCaptureOptionU32(opt, val)).map(drop)
+    }
+
+    fn get_capture_option_f32(&self, opt: CaptureOption) -> Result<f32, Error> {
+        match self.record(Call::GetCaptureOptionF32(opt))? {
+            Value::F32(val) => Ok(val),
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_capture_option_u32(&self, opt: CaptureOption) -> Result<u32, Error> {
+        match self.record(Call::GetCaptureOptionU32(opt))? {
+            Value::U32(val) => Ok(val),
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_focus_toggle_keys(&self, keys: &[InputButton]) -> Result<(), Error> {
+        self.record(Call::SetFocusToggleKeys(keys.to_vec())).map(drop)
+    }
+
+    fn set_capture_keys(&self, keys: &[InputButton]) -> Result<(), Error> {
+        self.record(Call::SetCaptureKeys(keys.to_vec())).map(drop)
+    }
+
+    fn get_overlay_bits(&self) -> Result<OverlayBits, Error> {
+        match self.record(Call::GetOverlayBits)? {
+            Value::Overlay(bits) => Ok(bits),
+            _ => unreachable!(),
+        }
+    }
+
+    fn mask_overlay_bits(&self, and: OverlayBits, or: OverlayBits) -> Result<(), Error> {
+        self.record(Call::MaskOverlayBits(and, or)).map(drop)
+    }
+
+    fn unload_crash_handler(&self) -> Result<(), Error> {
+        self.record(Call::UnloadCrashHandler).map(drop)
+    }
+
+    fn get_capture_file_path_template(&self) -> Result<PathBuf, Error> {
+        match self.record(Call::GetCaptureFilePathTemplate)? {
+            Value::Path(path) => Ok(path),
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_capture_file_path_template(&self, path_template: &Path) -> Result<(), Error> {
+        self.record(Call::SetCaptureFilePathTemplate(path_template.to_path_buf())).map(drop)
+    }
+
+    fn get_num_captures(&self) -> Result<u32, Error> {
+        match self.record(Call::GetNumCaptures)? {
+            Value::U32(num) => Ok(num),
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_capture(&self, index: u32) -> Result<Option<(String, u64)>, Error> {
+        match self.record(Call::GetCapture(index))? {
+            Value::Capture(capture) => Ok(capture),
+            _ => unreachable!(),
+        }
+    }
+
+    fn trigger_capture(&self) -> Result<(), Error> {
+        self.record(Call::TriggerCapture).map(drop)
+    }
+
+    fn is_target_control_connected(&self) -> Result<bool, Error> {
+        match self.record(Call::IsTargetControlConnected)? {
+            Value::Bool(connected) => Ok(connected),
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_active_window(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error> {
+        self.record(Call::SetActiveWindow {
+            device: *dev as usize,
+            window: win.as_raw() as usize,
+        })
+        .map(drop)
+    }
+
+    fn start_frame_capture(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error> {
+        self.record(Call::StartFrameCapture {
+            device: *dev as usize,
+            window: win.as_raw() as usize,
+        })
+        .map(drop)
+    }
+
+    fn is_frame_capturing(&self) -> Result<bool, Error> {
+        match self.record(Call::IsFrameCapturing)? {
+            Value::Bool(capturing) => Ok(capturing),
+            _ => unreachable!(),
+        }
+    }
+
+    fn end_frame_capture(&self, dev: DevicePointer, win: WindowHandle) -> Result<(), Error> {
+        self.record(Call::EndFrameCapture {
+            device: *dev as usize,
+            window: win.as_raw() as usize,
+        })
+        .map(drop)
+    }
+
+    fn trigger_multi_frame_capture(&self, num_frames: u32) -> Result<(), Error> {
+        self.record(Call::TriggerMultiFrameCapture(num_frames)).map(drop)
+    }
+}
+
+/// Replays a recorded call sequence against `api`.
+///
+/// Returns every call whose return value differs from the recording. Calls
+/// which aren't replayed, see `Call::apply()`, are skipped. Fails with the
+/// error of the first call which fails on `api`.
+pub fn replay(records: &[Record], api: &dyn RenderDocApi) -> Result<Vec<Divergence>, Error> {
+    let mut divergences = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        let actual = match record.call.apply(api)? {
+            Some(actual) => actual,
+            None => continue,
+        };
+        if actual != record.result {
+            divergences.push(Divergence {
+                index,
+                call: record.call.clone(),
+                expected: record.result.clone(),
+                actual,
+            });
+        }
+    }
+    Ok(divergences)
+}
+
+/// The original function table of an interposed entry point, together with
+/// the log of calls made through it.
+struct Interposed {
+    entry: EntryV100,
+    trigger_multi_frame_capture: Option<TriggerMultiFrameCaptureFn>,
+    records: Vec<Record>,
+}
+
+lazy_static! {
+    static ref INTERPOSED: Mutex<Option<Interposed>> = Mutex::new(None);
+}
+
+/// Initializes a new instance of the RenderDoc API, logging every call of a
+/// function of its function table.
+///
+/// Like `RenderDoc::new()`, this fails if a handle is already alive. Calls
+/// are logged no matter which handle or API they are made through, and can be
+/// read with `records()`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use renderdoc::prelude::*;
+/// use renderdoc::record;
+///
+/// # fn run() -> Result<(), renderdoc::Error> {
+/// let rd: RenderDoc<V110> = record::load()?;
+/// rd.trigger_capture()?;
+/// for record in record::take_records() {
+///     println!("{}", record);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn load<V: ApiVersion>() -> Result<RenderDoc<V>, Error> {
+    global::init(|| V::load().map(interpose::<V>))
+}
+
+/// Returns all calls logged by handles created with `load()`, in order.
+pub fn records() -> Vec<Record> {
+    interposed().as_ref().map(|interposed| interposed.records.clone()).unwrap_or_default()
+}
+
+/// Returns and clears all calls logged by handles created with `load()`.
+pub fn take_records() -> Vec<Record> {
+    interposed()
+        .as_mut()
+        .map(|interposed| mem::take(&mut interposed.records))
+        .unwrap_or_default()
+}
+
+fn interposed() -> MutexGuard<'static, Option<Interposed>> {
+    INTERPOSED.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Replaces the functions of `entry` with ones logging each call.
+fn interpose<V: ApiVersion>(mut entry: V::Entry) -> V::Entry {
+    let v100 = AsRef::<EntryV100>::as_ref(&entry) as *const EntryV100;
+    let v110 = V::entry_v110(&entry).map(|v110| v110 as *const EntryV110);
+
+    let mut interposed = interposed();
+    let records = interposed.take().map(|interposed| interposed.records).unwrap_or_default();
+    unsafe {
+        let v100 = table_mut(&mut entry, v100);
+        let trigger_multi_frame_capture = v110.map(|v110| {
+            let v110 = table_mut(&mut entry, v110);
+            mem::replace(&mut (*v110).trigger_multi_frame_capture, shim::trigger_multi_frame_capture)
+        });
+        *interposed = Some(Interposed {
+            entry: mem::replace(&mut *v100, shim::ENTRY_V100),
+            trigger_multi_frame_capture,
+            records,
+        });
+    }
+    entry
+}
+
+/// Turns a pointer to a function table within `entry`, as returned by
+/// `AsRef` or `ApiVersion::entry_v110()`, into a mutable one.
+unsafe fn table_mut<E, T>(entry: &mut E, table: *const T) -> *mut T {
+    let offset = (table as usize)
+        .checked_sub(entry as *const E as usize)
+        .filter(|offset| offset + mem::size_of::<T>() <= mem::size_of::<E>())
+        .expect("function table lies outside of the entry point");
+    (entry as *mut E as *mut u8).add(offset) as *mut T
+}
+
+/// Functions logging each call before forwarding it to the original table.
+mod shim {
+    use super::*;
+
+    pub(super) const ENTRY_V100: EntryV100 = EntryV100 {
+        get_api_version,
+        set_capture_option_u32,
+        set_capture_option_f32,
+        get_capture_option_u32,
+        get_capture_option_f32,
+        set_focus_toggle_keys,
+        set_capture_keys,
+        get_overlay_bits,
+        mask_overlay_bits,
+        shutdown,
+        unload_crash_handler,
+        set_log_file_path_template,
+        get_log_file_path_template,
+        get_num_captures,
+        get_capture,
+        trigger_capture,
+        is_target_control_connected,
+        launch_replay_ui,
+        set_active_window,
+        start_frame_capture,
+        is_frame_capturing,
+        end_frame_capture,
+    };
+
+    fn original() -> EntryV100 {
+        interposed().as_ref().expect("RenderDoc API is not interposed").entry.clone()
+    }
+
+    fn log(call: Call, result: Value) {
+        if let Some(ref mut interposed) = *interposed() {
+            interposed.records.push(Record { call, result });
+        }
+    }
+
+    unsafe fn keys(keys: *const InputButton, num: c_int) -> Vec<InputButton> {
+        if keys.is_null() || num <= 0 {
+            Vec::new()
+        } else {
+            slice::from_raw_parts(keys, num as usize).to_vec()
+        }
+    }
+
+    unsafe fn string(s: *const c_char) -> String {
+        if s.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(s).to_string_lossy().into_owned()
+        }
+    }
+
+    #[cfg(unix)]
+    unsafe fn path(s: *const c_char) -> PathBuf {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        if s.is_null() {
+            PathBuf::new()
+        } else {
+            PathBuf::from(OsStr::from_bytes(CStr::from_ptr(s).to_bytes()))
+        }
+    }
+
+    #[cfg(not(unix))]
+    unsafe fn path(s: *const c_char) -> PathBuf {
+        PathBuf::from(string(s))
+    }
+
+    unsafe extern "C" fn get_api_version(major: *mut c_int, minor: *mut c_int, patch: *mut c_int) {
+        let mut version: (c_int, c_int, c_int) = (0, 0, 0);
+        (original().get_api_version)(&mut version.0, &mut version.1, &mut version.2);
+        for &(out, val) in &[(major, version.0), (minor, version.1), (patch, version.2)] {
+            if !out.is_null() {
+                *out = val;
+            }
+        }
+        let version = ApiVersionNumber::new(version.0 as u32, version.1 as u32, version.2 as u32);
+        log(Call::GetApiVersion, Value::Version(version));
+    }
+
+    unsafe extern "C" fn set_capture_option_u32(opt: CaptureOption, val: u32) -> c_int {
+        let ret = (original().set_capture_option_u32)(opt, val);
+        log(Call::SetCaptureOptionU32(opt, val), Value::Unit);
+        ret
+    }
+
+    unsafe extern "C" fn set_capture_option_f32(opt: CaptureOption, val: f32) -> c_int {
+        let ret = (original().set_capture_option_f32)(opt, val);
+        log(Call::SetCaptureOptionF32(opt, val), Value::Unit);
+        ret
+    }
+
+    unsafe extern "C" fn get_capture_option_u32(opt: CaptureOption) -> u32 {
+        let val = (original().get_capture_option_u32)(opt);
+        log(Call::GetCaptureOptionU32(opt), Value::U32(val));
+        val
+    }
+
+    unsafe extern "C" fn get_capture_option_f32(opt: CaptureOption) -> f32 {
+        let val = (original().get_capture_option_f32)(opt);
+        log(Call::GetCaptureOptionF32(opt), Value::F32(val));
+        val
+    }
+
+    unsafe extern "C" fn set_focus_toggle_keys(keys_ptr: *const InputButton, num: c_int) {
+        (original().set_focus_toggle_keys)(keys_ptr, num);
+        log(Call::SetFocusToggleKeys(keys(keys_ptr, num)), Value::Unit);
+    }
+
+    unsafe extern "C" fn set_capture_keys(keys_ptr: *const InputButton, num: c_int) {
+        (original().set_capture_keys)(keys_ptr, num);
+        log(Call::SetCaptureKeys(keys(keys_ptr, num)), Value::Unit);
+    }
+
+    // `OverlayBits` wraps a single `u32`, and the function tables in `sys`
+    // pass it the same way.
+    #[allow(improper_ctypes_definitions)]
+    unsafe extern "C" fn get_overlay_bits() -> OverlayBits {
+        let bits = (original().get_overlay_bits)();
+        log(Call::GetOverlayBits, Value::Overlay(bits));
+        bits
+    }
+
+    #[allow(improper_ctypes_definitions)]
+    unsafe extern "C" fn mask_overlay_bits(and: OverlayBits, or: OverlayBits) {
+        (original().mask_overlay_bits)(and, or);
+        log(Call::MaskOverlayBits(and, or), Value::Unit);
+    }
+
+    unsafe extern "C" fn shutdown() {
+        // Nothing may be called after shutting down, so log beforehand.
+        let shutdown = original().shutdown;
+        log(Call::Shutdown, Value::Unit);
+        shutdown();
+    }
+
+    unsafe extern "C" fn unload_crash_handler() {
+        (original().unload_crash_handler)();
+        log(Call::UnloadCrashHandler, Value::Unit);
+    }
+
+    unsafe extern "C" fn set_log_file_path_template(path_template: *const c_char) {
+        (original().set_log_file_path_template)(path_template);
+        // A null template leaves the template unchanged.
+        if !path_template.is_null() {
+            log(Call::SetCaptureFilePathTemplate(path(path_template)), Value::Unit);
+        }
+    }
+
+    unsafe extern "C" fn get_log_file_path_template() -> *const c_char {
+        let path_template = (original().get_log_file_path_template)();
+        log(Call::GetCaptureFilePathTemplate, Value::Path(path(path_template)));
+        path_template
+    }
+
+    unsafe extern "C" fn get_num_captures() -> u32 {
+        let num = (original().get_num_captures)();
+        log(Call::GetNumCaptures, Value::U32(num));
+        num
+    }
+
+    unsafe extern "C" fn get_capture(idx: u32, log_file: *mut c_char, path_len: *mut u32, timestamp: *mut u64) -> u32 {
+        let ret = (original().get_capture)(idx, log_file, path_len, timestamp);
+        // Paths are retrieved by first querying their length, which isn't
+        // worth a record of its own.
+        if ret != 1 {
+            log(Call::GetCapture(idx), Value::Capture(None));
+        } else if !log_file.is_null() {
+            let timestamp = if timestamp.is_null() { 0 } else { *timestamp };
+            log(Call::GetCapture(idx), Value::Capture(Some((string(log_file), timestamp))));
+        }
+        ret
+    }
+
+    unsafe extern "C" fn trigger_capture() {
+        (original().trigger_capture)();
+        log(Call::TriggerCapture, Value::Unit);
+    }
+
+    unsafe extern "C" fn is_target_control_connected() -> u32 {
+        let ret = (original().is_target_control_connected)();
+        log(Call::IsTargetControlConnected, Value::Bool(ret == 1));
+        ret
+    }
+
+    unsafe extern "C" fn launch_replay_ui(connect_target_control: u32, cmd_line: *const c_char) -> u32 {
+        let pid = (original().launch_replay_ui)(connect_target_control, cmd_line);
+        let call = Call::LaunchReplayUi {
+            connect_target_control: connect_target_control == 1,
+            cmd_line: string(cmd_line),
+        };
+        log(call, Value::U32(pid));
+        pid
+    }
+
+    unsafe extern "C" fn set_active_window(device: DevicePointer, wnd_handle: WindowHandle) {
+        let (dev, win) = (*device as usize, wnd_handle.as_raw() as usize);
+        (original().set_active_window)(device, wnd_handle);
+        log(Call::SetActiveWindow { device: dev, window: win }, Value::Unit);
+    }
+
+    unsafe extern "C" fn start_frame_capture(device: DevicePointer, wnd_handle: WindowHandle) {
+        let (dev, win) = (*device as usize, wnd_handle.as_raw() as usize);
+        (original().start_frame_capture)(device, wnd_handle);
+        log(Call::StartFrameCapture { device: dev, window: win }, Value::Unit);
+    }
+
+    unsafe extern "C" fn is_frame_capturing() -> u32 {
+        let ret = (original().is_frame_capturing)();
+        log(Call::IsFrameCapturing, Value::Bool(ret == 1));
+        ret
+    }
+
+    unsafe extern "C" fn end_frame_capture(device: DevicePointer, wnd_handle: WindowHandle) -> u32 {
+        let (dev, win) = (*device as usize, wnd_handle.as_raw() as usize);
+        let ret = (original().end_frame_capture)(device, wnd_handle);
+        log(Call::EndFrameCapture { device: dev, window: win }, Value::Unit);
+        ret
+    }
+
+    pub(super) unsafe extern "C" fn trigger_multi_frame_capture(num_frames: u32) {
+        let original = interposed().as_ref().and_then(|interposed| interposed.trigger_multi_frame_capture);
+        (original.expect("RenderDoc API 1.1.0 is not interposed"))(num_frames);
+        log(Call::TriggerMultiFrameCapture(num_frames), Value::Unit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn record_and_replay() {
+        use mock::MockRenderDoc;
+
+        let rd = Recorder::new(MockRenderDoc::new());
+        rd.set_capture_option_u32(CaptureOption::CaptureCallstacks, 1).unwrap();
+        assert_eq!(rd.get_capture_option_u32(CaptureOption::CaptureCallstacks), Ok(1));
+        rd.trigger_capture().unwrap();
+        assert_eq!(rd.get_num_captures(), Ok(1));
+
+        let (mock, records) = rd.into_parts();
+        assert_eq!(mock.calls().len(), 4);
+        assert_eq!(
+            records[1].to_string(),
+            "get_capture_option_u32(CaptureCallstacks) -> 1"
+        );
+        assert!(replay(&records, &MockRenderDoc::new()).unwrap().is_empty());
+
+        // A handle which already made a capture diverges on the count.
+        let other = MockRenderDoc::new();
+        other.trigger_capture().unwrap();
+        let divergences = replay(&records, &other).unwrap();
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].index, 3);
+        assert_eq!(divergences[0].actual, Value::U32(2));
+    }
+
+    #[test]
+    fn parse_printed_log() {
+        let records = vec![
+            Record {
+                call: Call::SetCaptureOptionF32(CaptureOption::DelayForDebugger, -f32::MAX),
+                result: Value::Unit,
+            },
+            Record {
+                call: Call::SetCaptureKeys(vec![InputButton::Key1, InputButton::NonPrintable, InputButton::PrtScrn]),
+                result: Value::Unit,
+            },
+            Record {
+                call: Call::SetFocusToggleKeys(Vec::new()),
+                result: Value::Unit,
+            },
+            Record {
+                call: Call::MaskOverlayBits(OverlayBits::NONE, OverlayBits::ALL),
+                result: Value::Unit,
+            },
+            Record {
+                call: Call::GetCaptureFilePathTemplate,
+                result: Value::Path(PathBuf::from("C:\\captures\\\"game\"")),
+            },
+            Record {
+                call: Call::GetCapture(0),
+                result: Value::Capture(Some(("caf\u{e9}\u{7f}\n.rdc".to_string(), 1_700_000_000))),
+            },
+            Record {
+                call: Call::GetCapture(1),
+                result: Value::Capture(None),
+            },
+            Record {
+                call: Call::LaunchReplayUi {
+                    connect_target_control: true,
+                    cmd_line: "--mode -> \"x\"".to_string(),
+                },
+                result: Value::U32(4242),
+            },
+            Record {
+                call: Call::EndFrameCapture {
+                    device: 0xdead_beef,
+                    window: 0,
+                },
+                result: Value::Unit,
+            },
+            Record {
+                call: Call::GetApiVersion,
+                result: Value::Version(ApiVersionNumber::new(1, 6, 0)),
+            },
+            Record {
+                call: Call::Shutdown,
+                result: Value::Unit,
+            },
+        ];
+        let log: String = records.iter().map(|record| format!("{}\n\n", record)).collect();
+        assert_eq!(parse_log(&log).unwrap(), records);
+
+        assert_eq!(
+            parse_log("trigger_capture() -> ()\nget_capture(x) -> None").unwrap_err(),
+            ParseError {
+                line: 2,
+                message: "invalid index `x`".to_string(),
+            }
+        );
+        assert!("is_frame_capturing() -> ()".parse::<Record>().is_err());
+        assert!("trigger_capture() -> () trailing".parse::<Record>().is_err());
+        assert!("set_capture_keys([F13]) -> ()".parse::<Record>().is_err());
+    }
+
+    #[test]
+    fn interpose_entry_point() {
+        use std::ptr;
+        use V110;
+
+        unsafe extern "C" fn noop() {}
+        unsafe extern "C" fn get_api_version(major: *mut c_int, minor: *mut c_int, patch: *mut c_int) {
+            *major = 1;
+            *minor = 1;
+            *patch = 2;
+        }
+        unsafe extern "C" fn get_num_captures() -> u32 {
+            1
+        }
+        unsafe extern "C" fn get_capture(idx: u32, log_file: *mut c_char, len: *mut u32, timestamp: *mut u64) -> u32 {
+            if idx != 0 {
+                return 0;
+            }
+            *len = 6;
+            *timestamp = 7;
+            if !log_file.is_null() {
+                ptr::copy_nonoverlapping(b"a.rdc\0".as_ptr() as *const c_char, log_file, 6);
+            }
+            1
+        }
+        unsafe extern "C" fn trigger_multi_frame_capture(_: u32) {}
+
+        // Slots 0, 13, 14 and 22 hold the functions called below.
+        let mut table = [noop as *const c_void; 23];
+        table[0] = get_api_version as *const c_void;
+        table[13] = get_num_captures as *const c_void;
+        table[14] = get_capture as *const c_void;
+        table[22] = trigger_multi_frame_capture as *const c_void;
+        let entry = interpose::<V110>(unsafe { ptr::read(table.as_ptr() as *const EntryV110) });
+
+        unsafe {
+            let (mut major, mut patch) = (0, 0);
+            (entry.entry_v100.get_api_version)(&mut major, ptr::null_mut(), &mut patch);
+            assert_eq!((major, patch), (1, 2));
+
+            assert_eq!((entry.entry_v100.get_num_captures)(), 1);
+            let (mut path, mut len, mut timestamp) = ([0 as c_char; 6], 0, 0);
+            (entry.entry_v100.get_capture)(0, ptr::null_mut(), &mut len, &mut timestamp);
+            (entry.entry_v100.get_capture)(0, path.as_mut_ptr(), &mut len, &mut timestamp);
+            assert_eq!((entry.entry_v100.get_capture)(1, ptr::null_mut(), &mut len, &mut timestamp), 0);
+            (entry.trigger_multi_frame_capture)(3);
+        }
+
+        let log: Vec<String> = take_records().iter().map(|record| record.to_string()).collect();
+        assert_eq!(
+            log,
+            vec![
+                "get_api_version() -> 1.1.2",
+                "get_num_captures() -> 1",
+                "get_capture(0) -> Some(\"a.rdc\", 7)",
+                "get_capture(1) -> None",
+                "trigger_multi_frame_capture(3) -> ()",
+            ]
+        );
+        assert!(records().is_empty());
+    }
+}
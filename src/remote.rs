@@ -0,0 +1,548 @@
+//! Client for RenderDoc's remote server protocol.
+//!
+//! The remote server is started with `renderdoccmd remoteserver` on another
+//! machine, or by the RenderDoc app on an Android device. It gives access to
+//! the remote file system, transfers capture files, and launches executables
+//! with RenderDoc injected, which allows device farms to be driven headlessly.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use renderdoc::remote::RemoteServer;
+//!
+//! # fn run() -> Result<(), renderdoc::protocol::Error> {
+//! let mut server = RemoteServer::connect(("build-agent-7", renderdoc::remote::DEFAULT_PORT))?;
+//! let home = server.home_dir()?;
+//! for entry in server.list_dir(&home)? {
+//!     println!("{}", entry.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+
+use command::CaptureOptions;
+use protocol::{read_packet, Error, PacketReader, PacketWriter};
+use replay_ui;
+
+/// Default port on which the remote server listens.
+pub const DEFAULT_PORT: u16 = 39920;
+
+/// Version of the remote server protocol spoken by this client.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Size of the chunks in which captures are copied, between progress reports.
+const COPY_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Remote server packet types.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum PacketType {
+    Handshake = 2,
+    VersionMismatch = 3,
+    Busy = 4,
+    Ping = 5,
+    CopyCaptureFromRemote = 9,
+    HomeDir = 13,
+    ListDir = 14,
+    ExecuteAndInject = 15,
+}
+
+bitflags! {
+    /// Properties of an entry in the remote file system.
+    pub struct PathFlags: u32 {
+        /// The entry is a directory.
+        const DIRECTORY = 0x1;
+        /// The entry is hidden.
+        const HIDDEN = 0x2;
+        /// The entry is an executable file.
+        const EXECUTABLE = 0x4;
+        /// The entry could not be listed for an unknown reason.
+        const ERROR_UNKNOWN = 0x8;
+        /// The entry could not be listed due to missing permissions.
+        const ERROR_ACCESS_DENIED = 0x10;
+        /// The listed path does not exist.
+        const ERROR_INVALID_PATH = 0x20;
+    }
+}
+
+/// An entry in a directory listing of the remote file system.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PathEntry {
+    /// File name of the entry, without its directory.
+    pub name: String,
+    /// Properties of the entry.
+    pub flags: PathFlags,
+    /// Last modification time, measured in seconds since the UNIX epoch.
+    pub modified: u64,
+    /// Size of the file in bytes.
+    pub size: u64,
+}
+
+/// A capture file on the remote machine, as listed by
+/// `RemoteServer::list_captures()`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RemoteCapture {
+    /// Full path of the capture on the remote machine.
+    pub path: String,
+    /// Last modification time, measured in seconds since the UNIX epoch.
+    pub modified: u64,
+    /// Size of the capture in bytes.
+    pub size: u64,
+}
+
+impl RemoteCapture {
+    /// Returns the file name of the capture, without its directory.
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path)
+    }
+}
+
+/// How to launch an executable with `RemoteServer::launch()`.
+///
+/// Mirrors the settings of the "Launch Application" panel of the RenderDoc UI
+/// when connected to a remote host.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LaunchOptions {
+    /// Working directory of the process, or its executable's directory if
+    /// empty.
+    pub working_dir: String,
+    /// Command line arguments, quoted as needed before being sent.
+    pub args: Vec<String>,
+    /// Environment variables set for the process, in addition to those of the
+    /// remote server.
+    pub env: Vec<(String, String)>,
+    /// Options applied to captures of the process.
+    pub capture_options: CaptureOptions,
+}
+
+/// Joins `args` to a single command line, quoted the same way as the replay
+/// UI's command line.
+fn join_args(args: &[String]) -> String {
+    args.iter().map(|arg| replay_ui::quote(arg)).collect::<Vec<_>>().join(" ")
+}
+
+/// A blocking connection to a remote server.
+#[derive(Debug)]
+pub struct RemoteServer {
+    stream: TcpStream,
+}
+
+impl RemoteServer {
+    /// Connects to the remote server listening on `addr`.
+    ///
+    /// Remote servers only accept a single client at a time, and connecting
+    /// to a busy server fails with `Error::Busy`.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<RemoteServer, Error> {
+        let mut stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        stream.write_all(&PacketWriter::new().u32(PROTOCOL_VERSION).finish(PacketType::Handshake as u32))?;
+
+        let (ty, payload) = read_packet(&mut stream)?;
+        let mut reader = PacketReader::new(&payload);
+        match ty {
+            ty if ty == PacketType::Handshake as u32 => Ok(RemoteServer { stream }),
+            ty if ty == PacketType::VersionMismatch as u32 => Err(Error::VersionMismatch(reader.u32()?)),
+            ty if ty == PacketType::Busy as u32 => Err(Error::Busy(reader.string()?)),
+            ty => Err(Error::UnexpectedPacket(ty)),
+        }
+    }
+
+    /// Sends a request and waits for the reply of the same type.
+    fn request(&mut self, ty: PacketType, request: PacketWriter) -> Result<Vec<u8>, Error> {
+        self.stream.write_all(&request.finish(ty as u32))?;
+        let (reply, payload) = read_packet(&mut self.stream)?;
+        if reply != ty as u32 {
+            return Err(Error::UnexpectedPacket(reply));
+        }
+
+        Ok(payload)
+    }
+
+    /// Checks whether the server is still responsive.
+    pub fn ping(&mut self) -> Result<(), Error> {
+        self.request(PacketType::Ping, PacketWriter::new()).map(|_| ())
+    }
+
+    /// Returns the home directory of the user running the remote server.
+    pub fn home_dir(&mut self) -> Result<String, Error> {
+        let payload = self.request(PacketType::HomeDir, PacketWriter::new())?;
+        PacketReader::new(&payload).string()
+    }
+
+    /// Lists the contents of the given directory on the remote machine.
+    pub fn list_dir(&mut self, path: &str) -> Result<Vec<PathEntry>, Error> {
+        let payload = self.request(PacketType::ListDir, PacketWriter::new().str(path))?;
+        let mut reader = PacketReader::new(&payload);
+        let count = reader.u64()?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            entries.push(PathEntry {
+                name: reader.string()?,
+                flags: PathFlags::from_bits_truncate(reader.u32()?),
+                modified: reader.u64()?,
+                size: reader.u64()?,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Lists the capture files in the given directory on the remote machine,
+    /// oldest first.
+    ///
+    /// Only the directory itself is searched, not its subdirectories.
+    pub fn list_captures(&mut self, dir: &str) -> Result<Vec<RemoteCapture>, Error> {
+        let separator = if dir.ends_with('/') || dir.ends_with('\\') { "" } else { "/" };
+        let mut captures: Vec<_> = self
+            .list_dir(dir)?
+            .into_iter()
+            .filter(|entry| !entry.flags.contains(PathFlags::DIRECTORY) && entry.name.ends_with(".rdc"))
+            .map(|entry| RemoteCapture {
+                path: format!("{}{}{}", dir, separator, entry.name),
+                modified: entry.modified,
+                size: entry.size,
+            })
+            .collect();
+        captures.sort_by_key(|capture| capture.modified);
+        Ok(captures)
+    }
+
+    /// Copies a capture file from the remote machine to `local_path`.
+    ///
+    /// Returns the number of bytes copied.
+    pub fn copy_capture_from_remote<P: AsRef<Path>>(&mut self, remote_path: &str, local_path: P) -> Result<u64, Error> {
+        self.copy_capture_with_progress(remote_path, local_path, |_, _| {})
+    }
+
+    /// Copies a capture file from the remote machine to `local_path`, calling
+    /// `progress` with the bytes copied so far and the total size as the
+    /// transfer advances.
+    ///
+    /// Returns the number of bytes copied.
+    pub fn copy_capture_with_progress<P, F>(
+        &mut self,
+        remote_path: &str,
+        local_path: P,
+        mut progress: F,
+    ) -> Result<u64, Error>
+    where
+        P: AsRef<Path>,
+        F: FnMut(u64, u64),
+    {
+        let payload = self.request(PacketType::CopyCaptureFromRemote, PacketWriter::new().str(remote_path))?;
+        let size = PacketReader::new(&payload).u64()?;
+
+        let local_path = local_path.as_ref();
+        let stream = &mut self.stream;
+        let result = (|| {
+            let mut file = File::create(local_path)?;
+            let mut buf = vec![0u8; COPY_CHUNK_SIZE];
+            let mut copied = 0;
+            progress(0, size);
+            while copied < size {
+                let len = (size - copied).min(buf.len() as u64) as usize;
+                match stream.read(&mut buf[..len])? {
+                    0 => return Err(Error::Malformed("capture transfer ended early")),
+                    read => {
+                        file.write_all(&buf[..read])?;
+                        copied += read as u64;
+                        progress(copied, size);
+                    }
+                }
+            }
+            Ok(copied)
+        })();
+        if result.is_err() {
+            let _ = fs::remove_file(local_path);
+        }
+        result
+    }
+
+    /// Copies the given captures into `local_dir`, keeping their file names.
+    ///
+    /// `progress` is called with the capture being copied, the bytes copied
+    /// so far and its total size. Returns the local paths of the copies.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use renderdoc::remote::{RemoteServer, DEFAULT_PORT};
+    ///
+    /// # fn run() -> Result<(), renderdoc::protocol::Error> {
+    /// let mut server = RemoteServer::connect(("localhost", DEFAULT_PORT))?;
+    /// let captures = server.list_captures("/sdcard/RenderDoc")?;
+    /// server.pull_captures(&captures, "captures", |capture, copied, size| {
+    ///     println!("{}: {}/{} bytes", capture.file_name(), copied, size);
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pull_captures<P, F>(
+        &mut self,
+        captures: &[RemoteCapture],
+        local_dir: P,
+        mut progress: F,
+    ) -> Result<Vec<PathBuf>, Error>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&RemoteCapture, u64, u64),
+    {
+        fs::create_dir_all(&local_dir)?;
+        let mut paths = Vec::new();
+        for capture in captures {
+            let local_path = local_dir.as_ref().join(capture.file_name());
+            self.copy_capture_with_progress(&capture.path, &local_path, |copied, size| {
+                progress(capture, copied, size)
+            })?;
+            paths.push(local_path);
+        }
+
+        Ok(paths)
+    }
+
+    /// Launches an executable on the remote machine with RenderDoc injected.
+    ///
+    /// Returns the target control port of the launched process, which can be
+    /// connected to with `TargetControl::connect()`.
+    pub fn execute_and_inject(&mut self, app: &str, working_dir: &str, cmd_line: &str) -> Result<u16, Error> {
+        let request = PacketWriter::new()
+            .str(app)
+            .str(working_dir)
+            .str(cmd_line)
+            .u64(0)
+            .u64(0);
+        self.inject(request)
+    }
+
+    /// Launches an executable on the remote machine with RenderDoc injected,
+    /// using the given arguments, environment and capture options.
+    ///
+    /// Returns the target control port of the launched process, which can be
+    /// connected to with `TargetControl::connect()`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use renderdoc::remote::{LaunchOptions, RemoteServer, DEFAULT_PORT};
+    /// use renderdoc::targetcontrol::TargetControl;
+    ///
+    /// # fn run() -> Result<(), renderdoc::protocol::Error> {
+    /// let mut server = RemoteServer::connect(("build-agent-7", DEFAULT_PORT))?;
+    /// let mut options = LaunchOptions {
+    ///     working_dir: "/opt/game".into(),
+    ///     args: vec!["--level".into(), "Test Map".into()],
+    ///     ..LaunchOptions::default()
+    /// };
+    /// options.capture_options.api_validation = true;
+    ///
+    /// let port = server.launch("/opt/game/bin/game", &options)?;
+    /// let target = TargetControl::connect(("build-agent-7", port), "automation", false)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn launch(&mut self, app: &str, options: &LaunchOptions) -> Result<u16, Error> {
+        let mut request = PacketWriter::new()
+            .str(app)
+            .str(&options.working_dir)
+            .str(&join_args(&options.args))
+            .u64(options.env.len() as u64);
+        for (name, value) in &options.env {
+            // Plain assignments, without a separator for appending to lists.
+            request = request.u32(0).u32(0).str(name).str(value);
+        }
+        self.inject(request.bytes(&options.capture_options.raw()))
+    }
+
+    /// Sends an `ExecuteAndInject` request, returning the target control port.
+    fn inject(&mut self, request: PacketWriter) -> Result<u16, Error> {
+        let payload = self.request(PacketType::ExecuteAndInject, request)?;
+
+        let mut reader = PacketReader::new(&payload);
+        let status = reader.u32()?;
+        let ident = reader.u32()?;
+        match status {
+            0 => u16::try_from(ident).map_err(|_| Error::Malformed("target control port out of range")),
+            status => Err(Error::Remote(status)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::net::TcpListener;
+    use std::process;
+    use std::thread;
+
+    fn accept(listener: TcpListener) -> TcpStream {
+        let (mut stream, _) = listener.accept().unwrap();
+        let (ty, _) = read_packet(&mut stream).unwrap();
+        assert_eq!(ty, PacketType::Handshake as u32);
+        stream.write_all(&PacketWriter::new().finish(PacketType::Handshake as u32)).unwrap();
+        stream
+    }
+
+    #[test]
+    fn list_dir_over_loopback() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let mut stream = accept(listener);
+
+            let (ty, payload) = read_packet(&mut stream).unwrap();
+            assert_eq!(ty, PacketType::ListDir as u32);
+            assert_eq!(PacketReader::new(&payload).string().unwrap(), "/captures");
+            let reply = PacketWriter::new()
+                .u64(1)
+                .str("frame12.rdc")
+                .u32(0)
+                .u64(1_500_000_000)
+                .u64(4096)
+                .finish(PacketType::ListDir as u32);
+            stream.write_all(&reply).unwrap();
+        });
+
+        let mut client = RemoteServer::connect(addr).unwrap();
+        let entries = client.list_dir("/captures").unwrap();
+        server.join().unwrap();
+
+        let expected = PathEntry {
+            name: "frame12.rdc".into(),
+            flags: PathFlags::empty(),
+            modified: 1_500_000_000,
+            size: 4096,
+        };
+        assert_eq!(entries, vec![expected]);
+    }
+
+    #[test]
+    fn quote_args() {
+        let args = ["--level", "Test Map", "say \"hi\"", "", r"C:\Game Data\"];
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(join_args(&args), r#"--level "Test Map" "say \"hi\"" "" "C:\Game Data\\""#);
+    }
+
+    #[test]
+    fn launch_over_loopback() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let options = LaunchOptions {
+            working_dir: "/opt/game".into(),
+            args: vec!["--frames".into(), "3".into()],
+            env: vec![("RUST_LOG".into(), "debug".into())],
+            capture_options: CaptureOptions::default(),
+        };
+        let raw_options = options.capture_options.raw();
+
+        let server = thread::spawn(move || {
+            let mut stream = accept(listener);
+            let (ty, payload) = read_packet(&mut stream).unwrap();
+            assert_eq!(ty, PacketType::ExecuteAndInject as u32);
+            let mut reader = PacketReader::new(&payload);
+            assert_eq!(reader.string().unwrap(), "/opt/game/bin/game");
+            assert_eq!(reader.string().unwrap(), "/opt/game");
+            assert_eq!(reader.string().unwrap(), "--frames 3");
+            assert_eq!(reader.u64().unwrap(), 1);
+            assert_eq!((reader.u32().unwrap(), reader.u32().unwrap()), (0, 0));
+            assert_eq!(reader.string().unwrap(), "RUST_LOG");
+            assert_eq!(reader.string().unwrap(), "debug");
+            assert_eq!(reader.u64().unwrap(), 20);
+            assert!(payload.ends_with(&raw_options));
+
+            let reply = PacketWriter::new().u32(0).u32(38920);
+            stream.write_all(&reply.finish(PacketType::ExecuteAndInject as u32)).unwrap();
+        });
+
+        let mut client = RemoteServer::connect(addr).unwrap();
+        let port = client.launch("/opt/game/bin/game", &options).unwrap();
+        server.join().unwrap();
+        assert_eq!(port, 38920);
+    }
+
+    #[test]
+    fn pull_captures_over_loopback() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let capture = vec![7u8; COPY_CHUNK_SIZE + 10];
+        let data = capture.clone();
+        let server = thread::spawn(move || {
+            let mut stream = accept(listener);
+            read_packet(&mut stream).unwrap();
+            let reply = PacketWriter::new()
+                .u64(3)
+                .str("new.rdc")
+                .u32(0)
+                .u64(20)
+                .u64(data.len() as u64)
+                .str("old.rdc")
+                .u32(0)
+                .u64(10)
+                .u64(0)
+                .str("log.txt")
+                .u32(0)
+                .u64(30)
+                .u64(0)
+                .finish(PacketType::ListDir as u32);
+            stream.write_all(&reply).unwrap();
+
+            let (ty, payload) = read_packet(&mut stream).unwrap();
+            assert_eq!(ty, PacketType::CopyCaptureFromRemote as u32);
+            assert_eq!(PacketReader::new(&payload).string().unwrap(), "/captures/new.rdc");
+            let reply = PacketWriter::new().u64(data.len() as u64);
+            stream.write_all(&reply.finish(PacketType::CopyCaptureFromRemote as u32)).unwrap();
+            stream.write_all(&data).unwrap();
+        });
+
+        let mut client = RemoteServer::connect(addr).unwrap();
+        let captures = client.list_captures("/captures").unwrap();
+        let names: Vec<_> = captures.iter().map(RemoteCapture::file_name).collect();
+        assert_eq!(names, vec!["old.rdc", "new.rdc"]);
+
+        let local_dir = env::temp_dir().join(format!("renderdoc-rs-remote-pull-{}", process::id()));
+        let mut reports = Vec::new();
+        let paths = client
+            .pull_captures(&captures[1..], &local_dir, |capture, copied, size| {
+                reports.push((capture.file_name().to_string(), copied, size));
+            })
+            .unwrap();
+        server.join().unwrap();
+
+        assert_eq!(paths, vec![local_dir.join("new.rdc")]);
+        assert_eq!(fs::read(&paths[0]).unwrap(), capture);
+        let size = capture.len() as u64;
+        assert_eq!(reports.first(), Some(&("new.rdc".to_string(), 0, size)));
+        assert_eq!(reports.last(), Some(&("new.rdc".to_string(), size, size)));
+        fs::remove_dir_all(&local_dir).unwrap();
+    }
+
+    #[test]
+    fn truncated_copy_removes_file() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let mut stream = accept(listener);
+            read_packet(&mut stream).unwrap();
+            let reply = PacketWriter::new().u64(100);
+            stream.write_all(&reply.finish(PacketType::CopyCaptureFromRemote as u32)).unwrap();
+            stream.write_all(&[7u8; 10]).unwrap();
+        });
+
+        let mut client = RemoteServer::connect(addr).unwrap();
+        let local_path = env::temp_dir().join(format!("renderdoc-rs-remote-truncated-{}.rdc", process::id()));
+        let result = client.copy_capture_from_remote("/captures/new.rdc", &local_path);
+        server.join().unwrap();
+
+        match result {
+            Err(Error::Malformed(_)) => {}
+            other => panic!("expected a malformed transfer, got {:?}", other),
+        }
+        assert!(!local_path.exists());
+    }
+}
@@ -0,0 +1,106 @@
+//! Actions recorded in a capture.
+
+use serde::{Deserialize, Deserializer};
+
+bitflags! {
+    /// Kinds of work performed by an action.
+    ///
+    /// The bits are assigned by this crate and do not match RenderDoc's own
+    /// `ActionFlags`, which changed between releases.
+    pub struct ActionFlags: u32 {
+        /// The action clears a resource.
+        const CLEAR = 0x1;
+        /// The action is a draw call.
+        const DRAWCALL = 0x2;
+        /// The action is a compute dispatch.
+        const DISPATCH = 0x4;
+        /// The action executes a secondary command list or bundle.
+        const CMD_LIST = 0x8;
+        /// The action is a single debug marker.
+        const SET_MARKER = 0x10;
+        /// The action opens a debug marker region containing its children.
+        const PUSH_MARKER = 0x20;
+        /// The action closes a debug marker region.
+        const POP_MARKER = 0x40;
+        /// The action presents a swapchain image.
+        const PRESENT = 0x80;
+        /// The action is a multi-draw or multi-dispatch.
+        const MULTI_ACTION = 0x100;
+        /// The action copies between resources.
+        const COPY = 0x200;
+        /// The action resolves a multisampled resource.
+        const RESOLVE = 0x400;
+        /// The action generates mipmaps.
+        const GEN_MIPS = 0x800;
+        /// The action begins or ends a render pass.
+        const PASS_BOUNDARY = 0x1000;
+        /// The draw call is indexed.
+        const INDEXED = 0x2000;
+        /// The draw call is instanced.
+        const INSTANCED = 0x4000;
+        /// The draw call or dispatch takes its parameters from a buffer.
+        const INDIRECT = 0x8000;
+    }
+}
+
+impl<'de> Deserialize<'de> for ActionFlags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(ActionFlags::from_bits_truncate)
+    }
+}
+
+/// A single action in the captured frame, e.g. a draw call or a marker.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Action {
+    /// Event ID of the action, used to select it for inspection.
+    pub event_id: u32,
+    /// Sequential index of the action within the frame.
+    pub action_id: u32,
+    /// Name of the action, e.g. the API call or the marker label.
+    pub name: String,
+    /// Kinds of work performed by the action.
+    pub flags: ActionFlags,
+    /// Event IDs of all API calls up to and including this action.
+    pub events: Vec<u32>,
+    /// Nested actions, e.g. the contents of a marker region.
+    pub children: Vec<Action>,
+}
+
+impl Action {
+    /// Returns whether this action is a marker region rather than GPU work.
+    pub fn is_marker(&self) -> bool {
+        self.flags.intersects(ActionFlags::PUSH_MARKER | ActionFlags::SET_MARKER)
+    }
+
+    /// Visits this action and all of its descendants in frame order.
+    pub fn walk<F: FnMut(&Action)>(&self, f: &mut F) {
+        f(self);
+        for child in &self.children {
+            child.walk(f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn decode_marker_tree() {
+        let json = r#"{
+            "event_id": 1, "action_id": 1, "name": "GBuffer", "flags": 32, "events": [1],
+            "children": [
+                {"event_id": 4, "action_id": 2, "name": "vkCmdDrawIndexed", "flags": 8194,
+                 "events": [2, 3, 4], "children": []}
+            ]
+        }"#;
+        let action: Action = serde_json::from_str(json).unwrap();
+        assert!(action.is_marker());
+        assert_eq!(action.children[0].flags, ActionFlags::DRAWCALL | ActionFlags::INDEXED);
+
+        let mut visited = Vec::new();
+        action.walk(&mut |a| visited.push(a.event_id));
+        assert_eq!(visited, vec![1, 4]);
+    }
+}
@@ -0,0 +1,128 @@
+//! GPU and generic performance counters.
+
+use std::collections::BTreeMap;
+
+/// Identifier of a counter, as enumerated by `ReplayController::counters()`.
+///
+/// Generic counters have fixed IDs across drivers, while the IDs of vendor
+/// specific counters may differ between machines, so look those up by name.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Counter(pub u32);
+
+impl Counter {
+    /// GPU time spent on an event, in seconds.
+    pub const EVENT_GPU_DURATION: Counter = Counter(1);
+    /// Number of vertices read by the input assembler.
+    pub const INPUT_VERTICES_READ: Counter = Counter(2);
+    /// Number of primitives read by the input assembler.
+    pub const IA_PRIMITIVES: Counter = Counter(3);
+    /// Number of primitives output by geometry shaders.
+    pub const GS_PRIMITIVES: Counter = Counter(4);
+    /// Number of primitives sent to the rasterizer.
+    pub const RASTERIZER_INVOCATIONS: Counter = Counter(5);
+    /// Number of primitives rasterized after clipping.
+    pub const RASTERIZED_PRIMITIVES: Counter = Counter(6);
+    /// Number of samples which passed the depth and stencil tests.
+    pub const SAMPLES_PASSED: Counter = Counter(7);
+    /// Number of vertex shader invocations.
+    pub const VS_INVOCATIONS: Counter = Counter(8);
+    /// Number of hull or tessellation control shader invocations.
+    pub const HS_INVOCATIONS: Counter = Counter(9);
+    /// Number of domain or tessellation evaluation shader invocations.
+    pub const DS_INVOCATIONS: Counter = Counter(10);
+    /// Number of geometry shader invocations.
+    pub const GS_INVOCATIONS: Counter = Counter(11);
+    /// Number of pixel shader invocations.
+    pub const PS_INVOCATIONS: Counter = Counter(12);
+    /// Number of compute shader invocations.
+    pub const CS_INVOCATIONS: Counter = Counter(13);
+}
+
+/// Unit of the values of a counter.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub enum CounterUnit {
+    /// A plain count.
+    Absolute,
+    /// Time in seconds.
+    Seconds,
+    /// A percentage between 0 and 100.
+    Percentage,
+    /// A ratio between two other values.
+    Ratio,
+    /// Size in bytes.
+    Bytes,
+    /// Number of GPU clock cycles.
+    Cycles,
+    /// Frequency in Hertz.
+    Hertz,
+    /// Voltage in Volts.
+    Volt,
+    /// Temperature in degrees Celsius.
+    Celsius,
+}
+
+/// Description of a counter supported by the replaying GPU.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct CounterDescription {
+    /// ID used to fetch the counter.
+    pub counter: Counter,
+    /// Name of the counter, e.g. `GPU Duration`.
+    pub name: String,
+    /// Category the counter is grouped under, e.g. the vendor.
+    pub category: String,
+    /// Explanation of what the counter measures.
+    pub description: String,
+    /// Unit of the counter's values.
+    pub unit: CounterUnit,
+}
+
+/// Value of a counter for a single event.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub struct CounterResult {
+    /// Event ID of the action the value was measured for.
+    pub event_id: u32,
+    /// The measured counter.
+    pub counter: Counter,
+    /// The measured value, converted to floating point regardless of the
+    /// counter's native type.
+    pub value: f64,
+}
+
+impl CounterResult {
+    /// Groups results by event ID and counter, e.g. to compare the values of
+    /// two builds on the same capture.
+    pub fn by_event(results: &[CounterResult]) -> BTreeMap<u32, BTreeMap<Counter, f64>> {
+        let mut events = BTreeMap::new();
+        for result in results {
+            events
+                .entry(result.event_id)
+                .or_insert_with(BTreeMap::new)
+                .insert(result.counter, result.value);
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn decode_counters() {
+        let json = r#"{"counter": 1, "name": "GPU Duration", "category": "Generic",
+                       "description": "Time taken for this event on the GPU", "unit": "Seconds"}"#;
+        let desc: CounterDescription = serde_json::from_str(json).unwrap();
+        assert_eq!(desc.counter, Counter::EVENT_GPU_DURATION);
+        assert_eq!(desc.unit, CounterUnit::Seconds);
+
+        let json = r#"[{"event_id": 12, "counter": 1, "value": 0.5},
+                       {"event_id": 12, "counter": 12, "value": 1024},
+                       {"event_id": 7, "counter": 1, "value": 0.25}]"#;
+        let results: Vec<CounterResult> = serde_json::from_str(json).unwrap();
+        let events = CounterResult::by_event(&results);
+        assert_eq!(events.keys().cloned().collect::<Vec<_>>(), vec![7, 12]);
+        assert_eq!(events[&12][&Counter::PS_INVOCATIONS], 1024.0);
+        assert_eq!(events[&7][&Counter::EVENT_GPU_DURATION], 0.25);
+    }
+}
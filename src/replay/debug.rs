@@ -0,0 +1,123 @@
+//! Step-by-step traces of single shader invocations.
+
+use std::collections::BTreeMap;
+
+use super::ShaderStage;
+
+/// Value of a shader variable or register.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DebugVariable {
+    /// Name of the variable, or of the register for disassembly-level
+    /// debugging, e.g. `_input0` or `r3`.
+    pub name: String,
+    /// Base type of the components, as named by RenderDoc, e.g. `Float`.
+    pub type_name: String,
+    /// Number of rows, 1 for scalars and vectors.
+    pub rows: u32,
+    /// Number of columns, or vector components.
+    pub columns: u32,
+    /// Component values row by row, converted to floating point.
+    pub values: Vec<f64>,
+    /// Members if the variable is a struct or array.
+    pub members: Vec<DebugVariable>,
+}
+
+/// A variable changed by a single step.
+///
+/// Newly created variables only have an `after` value, and variables going
+/// out of scope only a `before` value.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct VariableChange {
+    /// The variable before the step.
+    pub before: Option<DebugVariable>,
+    /// The variable after the step.
+    pub after: Option<DebugVariable>,
+}
+
+/// A single step of a shader invocation.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DebugStep {
+    /// Index of the step, starting at 0 for the state before the first
+    /// instruction.
+    pub step_index: u32,
+    /// Index of the instruction executed next in the disassembly.
+    pub next_instruction: u32,
+    /// Variables changed by this step.
+    pub changes: Vec<VariableChange>,
+}
+
+/// The complete execution of a shader invocation.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ShaderDebugTrace {
+    /// Stage of the debugged shader.
+    pub stage: ShaderStage,
+    /// Inputs of the invocation, e.g. vertex attributes.
+    pub inputs: Vec<DebugVariable>,
+    /// Contents of the bound constant blocks.
+    pub constant_blocks: Vec<DebugVariable>,
+    /// Every step in execution order.
+    pub steps: Vec<DebugStep>,
+}
+
+impl ShaderDebugTrace {
+    /// Returns the variables live after the step at `step_index`, by name.
+    ///
+    /// This applies the changes of all steps up to and including the given
+    /// one, so dumping every step is quadratic in the trace length.
+    pub fn variables_at(&self, step_index: usize) -> BTreeMap<String, DebugVariable> {
+        let mut variables = BTreeMap::new();
+        for step in self.steps.iter().take(step_index + 1) {
+            for change in &step.changes {
+                match (&change.before, &change.after) {
+                    (_, Some(after)) => {
+                        variables.insert(after.name.clone(), after.clone());
+                    }
+                    (Some(before), None) => {
+                        variables.remove(&before.name);
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        variables
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn replay_changes() {
+        let json = r#"{
+            "stage": "Pixel", "inputs": [], "constant_blocks": [],
+            "steps": [
+                {"step_index": 0, "next_instruction": 0, "changes": [
+                    {"before": null, "after": {"name": "r0", "type_name": "Float", "rows": 1, "columns": 2,
+                                               "values": [0, 0], "members": []}},
+                    {"before": null, "after": {"name": "r1", "type_name": "UInt", "rows": 1, "columns": 1,
+                                               "values": [7], "members": []}}
+                ]},
+                {"step_index": 1, "next_instruction": 1, "changes": [
+                    {"before": {"name": "r0", "type_name": "Float", "rows": 1, "columns": 2,
+                                "values": [0, 0], "members": []},
+                     "after": {"name": "r0", "type_name": "Float", "rows": 1, "columns": 2,
+                               "values": [0.5, 1], "members": []}},
+                    {"before": {"name": "r1", "type_name": "UInt", "rows": 1, "columns": 1,
+                                "values": [7], "members": []}, "after": null}
+                ]}
+            ]
+        }"#;
+        let trace: ShaderDebugTrace = serde_json::from_str(json).unwrap();
+        assert_eq!(trace.stage, ShaderStage::Pixel);
+
+        let first = trace.variables_at(0);
+        assert_eq!(first.keys().collect::<Vec<_>>(), vec!["r0", "r1"]);
+        assert_eq!(first["r0"].values, vec![0.0, 0.0]);
+
+        let last = trace.variables_at(1);
+        assert_eq!(last.keys().collect::<Vec<_>>(), vec!["r0"]);
+        assert_eq!(last["r0"].values, vec![0.5, 1.0]);
+    }
+}
@@ -0,0 +1,574 @@
+//! Comparing two captures of the same frame, e.g. from different commits.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde_json;
+
+use super::{Action, ActionFlags, Error, ImageFormat, PipelineState, ReplayController, ResourceDescription, ResourceId};
+
+/// Separator between nested marker names in region paths.
+const PATH_SEPARATOR: &str = " / ";
+
+/// Settings for `diff()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiffOptions {
+    /// Whether to compare the contents of named buffers and textures, which
+    /// reads them all back.
+    pub resource_contents: bool,
+    /// Whether to compare the final output image.
+    pub output_image: bool,
+    /// Perceptual difference from 0 to 1 above which output pixels count as
+    /// different.
+    pub pixel_threshold: f64,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        DiffOptions {
+            resource_contents: true,
+            output_image: true,
+            pixel_threshold: 0.1,
+        }
+    }
+}
+
+/// A marker region with a different number of actions in each capture.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
+pub struct RegionDiff {
+    /// Names of the nested markers, separated by ` / `, or an empty string
+    /// for actions outside of any marker.
+    pub path: String,
+    /// Number of actions directly inside the region in the first capture.
+    pub first: usize,
+    /// Number of actions directly inside the region in the second capture.
+    pub second: usize,
+}
+
+/// A draw or dispatch whose pipeline state differs between the captures.
+///
+/// Draws are only matched within regions having the same number of actions
+/// in both captures.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
+pub struct PipelineDiff {
+    /// Path of the marker region containing the action.
+    pub path: String,
+    /// Event ID of the action in the first capture.
+    pub first_event: u32,
+    /// Event ID of the action in the second capture.
+    pub second_event: u32,
+    /// The parts of the state that differ: `shaders`, `render_targets`,
+    /// `depth_target`, `viewport` or `blends`.
+    pub changes: Vec<&'static str>,
+}
+
+/// How a resource differs between the captures.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceChange {
+    /// The resource only exists in the first capture.
+    Removed,
+    /// The resource only exists in the second capture.
+    Added,
+    /// The kind, size or texture properties differ.
+    Description,
+    /// The contents at the end of the frame differ.
+    Contents,
+}
+
+/// A named resource which differs between the captures.
+///
+/// Resources are matched by their debug names, so resources without a name
+/// set by the application, or whose name isn't unique, are not compared.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
+pub struct ResourceDiff {
+    /// Debug name of the resource.
+    pub name: String,
+    /// How the resource differs.
+    pub change: ResourceChange,
+}
+
+/// Perceptual difference between the output images of the captures.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ImageDiff {
+    /// Width of the first output in pixels.
+    pub width: u32,
+    /// Height of the first output in pixels.
+    pub height: u32,
+    /// Number of pixels differing by more than `DiffOptions::pixel_threshold`,
+    /// or all pixels if the sizes of the outputs differ.
+    pub differing_pixels: u64,
+    /// Largest perceptual difference of any pixel, from 0 to 1.
+    pub max_difference: f64,
+}
+
+/// Report of the differences between two captures.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CaptureDiff {
+    /// Marker regions with different action counts.
+    pub regions: Vec<RegionDiff>,
+    /// Actions with different pipeline state.
+    pub pipelines: Vec<PipelineDiff>,
+    /// Named resources which differ.
+    pub resources: Vec<ResourceDiff>,
+    /// Difference of the output images, if compared and both captures have
+    /// an output.
+    pub output: Option<ImageDiff>,
+}
+
+impl CaptureDiff {
+    /// Returns whether no differences were found.
+    // `Option::is_none_or()` needs Rust 1.82.
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+            && self.pipelines.is_empty()
+            && self.resources.is_empty()
+            && self.output.as_ref().map_or(true, |image| image.differing_pixels == 0)
+    }
+
+    /// Returns the report as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("diff reports are serializable")
+    }
+}
+
+/// Compares the captures replayed by `first` and `second`.
+///
+/// Every draw and dispatch is inspected in both captures, so this takes a
+/// while for large frames.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use renderdoc::replay::{self, CaptureFile, DiffOptions};
+///
+/// # fn run() -> Result<(), renderdoc::replay::Error> {
+/// let mut before = CaptureFile::open("before.rdc")?.open_replay()?;
+/// let mut after = CaptureFile::open("after.rdc")?.open_replay()?;
+/// let report = replay::diff(&mut before, &mut after, &DiffOptions::default())?;
+/// if !report.is_empty() {
+///     println!("{}", report.to_json());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn diff(
+    first: &mut ReplayController,
+    second: &mut ReplayController,
+    options: &DiffOptions,
+) -> Result<CaptureDiff, Error> {
+    let first_frame = Frame::new(first.actions()?);
+    let second_frame = Frame::new(second.actions()?);
+    let mut report = CaptureDiff::default();
+
+    let paths: BTreeSet<&String> = first_frame.regions.keys().chain(second_frame.regions.keys()).collect();
+    for path in paths {
+        let first_actions = first_frame.regions.get(path).map_or(&[][..], |a| &a[..]);
+        let second_actions = second_frame.regions.get(path).map_or(&[][..], |a| &a[..]);
+        if first_actions.len() != second_actions.len() {
+            report.regions.push(RegionDiff {
+                path: path.clone(),
+                first: first_actions.len(),
+                second: second_actions.len(),
+            });
+            continue;
+        }
+
+        for (a, b) in first_actions.iter().zip(second_actions) {
+            if !a.is_work || !b.is_work {
+                continue;
+            }
+            let changes = pipeline_changes(&first.pipeline_state(a.event_id)?, &second.pipeline_state(b.event_id)?);
+            if !changes.is_empty() {
+                report.pipelines.push(PipelineDiff {
+                    path: path.clone(),
+                    first_event: a.event_id,
+                    second_event: b.event_id,
+                    changes,
+                });
+            }
+        }
+    }
+
+    report.resources = diff_resources(first, &first_frame, second, &second_frame, options)?;
+
+    if options.output_image {
+        if let (Some(a), Some(b)) = (output_image(first, &first_frame)?, output_image(second, &second_frame)?) {
+            report.output = Some(compare_images(&a, &b, options.pixel_threshold));
+        }
+    }
+
+    Ok(report)
+}
+
+/// An action directly inside a marker region.
+#[derive(Debug)]
+struct RegionAction {
+    event_id: u32,
+    /// Whether the action is a draw or dispatch.
+    is_work: bool,
+}
+
+/// The actions of a capture, grouped by marker region.
+#[derive(Debug, Default)]
+struct Frame {
+    regions: BTreeMap<String, Vec<RegionAction>>,
+    /// Event ID of the last action of the frame.
+    last_event: u32,
+    /// Event ID of the last draw.
+    last_draw: Option<u32>,
+}
+
+impl Frame {
+    fn new(actions: Vec<Action>) -> Frame {
+        let mut frame = Frame::default();
+        frame.add(&actions, "");
+        frame
+    }
+
+    fn add(&mut self, actions: &[Action], path: &str) {
+        for action in actions {
+            self.last_event = self.last_event.max(action.event_id);
+            if action.flags.contains(ActionFlags::PUSH_MARKER) {
+                let nested = if path.is_empty() {
+                    action.name.clone()
+                } else {
+                    format!("{}{}{}", path, PATH_SEPARATOR, action.name)
+                };
+                self.regions.entry(nested.clone()).or_default();
+                self.add(&action.children, &nested);
+                continue;
+            }
+
+            if action.flags.intersects(ActionFlags::SET_MARKER | ActionFlags::POP_MARKER) {
+                continue;
+            }
+            if action.flags.contains(ActionFlags::DRAWCALL) {
+                self.last_draw = Some(action.event_id);
+            }
+            self.regions.entry(path.to_string()).or_default().push(RegionAction {
+                event_id: action.event_id,
+                is_work: action.flags.intersects(ActionFlags::DRAWCALL | ActionFlags::DISPATCH),
+            });
+            self.add(&action.children, path);
+        }
+    }
+}
+
+/// Returns the parts of the pipeline state which differ, ignoring resource
+/// IDs, which are assigned anew in every capture.
+fn pipeline_changes(a: &PipelineState, b: &PipelineState) -> Vec<&'static str> {
+    let mut changes = Vec::new();
+    let shaders = |state: &PipelineState| {
+        state
+            .shaders
+            .iter()
+            .map(|s| (s.stage, s.entry_point.clone()))
+            .collect::<Vec<_>>()
+    };
+    if shaders(a) != shaders(b) {
+        changes.push("shaders");
+    }
+
+    let bound = |targets: &[Option<ResourceId>]| targets.iter().map(Option::is_some).collect::<Vec<_>>();
+    if bound(&a.render_targets) != bound(&b.render_targets) {
+        changes.push("render_targets");
+    }
+    if a.depth_target.is_some() != b.depth_target.is_some() {
+        changes.push("depth_target");
+    }
+    if a.viewport != b.viewport {
+        changes.push("viewport");
+    }
+    if a.blends != b.blends {
+        changes.push("blends");
+    }
+    changes
+}
+
+/// Returns the resources with a unique, application provided name, by name.
+fn named_resources(resources: Vec<ResourceDescription>) -> BTreeMap<String, ResourceDescription> {
+    let mut named = BTreeMap::new();
+    let mut duplicates = BTreeSet::new();
+    for res in resources.into_iter().filter(|res| !res.autogenerated_name) {
+        if named.contains_key(&res.name) {
+            duplicates.insert(res.name.clone());
+        } else {
+            named.insert(res.name.clone(), res);
+        }
+    }
+    for name in duplicates {
+        named.remove(&name);
+    }
+    named
+}
+
+fn diff_resources(
+    first: &mut ReplayController,
+    first_frame: &Frame,
+    second: &mut ReplayController,
+    second_frame: &Frame,
+    options: &DiffOptions,
+) -> Result<Vec<ResourceDiff>, Error> {
+    let first_resources = named_resources(first.resources()?);
+    let mut second_resources = named_resources(second.resources()?);
+    let mut diffs = Vec::new();
+
+    for (name, a) in first_resources {
+        let change = match second_resources.remove(&name) {
+            None => Some(ResourceChange::Removed),
+            Some(ref b) if a.kind != b.kind || a.byte_size != b.byte_size || a.texture != b.texture => {
+                Some(ResourceChange::Description)
+            }
+            Some(ref b) if options.resource_contents && a.byte_size.is_some() => {
+                let a = checksum(first, &a, first_frame.last_event)?;
+                let b = checksum(second, b, second_frame.last_event)?;
+                if a != b {
+                    Some(ResourceChange::Contents)
+                } else {
+                    None
+                }
+            }
+            Some(_) => None,
+        };
+        if let Some(change) = change {
+            diffs.push(ResourceDiff { name, change });
+        }
+    }
+
+    diffs.extend(second_resources.into_keys().map(|name| ResourceDiff {
+        name,
+        change: ResourceChange::Added,
+    }));
+    Ok(diffs)
+}
+
+/// Returns a checksum of the contents of a buffer or texture at `event_id`.
+fn checksum(replay: &mut ReplayController, res: &ResourceDescription, event_id: u32) -> Result<u64, Error> {
+    let data = if res.is_texture() {
+        // DDS keeps every mip and slice in the original format.
+        let path = temp_path("dds");
+        let saved = replay
+            .save_texture(res.id, event_id, &path, ImageFormat::Dds)
+            .and_then(|()| fs::read(&path).map_err(Error::from));
+        let _ = fs::remove_file(&path);
+        saved?
+    } else {
+        replay.buffer_data(res.id, event_id, 0, 0)?
+    };
+    Ok(fnv1a(&data))
+}
+
+/// Returns the 64-bit FNV-1a hash of `data`.
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn temp_path(extension: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    env::temp_dir().join(format!("renderdoc-rs-diff-{}-{}.{}", process::id(), n, extension))
+}
+
+/// An RGB image, row by row from the top.
+#[derive(Debug)]
+pub(crate) struct Image {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) pixels: Vec<[u8; 3]>,
+}
+
+/// Returns the first color target of the last draw, as it is at the end of
+/// the frame.
+fn output_image(replay: &mut ReplayController, frame: &Frame) -> Result<Option<Image>, Error> {
+    let draw = match frame.last_draw {
+        Some(draw) => draw,
+        None => return Ok(None),
+    };
+    let target = match replay.pipeline_state(draw)?.render_targets.iter().flatten().next() {
+        Some(&target) => target,
+        None => return Ok(None),
+    };
+
+    let path = temp_path("bmp");
+    let saved = replay
+        .save_texture(target, frame.last_event, &path, ImageFormat::Bmp)
+        .and_then(|()| fs::read(&path).map_err(Error::from));
+    let _ = fs::remove_file(&path);
+    decode_bmp(&saved?)
+        .map(Some)
+        .ok_or_else(|| Error::Replay("unsupported bitmap saved for the output image".to_string()))
+}
+
+/// Decodes an uncompressed 24 or 32-bit Windows bitmap.
+fn decode_bmp(data: &[u8]) -> Option<Image> {
+    let u16_at = |at: usize| data.get(at..at + 2).map(|b| u16::from(b[0]) | u16::from(b[1]) << 8);
+    let u32_at = |at: usize| {
+        data.get(at..at + 4)
+            .map(|b| u32::from(b[0]) | u32::from(b[1]) << 8 | u32::from(b[2]) << 16 | u32::from(b[3]) << 24)
+    };
+
+    if data.get(0..2) != Some(b"BM") || u32_at(30)? != 0 {
+        return None;
+    }
+    let offset = u32_at(10)? as usize;
+    let width = u32_at(18)? as i32;
+    let height = u32_at(22)? as i32;
+    let bytes_per_pixel = match u16_at(28)? {
+        24 => 3,
+        32 => 4,
+        _ => return None,
+    };
+    if width <= 0 || height == 0 {
+        return None;
+    }
+
+    let (width, height, top_down) = (width as u32, height.unsigned_abs(), height < 0);
+    let stride = (width as usize * bytes_per_pixel + 3) & !3;
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as usize {
+        let row = if top_down { y } else { height as usize - 1 - y };
+        let start = offset + row * stride;
+        let row = data.get(start..start + width as usize * bytes_per_pixel)?;
+        pixels.extend(row.chunks(bytes_per_pixel).map(|p| [p[2], p[1], p[0]]));
+    }
+
+    Some(Image { width, height, pixels })
+}
+
+/// Compares two images pixel by pixel in the YIQ color space, which weighs
+/// differences by how noticeable they are.
+pub(crate) fn compare_images(a: &Image, b: &Image, threshold: f64) -> ImageDiff {
+    if a.width != b.width || a.height != b.height {
+        return ImageDiff {
+            width: a.width,
+            height: a.height,
+            differing_pixels: u64::from(a.width) * u64::from(a.height),
+            max_difference: 1.0,
+        };
+    }
+
+    let mut differing_pixels = 0;
+    let mut max_difference = 0.0f64;
+    for (pa, pb) in a.pixels.iter().zip(&b.pixels) {
+        let difference = color_difference(pa, pb);
+        max_difference = max_difference.max(difference);
+        if difference > threshold {
+            differing_pixels += 1;
+        }
+    }
+
+    ImageDiff {
+        width: a.width,
+        height: a.height,
+        differing_pixels,
+        max_difference,
+    }
+}
+
+/// Returns the perceptual difference between two colors from 0 to 1, as
+/// used by `pixelmatch`.
+pub(crate) fn color_difference(a: &[u8; 3], b: &[u8; 3]) -> f64 {
+    // Largest possible value of the weighted YIQ distance.
+    const MAX_DELTA: f64 = 35215.0;
+
+    let yiq = |c: &[u8; 3]| {
+        let (r, g, b) = (f64::from(c[0]), f64::from(c[1]), f64::from(c[2]));
+        (
+            r * 0.298_895_31 + g * 0.586_622_47 + b * 0.114_482_23,
+            r * 0.595_977_99 - g * 0.274_176_10 - b * 0.321_801_89,
+            r * 0.211_470_17 - g * 0.522_617_24 + b * 0.311_147_07,
+        )
+    };
+    let (y1, i1, q1) = yiq(a);
+    let (y2, i2, q2) = yiq(b);
+    let (dy, di, dq) = (y1 - y2, i1 - i2, q1 - q2);
+    (0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq) / MAX_DELTA
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(event_id: u32, name: &str, flags: ActionFlags, children: Vec<Action>) -> Action {
+        Action {
+            event_id,
+            action_id: event_id,
+            name: name.to_string(),
+            flags,
+            events: vec![event_id],
+            children,
+        }
+    }
+
+    #[test]
+    fn group_actions_by_region() {
+        let frame = Frame::new(vec![
+            action(
+                1,
+                "GBuffer",
+                ActionFlags::PUSH_MARKER,
+                vec![
+                    action(2, "Draw", ActionFlags::DRAWCALL, vec![]),
+                    action(3, "Sky", ActionFlags::PUSH_MARKER, vec![action(4, "Draw", ActionFlags::DRAWCALL, vec![])]),
+                    action(5, "Pop", ActionFlags::POP_MARKER, vec![]),
+                ],
+            ),
+            action(6, "Present", ActionFlags::PRESENT, vec![]),
+        ]);
+
+        let regions: Vec<_> = frame.regions.iter().map(|(path, actions)| (path.as_str(), actions.len())).collect();
+        assert_eq!(regions, vec![("", 1), ("GBuffer", 1), ("GBuffer / Sky", 1)]);
+        assert_eq!(frame.last_event, 6);
+        assert_eq!(frame.last_draw, Some(4));
+        assert!(!frame.regions[""][0].is_work);
+    }
+
+    #[test]
+    fn decode_bitmaps() {
+        // 2x2 pixels at 24 bits, stored bottom-up with rows padded to 8 bytes.
+        let mut bmp = vec![0u8; 54];
+        bmp[0..2].copy_from_slice(b"BM");
+        bmp[10] = 54;
+        bmp[18] = 2;
+        bmp[22] = 2;
+        bmp[28] = 24;
+        bmp.extend_from_slice(&[0, 0, 255, 0, 255, 0, 0, 0]);
+        bmp.extend_from_slice(&[255, 0, 0, 255, 255, 255, 0, 0]);
+
+        let image = decode_bmp(&bmp).unwrap();
+        assert_eq!((image.width, image.height), (2, 2));
+        assert_eq!(image.pixels, vec![[0, 0, 255], [255, 255, 255], [255, 0, 0], [0, 255, 0]]);
+        assert!(decode_bmp(&bmp[..60]).is_none());
+    }
+
+    #[test]
+    fn compare_pixels() {
+        let image = |pixels: Vec<[u8; 3]>| Image {
+            width: 2,
+            height: 1,
+            pixels,
+        };
+        let a = image(vec![[0, 0, 0], [255, 0, 255]]);
+        let b = image(vec![[255, 255, 255], [254, 0, 255]]);
+
+        let diff = compare_images(&a, &b, 0.1);
+        assert_eq!(diff.differing_pixels, 1);
+        assert!(diff.max_difference > 0.9 && diff.max_difference <= 1.0);
+        assert!(color_difference(&[255, 0, 255], &[254, 0, 255]) < 0.001);
+        assert_eq!(compare_images(&a, &a, 0.0).differing_pixels, 0);
+    }
+
+    #[test]
+    fn hash_contents() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -0,0 +1,100 @@
+//! Exporting resource contents from a capture.
+
+use serde::de::{self, Deserialize, Deserializer};
+
+/// File format for saved textures.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+pub enum ImageFormat {
+    /// Portable Network Graphics, 8 bits per channel.
+    #[serde(rename = "PNG")]
+    Png,
+    /// JPEG, 8 bits per channel without alpha.
+    #[serde(rename = "JPG")]
+    Jpg,
+    /// Windows bitmap.
+    #[serde(rename = "BMP")]
+    Bmp,
+    /// Truevision TGA.
+    #[serde(rename = "TGA")]
+    Tga,
+    /// Radiance HDR, preserving floating point values.
+    #[serde(rename = "HDR")]
+    Hdr,
+    /// OpenEXR, preserving floating point values.
+    #[serde(rename = "EXR")]
+    Exr,
+    /// DirectDraw Surface, preserving the original format, mips and slices.
+    #[serde(rename = "DDS")]
+    Dds,
+}
+
+/// Structured file format a capture can be converted to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+pub enum CaptureFormat {
+    /// A single XML file containing every chunk, with buffers inlined.
+    #[serde(rename = "xml")]
+    Xml,
+    /// A zip archive of the chunk XML plus each buffer as a separate file.
+    #[serde(rename = "zip.xml")]
+    ZipXml,
+}
+
+/// Encoding of a capture thumbnail.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum ThumbnailFormat {
+    /// A JPEG file, as embedded in the capture.
+    #[serde(rename = "JPG")]
+    Jpeg,
+    /// Tightly packed 8-bit RGB pixels, row by row from the top.
+    #[serde(rename = "Raw")]
+    Rgb,
+}
+
+/// Preview image embedded in a capture file.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct Thumbnail {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// Encoding of `data`.
+    pub format: ThumbnailFormat,
+    /// The image data.
+    #[serde(deserialize_with = "from_hex")]
+    pub data: Vec<u8>,
+}
+
+/// Decodes binary data which the shim sends hex encoded.
+pub(crate) fn from_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    if hex.len() % 2 != 0 {
+        return Err(de::Error::custom("odd number of hex digits"));
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(de::Error::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[derive(Deserialize)]
+    struct Data(#[serde(deserialize_with = "from_hex")] Vec<u8>);
+
+    #[test]
+    fn capture_format_names() {
+        assert_eq!(serde_json::to_string(&CaptureFormat::Xml).unwrap(), r#""xml""#);
+        assert_eq!(serde_json::to_string(&CaptureFormat::ZipXml).unwrap(), r#""zip.xml""#);
+    }
+
+    #[test]
+    fn decode_hex_data() {
+        let Data(data) = serde_json::from_str(r#""00ff7f10""#).unwrap();
+        assert_eq!(data, vec![0x00, 0xff, 0x7f, 0x10]);
+        assert!(serde_json::from_str::<Data>(r#""abc""#).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Pixel history, i.e. every modification of a texel across the frame.
+
+/// Selects a single subresource of a texture.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize)]
+pub struct Subresource {
+    /// Mip level.
+    pub mip: u32,
+    /// Array slice, or depth slice of a 3D texture.
+    pub slice: u32,
+    /// Sample of a multisampled texture.
+    pub sample: u32,
+}
+
+/// Contents of a texel at some point of its history.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub struct PixelValue {
+    /// Color as floating point RGBA, with integer formats converted.
+    pub color: [f32; 4],
+    /// Depth, or a negative value if there's no depth target.
+    pub depth: f32,
+    /// Stencil, or a negative value if there's no stencil target.
+    pub stencil: i32,
+}
+
+impl PixelValue {
+    /// Returns whether each color channel is within `tolerance` of `color`.
+    pub fn color_near(&self, color: [f32; 4], tolerance: f32) -> bool {
+        self.color.iter().zip(&color).all(|(a, b)| (a - b).abs() <= tolerance)
+    }
+}
+
+/// A single fragment or write affecting a texel.
+///
+/// Draws covering the texel with several fragments, e.g. overlapping
+/// triangles, report one modification per fragment.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub struct PixelModification {
+    /// Event ID of the action which modified the texel.
+    pub event_id: u32,
+    /// Primitive that produced the fragment.
+    pub primitive_id: u32,
+    /// Index of the fragment among those of the same event.
+    pub fragment_index: u32,
+    /// Whether the texel was written by a shader storage write or a copy
+    /// rather than by rasterization.
+    pub direct_shader_write: bool,
+    /// Whether no pixel shader was bound.
+    pub unbound_ps: bool,
+    /// The texel before the modification.
+    pub pre_mod: PixelValue,
+    /// Output of the pixel shader for the fragment, before blending.
+    pub shader_out: PixelValue,
+    /// The texel after the modification.
+    pub post_mod: PixelValue,
+    /// Whether the sample mask excluded the texel.
+    pub sample_masked: bool,
+    /// Whether the primitive was culled for facing away.
+    pub backface_culled: bool,
+    /// Whether the fragment failed depth clipping.
+    pub depth_clipped: bool,
+    /// Whether the fragment was outside the viewport.
+    pub view_clipped: bool,
+    /// Whether the fragment was outside the scissor rectangle.
+    pub scissor_clipped: bool,
+    /// Whether the pixel shader discarded the fragment.
+    pub shader_discarded: bool,
+    /// Whether the fragment failed the depth test.
+    pub depth_test_failed: bool,
+    /// Whether the fragment failed the stencil test.
+    pub stencil_test_failed: bool,
+}
+
+impl PixelModification {
+    /// Returns whether the fragment passed all tests and was written.
+    pub fn passed(&self) -> bool {
+        !(self.sample_masked
+            || self.backface_culled
+            || self.depth_clipped
+            || self.view_clipped
+            || self.scissor_clipped
+            || self.shader_discarded
+            || self.depth_test_failed
+            || self.stencil_test_failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn decode_modification() {
+        let value = r#"{"color": [1.0, 0.0, 1.0, 1.0], "depth": 0.5, "stencil": -1}"#;
+        let json = format!(
+            r#"{{"event_id": 87, "primitive_id": 3, "fragment_index": 0,
+                "direct_shader_write": false, "unbound_ps": false,
+                "pre_mod": {0}, "shader_out": {0}, "post_mod": {0},
+                "sample_masked": false, "backface_culled": false, "depth_clipped": false,
+                "view_clipped": false, "scissor_clipped": false, "shader_discarded": false,
+                "depth_test_failed": true, "stencil_test_failed": false}}"#,
+            value
+        );
+        let modification: PixelModification = serde_json::from_str(&json).unwrap();
+        assert!(!modification.passed());
+        assert!(modification.post_mod.color_near([1.0, 0.0, 1.0, 1.0], 0.01));
+        assert!(!modification.post_mod.color_near([1.0, 0.0, 0.0, 1.0], 0.01));
+    }
+}
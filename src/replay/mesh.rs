@@ -0,0 +1,130 @@
+//! Vertex data as transformed by a draw's geometry stages.
+
+/// Point in the geometry pipeline to fetch transformed vertices from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+pub enum MeshStage {
+    /// Output of the vertex shader.
+    #[serde(rename = "VSOut")]
+    VertexOutput,
+    /// Output of the last geometry stage, i.e. the geometry shader or, if
+    /// none is bound, the tessellation evaluation shader.
+    #[serde(rename = "GSOut")]
+    GeometryOutput,
+}
+
+/// One output of the shader, e.g. the position or a texture coordinate, for
+/// all vertices.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct VertexStream {
+    /// Name of the output variable, or its semantic if unnamed.
+    pub name: String,
+    /// Base type of the components, as named by RenderDoc, e.g. `Float`.
+    pub type_name: String,
+    /// Number of components per vertex, from 1 to 4.
+    pub component_count: u32,
+    /// Components of all vertices, converted to floating point.
+    pub values: Vec<f64>,
+}
+
+impl VertexStream {
+    /// Returns the number of vertices.
+    pub fn len(&self) -> usize {
+        match self.component_count {
+            0 => 0,
+            count => self.values.len() / count as usize,
+        }
+    }
+
+    /// Returns whether the stream has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the components of the vertex at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn vertex(&self, index: usize) -> &[f64] {
+        let count = self.component_count as usize;
+        &self.values[index * count..(index + 1) * count]
+    }
+}
+
+/// Transformed vertices of a single draw and instance.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct PostTransformMesh {
+    /// Primitive topology, as named by RenderDoc, e.g. `TriangleList`.
+    pub topology: String,
+    /// Vertex indices in draw order for indexed draws, or `None` if the
+    /// vertices are in draw order already.
+    pub indices: Option<Vec<u32>>,
+    /// All outputs of the shader, starting with the position.
+    pub streams: Vec<VertexStream>,
+}
+
+impl PostTransformMesh {
+    /// Returns the position output, if the shader writes one.
+    pub fn position(&self) -> Option<&VertexStream> {
+        self.streams.first()
+    }
+
+    /// Returns the output named `name`.
+    pub fn stream(&self, name: &str) -> Option<&VertexStream> {
+        self.streams.iter().find(|stream| stream.name == name)
+    }
+
+    /// Returns the largest difference between any component of `self` and
+    /// `other`, or `None` if their topology, indices or layout differ.
+    ///
+    /// This allows checking for mesh corruption against a known good mesh
+    /// within a tolerance.
+    pub fn max_difference(&self, other: &PostTransformMesh) -> Option<f64> {
+        if self.topology != other.topology || self.indices != other.indices || self.streams.len() != other.streams.len()
+        {
+            return None;
+        }
+
+        let mut max = 0.0f64;
+        for (a, b) in self.streams.iter().zip(&other.streams) {
+            if a.name != b.name || a.component_count != b.component_count || a.values.len() != b.values.len() {
+                return None;
+            }
+            for (x, y) in a.values.iter().zip(&b.values) {
+                max = max.max((x - y).abs());
+            }
+        }
+        Some(max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn compare_meshes() {
+        let json = r#"{
+            "topology": "TriangleList", "indices": [0, 1, 2, 2, 1, 3],
+            "streams": [
+                {"name": "gl_Position", "type_name": "Float", "component_count": 4,
+                 "values": [0, 0, 0, 1, 1, 0, 0, 1, 0, 1, 0, 1, 1, 1, 0, 1]},
+                {"name": "uv", "type_name": "Float", "component_count": 2, "values": [0, 0, 1, 0, 0, 1, 1, 1]}
+            ]
+        }"#;
+        let mesh: PostTransformMesh = serde_json::from_str(json).unwrap();
+        let uv = mesh.stream("uv").unwrap();
+        assert_eq!(uv.len(), 4);
+        assert_eq!(uv.vertex(1), &[1.0, 0.0]);
+        assert_eq!(mesh.position().unwrap().vertex(3), &[1.0, 1.0, 0.0, 1.0]);
+
+        let mut moved = mesh.clone();
+        moved.streams[0].values[4] = 1.25;
+        assert_eq!(mesh.max_difference(&mesh), Some(0.0));
+        assert_eq!(mesh.max_difference(&moved), Some(0.25));
+
+        moved.indices = None;
+        assert_eq!(mesh.max_difference(&moved), None);
+    }
+}
@@ -0,0 +1,481 @@
+//! Capture analysis through RenderDoc's replay interface.
+//!
+//! RenderDoc only exposes its replay API (`ICaptureFile`, `IReplayController`)
+//! as C++ interfaces, whose layouts change between releases. This module
+//! calls them through a thin C shim, which the build script compiles against
+//! the headers of the RenderDoc release being linked.
+//!
+//! Requires the `replay` feature, RenderDoc 1.31 or newer, and these
+//! environment variables at build time:
+//!
+//! * `RENDERDOC_RS_SOURCE_DIR`, a RenderDoc source checkout of the same
+//!   release as the library, providing the replay headers.
+//! * `RENDERDOC_RS_LINK_DIR`, optionally, the directory containing
+//!   `librenderdoc.so` or `renderdoc.lib`, if the linker doesn't find it.
+//!
+//! The library must also be found by the platform loader at runtime. Replays
+//! aren't thread safe and must stay on the thread they were opened on.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use renderdoc::replay::CaptureFile;
+//!
+//! # fn run() -> Result<(), renderdoc::replay::Error> {
+//! let mut replay = CaptureFile::open("frame12.rdc")?.open_replay()?;
+//! for action in replay.actions()? {
+//!     println!("{:>6} {}", action.event_id, action.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub use self::action::{Action, ActionFlags};
+pub use self::counter::{Counter, CounterDescription, CounterResult, CounterUnit};
+pub use self::debug::{DebugStep, DebugVariable, ShaderDebugTrace, VariableChange};
+pub use self::diff::{diff, CaptureDiff, DiffOptions, ImageDiff, PipelineDiff, RegionDiff, ResourceChange, ResourceDiff};
+pub use self::export::{CaptureFormat, ImageFormat, Thumbnail, ThumbnailFormat};
+pub use self::history::{PixelModification, PixelValue, Subresource};
+pub use self::mesh::{MeshStage, PostTransformMesh, VertexStream};
+pub use self::pipeline::{BlendEquation, BoundShader, ColorBlend, PipelineState, ShaderStage, Viewport};
+pub use self::reflection::{ConstantBlock, ShaderConstant, ShaderReflection, ShaderResource, SignatureParameter};
+pub use self::resource::{EventUsage, ResourceDescription, TextureDescription};
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use serde_json;
+
+use self::shim::{Request, Session};
+#[cfg(feature = "testing")]
+pub(crate) use self::diff::{color_difference, compare_images, Image};
+
+mod action;
+mod counter;
+mod debug;
+mod diff;
+mod export;
+mod history;
+mod mesh;
+mod pipeline;
+mod reflection;
+mod resource;
+mod shim;
+
+/// Identifier of a resource within a capture, e.g. a texture or a shader.
+///
+/// The null ID `ResourceId(0)` refers to no resource.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct ResourceId(pub u64);
+
+impl ResourceId {
+    /// Returns whether this is the null ID.
+    pub fn is_null(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// Errors that can occur while replaying a capture.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// The replay shim sent a reply that could not be decoded.
+    Json(serde_json::Error),
+    /// RenderDoc reported an error.
+    Replay(String),
+    /// An argument can't be passed to RenderDoc, e.g. a path containing a NUL
+    /// byte.
+    InvalidArgument(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+            Error::Json(ref err) => write!(f, "Invalid reply from replay shim: {}", err),
+            Error::Replay(ref msg) => write!(f, "Replay failed: {}", msg),
+            Error::InvalidArgument(ref msg) => write!(f, "Invalid argument: {}", msg),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Json(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Error {
+        Error::Json(err)
+    }
+}
+
+/// An opened capture file.
+#[derive(Debug)]
+pub struct CaptureFile {
+    session: Session,
+}
+
+impl CaptureFile {
+    /// Opens the capture file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<CaptureFile, Error> {
+        let mut session = Session::new();
+        session.call::<()>(&Request::OpenFile { path: path.as_ref() })?;
+        Ok(CaptureFile { session })
+    }
+
+    /// Returns the version of RenderDoc used to read the capture.
+    pub fn renderdoc_version(&self) -> &str {
+        self.session.version()
+    }
+
+    /// Returns the preview image embedded in the capture as a JPEG file, or
+    /// `None` if the capture has no thumbnail.
+    pub fn thumbnail(&mut self) -> Result<Option<Thumbnail>, Error> {
+        self.session.call(&Request::Thumbnail {
+            format: ThumbnailFormat::Jpeg,
+        })
+    }
+
+    /// Returns the preview image embedded in the capture decoded to RGB
+    /// pixels, or `None` if the capture has no thumbnail.
+    pub fn thumbnail_rgb(&mut self) -> Result<Option<Thumbnail>, Error> {
+        self.session.call(&Request::Thumbnail {
+            format: ThumbnailFormat::Rgb,
+        })
+    }
+
+    /// Converts the capture's chunk stream to a structured file at `path`,
+    /// like `renderdoccmd convert` does.
+    ///
+    /// Converting two captures makes them comparable with text diff tools.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use renderdoc::replay::{CaptureFile, CaptureFormat};
+    ///
+    /// # fn run() -> Result<(), renderdoc::replay::Error> {
+    /// let mut capture = CaptureFile::open("frame12.rdc")?;
+    /// capture.convert("frame12.xml", CaptureFormat::Xml)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn convert<P: AsRef<Path>>(&mut self, path: P, format: CaptureFormat) -> Result<(), Error> {
+        self.session.call(&Request::Convert {
+            path: path.as_ref(),
+            format,
+        })
+    }
+
+    /// Replays the capture on the local machine.
+    ///
+    /// This fails if the capture was made with a graphics API or on hardware
+    /// that is not available locally.
+    pub fn open_replay(mut self) -> Result<ReplayController, Error> {
+        self.session.call::<()>(&Request::OpenReplay)?;
+        Ok(ReplayController { session: self.session })
+    }
+
+    /// Replays the capture on the remote server at `host`, e.g. on the
+    /// machine or device the capture was made on.
+    ///
+    /// The capture is copied to the remote server first, and all queries are
+    /// then answered by the remote replay. `host` is either a host name or IP
+    /// address, optionally with a port, or `adb://<serial>` for Android
+    /// devices, whose remote server must already be running, e.g. started by
+    /// `android::AndroidSession::start()`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use renderdoc::replay::CaptureFile;
+    ///
+    /// # fn run() -> Result<(), renderdoc::replay::Error> {
+    /// let mut replay = CaptureFile::open("frame12.rdc")?.open_remote_replay("gpu-box.local")?;
+    /// println!("{} actions", replay.actions()?.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_remote_replay(mut self, host: &str) -> Result<ReplayController, Error> {
+        self.session.call::<()>(&Request::OpenRemoteReplay { host })?;
+        Ok(ReplayController { session: self.session })
+    }
+}
+
+/// A capture loaded for replay.
+#[derive(Debug)]
+pub struct ReplayController {
+    session: Session,
+}
+
+impl ReplayController {
+    /// Returns the version of RenderDoc performing the replay.
+    pub fn renderdoc_version(&self) -> &str {
+        self.session.version()
+    }
+
+    /// Returns the root actions of the captured frame.
+    ///
+    /// Markers and command lists contain their nested actions as children.
+    pub fn actions(&mut self) -> Result<Vec<Action>, Error> {
+        self.session.call(&Request::Actions)
+    }
+
+    /// Returns all resources in the capture.
+    ///
+    /// # Examples
+    ///
+    /// Flagging large textures:
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::replay::CaptureFile;
+    /// # fn run() -> Result<(), renderdoc::replay::Error> {
+    /// let mut replay = CaptureFile::open("frame12.rdc")?.open_replay()?;
+    /// for res in replay.resources()? {
+    ///     if res.is_texture() && res.byte_size.unwrap_or(0) > 64 << 20 {
+    ///         println!("{} is {} MiB", res.name, res.byte_size.unwrap() >> 20);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resources(&mut self) -> Result<Vec<ResourceDescription>, Error> {
+        self.session.call(&Request::Resources)
+    }
+
+    /// Returns every use of `resource` across the frame, in frame order.
+    pub fn resource_usage(&mut self, resource: ResourceId) -> Result<Vec<EventUsage>, Error> {
+        self.session.call(&Request::ResourceUsage { resource })
+    }
+
+    /// Returns the pipeline state right after the action with `event_id`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::replay::CaptureFile;
+    /// # fn run() -> Result<(), renderdoc::replay::Error> {
+    /// let mut replay = CaptureFile::open("frame12.rdc")?.open_replay()?;
+    /// let state = replay.pipeline_state(1337)?;
+    /// assert_eq!(state.render_target_count(), 4, "G-buffer pass must bind 4 MRTs");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pipeline_state(&mut self, event_id: u32) -> Result<PipelineState, Error> {
+        self.session.call(&Request::PipelineState { event_id })
+    }
+
+    /// Returns reflection data of the shader bound to `stage` at the action
+    /// with `event_id`, or `None` if no shader is bound.
+    pub fn shader_reflection(&mut self, event_id: u32, stage: ShaderStage) -> Result<Option<ShaderReflection>, Error> {
+        self.session.call(&Request::ShaderReflection { event_id, stage })
+    }
+
+    /// Saves the contents of a texture right after the action with
+    /// `event_id` to an image file.
+    ///
+    /// Only the first mip level and slice are saved, except for
+    /// `ImageFormat::Dds` which keeps all of them.
+    pub fn save_texture<P: AsRef<Path>>(
+        &mut self,
+        texture: ResourceId,
+        event_id: u32,
+        path: P,
+        format: ImageFormat,
+    ) -> Result<(), Error> {
+        self.session.call(&Request::SaveTexture {
+            texture,
+            event_id,
+            path: path.as_ref(),
+            format,
+        })
+    }
+
+    /// Returns the vertices of `instance` of the draw with `event_id`, as
+    /// output by the geometry stages up to `stage`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::replay::{CaptureFile, MeshStage};
+    /// # fn run() -> Result<(), renderdoc::replay::Error> {
+    /// let mut good = CaptureFile::open("good.rdc")?.open_replay()?;
+    /// let mut bad = CaptureFile::open("bad.rdc")?.open_replay()?;
+    /// let expected = good.post_transform_mesh(1337, 0, MeshStage::VertexOutput)?;
+    /// let actual = bad.post_transform_mesh(1337, 0, MeshStage::VertexOutput)?;
+    /// match actual.max_difference(&expected) {
+    ///     Some(diff) if diff < 1e-4 => {}
+    ///     diff => panic!("mesh differs from the known good capture: {:?}", diff),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn post_transform_mesh(
+        &mut self,
+        event_id: u32,
+        instance: u32,
+        stage: MeshStage,
+    ) -> Result<PostTransformMesh, Error> {
+        self.session.call(&Request::PostTransformMesh {
+            event_id,
+            instance,
+            stage,
+        })
+    }
+
+    /// Returns every modification of the texel at `x`, `y` of `subresource`
+    /// of `texture` across the frame, in frame order.
+    ///
+    /// # Examples
+    ///
+    /// Finding the draw that wrote magenta into a pixel:
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::replay::{CaptureFile, ResourceId, Subresource};
+    /// # fn run() -> Result<(), renderdoc::replay::Error> {
+    /// let mut replay = CaptureFile::open("frame12.rdc")?.open_replay()?;
+    /// let history = replay.pixel_history(ResourceId(201), 640, 360, Subresource::default())?;
+    /// let culprit = history
+    ///     .iter()
+    ///     .rev()
+    ///     .find(|m| m.passed() && m.post_mod.color_near([1.0, 0.0, 1.0, 1.0], 0.01));
+    /// if let Some(m) = culprit {
+    ///     println!("Magenta written by event {}, primitive {}", m.event_id, m.primitive_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pixel_history(
+        &mut self,
+        texture: ResourceId,
+        x: u32,
+        y: u32,
+        subresource: Subresource,
+    ) -> Result<Vec<PixelModification>, Error> {
+        self.session.call(&Request::PixelHistory {
+            texture,
+            x,
+            y,
+            subresource,
+        })
+    }
+
+    /// Traces the vertex shader invocation for `vertex` of `instance` at the
+    /// draw with `event_id`, or returns `None` if the shader can't be
+    /// debugged.
+    ///
+    /// `index` is the vertex's position in the index buffer for indexed
+    /// draws, and the same as `vertex` otherwise.
+    pub fn debug_vertex(
+        &mut self,
+        event_id: u32,
+        vertex: u32,
+        instance: u32,
+        index: u32,
+    ) -> Result<Option<ShaderDebugTrace>, Error> {
+        self.session.call(&Request::DebugVertex {
+            event_id,
+            vertex,
+            instance,
+            index,
+        })
+    }
+
+    /// Traces the pixel shader invocation which wrote the pixel at `x`, `y`
+    /// in the draw with `event_id`, or returns `None` if no fragment was
+    /// shaded there or the shader can't be debugged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::replay::CaptureFile;
+    /// # fn run() -> Result<(), renderdoc::replay::Error> {
+    /// let mut replay = CaptureFile::open("frame12.rdc")?.open_replay()?;
+    /// if let Some(trace) = replay.debug_pixel(1337, 640, 360)? {
+    ///     let last = trace.steps.len() - 1;
+    ///     for (name, var) in trace.variables_at(last) {
+    ///         println!("{} = {:?}", name, var.values);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn debug_pixel(&mut self, event_id: u32, x: u32, y: u32) -> Result<Option<ShaderDebugTrace>, Error> {
+        self.session.call(&Request::DebugPixel { event_id, x, y })
+    }
+
+    /// Traces the compute shader invocation of `thread` within `group` in the
+    /// dispatch with `event_id`, or returns `None` if the shader can't be
+    /// debugged.
+    pub fn debug_thread(
+        &mut self,
+        event_id: u32,
+        group: [u32; 3],
+        thread: [u32; 3],
+    ) -> Result<Option<ShaderDebugTrace>, Error> {
+        self.session.call(&Request::DebugThread {
+            event_id,
+            group,
+            thread,
+        })
+    }
+
+    /// Returns the counters supported by the GPU replaying the capture.
+    pub fn counters(&mut self) -> Result<Vec<CounterDescription>, Error> {
+        self.session.call(&Request::Counters)
+    }
+
+    /// Measures `counters` for every action in the frame.
+    ///
+    /// The frame is replayed once per counter, so fetching many counters of a
+    /// large capture takes a while.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use renderdoc::replay::{CaptureFile, Counter, CounterResult};
+    /// # fn run() -> Result<(), renderdoc::replay::Error> {
+    /// let mut replay = CaptureFile::open("frame12.rdc")?.open_replay()?;
+    /// let results = replay.fetch_counters(&[Counter::EVENT_GPU_DURATION])?;
+    /// for (event_id, counters) in CounterResult::by_event(&results) {
+    ///     println!("{:>6} {:.3} ms", event_id, counters[&Counter::EVENT_GPU_DURATION] * 1000.0);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fetch_counters(&mut self, counters: &[Counter]) -> Result<Vec<CounterResult>, Error> {
+        self.session.call(&Request::FetchCounters { counters })
+    }
+
+    /// Reads back `len` bytes starting at `offset` of a buffer, as they were
+    /// right after the action with `event_id`.
+    ///
+    /// A `len` of zero reads until the end of the buffer.
+    pub fn buffer_data(&mut self, buffer: ResourceId, event_id: u32, offset: u64, len: u64) -> Result<Vec<u8>, Error> {
+        #[derive(Deserialize)]
+        struct Data(#[serde(deserialize_with = "export::from_hex")] Vec<u8>);
+
+        let request = Request::BufferData {
+            buffer,
+            event_id,
+            offset,
+            len,
+        };
+        self.session.call(&request).map(|Data(data)| data)
+    }
+}
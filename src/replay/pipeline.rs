@@ -0,0 +1,132 @@
+//! Abstracted pipeline state at a given event.
+
+use super::ResourceId;
+
+/// A programmable stage of the graphics or compute pipeline.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum ShaderStage {
+    /// Vertex shader.
+    Vertex,
+    /// Hull or tessellation control shader.
+    Hull,
+    /// Domain or tessellation evaluation shader.
+    Domain,
+    /// Geometry shader.
+    Geometry,
+    /// Pixel or fragment shader.
+    Pixel,
+    /// Compute shader.
+    Compute,
+    /// Task or amplification shader.
+    Task,
+    /// Mesh shader.
+    Mesh,
+}
+
+/// A shader bound to a pipeline stage.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct BoundShader {
+    /// Stage the shader is bound to.
+    pub stage: ShaderStage,
+    /// The shader resource.
+    pub shader: ResourceId,
+    /// Name of the entry point, e.g. `main`.
+    pub entry_point: String,
+}
+
+/// A viewport transform.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub struct Viewport {
+    /// Left edge of the viewport in pixels.
+    pub x: f32,
+    /// Top edge of the viewport in pixels.
+    pub y: f32,
+    /// Width of the viewport in pixels.
+    pub width: f32,
+    /// Height of the viewport in pixels.
+    pub height: f32,
+    /// Depth which the near plane maps to.
+    pub min_depth: f32,
+    /// Depth which the far plane maps to.
+    pub max_depth: f32,
+}
+
+/// One half of a blend equation, i.e. either color or alpha.
+///
+/// Factors and operations are named as in RenderDoc, e.g. `SrcAlpha` or `Add`.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct BlendEquation {
+    /// Factor the source value is multiplied with.
+    pub source: String,
+    /// Factor the destination value is multiplied with.
+    pub destination: String,
+    /// Operation combining both products.
+    pub operation: String,
+}
+
+/// Blend state of a single render target.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct ColorBlend {
+    /// Whether blending is enabled at all.
+    pub enabled: bool,
+    /// Equation for the color channels.
+    pub color: BlendEquation,
+    /// Equation for the alpha channel.
+    pub alpha: BlendEquation,
+    /// Mask of the channels written, with red in the lowest bit.
+    pub write_mask: u8,
+}
+
+/// Pipeline state after a given event, abstracted over the graphics API.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct PipelineState {
+    /// Event the state was captured at.
+    pub event_id: u32,
+    /// Shaders bound to each active stage.
+    pub shaders: Vec<BoundShader>,
+    /// Color targets by slot, with `None` for unbound slots.
+    pub render_targets: Vec<Option<ResourceId>>,
+    /// The bound depth-stencil target, if any.
+    pub depth_target: Option<ResourceId>,
+    /// The first viewport, if one is set.
+    pub viewport: Option<Viewport>,
+    /// Blend state for each render target slot.
+    pub blends: Vec<ColorBlend>,
+}
+
+impl PipelineState {
+    /// Returns the shader bound to `stage`, if any.
+    pub fn shader(&self, stage: ShaderStage) -> Option<&BoundShader> {
+        self.shaders.iter().find(|shader| shader.stage == stage)
+    }
+
+    /// Returns the number of bound color targets.
+    pub fn render_target_count(&self) -> usize {
+        self.render_targets.iter().filter(|rt| rt.is_some()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn decode_pipeline_state() {
+        let json = r#"{
+            "event_id": 42,
+            "shaders": [
+                {"stage": "Vertex", "shader": 101, "entry_point": "main"},
+                {"stage": "Pixel", "shader": 102, "entry_point": "main"}
+            ],
+            "render_targets": [201, null, 203],
+            "depth_target": 204,
+            "viewport": {"x": 0, "y": 0, "width": 1920, "height": 1080, "min_depth": 0, "max_depth": 1},
+            "blends": []
+        }"#;
+        let state: PipelineState = serde_json::from_str(json).unwrap();
+        assert_eq!(state.render_target_count(), 2);
+        assert_eq!(state.shader(ShaderStage::Pixel).unwrap().shader, ResourceId(102));
+        assert!(state.shader(ShaderStage::Geometry).is_none());
+    }
+}
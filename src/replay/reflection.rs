@@ -0,0 +1,114 @@
+//! Reflection data of shaders referenced in a capture.
+
+/// A parameter in the input or output signature of a shader.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct SignatureParameter {
+    /// Name of the variable in the shader source, if known.
+    pub name: String,
+    /// HLSL semantic name, or empty for APIs without semantics.
+    pub semantic_name: String,
+    /// HLSL semantic index.
+    pub semantic_index: u32,
+    /// Register or location index of the parameter.
+    pub register: u32,
+    /// Number of components, from 1 to 4.
+    pub component_count: u32,
+}
+
+/// A variable within a constant block.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct ShaderConstant {
+    /// Name of the variable.
+    pub name: String,
+    /// Offset in bytes from the start of the enclosing block or struct.
+    pub byte_offset: u32,
+    /// Name of the variable's type, e.g. `float4x4`.
+    pub type_name: String,
+    /// Number of rows for matrices, 1 otherwise.
+    pub rows: u32,
+    /// Number of columns, or vector components.
+    pub columns: u32,
+    /// Number of array elements, 1 for non-arrays.
+    pub elements: u32,
+    /// Members if the variable is a struct.
+    pub members: Vec<ShaderConstant>,
+}
+
+/// A constant or uniform buffer declared by a shader.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct ConstantBlock {
+    /// Name of the block.
+    pub name: String,
+    /// Descriptor set or register space the block is bound in.
+    pub set: u32,
+    /// Binding or register number within the set.
+    pub binding: u32,
+    /// Size of the block in bytes.
+    pub byte_size: u32,
+    /// Variables declared in the block.
+    pub variables: Vec<ShaderConstant>,
+}
+
+/// A texture, buffer or sampler bound to a shader.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct ShaderResource {
+    /// Name of the resource in the shader source.
+    pub name: String,
+    /// Descriptor set or register space the resource is bound in.
+    pub set: u32,
+    /// Binding or register number within the set.
+    pub binding: u32,
+    /// Whether the resource is a texture rather than a buffer or sampler.
+    pub is_texture: bool,
+}
+
+/// Interface of a shader as seen by the graphics API.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct ShaderReflection {
+    /// Name of the entry point, e.g. `main`.
+    pub entry_point: String,
+    /// Parameters read from the previous stage or the vertex buffers.
+    pub inputs: Vec<SignatureParameter>,
+    /// Parameters written for the next stage or the render targets.
+    pub outputs: Vec<SignatureParameter>,
+    /// Constant and uniform buffers.
+    pub constant_blocks: Vec<ConstantBlock>,
+    /// Read-only resources, e.g. sampled textures.
+    pub read_only_resources: Vec<ShaderResource>,
+    /// Read-write resources, e.g. storage buffers and images.
+    pub read_write_resources: Vec<ShaderResource>,
+    /// Samplers.
+    pub samplers: Vec<ShaderResource>,
+}
+
+impl ShaderReflection {
+    /// Returns the constant block called `name`, if any.
+    pub fn constant_block(&self, name: &str) -> Option<&ConstantBlock> {
+        self.constant_blocks.iter().find(|block| block.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn decode_constant_block() {
+        let json = r#"{
+            "entry_point": "main",
+            "inputs": [], "outputs": [],
+            "constant_blocks": [{
+                "name": "Camera", "set": 0, "binding": 1, "byte_size": 64,
+                "variables": [{"name": "view_proj", "byte_offset": 0, "type_name": "float4x4",
+                               "rows": 4, "columns": 4, "elements": 1, "members": []}]
+            }],
+            "read_only_resources": [], "read_write_resources": [], "samplers": []
+        }"#;
+        let refl: ShaderReflection = serde_json::from_str(json).unwrap();
+        let camera = refl.constant_block("Camera").unwrap();
+        assert_eq!((camera.set, camera.binding), (0, 1));
+        assert_eq!(camera.variables[0].type_name, "float4x4");
+        assert!(refl.constant_block("Lights").is_none());
+    }
+}
@@ -0,0 +1,82 @@
+//! Resources created by the captured application and their usage.
+
+use super::ResourceId;
+
+/// Properties of a texture resource.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct TextureDescription {
+    /// Dimensionality, as named by RenderDoc, e.g. `Texture2D` or
+    /// `TextureCube`.
+    pub dimension: String,
+    /// Pixel format, as named by RenderDoc, e.g. `R8G8B8A8_UNORM`.
+    pub format: String,
+    /// Width of the first mip level in pixels.
+    pub width: u32,
+    /// Height of the first mip level in pixels.
+    pub height: u32,
+    /// Depth of the first mip level of 3D textures, 1 otherwise.
+    pub depth: u32,
+    /// Number of mip levels.
+    pub mips: u32,
+    /// Number of array slices.
+    pub array_size: u32,
+    /// Number of samples per pixel, 1 if not multisampled.
+    pub samples: u32,
+}
+
+/// A resource in a capture, e.g. a texture, buffer or shader.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct ResourceDescription {
+    /// ID of the resource.
+    pub id: ResourceId,
+    /// Debug name set by the application, or one generated by RenderDoc.
+    pub name: String,
+    /// Whether `name` was generated by RenderDoc rather than set by the
+    /// application.
+    pub autogenerated_name: bool,
+    /// Kind of resource, as named by RenderDoc, e.g. `Texture` or `Buffer`.
+    pub kind: String,
+    /// Size of the resource's memory in bytes, for textures and buffers.
+    pub byte_size: Option<u64>,
+    /// Properties of textures.
+    pub texture: Option<TextureDescription>,
+}
+
+impl ResourceDescription {
+    /// Returns whether the resource is a texture.
+    pub fn is_texture(&self) -> bool {
+        self.texture.is_some()
+    }
+}
+
+/// A use of a resource by a single event.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub struct EventUsage {
+    /// Event ID of the action using the resource.
+    pub event_id: u32,
+    /// How the resource is used, as named by RenderDoc, e.g.
+    /// `ColorTarget`, `PS_Resource` or `CopyDst`.
+    pub usage: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn decode_resources() {
+        let json = r#"[
+            {"id": 12, "name": "GBuffer Albedo", "autogenerated_name": false, "kind": "Texture",
+             "byte_size": 8294400, "texture": {"dimension": "Texture2D", "format": "R8G8B8A8_UNORM",
+             "width": 1920, "height": 1080, "depth": 1, "mips": 1, "array_size": 1, "samples": 1}},
+            {"id": 13, "name": "Shader 13", "autogenerated_name": true, "kind": "Shader",
+             "byte_size": null, "texture": null}
+        ]"#;
+        let resources: Vec<ResourceDescription> = serde_json::from_str(json).unwrap();
+        assert!(resources[0].is_texture());
+        assert_eq!(resources[0].texture.as_ref().unwrap().format, "R8G8B8A8_UNORM");
+        assert!(!resources[1].is_texture());
+        assert_eq!(resources[1].byte_size, None);
+    }
+}
@@ -0,0 +1,1057 @@
+// C interface over RenderDoc's C++ replay API, used by src/replay/shim.rs.
+//
+// Built by build.rs against the replay headers of the RenderDoc release that
+// is linked, since the layouts of the replay types change between releases.
+// Written against RenderDoc 1.31 onwards, where descriptors replaced
+// `BoundResource`.
+//
+// Every function taking a session replies with a JSON object allocated with
+// `malloc()`, either `{"ok": <value>}` or `{"error": "<message>"}`, which the
+// caller releases with `rdrs_free()`.
+
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include <algorithm>
+#include <map>
+#include <mutex>
+#include <stdexcept>
+#include <string>
+#include <vector>
+
+#include "renderdoc_replay.h"
+
+// `PipeState` is implemented inline for every module using it.
+#include "pipestate.inl"
+
+struct rdrs_session
+{
+  rdcstr path;
+  ICaptureFile *cap = NULL;
+  IRemoteServer *remote = NULL;
+  IReplayController *controller = NULL;
+};
+
+namespace
+{
+struct ReplayError : std::runtime_error
+{
+  explicit ReplayError(const std::string &msg) : std::runtime_error(msg) {}
+};
+
+// Appends JSON values, inserting separators between the members of objects
+// and arrays.
+class Writer
+{
+public:
+  std::string out;
+
+  Writer &key(const char *name)
+  {
+    str(name);
+    out += ':';
+    comma = false;
+    return *this;
+  }
+
+  void begin_object() { open('{'); }
+  void end_object() { close('}'); }
+  void begin_array() { open('['); }
+  void end_array() { close(']'); }
+
+  void null()
+  {
+    separate();
+    out += "null";
+  }
+
+  void boolean(bool value)
+  {
+    separate();
+    out += value ? "true" : "false";
+  }
+
+  void num(uint64_t value)
+  {
+    separate();
+    out += std::to_string(value);
+  }
+
+  void num(int64_t value)
+  {
+    separate();
+    out += std::to_string(value);
+  }
+
+  void num(uint32_t value) { num(uint64_t(value)); }
+  void num(int32_t value) { num(int64_t(value)); }
+
+  void num(double value)
+  {
+    char buf[32];
+    snprintf(buf, sizeof(buf), "%.17g", value);
+    separate();
+    out += buf;
+  }
+
+  void str(const char *value) { str(value, strlen(value)); }
+  void str(const std::string &value) { str(value.c_str(), value.size()); }
+  void str(const rdcstr &value) { str(value.c_str(), value.size()); }
+
+  void str(const char *value, size_t len)
+  {
+    separate();
+    out += '"';
+    for(size_t i = 0; i < len; i++)
+    {
+      char c = value[i];
+      if(c == '"' || c == '\\')
+      {
+        out += '\\';
+        out += c;
+      }
+      else if((unsigned char)c < 0x20)
+      {
+        char buf[8];
+        snprintf(buf, sizeof(buf), "\\u%04x", (unsigned)c);
+        out += buf;
+      }
+      else
+      {
+        out += c;
+      }
+    }
+    out += '"';
+  }
+
+  // Binary data is sent hex encoded.
+  void hex(const byte *data, size_t len)
+  {
+    static const char digits[] = "0123456789abcdef";
+    separate();
+    out += '"';
+    for(size_t i = 0; i < len; i++)
+    {
+      out += digits[data[i] >> 4];
+      out += digits[data[i] & 0xf];
+    }
+    out += '"';
+  }
+
+private:
+  bool comma = false;
+
+  void separate()
+  {
+    if(comma)
+      out += ',';
+    comma = true;
+  }
+
+  void open(char c)
+  {
+    separate();
+    out += c;
+    comma = false;
+  }
+
+  void close(char c)
+  {
+    out += c;
+    comma = true;
+  }
+};
+
+char *dup(const std::string &s)
+{
+  char *ret = (char *)malloc(s.size() + 1);
+  if(ret)
+    memcpy(ret, s.c_str(), s.size() + 1);
+  return ret;
+}
+
+// Runs `f` writing the reply value, and wraps its result or error.
+template <typename F>
+char *reply(F f)
+{
+  Writer w;
+  try
+  {
+    Writer value;
+    f(value);
+    w.out = "{\"ok\":" + value.out + "}";
+  }
+  catch(const std::exception &e)
+  {
+    w.out.clear();
+    w.begin_object();
+    w.key("error").str(e.what());
+    w.end_object();
+  }
+  return dup(w.out);
+}
+
+void check(const ResultDetails &result)
+{
+  if(!result.OK())
+    throw ReplayError(result.Message().c_str());
+}
+
+IReplayController *controller(rdrs_session *s)
+{
+  if(!s->controller)
+    throw ReplayError("capture is not opened for replay");
+  return s->controller;
+}
+
+// `ResourceId` wraps a single 64-bit value without accessors.
+static_assert(sizeof(ResourceId) == sizeof(uint64_t), "ResourceId must be 64 bits");
+
+uint64_t id_num(ResourceId id)
+{
+  uint64_t ret;
+  memcpy(&ret, &id, sizeof(ret));
+  return ret;
+}
+
+ResourceId id_from(uint64_t num)
+{
+  ResourceId ret;
+  memcpy((void *)&ret, &num, sizeof(ret));
+  return ret;
+}
+
+void resource_id(Writer &w, ResourceId id)
+{
+  if(id == ResourceId::Null())
+    w.null();
+  else
+    w.num(id_num(id));
+}
+
+// Must match `ImageFormat` and `ThumbnailFormat` in src/replay/export.rs.
+FileType file_type(const char *name)
+{
+  static const struct
+  {
+    const char *name;
+    FileType type;
+  } types[] = {
+      {"DDS", FileType::DDS}, {"PNG", FileType::PNG}, {"JPG", FileType::JPG},
+      {"BMP", FileType::BMP}, {"TGA", FileType::TGA}, {"HDR", FileType::HDR},
+      {"EXR", FileType::EXR}, {"Raw", FileType::Raw},
+  };
+  for(const auto &t : types)
+    if(!strcmp(t.name, name))
+      return t.type;
+  throw ReplayError(std::string("unknown file type ") + name);
+}
+
+// Must match `ShaderStage` in src/replay/pipeline.rs.
+const struct
+{
+  const char *name;
+  ShaderStage stage;
+} shader_stages[] = {
+    {"Vertex", ShaderStage::Vertex},     {"Hull", ShaderStage::Hull},
+    {"Domain", ShaderStage::Domain},     {"Geometry", ShaderStage::Geometry},
+    {"Pixel", ShaderStage::Pixel},       {"Compute", ShaderStage::Compute},
+    {"Task", ShaderStage::Task},         {"Mesh", ShaderStage::Mesh},
+};
+
+ShaderStage shader_stage(const char *name)
+{
+  for(const auto &s : shader_stages)
+    if(!strcmp(s.name, name))
+      return s.stage;
+  throw ReplayError(std::string("unknown shader stage ") + name);
+}
+
+// Must match `ActionFlags` in src/replay/action.rs.
+uint32_t action_flags(ActionFlags flags)
+{
+  static const struct
+  {
+    ActionFlags flag;
+    uint32_t bit;
+  } bits[] = {
+      {ActionFlags::Clear, 0x1},          {ActionFlags::Drawcall, 0x2},
+      {ActionFlags::Dispatch, 0x4},       {ActionFlags::CmdList, 0x8},
+      {ActionFlags::SetMarker, 0x10},     {ActionFlags::PushMarker, 0x20},
+      {ActionFlags::PopMarker, 0x40},     {ActionFlags::Present, 0x80},
+      {ActionFlags::MultiAction, 0x100},  {ActionFlags::Copy, 0x200},
+      {ActionFlags::Resolve, 0x400},      {ActionFlags::GenMips, 0x800},
+      {ActionFlags::PassBoundary, 0x1000}, {ActionFlags::Indexed, 0x2000},
+      {ActionFlags::Instanced, 0x4000},   {ActionFlags::Indirect, 0x8000},
+  };
+  uint32_t ret = 0;
+  for(const auto &b : bits)
+    if((flags & b.flag) != ActionFlags::NoFlags)
+      ret |= b.bit;
+  return ret;
+}
+
+void action(Writer &w, const ActionDescription &a, const SDFile &sfile)
+{
+  w.begin_object();
+  w.key("event_id").num(a.eventId);
+  w.key("action_id").num(a.actionId);
+  w.key("name").str(a.GetName(sfile));
+  w.key("flags").num(action_flags(a.flags));
+  w.key("events").begin_array();
+  for(const APIEvent &e : a.events)
+    w.num(e.eventId);
+  w.end_array();
+  w.key("children").begin_array();
+  for(const ActionDescription &c : a.children)
+    action(w, c, sfile);
+  w.end_array();
+  w.end_object();
+}
+
+void blend_equation(Writer &w, const BlendEquation &eq)
+{
+  w.begin_object();
+  w.key("source").str(ToStr(eq.source));
+  w.key("destination").str(ToStr(eq.destination));
+  w.key("operation").str(ToStr(eq.operation));
+  w.end_object();
+}
+
+void signature(Writer &w, const rdcarray<SigParameter> &params)
+{
+  w.begin_array();
+  for(const SigParameter &p : params)
+  {
+    w.begin_object();
+    w.key("name").str(p.varName);
+    w.key("semantic_name").str(p.semanticName);
+    w.key("semantic_index").num(p.semanticIndex);
+    w.key("register").num(p.regIndex);
+    w.key("component_count").num(uint32_t(p.compCount));
+    w.end_object();
+  }
+  w.end_array();
+}
+
+void constant(Writer &w, const ShaderConstant &c)
+{
+  w.begin_object();
+  w.key("name").str(c.name);
+  w.key("byte_offset").num(c.byteOffset);
+  w.key("type_name").str(c.type.name);
+  w.key("rows").num(uint32_t(c.type.rows));
+  w.key("columns").num(uint32_t(c.type.columns));
+  w.key("elements").num(c.type.elements);
+  w.key("members").begin_array();
+  for(const ShaderConstant &m : c.type.members)
+    constant(w, m);
+  w.end_array();
+  w.end_object();
+}
+
+void constant_block(Writer &w, const ConstantBlock &block)
+{
+  w.begin_object();
+  w.key("name").str(block.name);
+  w.key("set").num(uint32_t(block.fixedBindSetOrSpace));
+  w.key("binding").num(uint32_t(block.fixedBindNumber));
+  w.key("byte_size").num(block.byteSize);
+  w.key("variables").begin_array();
+  for(const ShaderConstant &v : block.variables)
+    constant(w, v);
+  w.end_array();
+  w.end_object();
+}
+
+void shader_resource(Writer &w, const rdcstr &name, uint32_t set, uint32_t binding, bool is_texture)
+{
+  w.begin_object();
+  w.key("name").str(name);
+  w.key("set").num(set);
+  w.key("binding").num(binding);
+  w.key("is_texture").boolean(is_texture);
+  w.end_object();
+}
+
+void shader_resources(Writer &w, const rdcarray<ShaderResource> &resources)
+{
+  w.begin_array();
+  for(const ShaderResource &r : resources)
+    shader_resource(w, r.name, r.fixedBindSetOrSpace, r.fixedBindNumber, r.isTexture);
+  w.end_array();
+}
+
+void pixel_value(Writer &w, const ModificationValue &value)
+{
+  w.begin_object();
+  w.key("color").begin_array();
+  for(int i = 0; i < 4; i++)
+    w.num(double(value.col.floatValue[i]));
+  w.end_array();
+  w.key("depth").num(double(value.depth));
+  w.key("stencil").num(value.stencil);
+  w.end_object();
+}
+
+void debug_variable(Writer &w, const ShaderVariable &var)
+{
+  w.begin_object();
+  w.key("name").str(var.name);
+  w.key("type_name").str(ToStr(var.type));
+  w.key("rows").num(uint32_t(var.rows));
+  w.key("columns").num(uint32_t(var.columns));
+  w.key("values").begin_array();
+  if(var.members.empty())
+  {
+    size_t count = std::min<size_t>(size_t(var.rows) * var.columns, 16);
+    for(size_t i = 0; i < count; i++)
+    {
+      switch(var.type)
+      {
+        case VarType::Float:
+        case VarType::Half: w.num(double(var.value.f32v[i])); break;
+        case VarType::Double: w.num(var.value.f64v[i]); break;
+        case VarType::SInt:
+        case VarType::SShort:
+        case VarType::SByte: w.num(var.value.s32v[i]); break;
+        default: w.num(var.value.u32v[i]); break;
+      }
+    }
+  }
+  w.end_array();
+  w.key("members").begin_array();
+  for(const ShaderVariable &m : var.members)
+    debug_variable(w, m);
+  w.end_array();
+  w.end_object();
+}
+
+void debug_trace(Writer &w, IReplayController *r, ShaderDebugTrace *trace, const char *stage)
+{
+  if(!trace || !trace->debugger)
+  {
+    if(trace)
+      r->FreeTrace(trace);
+    w.null();
+    return;
+  }
+
+  w.begin_object();
+  w.key("stage").str(stage);
+  w.key("inputs").begin_array();
+  for(const ShaderVariable &v : trace->inputs)
+    debug_variable(w, v);
+  w.end_array();
+  w.key("constant_blocks").begin_array();
+  for(const ShaderVariable &v : trace->constantBlocks)
+    debug_variable(w, v);
+  w.end_array();
+  w.key("steps").begin_array();
+  while(true)
+  {
+    rdcarray<ShaderDebugState> states = r->ContinueDebug(trace->debugger);
+    if(states.empty())
+      break;
+    for(const ShaderDebugState &state : states)
+    {
+      w.begin_object();
+      w.key("step_index").num(state.stepIndex);
+      w.key("next_instruction").num(state.nextInstruction);
+      w.key("changes").begin_array();
+      for(const ShaderVariableChange &c : state.changes)
+      {
+        w.begin_object();
+        w.key("before");
+        if(c.before.name.empty())
+          w.null();
+        else
+          debug_variable(w, c.before);
+        w.key("after");
+        if(c.after.name.empty())
+          w.null();
+        else
+          debug_variable(w, c.after);
+        w.end_object();
+      }
+      w.end_array();
+      w.end_object();
+    }
+  }
+  w.end_array();
+  w.end_object();
+  r->FreeTrace(trace);
+}
+
+void counter_description(Writer &w, const CounterDescription &desc)
+{
+  w.begin_object();
+  w.key("counter").num(uint32_t(desc.counter));
+  w.key("name").str(desc.name);
+  w.key("category").str(desc.category);
+  w.key("description").str(desc.description);
+  w.key("unit").str(ToStr(desc.unit));
+  w.end_object();
+}
+
+double counter_value(const CounterValue &value, const CounterDescription &desc)
+{
+  if(desc.resultType == CompType::Float)
+    return desc.resultByteWidth == 8 ? value.d : double(value.f);
+  return desc.resultByteWidth == 8 ? double(value.u64) : double(value.u32);
+}
+
+// Reads a little endian value of type `T` at `offset`.
+template <typename T>
+T read(const bytebuf &data, size_t offset)
+{
+  if(offset + sizeof(T) > data.size())
+    throw ReplayError("mesh data ends unexpectedly");
+  T ret;
+  memcpy(&ret, data.data() + offset, sizeof(T));
+  return ret;
+}
+}    // namespace
+
+extern "C" {
+
+rdrs_session *rdrs_create()
+{
+  // Replay is initialised once and stays so for the remainder of the
+  // process, as librenderdoc stays loaded anyway.
+  static std::once_flag init;
+  std::call_once(init, [] { RENDERDOC_InitialiseReplay(GlobalEnvironment(), rdcarray<rdcstr>()); });
+  return new rdrs_session();
+}
+
+void rdrs_destroy(rdrs_session *s)
+{
+  if(s->remote)
+  {
+    if(s->controller)
+      s->remote->CloseCapture(s->controller);
+    s->remote->ShutdownConnection();
+  }
+  else if(s->controller)
+  {
+    s->controller->Shutdown();
+  }
+  if(s->cap)
+    s->cap->Shutdown();
+  delete s;
+}
+
+void rdrs_free(char *reply)
+{
+  free(reply);
+}
+
+const char *rdrs_version()
+{
+  return RENDERDOC_GetVersionString();
+}
+
+char *rdrs_open_file(rdrs_session *s, const char *path, size_t len)
+{
+  return reply([&](Writer &w) {
+    s->path = rdcstr(path, len);
+    s->cap = RENDERDOC_OpenCaptureFile();
+    check(s->cap->OpenFile(s->path, "", RENDERDOC_ProgressCallback()));
+    w.null();
+  });
+}
+
+char *rdrs_open_replay(rdrs_session *s)
+{
+  return reply([&](Writer &w) {
+    if(s->cap->LocalReplaySupport() != ReplaySupport::Supported)
+      throw ReplayError("capture cannot be replayed on this machine");
+    rdcpair<ResultDetails, IReplayController *> result =
+        s->cap->OpenCapture(ReplayOptions(), RENDERDOC_ProgressCallback());
+    check(result.first);
+    s->controller = result.second;
+    w.null();
+  });
+}
+
+char *rdrs_open_remote_replay(rdrs_session *s, const char *host)
+{
+  return reply([&](Writer &w) {
+    check(RENDERDOC_CreateRemoteServerConnection(host, &s->remote));
+
+    rdcstr remote_path = s->remote->CopyCaptureToRemote(s->path, RENDERDOC_ProgressCallback());
+    if(remote_path.empty())
+      throw ReplayError(std::string("failed to copy the capture to ") + host);
+    rdcpair<ResultDetails, IReplayController *> result = s->remote->OpenCapture(
+        IRemoteServer::NoPreference, remote_path, ReplayOptions(), RENDERDOC_ProgressCallback());
+    check(result.first);
+    s->controller = result.second;
+    w.null();
+  });
+}
+
+char *rdrs_thumbnail(rdrs_session *s, const char *format)
+{
+  return reply([&](Writer &w) {
+    Thumbnail thumb = s->cap->GetThumbnail(file_type(format), 0);
+    if(thumb.data.empty())
+    {
+      w.null();
+      return;
+    }
+    w.begin_object();
+    w.key("width").num(thumb.width);
+    w.key("height").num(thumb.height);
+    w.key("format").str(format);
+    w.key("data").hex(thumb.data.data(), thumb.data.size());
+    w.end_object();
+  });
+}
+
+char *rdrs_convert(rdrs_session *s, const char *path, size_t len, const char *format)
+{
+  return reply([&](Writer &w) {
+    check(s->cap->Convert(rdcstr(path, len), format, NULL, RENDERDOC_ProgressCallback()));
+    w.null();
+  });
+}
+
+char *rdrs_actions(rdrs_session *s)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    const SDFile &sfile = r->GetStructuredFile();
+    w.begin_array();
+    for(const ActionDescription &a : r->GetRootActions())
+      action(w, a, sfile);
+    w.end_array();
+  });
+}
+
+char *rdrs_resources(rdrs_session *s)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    std::map<uint64_t, const TextureDescription *> textures;
+    for(const TextureDescription &t : r->GetTextures())
+      textures[id_num(t.resourceId)] = &t;
+    std::map<uint64_t, const BufferDescription *> buffers;
+    for(const BufferDescription &b : r->GetBuffers())
+      buffers[id_num(b.resourceId)] = &b;
+
+    w.begin_array();
+    for(const ResourceDescription &res : r->GetResources())
+    {
+      uint64_t id = id_num(res.resourceId);
+      auto tex = textures.find(id);
+      auto buf = buffers.find(id);
+
+      w.begin_object();
+      w.key("id").num(id);
+      w.key("name").str(res.name);
+      w.key("autogenerated_name").boolean(res.autogeneratedName);
+      w.key("kind").str(ToStr(res.type));
+      w.key("byte_size");
+      if(tex != textures.end())
+        w.num(tex->second->byteSize);
+      else if(buf != buffers.end())
+        w.num(buf->second->length);
+      else
+        w.null();
+      w.key("texture");
+      if(tex != textures.end())
+      {
+        const TextureDescription &t = *tex->second;
+        w.begin_object();
+        w.key("dimension").str(ToStr(t.type));
+        w.key("format").str(t.format.Name());
+        w.key("width").num(t.width);
+        w.key("height").num(t.height);
+        w.key("depth").num(t.depth);
+        w.key("mips").num(t.mips);
+        w.key("array_size").num(t.arraysize);
+        w.key("samples").num(t.msSamp);
+        w.end_object();
+      }
+      else
+      {
+        w.null();
+      }
+      w.end_object();
+    }
+    w.end_array();
+  });
+}
+
+char *rdrs_resource_usage(rdrs_session *s, uint64_t resource)
+{
+  return reply([&](Writer &w) {
+    w.begin_array();
+    for(const EventUsage &u : controller(s)->GetUsage(id_from(resource)))
+    {
+      w.begin_object();
+      w.key("event_id").num(u.eventId);
+      w.key("usage").str(ToStr(u.usage));
+      w.end_object();
+    }
+    w.end_array();
+  });
+}
+
+char *rdrs_pipeline_state(rdrs_session *s, uint32_t event_id)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    r->SetFrameEvent(event_id, true);
+    const PipeState &pipe = r->GetPipelineState();
+
+    w.begin_object();
+    w.key("event_id").num(event_id);
+    w.key("shaders").begin_array();
+    for(const auto &stage : shader_stages)
+    {
+      ResourceId shader = pipe.GetShader(stage.stage);
+      if(shader == ResourceId::Null())
+        continue;
+      w.begin_object();
+      w.key("stage").str(stage.name);
+      w.key("shader").num(id_num(shader));
+      w.key("entry_point").str(pipe.GetShaderEntryPoint(stage.stage));
+      w.end_object();
+    }
+    w.end_array();
+
+    w.key("render_targets").begin_array();
+    for(const Descriptor &target : pipe.GetOutputTargets())
+      resource_id(w, target.resource);
+    w.end_array();
+    w.key("depth_target");
+    resource_id(w, pipe.GetDepthTarget().resource);
+
+    Viewport vp = pipe.GetViewport(0);
+    w.key("viewport");
+    if(vp.enabled)
+    {
+      w.begin_object();
+      w.key("x").num(double(vp.x));
+      w.key("y").num(double(vp.y));
+      w.key("width").num(double(vp.width));
+      w.key("height").num(double(vp.height));
+      w.key("min_depth").num(double(vp.minDepth));
+      w.key("max_depth").num(double(vp.maxDepth));
+      w.end_object();
+    }
+    else
+    {
+      w.null();
+    }
+
+    w.key("blends").begin_array();
+    for(const ColorBlend &b : pipe.GetColorBlends())
+    {
+      w.begin_object();
+      w.key("enabled").boolean(b.enabled);
+      w.key("color");
+      blend_equation(w, b.colorBlend);
+      w.key("alpha");
+      blend_equation(w, b.alphaBlend);
+      w.key("write_mask").num(uint32_t(b.writeMask));
+      w.end_object();
+    }
+    w.end_array();
+    w.end_object();
+  });
+}
+
+char *rdrs_shader_reflection(rdrs_session *s, uint32_t event_id, const char *stage)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    r->SetFrameEvent(event_id, true);
+    const ShaderReflection *refl = r->GetPipelineState().GetShaderReflection(shader_stage(stage));
+    if(!refl)
+    {
+      w.null();
+      return;
+    }
+
+    w.begin_object();
+    w.key("entry_point").str(refl->entryPoint);
+    w.key("inputs");
+    signature(w, refl->inputSignature);
+    w.key("outputs");
+    signature(w, refl->outputSignature);
+    w.key("constant_blocks").begin_array();
+    for(const ConstantBlock &block : refl->constantBlocks)
+      constant_block(w, block);
+    w.end_array();
+    w.key("read_only_resources");
+    shader_resources(w, refl->readOnlyResources);
+    w.key("read_write_resources");
+    shader_resources(w, refl->readWriteResources);
+    w.key("samplers").begin_array();
+    for(const ShaderSampler &sampler : refl->samplers)
+      shader_resource(w, sampler.name, sampler.fixedBindSetOrSpace, sampler.fixedBindNumber, false);
+    w.end_array();
+    w.end_object();
+  });
+}
+
+char *rdrs_save_texture(rdrs_session *s, uint64_t texture, uint32_t event_id, const char *path,
+                        size_t len, const char *format)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    r->SetFrameEvent(event_id, true);
+
+    TextureSave save;
+    save.resourceId = id_from(texture);
+    save.destType = file_type(format);
+    // DDS files hold every mip and slice, which RenderDoc saves for -1.
+    int32_t everything = save.destType == FileType::DDS ? -1 : 0;
+    save.mip = everything;
+    save.slice.sliceIndex = everything;
+    save.alpha = AlphaMapping::Preserve;
+    check(r->SaveTexture(save, rdcstr(path, len)));
+    w.null();
+  });
+}
+
+char *rdrs_buffer_data(rdrs_session *s, uint64_t buffer, uint32_t event_id, uint64_t offset,
+                       uint64_t len)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    r->SetFrameEvent(event_id, true);
+    bytebuf data = r->GetBufferData(id_from(buffer), offset, len);
+    w.hex(data.data(), data.size());
+  });
+}
+
+char *rdrs_post_transform_mesh(rdrs_session *s, uint32_t event_id, uint32_t instance,
+                               const char *stage)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    r->SetFrameEvent(event_id, true);
+    const PipeState &pipe = r->GetPipelineState();
+
+    // Must match `MeshStage` in src/replay/mesh.rs.
+    MeshDataStage mesh_stage;
+    ShaderStage shader;
+    if(!strcmp(stage, "VSOut"))
+    {
+      mesh_stage = MeshDataStage::VSOut;
+      shader = ShaderStage::Vertex;
+    }
+    else if(!strcmp(stage, "GSOut"))
+    {
+      mesh_stage = MeshDataStage::GSOut;
+      shader = pipe.GetShader(ShaderStage::Geometry) != ResourceId::Null() ? ShaderStage::Geometry
+                                                                           : ShaderStage::Domain;
+    }
+    else
+    {
+      throw ReplayError(std::string("unknown mesh stage ") + stage);
+    }
+
+    const ShaderReflection *refl = pipe.GetShaderReflection(shader);
+    if(!refl)
+      throw ReplayError(std::string("no shader bound for ") + stage);
+
+    MeshFormat out = r->GetPostVSData(instance, 0, mesh_stage);
+    if(out.vertexResourceId == ResourceId::Null())
+      throw ReplayError("no post-transform data at event " + std::to_string(event_id));
+
+    bool indexed = out.indexResourceId != ResourceId::Null();
+    std::vector<uint32_t> indices;
+    uint32_t count = out.numIndices;
+    if(indexed)
+    {
+      bytebuf data = r->GetBufferData(out.indexResourceId, out.indexByteOffset,
+                                      uint64_t(out.numIndices) * out.indexByteStride);
+      for(uint32_t i = 0; i < out.numIndices; i++)
+      {
+        size_t at = size_t(i) * out.indexByteStride;
+        int64_t index;
+        switch(out.indexByteStride)
+        {
+          case 1: index = read<uint8_t>(data, at); break;
+          case 2: index = read<uint16_t>(data, at); break;
+          case 4: index = read<uint32_t>(data, at); break;
+          default: throw ReplayError("unsupported index size");
+        }
+        indices.push_back(uint32_t(index + out.baseVertex));
+      }
+      count = indices.empty() ? 0 : *std::max_element(indices.begin(), indices.end()) + 1;
+    }
+
+    // RenderDoc stores outputs tightly packed per vertex, starting with the
+    // position.
+    std::vector<const SigParameter *> outputs;
+    for(const SigParameter &o : refl->outputSignature)
+      outputs.push_back(&o);
+    std::stable_sort(outputs.begin(), outputs.end(), [](const SigParameter *a, const SigParameter *b) {
+      return a->systemValue == ShaderBuiltin::Position && b->systemValue != ShaderBuiltin::Position;
+    });
+    bytebuf data = r->GetBufferData(out.vertexResourceId, out.vertexByteOffset, 0);
+
+    w.begin_object();
+    w.key("topology").str(ToStr(out.topology));
+    w.key("indices");
+    if(indexed)
+    {
+      w.begin_array();
+      for(uint32_t index : indices)
+        w.num(index);
+      w.end_array();
+    }
+    else
+    {
+      w.null();
+    }
+
+    w.key("streams").begin_array();
+    size_t offset = 0;
+    for(const SigParameter *o : outputs)
+    {
+      size_t width = o->varType == VarType::Double ? 8 : 4;
+      w.begin_object();
+      w.key("name").str(o->varName.empty() ? o->semanticIdxName : o->varName);
+      w.key("type_name").str(ToStr(o->varType));
+      w.key("component_count").num(uint32_t(o->compCount));
+      w.key("values").begin_array();
+      for(uint32_t v = 0; v < count; v++)
+      {
+        size_t start = size_t(v) * out.vertexByteStride + offset;
+        for(size_t c = 0; c < o->compCount; c++)
+        {
+          size_t at = start + c * width;
+          switch(o->varType)
+          {
+            case VarType::Float: w.num(double(read<float>(data, at))); break;
+            case VarType::Double: w.num(read<double>(data, at)); break;
+            case VarType::SInt: w.num(read<int32_t>(data, at)); break;
+            default: w.num(read<uint32_t>(data, at)); break;
+          }
+        }
+      }
+      w.end_array();
+      w.end_object();
+      offset += width * o->compCount;
+    }
+    w.end_array();
+    w.end_object();
+  });
+}
+
+char *rdrs_pixel_history(rdrs_session *s, uint64_t texture, uint32_t x, uint32_t y, uint32_t mip,
+                         uint32_t slice, uint32_t sample)
+{
+  return reply([&](Writer &w) {
+    rdcarray<PixelModification> history = controller(s)->PixelHistory(
+        id_from(texture), x, y, Subresource(mip, slice, sample), CompType::Typeless);
+
+    w.begin_array();
+    for(const PixelModification &m : history)
+    {
+      w.begin_object();
+      w.key("event_id").num(m.eventId);
+      w.key("primitive_id").num(m.primitiveID);
+      w.key("fragment_index").num(m.fragIndex);
+      w.key("direct_shader_write").boolean(m.directShaderWrite);
+      w.key("unbound_ps").boolean(m.unboundPS);
+      w.key("pre_mod");
+      pixel_value(w, m.preMod);
+      w.key("shader_out");
+      pixel_value(w, m.shaderOut);
+      w.key("post_mod");
+      pixel_value(w, m.postMod);
+      w.key("sample_masked").boolean(m.sampleMasked);
+      w.key("backface_culled").boolean(m.backfaceCulled);
+      w.key("depth_clipped").boolean(m.depthClipped);
+      w.key("view_clipped").boolean(m.viewClipped);
+      w.key("scissor_clipped").boolean(m.scissorClipped);
+      w.key("shader_discarded").boolean(m.shaderDiscarded);
+      w.key("depth_test_failed").boolean(m.depthTestFailed);
+      w.key("stencil_test_failed").boolean(m.stencilTestFailed);
+      w.end_object();
+    }
+    w.end_array();
+  });
+}
+
+char *rdrs_debug_vertex(rdrs_session *s, uint32_t event_id, uint32_t vertex, uint32_t instance,
+                        uint32_t index)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    r->SetFrameEvent(event_id, true);
+    debug_trace(w, r, r->DebugVertex(vertex, instance, index, 0), "Vertex");
+  });
+}
+
+char *rdrs_debug_pixel(rdrs_session *s, uint32_t event_id, uint32_t x, uint32_t y)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    r->SetFrameEvent(event_id, true);
+    debug_trace(w, r, r->DebugPixel(x, y, DebugPixelInputs()), "Pixel");
+  });
+}
+
+char *rdrs_debug_thread(rdrs_session *s, uint32_t event_id, const uint32_t *group,
+                        const uint32_t *thread)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    r->SetFrameEvent(event_id, true);
+    rdcfixedarray<uint32_t, 3> groupid, threadid;
+    for(int i = 0; i < 3; i++)
+    {
+      groupid[i] = group[i];
+      threadid[i] = thread[i];
+    }
+    debug_trace(w, r, r->DebugThread(groupid, threadid), "Compute");
+  });
+}
+
+char *rdrs_counters(rdrs_session *s)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    w.begin_array();
+    for(GPUCounter c : r->EnumerateCounters())
+      counter_description(w, r->DescribeCounter(c));
+    w.end_array();
+  });
+}
+
+char *rdrs_fetch_counters(rdrs_session *s, const uint32_t *ids, size_t count)
+{
+  return reply([&](Writer &w) {
+    IReplayController *r = controller(s);
+    rdcarray<GPUCounter> counters;
+    std::map<uint32_t, CounterDescription> descs;
+    for(size_t i = 0; i < count; i++)
+    {
+      counters.push_back(GPUCounter(ids[i]));
+      descs[ids[i]] = r->DescribeCounter(GPUCounter(ids[i]));
+    }
+
+    w.begin_array();
+    for(const CounterResult &result : r->FetchCounters(counters))
+    {
+      w.begin_object();
+      w.key("event_id").num(result.eventId);
+      w.key("counter").num(uint32_t(result.counter));
+      w.key("value").num(counter_value(result.value, descs[uint32_t(result.counter)]));
+      w.end_object();
+    }
+    w.end_array();
+  });
+}
+
+}    // extern "C"
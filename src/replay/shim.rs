@@ -0,0 +1,322 @@
+//! Bindings to `shim.cpp`, a C interface over RenderDoc's C++ replay API.
+//!
+//! Every call on a session is answered with a JSON object, either
+//! `{"ok": <value>}` or `{"error": "<message>"}`, decoded into the types of
+//! the `replay` module.
+
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::os::raw::c_char;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json;
+
+use super::{CaptureFormat, Counter, Error, ImageFormat, MeshStage, ResourceId, ShaderStage, Subresource, ThumbnailFormat};
+
+#[allow(non_camel_case_types)]
+enum rdrs_session {}
+
+extern "C" {
+    fn rdrs_create() -> *mut rdrs_session;
+    fn rdrs_destroy(s: *mut rdrs_session);
+    fn rdrs_free(reply: *mut c_char);
+    fn rdrs_version() -> *const c_char;
+    fn rdrs_open_file(s: *mut rdrs_session, path: *const c_char, len: usize) -> *mut c_char;
+    fn rdrs_open_replay(s: *mut rdrs_session) -> *mut c_char;
+    fn rdrs_open_remote_replay(s: *mut rdrs_session, host: *const c_char) -> *mut c_char;
+    fn rdrs_thumbnail(s: *mut rdrs_session, format: *const c_char) -> *mut c_char;
+    fn rdrs_convert(s: *mut rdrs_session, path: *const c_char, len: usize, format: *const c_char) -> *mut c_char;
+    fn rdrs_actions(s: *mut rdrs_session) -> *mut c_char;
+    fn rdrs_resources(s: *mut rdrs_session) -> *mut c_char;
+    fn rdrs_resource_usage(s: *mut rdrs_session, resource: u64) -> *mut c_char;
+    fn rdrs_pipeline_state(s: *mut rdrs_session, event_id: u32) -> *mut c_char;
+    fn rdrs_shader_reflection(s: *mut rdrs_session, event_id: u32, stage: *const c_char) -> *mut c_char;
+    fn rdrs_save_texture(
+        s: *mut rdrs_session,
+        texture: u64,
+        event_id: u32,
+        path: *const c_char,
+        len: usize,
+        format: *const c_char,
+    ) -> *mut c_char;
+    fn rdrs_buffer_data(s: *mut rdrs_session, buffer: u64, event_id: u32, offset: u64, len: u64) -> *mut c_char;
+    fn rdrs_post_transform_mesh(s: *mut rdrs_session, event_id: u32, instance: u32, stage: *const c_char)
+        -> *mut c_char;
+    fn rdrs_pixel_history(
+        s: *mut rdrs_session,
+        texture: u64,
+        x: u32,
+        y: u32,
+        mip: u32,
+        slice: u32,
+        sample: u32,
+    ) -> *mut c_char;
+    fn rdrs_debug_vertex(s: *mut rdrs_session, event_id: u32, vertex: u32, instance: u32, index: u32) -> *mut c_char;
+    fn rdrs_debug_pixel(s: *mut rdrs_session, event_id: u32, x: u32, y: u32) -> *mut c_char;
+    fn rdrs_debug_thread(s: *mut rdrs_session, event_id: u32, group: *const u32, thread: *const u32) -> *mut c_char;
+    fn rdrs_counters(s: *mut rdrs_session) -> *mut c_char;
+    fn rdrs_fetch_counters(s: *mut rdrs_session, counters: *const u32, count: usize) -> *mut c_char;
+}
+
+/// Requests understood by the shim.
+#[derive(Debug)]
+pub enum Request<'a> {
+    OpenFile { path: &'a Path },
+    OpenReplay,
+    OpenRemoteReplay { host: &'a str },
+    Thumbnail { format: ThumbnailFormat },
+    Convert { path: &'a Path, format: CaptureFormat },
+    Actions,
+    Resources,
+    ResourceUsage { resource: ResourceId },
+    PipelineState { event_id: u32 },
+    ShaderReflection { event_id: u32, stage: ShaderStage },
+    SaveTexture {
+        texture: ResourceId,
+        event_id: u32,
+        path: &'a Path,
+        format: ImageFormat,
+    },
+    BufferData {
+        buffer: ResourceId,
+        event_id: u32,
+        offset: u64,
+        len: u64,
+    },
+    PostTransformMesh {
+        event_id: u32,
+        instance: u32,
+        stage: MeshStage,
+    },
+    PixelHistory {
+        texture: ResourceId,
+        x: u32,
+        y: u32,
+        subresource: Subresource,
+    },
+    DebugVertex {
+        event_id: u32,
+        vertex: u32,
+        instance: u32,
+        index: u32,
+    },
+    DebugPixel { event_id: u32, x: u32, y: u32 },
+    DebugThread {
+        event_id: u32,
+        group: [u32; 3],
+        thread: [u32; 3],
+    },
+    Counters,
+    FetchCounters { counters: &'a [Counter] },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Reply<T> {
+    Ok(T),
+    Error(String),
+}
+
+/// A capture file, and the replay of it once opened.
+///
+/// RenderDoc's replay objects aren't thread safe, and OpenGL replays are tied
+/// to the thread which created their context, so sessions are neither `Send`
+/// nor `Sync`.
+pub struct Session {
+    raw: *mut rdrs_session,
+    version: String,
+}
+
+impl Session {
+    /// Creates a session, initialising RenderDoc's replay support first if
+    /// this is the first one in the process.
+    pub fn new() -> Session {
+        unsafe {
+            let raw = rdrs_create();
+            let version = CStr::from_ptr(rdrs_version()).to_string_lossy().into_owned();
+            Session { raw, version }
+        }
+    }
+
+    /// Returns the version of RenderDoc used for replay.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Performs a request and decodes its reply.
+    pub fn call<T: DeserializeOwned>(&mut self, request: &Request) -> Result<T, Error> {
+        let s = self.raw;
+        let reply = unsafe {
+            match *request {
+                Request::OpenFile { path } => {
+                    let path = path_bytes(path)?;
+                    rdrs_open_file(s, path.as_ptr() as *const c_char, path.len())
+                }
+                Request::OpenReplay => rdrs_open_replay(s),
+                Request::OpenRemoteReplay { host } => {
+                    let host = CString::new(host).map_err(|_| Error::InvalidArgument(format!("host {:?}", host)))?;
+                    rdrs_open_remote_replay(s, host.as_ptr())
+                }
+                Request::Thumbnail { format } => rdrs_thumbnail(s, name(&format).as_ptr()),
+                Request::Convert { path, format } => {
+                    let path = path_bytes(path)?;
+                    rdrs_convert(s, path.as_ptr() as *const c_char, path.len(), name(&format).as_ptr())
+                }
+                Request::Actions => rdrs_actions(s),
+                Request::Resources => rdrs_resources(s),
+                Request::ResourceUsage { resource } => rdrs_resource_usage(s, resource.0),
+                Request::PipelineState { event_id } => rdrs_pipeline_state(s, event_id),
+                Request::ShaderReflection { event_id, stage } => {
+                    rdrs_shader_reflection(s, event_id, name(&stage).as_ptr())
+                }
+                Request::SaveTexture {
+                    texture,
+                    event_id,
+                    path,
+                    format,
+                } => {
+                    let path = path_bytes(path)?;
+                    let format = name(&format);
+                    rdrs_save_texture(
+                        s,
+                        texture.0,
+                        event_id,
+                        path.as_ptr() as *const c_char,
+                        path.len(),
+                        format.as_ptr(),
+                    )
+                }
+                Request::BufferData {
+                    buffer,
+                    event_id,
+                    offset,
+                    len,
+                } => rdrs_buffer_data(s, buffer.0, event_id, offset, len),
+                Request::PostTransformMesh {
+                    event_id,
+                    instance,
+                    stage,
+                } => rdrs_post_transform_mesh(s, event_id, instance, name(&stage).as_ptr()),
+                Request::PixelHistory {
+                    texture,
+                    x,
+                    y,
+                    subresource,
+                } => rdrs_pixel_history(
+                    s,
+                    texture.0,
+                    x,
+                    y,
+                    subresource.mip,
+                    subresource.slice,
+                    subresource.sample,
+                ),
+                Request::DebugVertex {
+                    event_id,
+                    vertex,
+                    instance,
+                    index,
+                } => rdrs_debug_vertex(s, event_id, vertex, instance, index),
+                Request::DebugPixel { event_id, x, y } => rdrs_debug_pixel(s, event_id, x, y),
+                Request::DebugThread {
+                    event_id,
+                    ref group,
+                    ref thread,
+                } => rdrs_debug_thread(s, event_id, group.as_ptr(), thread.as_ptr()),
+                Request::Counters => rdrs_counters(s),
+                Request::FetchCounters { counters } => {
+                    let ids: Vec<u32> = counters.iter().map(|counter| counter.0).collect();
+                    rdrs_fetch_counters(s, ids.as_ptr(), ids.len())
+                }
+            }
+        };
+
+        let json = unsafe {
+            let json = CStr::from_ptr(reply).to_string_lossy().into_owned();
+            rdrs_free(reply);
+            json
+        };
+        match serde_json::from_str(&json)? {
+            Reply::Ok(value) => Ok(value),
+            Reply::Error(msg) => Err(Error::Replay(msg)),
+        }
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Session").field("version", &self.version).finish()
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        unsafe { rdrs_destroy(self.raw) }
+    }
+}
+
+/// Returns the name `value` serializes to, which the shim maps to RenderDoc's
+/// enums.
+fn name<T: Serialize>(value: &T) -> CString {
+    let name = match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => unreachable!("enums serialize to their name"),
+    };
+    CString::new(name).expect("enum names contain no NUL bytes")
+}
+
+/// Returns the bytes of `path` as passed to RenderDoc, which keeps paths as
+/// raw bytes on Unix.
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Result<&[u8], Error> {
+    use std::os::unix::ffi::OsStrExt;
+
+    check_nul(path, path.as_os_str().as_bytes())
+}
+
+/// Returns the bytes of `path` as passed to RenderDoc, which expects UTF-8
+/// paths outside of Unix.
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Result<&[u8], Error> {
+    match path.to_str() {
+        Some(utf8) => check_nul(path, utf8.as_bytes()),
+        None => Err(Error::InvalidArgument(format!("path {} is not valid UTF-8", path.display()))),
+    }
+}
+
+fn check_nul<'a>(path: &Path, bytes: &'a [u8]) -> Result<&'a [u8], Error> {
+    if bytes.contains(&0) {
+        Err(Error::InvalidArgument(format!("path {} contains a NUL byte", path.display())))
+    } else {
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enum_names() {
+        assert_eq!(name(&ImageFormat::Dds).to_str(), Ok("DDS"));
+        assert_eq!(name(&ThumbnailFormat::Rgb).to_str(), Ok("Raw"));
+        assert_eq!(name(&MeshStage::GeometryOutput).to_str(), Ok("GSOut"));
+        assert_eq!(name(&ShaderStage::Pixel).to_str(), Ok("Pixel"));
+        assert_eq!(name(&CaptureFormat::ZipXml).to_str(), Ok("zip.xml"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_paths_keep_their_bytes() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"captures/\xff.rdc"));
+        assert_eq!(path_bytes(path).unwrap(), b"captures/\xff.rdc");
+
+        let path = Path::new(OsStr::from_bytes(b"captures/\0.rdc"));
+        assert!(path_bytes(path).is_err());
+    }
+}
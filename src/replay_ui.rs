@@ -0,0 +1,336 @@
+//! Launching the replay UI from the application.
+
+use std::ffi::CString;
+use std::io;
+use std::path::Path;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use std::thread;
+#[cfg(unix)]
+use std::time::Duration;
+
+#[cfg(windows)]
+use kernel32;
+#[cfg(unix)]
+use libc;
+#[cfg(windows)]
+use winapi;
+
+/// Describes how `launch_replay_ui()` starts the replay UI.
+///
+/// By default, the UI connects back to the application through target control
+/// and is given no further arguments.
+///
+/// # Examples
+///
+/// ```rust
+/// # use renderdoc::ReplayUiOptions;
+/// let options = ReplayUiOptions::new()
+///     .connect_target_control(false)
+///     .open_capture("captures/my capture.rdc");
+/// assert_eq!(options.command_line(), "\"captures/my capture.rdc\"");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ReplayUiOptions {
+    connect_target_control: bool,
+    args: Vec<String>,
+}
+
+impl ReplayUiOptions {
+    /// Creates options which only connect to the application.
+    pub fn new() -> Self {
+        ReplayUiOptions {
+            connect_target_control: true,
+            args: Vec::new(),
+        }
+    }
+
+    /// Sets whether the UI connects to this application through target
+    /// control.
+    pub fn connect_target_control(mut self, connect: bool) -> Self {
+        self.connect_target_control = connect;
+        self
+    }
+
+    /// Opens the capture file at `path` in the UI.
+    pub fn open_capture<P: AsRef<Path>>(self, path: P) -> Self {
+        let path = path.as_ref().to_string_lossy().into_owned();
+        self.arg(path)
+    }
+
+    /// Appends an argument to the UI's command line.
+    pub fn arg<S: Into<String>>(mut self, arg: S) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Appends several arguments to the UI's command line.
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns whether the UI connects to this application.
+    pub fn connects_target_control(&self) -> bool {
+        self.connect_target_control
+    }
+
+    /// Returns the command line passed to the UI, with arguments quoted as
+    /// necessary.
+    pub fn command_line(&self) -> String {
+        self.args.iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Returns the command line as a C string, or `None` if an argument
+    /// contains a NUL byte.
+    pub(crate) fn to_c_command_line(&self) -> Option<CString> {
+        CString::new(self.command_line()).ok()
+    }
+}
+
+impl Default for ReplayUiOptions {
+    fn default() -> Self {
+        ReplayUiOptions::new()
+    }
+}
+
+/// Quotes `arg` for a command line, unless it consists only of characters
+/// which need no quoting.
+pub(crate) fn quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.chars().any(|c| c.is_whitespace() || c == '"') {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes preceding a quote need escaping as well.
+                quoted.extend((0..backslashes * 2 + 1).map(|_| '\\'));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.extend((0..backslashes).map(|_| '\\'));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    quoted.extend((0..backslashes * 2).map(|_| '\\'));
+    quoted.push('"');
+    quoted
+}
+
+/// A replay UI process started with `launch_replay_ui()`.
+///
+/// Dropping the handle leaves the process running.
+#[derive(Debug)]
+pub struct ReplayUiProcess {
+    pid: u32,
+    #[cfg(unix)]
+    exited: AtomicBool,
+    #[cfg(windows)]
+    handle: winapi::HANDLE,
+}
+
+impl ReplayUiProcess {
+    /// Returns the PID of the process.
+    pub fn id(&self) -> u32 {
+        self.pid
+    }
+}
+
+#[cfg(unix)]
+impl ReplayUiProcess {
+    pub(crate) fn new(pid: u32) -> Self {
+        ReplayUiProcess {
+            pid,
+            exited: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns whether the process is still running.
+    pub fn is_running(&self) -> bool {
+        if self.exited.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        // RenderDoc spawns the UI as a child of this process, which has to be
+        // reaped to not linger as a zombie.
+        let mut status = 0;
+        let ret = unsafe { libc::waitpid(self.pid as libc::pid_t, &mut status, libc::WNOHANG) };
+        let running = match ret {
+            0 => true,
+            -1 => self.is_alive(),
+            _ => false,
+        };
+        if !running {
+            self.exited.store(true, Ordering::SeqCst);
+        }
+        running
+    }
+
+    /// Waits for the process to exit.
+    pub fn wait(&self) -> io::Result<()> {
+        if self.exited.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let mut status = 0;
+        let ret = unsafe { libc::waitpid(self.pid as libc::pid_t, &mut status, 0) };
+        if ret == -1 {
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() != Some(libc::ECHILD) {
+                return Err(err);
+            }
+
+            // Not our child, so it can only be polled.
+            while self.is_alive() {
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+
+        self.exited.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Forcibly terminates the process.
+    pub fn kill(&self) -> io::Result<()> {
+        if !self.is_running() {
+            return Ok(());
+        }
+
+        if unsafe { libc::kill(self.pid as libc::pid_t, libc::SIGKILL) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        self.wait()
+    }
+
+    fn is_alive(&self) -> bool {
+        let ret = unsafe { libc::kill(self.pid as libc::pid_t, 0) };
+        ret == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+}
+
+#[cfg(windows)]
+impl ReplayUiProcess {
+    pub(crate) fn new(pid: u32) -> Self {
+        let access = winapi::SYNCHRONIZE | winapi::PROCESS_TERMINATE;
+        let handle = unsafe { kernel32::OpenProcess(access, winapi::FALSE, pid) };
+        ReplayUiProcess { pid, handle }
+    }
+
+    /// Returns whether the process is still running.
+    pub fn is_running(&self) -> bool {
+        !self.handle.is_null()
+            && unsafe { kernel32::WaitForSingleObject(self.handle, 0) } == winapi::WAIT_TIMEOUT
+    }
+
+    /// Waits for the process to exit.
+    pub fn wait(&self) -> io::Result<()> {
+        if self.handle.is_null() {
+            return Ok(());
+        }
+
+        match unsafe { kernel32::WaitForSingleObject(self.handle, winapi::INFINITE) } {
+            winapi::WAIT_OBJECT_0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    /// Forcibly terminates the process.
+    pub fn kill(&self) -> io::Result<()> {
+        if !self.is_running() {
+            return Ok(());
+        }
+
+        if unsafe { kernel32::TerminateProcess(self.handle, 1) } == winapi::FALSE {
+            return Err(io::Error::last_os_error());
+        }
+        self.wait()
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ReplayUiProcess {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            unsafe {
+                kernel32::CloseHandle(self.handle);
+            }
+        }
+    }
+}
+
+// The process handle is only used for waiting and termination, both of which
+// are safe to do from any thread.
+#[cfg(windows)]
+unsafe impl Send for ReplayUiProcess {}
+#[cfg(windows)]
+unsafe impl Sync for ReplayUiProcess {}
+
+#[cfg(not(any(unix, windows)))]
+impl ReplayUiProcess {
+    #[allow(dead_code)]
+    pub(crate) fn new(pid: u32) -> Self {
+        ReplayUiProcess { pid }
+    }
+
+    /// Returns whether the process is still running.
+    pub fn is_running(&self) -> bool {
+        false
+    }
+
+    /// Waits for the process to exit.
+    pub fn wait(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Forcibly terminates the process.
+    pub fn kill(&self) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other, "unsupported platform"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_arguments() {
+        assert_eq!(quote("plain"), "plain");
+        assert_eq!(quote(r"C:\captures\a.rdc"), r"C:\captures\a.rdc");
+        assert_eq!(quote(""), r#""""#);
+        assert_eq!(quote("with space"), r#""with space""#);
+        assert_eq!(quote(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(quote(r"dir\ x\"), r#""dir\ x\\""#);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn track_child_process() {
+        use std::process;
+
+        // The handle reaps the child, as it does for the replay UI spawned by
+        // RenderDoc.
+        let pid = process::Command::new("sleep").arg("10").spawn().unwrap().id();
+        let ui = ReplayUiProcess::new(pid);
+        assert!(ui.is_running());
+        ui.kill().unwrap();
+        assert!(!ui.is_running());
+    }
+
+    #[test]
+    fn reject_nul_bytes() {
+        let options = ReplayUiOptions::new().arg("a\0b");
+        assert!(options.to_c_command_line().is_none());
+    }
+}
@@ -0,0 +1,62 @@
+//! Applying settings gathered from builders, files, flags or the environment.
+
+use std::path::Path;
+
+use api::RenderDocV100;
+use {CaptureOption, Error, InputButton, OverlayBits};
+
+/// Value of a capture option, set with the matching setter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum OptionValue {
+    F32(f32),
+    U32(u32),
+}
+
+/// Settings borrowed from a `Builder`, `Config`, `InitConfig`,
+/// `RenderDocArgs` or the environment overrides.
+///
+/// Fields left at their defaults leave RenderDoc's settings untouched.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Settings<'a> {
+    pub(crate) options: Vec<(CaptureOption, OptionValue)>,
+    pub(crate) capture_keys: Option<&'a [InputButton]>,
+    pub(crate) focus_toggle_keys: Option<&'a [InputButton]>,
+    pub(crate) overlay_bits: Option<OverlayBits>,
+    pub(crate) capture_path_template: Option<&'a Path>,
+    pub(crate) unload_crash_handler: bool,
+}
+
+impl<'a> Settings<'a> {
+    /// Adds integer capture options, set after the ones already present.
+    pub(crate) fn extend_u32_options(&mut self, options: &[(CaptureOption, u32)]) {
+        self.options.extend(options.iter().map(|&(opt, val)| (opt, OptionValue::U32(val))));
+    }
+
+    /// Applies the settings to `rd` in a fixed order: capture options,
+    /// hotkeys, overlay, capture path template, and finally unloading the
+    /// crash handler.
+    pub(crate) fn apply<R: RenderDocV100>(&self, rd: &R) -> Result<(), Error> {
+        for &(opt, val) in &self.options {
+            match val {
+                OptionValue::F32(val) => rd.set_capture_option_f32(opt, val)?,
+                OptionValue::U32(val) => rd.set_capture_option_u32(opt, val)?,
+            }
+        }
+        if let Some(keys) = self.capture_keys {
+            rd.set_capture_keys(keys)?;
+        }
+        if let Some(keys) = self.focus_toggle_keys {
+            rd.set_focus_toggle_keys(keys)?;
+        }
+        if let Some(bits) = self.overlay_bits {
+            rd.mask_overlay_bits(OverlayBits::NONE, bits)?;
+        }
+        if let Some(path_template) = self.capture_path_template {
+            rd.set_capture_file_path_template(path_template)?;
+        }
+        if self.unload_crash_handler {
+            rd.unload_crash_handler()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,410 @@
+//! Raw FFI of the RenderDoc in-application API.
+//!
+//! This layer holds the function tables, enums and constants of
+//! `renderdoc_app.h` without any of the safe wrapper's policy: it doesn't load
+//! the library, keeps no global state, and only depends on `bitflags` and, on
+//! Windows, `winapi`. Integrations which manage the API themselves, e.g.
+//! engines calling `RENDERDOC_GetAPI` on their own, can build on it directly.
+//! The types are also re-exported at the crate root.
+//!
+//! The function tables are written by hand, so their function pointers can use
+//! this crate's enums and bit flags. They are checked at compile time against
+//! `raw`, the `bindgen` output of the official header, which is regenerated
+//! with `generate_bindings.sh`. A field in the wrong place, or an enum value
+//! drifting from the header, fails the build.
+
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+
+pub use self::types::{CaptureOption, DevicePointer, InputButton, OverlayBits, WindowHandle};
+#[cfg(windows)]
+pub use self::types::SHADER_MAGIC_DEBUG_VALUE_STRUCT;
+pub use self::types::{SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY, SHADER_MAGIC_DEBUG_VALUE_TRUNCATED};
+
+#[allow(dead_code, missing_docs, non_camel_case_types, non_snake_case, non_upper_case_globals)]
+#[allow(clippy::all)]
+pub mod raw;
+
+mod types;
+
+/// Retrieves the function table of the requested API `version`, one of the
+/// `raw::eRENDERDOC_API_Version_*` values, and stores a pointer to it in
+/// `out`.
+///
+/// This is the signature of `RENDERDOC_GetAPI`, the only function exported by
+/// the RenderDoc library. Returns `1` if the version is supported, otherwise
+/// returns `0` and leaves `out` unchanged.
+pub type GetApiFn = unsafe extern "C" fn(version: u32, out: *mut *mut c_void) -> c_int;
+
+/// Provides the major, minor, and patch version numbers of the RenderDoc API
+/// given to the application.
+///
+/// Note that RenderDoc will usually provide a higher API version than the one
+/// requested by the user if it's backwards compatible. If a parameter is
+/// `std::ptr::null_mut()`, it will be ignored while the others will be filled
+/// out.
+pub type GetApiVersionFn = unsafe extern "C" fn(major: *mut c_int,
+                                                minor: *mut c_int,
+                                                patch: *mut c_int);
+
+/// Sets the specified `CaptureOption` to the given `u32` value.
+///
+/// Returns `1` if the option and the value are valid. Otherwise, returns `0`
+/// and leaves the option unchanged.
+pub type SetCaptureOptionU32Fn = unsafe extern "C" fn(opt: CaptureOption, val: u32) -> c_int;
+
+/// Sets the specified `CaptureOption` to the given `f32` value.
+///
+/// Returns `1` if the option and the value are valid. Otherwise, returns `0`
+/// and leaves the option unchanged.
+pub type SetCaptureOptionF32Fn = unsafe extern "C" fn(opt: CaptureOption, val: f32) -> c_int;
+
+/// Returns the current value of the given `CaptureOption` as a `u32` value.
+///
+/// If the option is invalid, then `std::u32::MAX` is returned instead.
+pub type GetCaptureOptionU32Fn = unsafe extern "C" fn(opt: CaptureOption) -> u32;
+
+/// Returns the current value of the given `CaptureOption` as a `u32` value.
+///
+/// If the option is invalid, then `std::f32::MAX * -1f32` is returned instead.
+pub type GetCaptureOptionF32Fn = unsafe extern "C" fn(opt: CaptureOption) -> f32;
+
+/// Sets which key(s) can be used to toggle focus between multiple windows.
+///
+/// If `keys` is `std::ptr::null()`, then window toggling will be disabled.
+pub type SetFocusToggleKeysFn = unsafe extern "C" fn(keys: *const InputButton, num: c_int);
+
+/// Sets which key(s) can be used to capture the next frame.
+///
+/// If `keys` is `std::ptr::null()`, then frame capture functionality will
+/// be disabled.
+pub type SetCaptureKeysFn = unsafe extern "C" fn(keys: *const InputButton, num: c_int);
+
+/// Returns the `OverlayBits` that have been set.
+pub type GetOverlayBitsFn = unsafe extern "C" fn() -> OverlayBits;
+
+/// Sets the given `OverlayBits` with an AND and OR mask.
+pub type MaskOverlayBitsFn = unsafe extern "C" fn(and: OverlayBits, or: OverlayBits);
+
+/// Attempts to shut down RenderDoc.
+///
+/// Note that this will work correctly if done _immediately_ after the dynamic
+/// library is loaded, before any API work happens. At that point, RenderDoc
+/// will remove its injected hooks and shut down. Behavior is undefined if this
+/// is called after any API functions have been called.
+pub type ShutdownFn = unsafe extern "C" fn();
+
+/// Unloads the RenderDoc crash handler from your application.
+///
+/// If you use your own crash handler and don't want RenderDoc's handler to
+/// intercede, you may call this function to unload it and any unhandled
+/// exceptions will pass to the next handler instead.
+pub type UnloadCrashHandlerFn = unsafe extern "C" fn();
+
+/// Sets the naming prefix to be used when saving frame capture files.
+///
+/// `path_template` is a UTF-8 string that gives a template for how captures can
+/// be named and where they will be saved. Any extension is stripped off the
+/// path and the captures are saved in the directory specified with the file
+/// name and frame number appended. If the requested directory or directory
+/// structure does not exist, it will be created recursively for you.
+///
+/// If `path_template` is `std::ptr::null()`, then the template will be left
+/// unchanged.
+///
+/// # Example
+///
+/// ```c
+/// SetLogPathTemplateFn("my_captures/example");
+///
+/// // This function call will result in the following captures:
+/// //
+/// // Capture #1 -> my_captures/example_frame123.rdc
+/// // Capture #2 -> my_captures/example_frame456.rdc.
+/// ```
+pub type SetLogFilePathTemplateFn = unsafe extern "C" fn(path_template: *const c_char);
+
+/// Returns the current frame capture file template as a raw UTF-8 string.
+///
+/// See the `SetLogFilePathTemplateFn` description for details.
+pub type GetLogFilePathTemplateFn = unsafe extern "C" fn() -> *const c_char;
+
+/// Returns the number of frame captures that have been made so far.
+pub type GetNumCapturesFn = unsafe extern "C" fn() -> u32;
+
+/// Retrieves the details of a frame capture with the given index `idx`.
+///
+/// If `idx` is a valid frame capture number, then `log_file` will be filled
+/// with the absolute UTF-8 formatted path to the capture file; `path_len` will
+/// be the length in bytes of the `log_file` string; and `timestamp` will be the
+/// time of capture, measured in seconds passed since the UNIX epoch.
+///
+/// If a parameter is set to `std::ptr::null_mut()`, it will be skipped and the
+/// rest will be filled out.
+///
+/// Returns `1` if the capture index is valid. Otherwise, returns `0` and leaves
+/// the values of `log_file`, `path_len`, and `timestamp` all unchanged.
+pub type GetCaptureFn = unsafe extern "C" fn(idx: u32,
+                                             log_file: *mut c_char,
+                                             path_len: *mut u32,
+                                             timestamp: *mut u64)
+                                             -> u32;
+
+/// Captures the next frame from the currently active window and API device.
+///
+/// Data is saved to a capture log file at the location specified via the
+/// `SetLogFilePathTemplateFn` function call.
+///
+/// If no supported APIs have been initialized, this function will do nothing.
+pub type TriggerCaptureFn = unsafe extern "C" fn();
+
+/// Returns whether the external RenderDoc UI is connected to this application.
+///
+/// # Compatibility
+///
+/// Before version 1.1.1, this function was named `IsRemoteAccessConnected`.
+/// The rename kept both the signature and the slot in the function table, so
+/// `EntryV100::is_target_control_connected` is also the 1.0.x function, see
+/// `IsRemoteAccessConnectedFn`.
+pub type IsTargetControlConnectedFn = unsafe extern "C" fn() -> u32;
+
+/// Returns whether the external RenderDoc UI is connected to this application.
+///
+/// This is the name of `IsTargetControlConnectedFn` in versions 1.0.0 to
+/// 1.1.0, which occupies the same slot of the function table.
+pub type IsRemoteAccessConnectedFn = IsTargetControlConnectedFn;
+
+/// Launches the replay UI from within the injected application.
+///
+/// If `connect_target_control` is `1`, the replay UI will launch with a
+/// command line parameter specified by `cmd_line`, a UTF-8 string. If
+/// `cmd_line` is `std::ptr::null()`, then the command line will be empty.
+///
+/// Returns the PID of the replay UI if successful, otherwise returns `0`.
+pub type LaunchReplayUiFn = unsafe extern "C" fn(connect_target_control: u32,
+                                                 cmd_line: *const c_char)
+                                                 -> u32;
+
+/// Activates the RenderDoc in-app overlay inside the given window handle
+/// `wnd_handle` and API device pointer `device`.
+///
+/// Neither parameter can be `std::ptr::null_mut()`.
+pub type SetActiveWindowFn = unsafe extern "C" fn(device: DevicePointer, wnd_handle: WindowHandle);
+
+/// Immediately starts capturing API calls from the specified device pointer
+/// and window handle.
+///
+/// If `device` is `std::ptr::null_mut()`, then all API calls outputting to
+/// `wnd_handle` will be captured, regardless of API device(s). This is useful
+/// if the API device being used isn't necessarily known at runtime.
+///
+/// If `wnd_handle` is `std::ptr::null_mut()`, then all API calls to `device`
+/// will be captured, regardless of its output window(s). This is useful for
+/// headless rendering.
+///
+/// If both `device` and `wnd_handle` are set to `std::ptr::null_mut()`, then
+/// _all_ API calls in this application will be captured, regardless of output
+/// window(s) and/or API device(s).
+///
+/// If no supported APIs have been initialized, this function will do nothing.
+///
+/// If two or more started captures overlap each other, then this will result
+/// in undefined behavior (including crashes).
+pub type StartFrameCaptureFn = unsafe extern "C" fn(device: DevicePointer,
+                                                    wnd_handle: WindowHandle);
+
+/// Returns whether or not a frame capture is currently ongoing anywhere.
+pub type IsFrameCapturingFn = unsafe extern "C" fn() -> u32;
+
+/// Ends the ongoing capture on the given device pointer and window handle.
+///
+/// Data is saved to a capture log file at the location specified via the
+/// `SetLogFilePathTemplateFn` function call. Returns `1` if the capture
+/// succeeded, otherwise returns `0`.
+pub type EndFrameCaptureFn = unsafe extern "C" fn(device: DevicePointer, wnd_handle: WindowHandle)
+                                                  -> u32;
+
+/// Captures the next _n_ frames from the currently active window and API device.
+///
+/// Data is saved to a capture log file at the location specified via the
+/// `SetLogFilePathTemplateFn` function call.
+///
+/// If no supported APIs have been initialized, this function will do nothing.
+pub type TriggerMultiFrameCaptureFn = unsafe extern "C" fn(num_frames: u32);
+
+/// Entry point for RenderDoc API version 1.0.
+#[allow(missing_docs)]
+#[repr(C)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EntryV100 {
+    pub get_api_version: GetApiVersionFn,
+    pub set_capture_option_u32: SetCaptureOptionU32Fn,
+    pub set_capture_option_f32: SetCaptureOptionF32Fn,
+    pub get_capture_option_u32: GetCaptureOptionU32Fn,
+    pub get_capture_option_f32: GetCaptureOptionF32Fn,
+    pub set_focus_toggle_keys: SetFocusToggleKeysFn,
+    pub set_capture_keys: SetCaptureKeysFn,
+    pub get_overlay_bits: GetOverlayBitsFn,
+    pub mask_overlay_bits: MaskOverlayBitsFn,
+    pub shutdown: ShutdownFn,
+    pub unload_crash_handler: UnloadCrashHandlerFn,
+    pub set_log_file_path_template: SetLogFilePathTemplateFn,
+    pub get_log_file_path_template: GetLogFilePathTemplateFn,
+    pub get_num_captures: GetNumCapturesFn,
+    pub get_capture: GetCaptureFn,
+    pub trigger_capture: TriggerCaptureFn,
+    pub is_target_control_connected: IsTargetControlConnectedFn,
+    pub launch_replay_ui: LaunchReplayUiFn,
+    pub set_active_window: SetActiveWindowFn,
+    pub start_frame_capture: StartFrameCaptureFn,
+    pub is_frame_capturing: IsFrameCapturingFn,
+    pub end_frame_capture: EndFrameCaptureFn,
+}
+
+impl AsRef<EntryV100> for EntryV100 {
+    fn as_ref(&self) -> &EntryV100 {
+        self
+    }
+}
+
+entry_point! {
+    /// Entry point for RenderDoc API version 1.1.
+    pub struct EntryV110: entry_v100: EntryV100 [EntryV100] {
+        pub trigger_multi_frame_capture: TriggerMultiFrameCaptureFn,
+    }
+}
+
+/// Asserts that each field of `$entry` is at the offset of the given field of
+/// the header's function table.
+macro_rules! assert_entry_layout {
+    ($entry:ident { $($field:ident => $($header:ident).+,)+ }) => {
+        $(
+            const _: () = assert!(
+                mem::offset_of!($entry, $field) == mem::offset_of!(raw::RENDERDOC_API_1_6_0, $($header).+),
+                concat!("`", stringify!($entry), "::", stringify!($field), "` doesn't match renderdoc_app.h"),
+            );
+        )+
+    };
+}
+
+assert_entry_layout!(EntryV100 {
+    get_api_version => GetAPIVersion,
+    set_capture_option_u32 => SetCaptureOptionU32,
+    set_capture_option_f32 => SetCaptureOptionF32,
+    get_capture_option_u32 => GetCaptureOptionU32,
+    get_capture_option_f32 => GetCaptureOptionF32,
+    set_focus_toggle_keys => SetFocusToggleKeys,
+    set_capture_keys => SetCaptureKeys,
+    get_overlay_bits => GetOverlayBits,
+    mask_overlay_bits => MaskOverlayBits,
+    shutdown => __bindgen_anon_1.Shutdown,
+    unload_crash_handler => UnloadCrashHandler,
+    set_log_file_path_template => __bindgen_anon_2.SetLogFilePathTemplate,
+    get_log_file_path_template => __bindgen_anon_3.GetLogFilePathTemplate,
+    get_num_captures => GetNumCaptures,
+    get_capture => GetCapture,
+    trigger_capture => TriggerCapture,
+    is_target_control_connected => __bindgen_anon_4.IsTargetControlConnected,
+    is_target_control_connected => __bindgen_anon_4.IsRemoteAccessConnected,
+    launch_replay_ui => LaunchReplayUI,
+    set_active_window => SetActiveWindow,
+    start_frame_capture => StartFrameCapture,
+    is_frame_capturing => IsFrameCapturing,
+    end_frame_capture => EndFrameCapture,
+});
+
+assert_entry_layout!(EntryV110 {
+    trigger_multi_frame_capture => TriggerMultiFrameCapture,
+});
+
+// Each table ends where the header's next version starts.
+const _: () = assert!(
+    mem::size_of::<EntryV100>() == mem::offset_of!(raw::RENDERDOC_API_1_6_0, TriggerMultiFrameCapture)
+);
+const _: () = assert!(
+    mem::size_of::<EntryV110>() == mem::offset_of!(raw::RENDERDOC_API_1_6_0, SetCaptureFileComments)
+);
+const _: () = assert!(mem::align_of::<EntryV110>() == mem::align_of::<raw::RENDERDOC_API_1_6_0>());
+
+/// Asserts that each enum value equals the given constant of the header.
+macro_rules! assert_enum_values {
+    ($($value:expr => $header:ident,)+) => {
+        $(
+            const _: () = assert!(
+                $value as u32 == raw::$header,
+                concat!("`", stringify!($value), "` doesn't match renderdoc_app.h"),
+            );
+        )+
+    };
+}
+
+assert_enum_values! {
+    CaptureOption::AllowVSync => eRENDERDOC_Option_AllowVSync,
+    CaptureOption::AllowFullscreen => eRENDERDOC_Option_AllowFullscreen,
+    CaptureOption::ApiValidation => eRENDERDOC_Option_APIValidation,
+    CaptureOption::CaptureCallstacks => eRENDERDOC_Option_CaptureCallstacks,
+    CaptureOption::CaptureCallstacksOnlyDraws => eRENDERDOC_Option_CaptureCallstacksOnlyDraws,
+    CaptureOption::DelayForDebugger => eRENDERDOC_Option_DelayForDebugger,
+    CaptureOption::VerifyMapWrites => eRENDERDOC_Option_VerifyMapWrites,
+    CaptureOption::HookIntoChildren => eRENDERDOC_Option_HookIntoChildren,
+    CaptureOption::RefAllResources => eRENDERDOC_Option_RefAllResources,
+    CaptureOption::SaveAllInitials => eRENDERDOC_Option_SaveAllInitials,
+    CaptureOption::CaptureAllCmdLists => eRENDERDOC_Option_CaptureAllCmdLists,
+    CaptureOption::DebugOutputMute => eRENDERDOC_Option_DebugOutputMute,
+    InputButton::Key0 => eRENDERDOC_Key_0,
+    InputButton::Key9 => eRENDERDOC_Key_9,
+    InputButton::A => eRENDERDOC_Key_A,
+    InputButton::Z => eRENDERDOC_Key_Z,
+    InputButton::NonPrintable => eRENDERDOC_Key_NonPrintable,
+    InputButton::Divide => eRENDERDOC_Key_Divide,
+    InputButton::Plus => eRENDERDOC_Key_Plus,
+    InputButton::F1 => eRENDERDOC_Key_F1,
+    InputButton::F12 => eRENDERDOC_Key_F12,
+    InputButton::Home => eRENDERDOC_Key_Home,
+    InputButton::PageDn => eRENDERDOC_Key_PageDn,
+    InputButton::Pause => eRENDERDOC_Key_Pause,
+    InputButton::Max => eRENDERDOC_Key_Max,
+    OverlayBits::ENABLED.bits() => eRENDERDOC_Overlay_Enabled,
+    OverlayBits::FRAME_RATE.bits() => eRENDERDOC_Overlay_FrameRate,
+    OverlayBits::FRAME_NUMBER.bits() => eRENDERDOC_Overlay_FrameNumber,
+    OverlayBits::CAPTURE_LIST.bits() => eRENDERDOC_Overlay_CaptureList,
+    OverlayBits::DEFAULT.bits() => eRENDERDOC_Overlay_Default,
+    OverlayBits::ALL.bits() => eRENDERDOC_Overlay_All,
+    OverlayBits::NONE.bits() => eRENDERDOC_Overlay_None,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem;
+
+    #[test]
+    fn entry_v1_0_0_layout() {
+        assert_eq!(
+            mem::size_of::<EntryV100>(),
+            176usize,
+            concat!("Size of: ", stringify!(EntryV100))
+        );
+
+        assert_eq!(
+            mem::align_of::<EntryV100>(),
+            8usize,
+            concat!("Alignment of ", stringify!(EntryV100))
+        );
+    }
+
+    #[test]
+    fn entry_v1_1_0_layout() {
+        assert_eq!(
+            mem::size_of::<EntryV110>(),
+            184usize,
+            concat!("Size of: ", stringify!(EntryV110))
+        );
+
+        assert_eq!(
+            mem::align_of::<EntryV110>(),
+            8usize,
+            concat!("Alignment of ", stringify!(EntryV110))
+        );
+    }
+}
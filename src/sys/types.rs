@@ -0,0 +1,446 @@
+//! Enums, constants and handle types of the in-application API.
+
+use std::fmt;
+use std::ops;
+#[cfg(all(unix, not(any(target_os = "android", target_os = "macos", target_os = "ios"))))]
+use std::os::raw::c_ulong;
+use std::os::raw::{c_ulonglong, c_void};
+use std::ptr::{self, NonNull};
+use std::u32;
+
+#[cfg(windows)]
+use winapi;
+#[cfg(windows)]
+use winapi::guiddef::GUID;
+
+/// Magic value used for when applications pass a path where shader debug
+/// information can be found to match up with a stripped shader.
+///
+/// Windows GUID representation intended for consumption by D3D.
+#[cfg(windows)]
+pub const SHADER_MAGIC_DEBUG_VALUE_STRUCT: GUID = GUID {
+    Data1: 0xeab25520,
+    Data2: 0x6670,
+    Data3: 0x4865,
+    Data4: [0x84, 0x29, 0x6c, 0x8, 0x51, 0x54, 0x00, 0xff],
+};
+
+/// Magic value used for when applications pass a path where shader debug
+/// information can be found to match up with a stripped shader.
+///
+/// Raw byte array representation (assuming x86 endianness).
+pub const SHADER_MAGIC_DEBUG_VALUE_BYTE_ARRAY: &[u8] = &[
+    0x20,
+    0x55,
+    0xb2,
+    0xea,
+    0x70,
+    0x66,
+    0x65,
+    0x48,
+    0x84,
+    0x29,
+    0x6c,
+    0x8,
+    0x51,
+    0x54,
+    0x00,
+    0xff,
+];
+
+/// Magic value used for when applications pass a path where shader debug
+/// information can be found to match up with a stripped shader.
+///
+/// Truncated version when only a `uint64_t` is available (e.g. Vulkan tags).
+pub const SHADER_MAGIC_DEBUG_VALUE_TRUNCATED: c_ulonglong = 0x4856670eab25520;
+
+/// RenderDoc capture options.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CaptureOption {
+    /// Let the application enable vertical synchronization.
+    AllowVSync = 0,
+    /// Let the application enter fullscreen mode.
+    AllowFullscreen = 1,
+    /// Record API debugging events and messages.
+    ///
+    /// This option also goes by the deprecated name of `DebugDeviceMode`.
+    ApiValidation = 2,
+    /// Capture CPU callstacks for API events.
+    CaptureCallstacks = 3,
+    /// When capturing CPU callstacks, only capture them from drawcalls.
+    ///
+    /// This option does nothing without the above option being enabled.
+    CaptureCallstacksOnlyDraws = 4,
+    /// Specify a delay, measured in seconds, to wait for a debugger to attach
+    /// to the application after being injected.
+    DelayForDebugger = 5,
+    /// Verify any writes to mapped buffers by checking the memory after the
+    /// bounds of the returned pointer to detect any modification.
+    VerifyMapWrites = 6,
+    /// Hooks any system API calls that create child processes and injects
+    /// RenderDoc into them recursively with the same options.
+    HookIntoChildren = 7,
+    /// Reference all resources available at the time of capture.
+    ///
+    /// By default, RenderDoc only includes resources in the final capture file
+    /// necessary for that frame. This option allows you to override that
+    /// behavior.
+    RefAllResources = 8,
+    /// Save the initial state for all resources, regardless of usage.
+    ///
+    /// By default, RenderDoc skips saving initial states for resources where
+    /// the previous contents don't appear to be used (assuming that writes
+    /// before reads indicate the previous contents aren't used).
+    SaveAllInitials = 9,
+    /// Capture all command lists generated from the start of the application.
+    ///
+    /// In APIs that allow for recording of command lists to be replayed later,
+    /// RenderDoc may choose to not capture command lists before a frame capture
+    /// is triggered to reduce overhead. This means any command lists that are
+    /// recorded one and replayed many times will not be available, potentially
+    /// causing a failure to capture.
+    ///
+    /// Note that this is only true for APIs where multithreading is difficult
+    /// or otherwise discouraged. Newer APIs, e.g. Vulkan and D3D12, will ignore
+    /// this option and always capture all command lists since they are heavily
+    /// oriented around them and the associated overhead is mostly reduced due
+    /// to superior API design.
+    CaptureAllCmdLists = 10,
+    /// Mute API debug output when `CaptureOption::ApiValidation` is enabled.
+    DebugOutputMute = 11,
+}
+
+/// Raw mutable pointer to the API's root handle.
+///
+/// RenderDoc identifies devices by the following pointers:
+///
+/// * Direct3D 11: the `ID3D11Device*`.
+/// * Direct3D 12: the `ID3D12Device*`.
+/// * OpenGL: the context, i.e. the `HGLRC`, `GLXContext` or `EGLContext`.
+/// * Vulkan: the dispatch table pointer of the `VkInstance`, as returned by
+///   `from_vk_instance()`.
+///
+/// A null pointer acts as a wildcard, matching any device.
+///
+/// The pointer is `#[repr(transparent)]`, so it is passed to RenderDoc as is.
+#[repr(transparent)]
+#[derive(Clone, Hash, Eq, PartialEq)]
+pub struct DevicePointer(*const c_void);
+
+impl DevicePointer {
+    /// Wraps a raw device pointer, which must be one of the pointers listed
+    /// above.
+    pub fn from_raw(ptr: *const c_void) -> Self {
+        DevicePointer(ptr)
+    }
+
+    /// Returns the wildcard device pointer, matching any device.
+    pub fn null() -> Self {
+        DevicePointer(ptr::null())
+    }
+
+    /// Returns the device pointer of a Vulkan instance, which is the pointer
+    /// to its dispatch table, as with RenderDoc's
+    /// `RENDERDOC_DEVICEPOINTER_FROM_VKINSTANCE` macro.
+    ///
+    /// # Safety
+    ///
+    /// `instance` must be a valid, dispatchable `VkInstance` handle.
+    pub unsafe fn from_vk_instance(instance: *const c_void) -> Self {
+        DevicePointer(*(instance as *const *const c_void))
+    }
+
+    /// Returns the raw pointer.
+    pub fn as_raw(&self) -> *const c_void {
+        self.0
+    }
+}
+
+impl fmt::Debug for DevicePointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DevicePointer({:p})", self.0)
+    }
+}
+
+impl ops::Deref for DevicePointer {
+    type Target = *const c_void;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<*const c_void> for DevicePointer {
+    fn from(ptr: *const c_void) -> Self {
+        DevicePointer(ptr)
+    }
+}
+
+impl From<*mut c_void> for DevicePointer {
+    fn from(ptr: *mut c_void) -> Self {
+        DevicePointer(ptr)
+    }
+}
+
+impl From<NonNull<c_void>> for DevicePointer {
+    fn from(ptr: NonNull<c_void>) -> Self {
+        DevicePointer(ptr.as_ptr())
+    }
+}
+
+impl<T> From<&mut T> for DevicePointer {
+    fn from(device: &mut T) -> Self {
+        DevicePointer(device as *mut T as *const c_void)
+    }
+}
+
+/// User input key codes.
+#[allow(missing_docs)]
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum InputButton {
+    /// The '0' key over the letters.
+    Key0 = 0x30,
+    /// The '1' key over the letters.
+    Key1 = 0x31,
+    /// The '2' key over the letters.
+    Key2 = 0x32,
+    /// The '3' key over the letters.
+    Key3 = 0x33,
+    /// The '4' key over the letters.
+    Key4 = 0x34,
+    /// The '5' key over the letters.
+    Key5 = 0x35,
+    /// The '6' key over the letters.
+    Key6 = 0x36,
+    /// The '7' key over the letters.
+    Key7 = 0x37,
+    /// The '8' key over the letters.
+    Key8 = 0x38,
+    /// The '9' key over the letters.
+    Key9 = 0x39,
+
+    A = 0x41,
+    B = 0x42,
+    C = 0x43,
+    D = 0x44,
+    E = 0x45,
+    F = 0x46,
+    G = 0x47,
+    H = 0x48,
+    I = 0x49,
+    J = 0x4A,
+    K = 0x4B,
+    L = 0x4C,
+    M = 0x4D,
+    N = 0x4E,
+    O = 0x4F,
+    P = 0x50,
+    Q = 0x51,
+    R = 0x52,
+    S = 0x53,
+    T = 0x54,
+    U = 0x55,
+    V = 0x56,
+    W = 0x57,
+    X = 0x58,
+    Y = 0x59,
+    Z = 0x5A,
+
+    /// Leave the rest of the ASCII range free, in case the RenderDoc developers
+    /// decide to use it later.
+    NonPrintable = 0x100,
+
+    /// Division key on the numpad.
+    Divide,
+    /// Multiplication key on the numpad.
+    Multiply,
+    /// Subtraction key on the numpad.
+    Subtract,
+    /// Addition key on the numpad.
+    Plus,
+
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+
+    Home,
+    End,
+    Insert,
+    Delete,
+    PageUp,
+    PageDn,
+
+    Backspace,
+    Tab,
+    PrtScrn,
+    Pause,
+
+    Max,
+}
+
+bitflags! {
+    /// Bit flags for customizing the RenderDoc overlay.
+    pub struct OverlayBits: u32 {
+        /// Controls whether the overlay is enabled or disabled globally.
+        const ENABLED = 0x1;
+        /// Shows the average, minimum, and maximum sampled frame rate.
+        const FRAME_RATE = 0x2;
+        /// Shows the current frame number.
+        const FRAME_NUMBER = 0x4;
+        /// Shows a list of recent captures, out of the total captures made.
+        const CAPTURE_LIST = 0x8;
+        /// Sets the default configuration for the overlay.
+        const DEFAULT = (0x1 | 0x2 | 0x4 | 0x8);
+        /// Enables all overlay configuration bits.
+        const ALL = u32::MAX;
+        /// Disables all overlay configuration bits.
+        const NONE = u32::MIN;
+    }
+}
+
+/// Raw mutable pointer to the OS-provided window handle.
+///
+/// Use the constructor for the window system in use, or convert raw pointers,
+/// `NonNull<c_void>`, `isize` Win32 `HWND`s, `u64` X11 `Window` IDs and `u32`
+/// XCB windows with `From`. A null handle acts as a wildcard, matching any
+/// window.
+///
+/// The handle is `#[repr(transparent)]`, so it is passed to RenderDoc as is.
+#[repr(transparent)]
+#[derive(Clone, Copy, Hash, Eq, PartialEq)]
+pub struct WindowHandle(*const c_void);
+
+impl WindowHandle {
+    /// Wraps a raw window handle.
+    pub fn from_raw(ptr: *const c_void) -> Self {
+        WindowHandle(ptr)
+    }
+
+    /// Returns the wildcard window handle, matching any window.
+    pub fn null() -> Self {
+        WindowHandle(ptr::null())
+    }
+
+    /// Wraps a Win32 window.
+    #[cfg(windows)]
+    pub fn from_hwnd(hwnd: winapi::windef::HWND) -> Self {
+        WindowHandle(hwnd as *const c_void)
+    }
+
+    /// Wraps an Xlib `Window`.
+    #[cfg(all(unix, not(any(target_os = "android", target_os = "macos", target_os = "ios"))))]
+    pub fn from_xlib(window: c_ulong) -> Self {
+        WindowHandle(window as usize as *const c_void)
+    }
+
+    /// Wraps an XCB window.
+    #[cfg(all(unix, not(any(target_os = "android", target_os = "macos", target_os = "ios"))))]
+    pub fn from_xcb(window: u32) -> Self {
+        WindowHandle(window as usize as *const c_void)
+    }
+
+    /// Wraps a Wayland `wl_surface` pointer.
+    #[cfg(all(unix, not(any(target_os = "android", target_os = "macos", target_os = "ios"))))]
+    pub fn from_wayland(surface: *mut c_void) -> Self {
+        WindowHandle(surface)
+    }
+
+    /// Wraps an `ANativeWindow` pointer.
+    #[cfg(target_os = "android")]
+    pub fn from_anative_window(window: *mut c_void) -> Self {
+        WindowHandle(window)
+    }
+
+    /// Returns the raw handle.
+    pub fn as_raw(&self) -> *const c_void {
+        self.0
+    }
+}
+
+impl fmt::Debug for WindowHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WindowHandle({:p})", self.0)
+    }
+}
+
+impl From<*const c_void> for WindowHandle {
+    fn from(ptr: *const c_void) -> Self {
+        WindowHandle(ptr)
+    }
+}
+
+impl From<*mut c_void> for WindowHandle {
+    fn from(ptr: *mut c_void) -> Self {
+        WindowHandle(ptr)
+    }
+}
+
+impl From<NonNull<c_void>> for WindowHandle {
+    fn from(ptr: NonNull<c_void>) -> Self {
+        WindowHandle(ptr.as_ptr())
+    }
+}
+
+impl<T> From<&mut T> for WindowHandle {
+    fn from(window: &mut T) -> Self {
+        WindowHandle(window as *mut T as *const c_void)
+    }
+}
+
+impl From<isize> for WindowHandle {
+    fn from(hwnd: isize) -> Self {
+        WindowHandle(hwnd as *const c_void)
+    }
+}
+
+impl From<u64> for WindowHandle {
+    fn from(window: u64) -> Self {
+        WindowHandle(window as usize as *const c_void)
+    }
+}
+
+impl From<u32> for WindowHandle {
+    fn from(window: u32) -> Self {
+        WindowHandle(window as usize as *const c_void)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_pointers() {
+        let dispatch = 0x1000usize as *const c_void;
+        let instance = &dispatch as *const *const c_void as *const c_void;
+        let device = unsafe { DevicePointer::from_vk_instance(instance) };
+        assert_eq!(device.as_raw(), dispatch);
+        assert_eq!(format!("{:?}", device), "DevicePointer(0x1000)");
+        assert_eq!(DevicePointer::null(), DevicePointer::from_raw(ptr::null()));
+    }
+
+    #[test]
+    fn convert_handles() {
+        let mut device = 0u8;
+        let address = &mut device as *mut u8 as usize;
+        assert_eq!(*DevicePointer::from(&mut device) as usize, address);
+        let ptr = NonNull::new(address as *mut c_void).unwrap();
+        assert_eq!(*DevicePointer::from(ptr) as usize, address);
+
+        assert_eq!(WindowHandle::from(0x2aisize).as_raw() as usize, 0x2a);
+        assert_eq!(WindowHandle::from(0x2au64).as_raw() as usize, 0x2a);
+        assert_eq!(WindowHandle::from(ptr).as_raw() as usize, address);
+        assert_eq!(format!("{:?}", WindowHandle::from(0x2au32)), "WindowHandle(0x2a)");
+    }
+}
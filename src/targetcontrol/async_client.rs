@@ -0,0 +1,259 @@
+//! Asynchronous target control client built on `tokio`.
+
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::BytesMut;
+use futures::{ready, Sink, Stream};
+use tokio::net::TcpStream;
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+use super::{decode_handshake, decode_message, encode_handshake, Command, Message};
+use protocol::{Error, PacketReader, MAX_PAYLOAD_LEN};
+
+/// Splits the byte stream into `(packet type, payload)` frames.
+///
+/// Partially received packets stay buffered inside the codec, so dropping a
+/// pending read of the message stream never loses or corrupts messages.
+#[derive(Debug, Default)]
+struct PacketCodec;
+
+impl Decoder for PacketCodec {
+    type Item = (u32, Vec<u8>);
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Error> {
+        if src.len() < 8 {
+            return Ok(None);
+        }
+
+        let (ty, len) = {
+            let mut header = PacketReader::new(&src[..8]);
+            (header.u32()?, header.u32()?)
+        };
+        if len > MAX_PAYLOAD_LEN {
+            return Err(Error::Malformed("payload too large"));
+        }
+
+        let len = len as usize;
+        if src.len() < 8 + len {
+            src.reserve(8 + len - src.len());
+            return Ok(None);
+        }
+
+        let packet = src.split_to(8 + len);
+        Ok(Some((ty, packet[8..].to_vec())))
+    }
+}
+
+impl Encoder<Vec<u8>> for PacketCodec {
+    type Error = Error;
+
+    fn encode(&mut self, packet: Vec<u8>, dst: &mut BytesMut) -> Result<(), Error> {
+        dst.extend_from_slice(&packet);
+        Ok(())
+    }
+}
+
+/// An asynchronous connection to a single target.
+///
+/// This is a `Stream` of `Message`s sent by the target and a `Sink` of
+/// `Command`s to send to it. Use `StreamExt::split()` to drive both halves
+/// independently, e.g. when managing many targets concurrently.
+#[derive(Debug)]
+pub struct AsyncTargetControl {
+    framed: Framed<TcpStream, PacketCodec>,
+    version: u32,
+    target: String,
+    pid: u32,
+}
+
+impl AsyncTargetControl {
+    /// Returns the name of the target, usually its executable name.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the process ID of the target.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Returns the protocol version spoken by the target.
+    pub fn protocol_version(&self) -> u32 {
+        self.version
+    }
+}
+
+impl Stream for AsyncTargetControl {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let version = self.version;
+        let packet = ready!(Pin::new(&mut self.framed).poll_next(cx));
+        Poll::Ready(packet.map(|packet| packet.and_then(|(ty, payload)| decode_message(version, ty, &payload))))
+    }
+}
+
+impl Sink<Command> for AsyncTargetControl {
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.framed).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, cmd: Command) -> Result<(), Error> {
+        Pin::new(&mut self.framed).start_send(cmd.encode())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.framed).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.framed).poll_close(cx)
+    }
+}
+
+/// Future returned by `connect_async()`, which connects, sends the handshake
+/// and waits for the target's reply.
+struct Connect {
+    connecting: Option<Pin<Box<dyn Future<Output = io::Result<TcpStream>> + Send>>>,
+    framed: Option<Framed<TcpStream, PacketCodec>>,
+    handshake: Option<Vec<u8>>,
+}
+
+impl Future for Connect {
+    type Output = Result<AsyncTargetControl, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if let Some(ref mut connecting) = this.connecting {
+            let stream = ready!(connecting.as_mut().poll(cx))?;
+            stream.set_nodelay(true)?;
+            this.connecting = None;
+            this.framed = Some(Framed::new(stream, PacketCodec));
+        }
+
+        let framed = this.framed.as_mut().expect("polled after completion");
+        if this.handshake.is_some() {
+            ready!(Pin::new(&mut *framed).poll_ready(cx))?;
+            let handshake = this.handshake.take().unwrap();
+            Pin::new(&mut *framed).start_send(handshake)?;
+        }
+        ready!(Pin::new(&mut *framed).poll_flush(cx))?;
+
+        let reply = ready!(Pin::new(&mut *framed).poll_next(cx)).transpose()?;
+        let (ty, payload) = reply.ok_or(Error::Malformed("connection closed during handshake"))?;
+        let (version, target, pid) = decode_handshake(ty, &payload)?;
+        Poll::Ready(Ok(AsyncTargetControl {
+            framed: this.framed.take().unwrap(),
+            version,
+            target,
+            pid,
+        }))
+    }
+}
+
+/// Connects to the target listening on `addr`, identifying as `client`.
+///
+/// See `TargetControl::connect()` for the meaning of `force`. The returned
+/// future must run on a `tokio` runtime with I/O enabled.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # extern crate futures;
+/// # extern crate renderdoc;
+/// # extern crate tokio;
+/// use futures::{SinkExt, StreamExt};
+/// use renderdoc::targetcontrol::{self, Command};
+///
+/// # fn main() -> Result<(), renderdoc::protocol::Error> {
+/// let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build()?;
+/// let addr = ([127, 0, 0, 1], targetcontrol::FIRST_PORT).into();
+/// let mut target = runtime.block_on(targetcontrol::connect_async(&addr, "my-service", false))?;
+/// runtime.block_on(target.send(Command::TriggerCapture(1)))?;
+/// while let Some(msg) = runtime.block_on(target.next()) {
+///     println!("{:?}", msg?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn connect_async(
+    addr: &SocketAddr,
+    client: &str,
+    force: bool,
+) -> impl Future<Output = Result<AsyncTargetControl, Error>> {
+    Connect {
+        connecting: Some(Box::pin(TcpStream::connect(*addr))),
+        framed: None,
+        handshake: Some(encode_handshake(client, force)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::PacketWriter;
+
+    #[test]
+    fn decode_split_packets() {
+        let packet = PacketWriter::new().str("partial").finish(1);
+        let mut codec = PacketCodec;
+        let mut buf = BytesMut::from(&packet[..5]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+
+        buf.extend_from_slice(&packet[5..]);
+        let (ty, payload) = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(ty, 1);
+        assert_eq!(payload, &packet[8..]);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn talk_over_loopback() {
+        use futures::{SinkExt, StreamExt};
+        use protocol::read_packet;
+        use std::io::Write;
+        use std::net::TcpListener;
+        use std::thread;
+        use targetcontrol::{PacketType, PROTOCOL_VERSION};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let target = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_packet(&mut stream).unwrap();
+            let handshake = PacketWriter::new().u32(PROTOCOL_VERSION).str("game").u32(42);
+            stream.write_all(&handshake.finish(PacketType::Handshake as u32)).unwrap();
+
+            let (ty, _) = read_packet(&mut stream).unwrap();
+            assert_eq!(ty, PacketType::TriggerCapture as u32);
+            stream.write_all(&PacketWriter::new().finish(0x40)).unwrap();
+        });
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+        let mut client = runtime.block_on(connect_async(&addr, "test", false)).unwrap();
+        assert_eq!((client.target(), client.pid()), ("game", 42));
+        runtime.block_on(client.send(Command::TriggerCapture(1))).unwrap();
+        let msg = runtime.block_on(client.next()).unwrap().unwrap();
+        assert_eq!(msg, Message::Unknown(0x40, Vec::new()));
+        assert!(runtime.block_on(client.next()).is_none());
+        target.join().unwrap();
+    }
+
+    #[test]
+    fn reject_oversized_packet() {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&1u32.to_le_bytes());
+        buf.extend_from_slice(&(MAX_PAYLOAD_LEN + 1).to_le_bytes());
+        match PacketCodec.decode(&mut buf) {
+            Err(Error::Malformed("payload too large")) => (),
+            other => panic!("expected payload too large, got {:?}", other),
+        }
+    }
+}
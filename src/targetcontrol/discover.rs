@@ -0,0 +1,86 @@
+//! Enumeration of live capture targets.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use super::{Message, TargetControl, FIRST_PORT, LAST_PORT};
+use protocol::Error;
+
+/// Client name reported to targets while probing them.
+const PROBE_CLIENT: &str = "renderdoc-rs";
+
+/// How long to wait for a target to accept a connection.
+const CONNECT_TIMEOUT_MS: u64 = 250;
+
+/// How long to wait for a target to announce its graphics APIs.
+const API_TIMEOUT_MS: u64 = 100;
+
+/// A capture target found while scanning the target control ports.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TargetInfo {
+    /// Target control port of the target.
+    pub port: u16,
+    /// Name of the target, usually its executable name.
+    ///
+    /// This is `None` if the target is busy with another client.
+    pub target: Option<String>,
+    /// Process ID of the target.
+    ///
+    /// This is `None` if the target is busy with another client.
+    pub pid: Option<u32>,
+    /// Graphics APIs initialized by the target so far.
+    pub apis: Vec<String>,
+    /// Name of the client the target is currently connected to, if any.
+    pub busy_with: Option<String>,
+}
+
+/// Enumerates all capture targets running on the local machine.
+///
+/// This is the same list the RenderDoc UI shows under "Attach to running
+/// instance". Each target is briefly connected to without forcing, so targets
+/// which are busy with another client are reported but not disturbed.
+pub fn discover() -> Vec<TargetInfo> {
+    discover_host(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+}
+
+/// Enumerates all capture targets running on the given host.
+pub fn discover_host(host: IpAddr) -> Vec<TargetInfo> {
+    (FIRST_PORT..LAST_PORT + 1)
+        .filter_map(|port| probe(SocketAddr::new(host, port)))
+        .collect()
+}
+
+fn probe(addr: SocketAddr) -> Option<TargetInfo> {
+    let timeout = Duration::from_millis(CONNECT_TIMEOUT_MS);
+    let mut target = match TargetControl::connect_timeout(&addr, timeout, PROBE_CLIENT, false) {
+        Ok(target) => target,
+        Err(Error::Busy(client)) => {
+            return Some(TargetInfo {
+                port: addr.port(),
+                target: None,
+                pid: None,
+                apis: Vec::new(),
+                busy_with: Some(client),
+            });
+        }
+        Err(_) => return None,
+    };
+
+    // Targets announce their graphics APIs right after the handshake.
+    let mut apis = Vec::new();
+    if target.set_read_timeout(Some(Duration::from_millis(API_TIMEOUT_MS))).is_ok() {
+        while let Ok(msg) = target.receive() {
+            if let Message::RegisterApi { api, .. } = msg {
+                apis.push(api);
+            }
+        }
+    }
+
+    Some(TargetInfo {
+        port: addr.port(),
+        target: Some(target.target().to_string()),
+        pid: Some(target.pid()),
+        apis,
+        busy_with: None,
+    })
+}
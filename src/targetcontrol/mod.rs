@@ -0,0 +1,687 @@
+//! Client for RenderDoc's target control protocol.
+//!
+//! Every process with RenderDoc loaded opens a target control socket on the
+//! first free port in the range `FIRST_PORT..=LAST_PORT`. This is the same
+//! connection the RenderDoc UI uses to trigger captures of a running program
+//! and to get notified about new captures, which allows Rust tooling to drive
+//! captures of _other_ processes.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use renderdoc::targetcontrol::{Message, TargetControl, FIRST_PORT};
+//!
+//! # fn run() -> Result<(), renderdoc::protocol::Error> {
+//! let mut target = TargetControl::connect_local(FIRST_PORT, "my-tool", false)?;
+//! println!("Connected to {} (PID {})", target.target(), target.pid());
+//!
+//! target.trigger_capture(1)?;
+//! loop {
+//!     if let Message::NewCapture(capture) = target.receive()? {
+//!         println!("Captured {}", capture.path);
+//!         break;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use protocol::{read_packet, read_packet_header, read_payload, Error, PacketReader, PacketWriter};
+
+pub use self::discover::{discover, discover_host, TargetInfo};
+#[cfg(feature = "async")]
+pub use self::async_client::{connect_async, AsyncTargetControl};
+
+#[cfg(feature = "async")]
+mod async_client;
+mod discover;
+
+/// First port on which target control connections are accepted.
+pub const FIRST_PORT: u16 = 38920;
+
+/// Last port on which target control connections are accepted.
+pub const LAST_PORT: u16 = FIRST_PORT + 7;
+
+/// Newest version of the target control protocol spoken by this client.
+pub const PROTOCOL_VERSION: u32 = 10;
+
+/// Oldest version of the target control protocol spoken by this client.
+///
+/// Targets speaking version 9 don't report the frame numbers and thumbnails
+/// of new captures.
+pub const MIN_PROTOCOL_VERSION: u32 = 9;
+
+/// Target control packet types.
+#[allow(missing_docs)]
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum PacketType {
+    Noop = 1,
+    Handshake = 2,
+    Busy = 3,
+    NewCapture = 4,
+    RegisterApi = 5,
+    TriggerCapture = 6,
+    CopyCapture = 7,
+    DeleteCapture = 8,
+    QueueCapture = 9,
+    NewChild = 10,
+    CaptureProgress = 11,
+    CycleActiveWindow = 12,
+}
+
+/// Preview image of a capture.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Thumbnail {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// The JPEG encoded image.
+    pub data: Vec<u8>,
+}
+
+/// Details of a new capture made by the target.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NewCapture {
+    /// Capture ID, unique for the lifetime of the target.
+    pub id: u32,
+    /// Number of the captured frame, if the target reports it, which requires
+    /// protocol version 10.
+    pub frame_number: Option<u32>,
+    /// Time of capture, measured in seconds passed since the UNIX epoch.
+    pub timestamp: u64,
+    /// Path of the capture file on the target's machine.
+    pub path: String,
+    /// Whether the target runs on the same machine as this client.
+    pub local: bool,
+    /// Preview of the captured frame, if the target made and reports one,
+    /// which requires protocol version 10.
+    pub thumbnail: Option<Thumbnail>,
+}
+
+/// A child process of the target which RenderDoc has hooked into.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ChildProcess {
+    /// Process ID of the child.
+    pub pid: u32,
+    /// Port on which the child accepts target control connections.
+    pub port: u16,
+}
+
+/// Messages sent by the target.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    /// Keep-alive message without any content.
+    Noop,
+    /// The target finished a capture.
+    NewCapture(NewCapture),
+    /// A graphics API was initialized by the target.
+    RegisterApi {
+        /// Name of the graphics API.
+        api: String,
+        /// Whether the API is presenting frames to a window.
+        presenting: bool,
+        /// Whether RenderDoc supports capturing this API on the target.
+        supported: bool,
+    },
+    /// The target launched a child process, which RenderDoc hooked into as
+    /// `CaptureOption::HookIntoChildren` is enabled.
+    NewChild(ChildProcess),
+    /// Progress of an ongoing capture, between `0.0` and `1.0`.
+    CaptureProgress(f32),
+    /// Contents of a capture file, requested with `Command::CopyCapture`.
+    ///
+    /// Captures larger than 512 MiB are rejected with `Error::Malformed` when
+    /// received this way; `TargetControl::copy_capture()` streams them to disk
+    /// instead.
+    CaptureCopied {
+        /// Capture ID.
+        id: u32,
+        /// Contents of the capture file.
+        data: Vec<u8>,
+    },
+    /// A packet of a type this client doesn't know, e.g. one added by a newer
+    /// RenderDoc release, with its type and raw payload.
+    Unknown(u32, Vec<u8>),
+}
+
+/// Commands which can be sent to the target.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Command {
+    /// Captures the next `n` frames presented by the target.
+    TriggerCapture(u32),
+    /// Captures the frame with the given frame number once the target
+    /// presents it.
+    QueueCapture(u32),
+    /// Sends the capture file with the given capture ID over the connection.
+    CopyCapture(u32),
+    /// Deletes the capture file with the given capture ID on the target's
+    /// machine.
+    DeleteCapture(u32),
+    /// Makes the next window of the target the active one, as by pressing the
+    /// focus toggle key in the target.
+    CycleActiveWindow,
+}
+
+impl Command {
+    /// Encodes the command as a packet, ready to be sent.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        match *self {
+            Command::TriggerCapture(num_frames) => PacketWriter::new()
+                .u32(num_frames)
+                .finish(PacketType::TriggerCapture as u32),
+            Command::QueueCapture(frame) => PacketWriter::new()
+                .u32(frame)
+                .finish(PacketType::QueueCapture as u32),
+            Command::CopyCapture(id) => PacketWriter::new().u32(id).finish(PacketType::CopyCapture as u32),
+            Command::DeleteCapture(id) => PacketWriter::new().u32(id).finish(PacketType::DeleteCapture as u32),
+            Command::CycleActiveWindow => PacketWriter::new().finish(PacketType::CycleActiveWindow as u32),
+        }
+    }
+}
+
+/// A blocking connection to a single target.
+#[derive(Debug)]
+pub struct TargetControl {
+    stream: TcpStream,
+    version: u32,
+    target: String,
+    pid: u32,
+}
+
+impl TargetControl {
+    /// Connects to the target listening on `addr`, identifying as `client`.
+    ///
+    /// Targets only accept a single client at a time. If `force` is `true`,
+    /// any other connected client is kicked off; otherwise, connecting to a
+    /// busy target fails with `Error::Busy`.
+    pub fn connect<A: ToSocketAddrs>(addr: A, client: &str, force: bool) -> Result<TargetControl, Error> {
+        let stream = TcpStream::connect(addr)?;
+        Self::handshake(stream, client, force)
+    }
+
+    /// Connects to the target listening on `addr`, giving up on connecting
+    /// after `timeout` has elapsed.
+    pub fn connect_timeout(addr: &SocketAddr, timeout: Duration, client: &str, force: bool) -> Result<TargetControl, Error> {
+        let stream = TcpStream::connect_timeout(addr, timeout)?;
+        Self::handshake(stream, client, force)
+    }
+
+    /// Connects to a target on the local machine, identified by its port.
+    pub fn connect_local(port: u16, client: &str, force: bool) -> Result<TargetControl, Error> {
+        Self::connect(SocketAddr::from((Ipv4Addr::new(127, 0, 0, 1), port)), client, force)
+    }
+
+    /// Connects to a target on the local machine, retrying until it starts
+    /// listening on `port` or `timeout` has elapsed.
+    ///
+    /// This allows attaching to a target right as it starts, e.g. after
+    /// launching it with `Command::spawn()`, to capture its first frames.
+    pub fn wait_for_local(port: u16, client: &str, timeout: Duration) -> Result<TargetControl, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match Self::connect_local(port, client, false) {
+                Err(Error::Io(_)) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+                result => return result,
+            }
+        }
+    }
+
+    fn handshake(mut stream: TcpStream, client: &str, force: bool) -> Result<TargetControl, Error> {
+        stream.set_nodelay(true)?;
+        stream.write_all(&encode_handshake(client, force))?;
+
+        let (ty, payload) = read_packet(&mut stream)?;
+        let (version, target, pid) = decode_handshake(ty, &payload)?;
+        Ok(TargetControl {
+            stream,
+            version,
+            target,
+            pid,
+        })
+    }
+
+    /// Returns the name of the target, usually its executable name.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the process ID of the target.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Returns the protocol version spoken by the target.
+    pub fn protocol_version(&self) -> u32 {
+        self.version
+    }
+
+    /// Sets a timeout for `receive()`, or blocks indefinitely if `None`.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), Error> {
+        self.stream.set_read_timeout(timeout)?;
+        Ok(())
+    }
+
+    /// Sends a command to the target.
+    pub fn send(&mut self, cmd: Command) -> Result<(), Error> {
+        self.stream.write_all(&cmd.encode())?;
+        Ok(())
+    }
+
+    /// Captures the next `num_frames` frames presented by the target.
+    pub fn trigger_capture(&mut self, num_frames: u32) -> Result<(), Error> {
+        self.send(Command::TriggerCapture(num_frames))
+    }
+
+    /// Captures the frame with the given frame number once the target
+    /// presents it.
+    pub fn queue_capture(&mut self, frame: u32) -> Result<(), Error> {
+        self.send(Command::QueueCapture(frame))
+    }
+
+    /// Captures the frame with the given frame number, and waits until the
+    /// target has written the capture.
+    ///
+    /// Messages received before the capture completes are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use renderdoc::targetcontrol::{TargetControl, FIRST_PORT};
+    ///
+    /// # fn run() -> Result<(), renderdoc::protocol::Error> {
+    /// let mut target = TargetControl::wait_for_local(FIRST_PORT, "test-driver", Duration::from_secs(10))?;
+    /// let capture = target.capture_frame(100)?;
+    /// println!("Captured frame 100 to {}", capture.path);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn capture_frame(&mut self, frame: u32) -> Result<NewCapture, Error> {
+        self.queue_capture(frame)?;
+        loop {
+            if let Message::NewCapture(capture) = self.receive()? {
+                return Ok(capture);
+            }
+        }
+    }
+
+    /// Copies the capture with the given capture ID from the target's machine
+    /// to `local_path`, over this connection.
+    ///
+    /// This also works for targets on devices without a remote server
+    /// connection. The file is written as it arrives rather than held in
+    /// memory, so captures of any size can be copied. Messages received
+    /// before the copy completes are dropped. Returns the number of bytes
+    /// copied.
+    pub fn copy_capture<P: AsRef<Path>>(&mut self, id: u32, local_path: P) -> Result<u64, Error> {
+        self.send(Command::CopyCapture(id))?;
+        loop {
+            let (ty, len) = read_packet_header(&mut self.stream)?;
+            if ty != PacketType::CopyCapture as u32 {
+                let payload = read_payload(&mut self.stream, len)?;
+                decode_message(self.version, ty, &payload)?;
+                continue;
+            }
+
+            // The target answers with the capture ID, followed by the file
+            // contents serialized like `PacketWriter::bytes()`.
+            let mut header = [0u8; 12];
+            self.stream.read_exact(&mut header)?;
+            let mut reader = PacketReader::new(&header);
+            let copied = reader.u32()?;
+            let file_len = reader.u64()?;
+            let mut contents = (&mut self.stream).take(file_len);
+            if copied != id {
+                io::copy(&mut contents, &mut io::sink())?;
+                continue;
+            }
+
+            let local_path = local_path.as_ref();
+            let result = (|| {
+                let mut file = BufWriter::new(fs::File::create(local_path)?);
+                let written = io::copy(&mut contents, &mut file)?;
+                if written != file_len {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "capture copy ended early"));
+                }
+                file.flush()?;
+                Ok(written)
+            })();
+            if result.is_err() {
+                let _ = fs::remove_file(local_path);
+            }
+            return result.map_err(Error::from);
+        }
+    }
+
+    /// Deletes the capture with the given capture ID on the target's machine,
+    /// e.g. to free up space once it has been copied.
+    pub fn delete_capture(&mut self, id: u32) -> Result<(), Error> {
+        self.send(Command::DeleteCapture(id))
+    }
+
+    /// Makes the next window of the target the active one, which is captured
+    /// by `trigger_capture()` and `queue_capture()`.
+    ///
+    /// This is the same as pressing one of the focus toggle keys in the
+    /// target, for targets presenting to several windows.
+    pub fn cycle_active_window(&mut self) -> Result<(), Error> {
+        self.send(Command::CycleActiveWindow)
+    }
+
+    /// Connects to a child process of the target, on the same machine as the
+    /// target.
+    pub fn connect_child(&self, child: &ChildProcess, client: &str) -> Result<TargetControl, Error> {
+        let addr = SocketAddr::new(self.stream.peer_addr()?.ip(), child.port);
+        Self::connect(addr, client, false)
+    }
+
+    /// Waits until the target launches a child process, and connects to it.
+    ///
+    /// This follows launchers which start the actual application as a child,
+    /// provided the target enables `CaptureOption::HookIntoChildren`. Messages
+    /// received before the child is reported are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use renderdoc::targetcontrol::{TargetControl, FIRST_PORT};
+    ///
+    /// # fn run() -> Result<(), renderdoc::protocol::Error> {
+    /// let mut launcher = TargetControl::connect_local(FIRST_PORT, "controller", false)?;
+    /// let mut game = launcher.wait_for_child("controller")?;
+    /// game.trigger_capture(1)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wait_for_child(&mut self, client: &str) -> Result<TargetControl, Error> {
+        loop {
+            if let Message::NewChild(child) = self.receive()? {
+                return self.connect_child(&child, client);
+            }
+        }
+    }
+
+    /// Blocks until the next message from the target arrives.
+    pub fn receive(&mut self) -> Result<Message, Error> {
+        let (ty, payload) = read_packet(&mut self.stream)?;
+        decode_message(self.version, ty, &payload)
+    }
+
+    /// Returns an iterator over the messages of the target, ending when the
+    /// target disconnects.
+    pub fn messages(&mut self) -> Messages<'_> {
+        Messages { target: self }
+    }
+
+    /// Returns an iterator over the captures made by the target from now on,
+    /// ending when the target disconnects.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use renderdoc::targetcontrol::{TargetControl, FIRST_PORT};
+    ///
+    /// # fn run() -> Result<(), renderdoc::protocol::Error> {
+    /// let mut target = TargetControl::connect_local(FIRST_PORT, "dashboard", false)?;
+    /// for capture in target.new_captures() {
+    ///     let capture = capture?;
+    ///     let thumbnail = capture.thumbnail.as_ref().map_or(0, |thumbnail| thumbnail.data.len());
+    ///     println!("Frame {:?}: {} ({} byte thumbnail)", capture.frame_number, capture.path, thumbnail);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_captures(&mut self) -> impl Iterator<Item = Result<NewCapture, Error>> + '_ {
+        self.messages().filter_map(|msg| match msg {
+            Ok(Message::NewCapture(capture)) => Some(Ok(capture)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+    }
+}
+
+/// Iterator over the messages of a target, see `TargetControl::messages()`.
+#[derive(Debug)]
+pub struct Messages<'a> {
+    target: &'a mut TargetControl,
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = Result<Message, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.target.receive() {
+            Err(Error::Io(ref err)) if err.kind() == io::ErrorKind::UnexpectedEof => None,
+            result => Some(result),
+        }
+    }
+}
+
+/// Encodes the handshake packet sent by the client.
+pub(crate) fn encode_handshake(client: &str, force: bool) -> Vec<u8> {
+    PacketWriter::new()
+        .u32(PROTOCOL_VERSION)
+        .str(client)
+        .bool(force)
+        .finish(PacketType::Handshake as u32)
+}
+
+/// Decodes the target's reply to the handshake into its protocol version,
+/// name and PID.
+pub(crate) fn decode_handshake(ty: u32, payload: &[u8]) -> Result<(u32, String, u32), Error> {
+    let mut reader = PacketReader::new(payload);
+    match ty {
+        ty if ty == PacketType::Handshake as u32 => {
+            let version = reader.u32()?;
+            if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) {
+                return Err(Error::VersionMismatch(version));
+            }
+
+            let target = reader.string()?;
+            let pid = reader.u32()?;
+            Ok((version, target, pid))
+        }
+        ty if ty == PacketType::Busy as u32 => Err(Error::Busy(reader.string()?)),
+        ty => Err(Error::UnexpectedPacket(ty)),
+    }
+}
+
+/// Decodes a message sent by a target speaking protocol `version`.
+pub(crate) fn decode_message(version: u32, ty: u32, payload: &[u8]) -> Result<Message, Error> {
+    let mut reader = PacketReader::new(payload);
+    let msg = match ty {
+        ty if ty == PacketType::Noop as u32 => Message::Noop,
+        ty if ty == PacketType::NewCapture as u32 => {
+            let id = reader.u32()?;
+            let timestamp = reader.u64()?;
+            let path = reader.string()?;
+            let local = reader.bool()?;
+            let (mut frame_number, mut thumbnail) = (None, None);
+            if version >= 10 {
+                frame_number = Some(reader.u32()?);
+                let width = reader.u32()?;
+                let height = reader.u32()?;
+                let data = reader.bytes()?;
+                if !data.is_empty() {
+                    thumbnail = Some(Thumbnail { width, height, data });
+                }
+            }
+            Message::NewCapture(NewCapture {
+                id,
+                frame_number,
+                timestamp,
+                path,
+                local,
+                thumbnail,
+            })
+        }
+        ty if ty == PacketType::RegisterApi as u32 => Message::RegisterApi {
+            api: reader.string()?,
+            presenting: reader.bool()?,
+            supported: reader.bool()?,
+        },
+        ty if ty == PacketType::NewChild as u32 => {
+            let pid = reader.u32()?;
+            let port = reader.u32()?;
+            if port > u32::from(u16::MAX) {
+                return Err(Error::Malformed("invalid child port"));
+            }
+            Message::NewChild(ChildProcess { pid, port: port as u16 })
+        }
+        ty if ty == PacketType::CaptureProgress as u32 => Message::CaptureProgress(reader.f32()?),
+        ty if ty == PacketType::CopyCapture as u32 => Message::CaptureCopied {
+            id: reader.u32()?,
+            data: reader.bytes()?,
+        },
+        ty => Message::Unknown(ty, payload.to_vec()),
+    };
+
+    Ok(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_new_capture() {
+        let packet = PacketWriter::new()
+            .u32(3)
+            .u64(1_500_000_000)
+            .str("/tmp/capture_frame12.rdc")
+            .bool(true)
+            .u32(12)
+            .u32(2)
+            .u32(1)
+            .bytes(&[0xff, 0xd8])
+            .finish(PacketType::NewCapture as u32);
+
+        let msg = decode_message(PROTOCOL_VERSION, PacketType::NewCapture as u32, &packet[8..]).unwrap();
+        let mut expected = NewCapture {
+            id: 3,
+            frame_number: Some(12),
+            timestamp: 1_500_000_000,
+            path: "/tmp/capture_frame12.rdc".into(),
+            local: true,
+            thumbnail: Some(Thumbnail {
+                width: 2,
+                height: 1,
+                data: vec![0xff, 0xd8],
+            }),
+        };
+        assert_eq!(msg, Message::NewCapture(expected.clone()));
+
+        // Version 9 targets end the message after the locality flag.
+        let msg = decode_message(MIN_PROTOCOL_VERSION, PacketType::NewCapture as u32, &packet[8..]).unwrap();
+        expected.frame_number = None;
+        expected.thumbnail = None;
+        assert_eq!(msg, Message::NewCapture(expected));
+    }
+
+    #[test]
+    fn encode_commands() {
+        assert_eq!(Command::CycleActiveWindow.encode(), vec![12, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(Command::DeleteCapture(2).encode(), vec![8, 0, 0, 0, 4, 0, 0, 0, 2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn decode_unknown_packet() {
+        let msg = decode_message(PROTOCOL_VERSION, 0xff, &[1, 2]).unwrap();
+        assert_eq!(msg, Message::Unknown(0xff, vec![1, 2]));
+    }
+
+    #[test]
+    fn capture_frame_over_loopback() {
+        use std::env;
+        use std::net::TcpListener;
+        use std::process;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let target = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_packet(&mut stream).unwrap();
+            let handshake = PacketWriter::new().u32(PROTOCOL_VERSION).str("game").u32(42);
+            stream.write_all(&handshake.finish(PacketType::Handshake as u32)).unwrap();
+
+            let (ty, payload) = read_packet(&mut stream).unwrap();
+            assert_eq!(ty, PacketType::QueueCapture as u32);
+            assert_eq!(PacketReader::new(&payload).u32().unwrap(), 100);
+            stream.write_all(&PacketWriter::new().finish(PacketType::Noop as u32)).unwrap();
+            stream.write_all(&PacketWriter::new().u32(1).finish(0x40)).unwrap();
+            let capture = PacketWriter::new()
+                .u32(0)
+                .u64(1_500_000_000)
+                .str("/tmp/game_frame100.rdc")
+                .bool(true)
+                .u32(100)
+                .u32(0)
+                .u32(0)
+                .bytes(&[]);
+            stream.write_all(&capture.finish(PacketType::NewCapture as u32)).unwrap();
+
+            let (ty, payload) = read_packet(&mut stream).unwrap();
+            assert_eq!((ty, payload), (PacketType::CopyCapture as u32, vec![0, 0, 0, 0]));
+            let stale = PacketWriter::new().u32(5).bytes(b"OLD");
+            stream.write_all(&stale.finish(PacketType::CopyCapture as u32)).unwrap();
+            let copied = PacketWriter::new().u32(0).bytes(b"RDC");
+            stream.write_all(&copied.finish(PacketType::CopyCapture as u32)).unwrap();
+
+            let (ty, _) = read_packet(&mut stream).unwrap();
+            assert_eq!(ty, PacketType::DeleteCapture as u32);
+        });
+
+        let mut client = TargetControl::wait_for_local(port, "test", Duration::from_secs(5)).unwrap();
+        assert_eq!((client.target(), client.pid()), ("game", 42));
+        let capture = client.capture_frame(100).unwrap();
+        assert_eq!((capture.frame_number, capture.thumbnail), (Some(100), None));
+
+        let local_path = env::temp_dir().join(format!("renderdoc-rs-copy-capture-{}.rdc", process::id()));
+        assert_eq!(client.copy_capture(0, &local_path).unwrap(), 3);
+        assert_eq!(fs::read(&local_path).unwrap(), b"RDC");
+        fs::remove_file(&local_path).unwrap();
+        client.delete_capture(0).unwrap();
+        target.join().unwrap();
+        assert!(client.new_captures().next().is_none());
+    }
+
+    #[test]
+    fn follow_child_over_loopback() {
+        use std::net::TcpListener;
+
+        fn serve(listener: TcpListener, name: &'static str, child: Option<u16>) -> thread::JoinHandle<()> {
+            thread::spawn(move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                read_packet(&mut stream).unwrap();
+                let handshake = PacketWriter::new().u32(PROTOCOL_VERSION).str(name).u32(1);
+                stream.write_all(&handshake.finish(PacketType::Handshake as u32)).unwrap();
+                if let Some(port) = child {
+                    let packet = PacketWriter::new().u32(2).u32(u32::from(port));
+                    stream.write_all(&packet.finish(PacketType::NewChild as u32)).unwrap();
+                }
+            })
+        }
+
+        let launcher = TcpListener::bind("127.0.0.1:0").unwrap();
+        let game = TcpListener::bind("127.0.0.1:0").unwrap();
+        let launcher_port = launcher.local_addr().unwrap().port();
+        let game_port = game.local_addr().unwrap().port();
+        let servers = [serve(launcher, "launcher", Some(game_port)), serve(game, "game", None)];
+
+        let mut client = TargetControl::connect_local(launcher_port, "test", false).unwrap();
+        let child = client.wait_for_child("test").unwrap();
+        assert_eq!(child.target(), "game");
+        for server in servers {
+            server.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Frame captures of single tests.
+
+use std::cell::Cell;
+use std::fs;
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+
+use api::RenderDocV100;
+use {instance, DevicePointer, RenderDocHandle, WindowHandle};
+
+lazy_static! {
+    /// Held by the active guard, since RenderDoc captures every thread at
+    /// once and the test harness runs tests in parallel.
+    static ref CAPTURING: Mutex<()> = Mutex::new(());
+}
+
+thread_local! {
+    static NESTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Captures everything rendered during a test, reporting the capture if the
+/// test fails.
+///
+/// The capture starts when the guard is created and ends when it's dropped.
+/// If the thread is panicking at that point, i.e. the test failed, the path of
+/// the capture is printed, which the test harness shows along with the rest
+/// of the failed test's output. Captures of passing tests are deleted.
+///
+/// Only one guard captures at a time, so tests using guards run one after
+/// another even if the harness runs them in parallel. A guard created while
+/// the same thread already holds one does nothing, leaving the frames to the
+/// outer guard's capture.
+///
+/// Without RenderDoc loaded, e.g. when the tests aren't launched through
+/// RenderDoc, the guard does nothing.
+///
+/// The `#[renderdoc_capture]` attribute of the `macros` feature wraps a test
+/// in a guard, calling `fail()` if the test returns an `Err`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use renderdoc::TestCapture;
+/// // In a test function:
+/// let _capture = TestCapture::start("draws_triangle");
+/// // Render and assert...
+/// ```
+#[must_use = "the frame capture ends as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct TestCapture {
+    name: String,
+    rd: Option<&'static RenderDocHandle>,
+    first_capture: u32,
+    failed: bool,
+    _capturing: Option<MutexGuard<'static, ()>>,
+}
+
+impl TestCapture {
+    /// Starts capturing all devices and windows for the test `name`.
+    ///
+    /// Blocks while a guard on another thread is capturing.
+    pub fn start<S: Into<String>>(name: S) -> TestCapture {
+        let mut capture = TestCapture {
+            name: name.into(),
+            rd: None,
+            first_capture: 0,
+            failed: false,
+            _capturing: None,
+        };
+        let rd = match instance() {
+            Some(rd) if !NESTED.with(Cell::get) => rd,
+            _ => return capture,
+        };
+
+        // A test panicking while capturing poisons the lock, which is of no
+        // concern to the next test.
+        capture._capturing = Some(CAPTURING.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+        NESTED.with(|nested| nested.set(true));
+
+        let rd_lock = rd.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let started = rd_lock
+            .get_num_captures()
+            .and_then(|num| rd_lock.start_frame_capture(DevicePointer::null(), WindowHandle::null()).map(|()| num));
+        match started {
+            Ok(num) => {
+                capture.first_capture = num;
+                capture.rd = Some(rd);
+            }
+            // RenderDoc has been shut down since `instance()` returned.
+            Err(_) => NESTED.with(|nested| nested.set(false)),
+        }
+        capture
+    }
+
+    /// Marks the test as failed without panicking, keeping and reporting its
+    /// capture.
+    pub fn fail(&mut self) {
+        self.failed = true;
+    }
+}
+
+/// Outcome of a test, as returned by its function.
+///
+/// Used by `#[renderdoc_capture]` to tell whether a test failed by returning
+/// an `Err`.
+#[doc(hidden)]
+pub trait TestOutcome {
+    /// Returns whether the test failed.
+    fn is_failure(&self) -> bool;
+}
+
+impl TestOutcome for () {
+    fn is_failure(&self) -> bool {
+        false
+    }
+}
+
+impl<T, E> TestOutcome for Result<T, E> {
+    fn is_failure(&self) -> bool {
+        self.is_err()
+    }
+}
+
+impl Drop for TestCapture {
+    fn drop(&mut self) {
+        let rd = match self.rd {
+            Some(rd) => rd.lock().unwrap_or_else(|poisoned| poisoned.into_inner()),
+            None => return,
+        };
+        NESTED.with(|nested| nested.set(false));
+        let _ = rd.end_frame_capture(DevicePointer::null(), WindowHandle::null());
+
+        let num = rd.get_num_captures().unwrap_or(0);
+        let path = match num.checked_sub(1).filter(|&idx| idx >= self.first_capture) {
+            Some(idx) => rd.get_capture(idx).ok().flatten().map(|(path, _)| path),
+            None => None,
+        };
+
+        let failed = self.failed || thread::panicking();
+        match path {
+            Some(ref path) if failed => {
+                eprintln!("RenderDoc capture of failed test {}: {}", self.name, path);
+            }
+            Some(path) => {
+                let _ = fs::remove_file(path);
+            }
+            None if failed => {
+                eprintln!("RenderDoc did not capture failed test {}", self.name);
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Golden image testing against frame captures.
+//!
+//! `assert_frame_matches()` replays a capture, reads back the image a frame
+//! rendered, and compares it against a reference PNG checked into the
+//! repository. On a mismatch, the actual image and a diff image highlighting
+//! the differing pixels in red are written next to the reference, so CI can
+//! archive them.
+//!
+//! References are created or updated by running the tests with
+//! `RENDERDOC_RS_UPDATE_GOLDEN=1`, which writes the actual images instead of
+//! comparing them.
+//!
+//! Requires the `testing` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use renderdoc::testing::{assert_frame_matches, FrameEvent, Tolerance};
+//!
+//! # fn main() {
+//! assert_frame_matches("captures/shadows.rdc", FrameEvent::Final, "tests/golden/shadows.png", Tolerance::default());
+//! # }
+//! ```
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use png;
+
+use replay::{self, Action, ActionFlags, CaptureFile, ImageFormat, Image, ReplayController};
+
+/// Environment variable which, if set, makes `assert_frame_matches()` write
+/// the actual images as references instead of comparing against them.
+pub const UPDATE_ENV_VAR: &str = "RENDERDOC_RS_UPDATE_GOLDEN";
+
+/// Selects the image of a capture to compare.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FrameEvent {
+    /// The first color target bound at the action with the given event ID,
+    /// as it is right after the action.
+    Event(u32),
+    /// The first color target of the last draw, as it is at the end of the
+    /// frame.
+    Final,
+}
+
+/// How much an image may differ from its reference.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    /// Perceptual difference from 0 to 1 above which a pixel counts as
+    /// different.
+    pub pixel_threshold: f64,
+    /// Number of pixels which may differ.
+    pub max_differing_pixels: u64,
+}
+
+impl Tolerance {
+    /// Requires every pixel to match exactly.
+    pub fn exact() -> Self {
+        Tolerance {
+            pixel_threshold: 0.0,
+            max_differing_pixels: 0,
+        }
+    }
+}
+
+impl Default for Tolerance {
+    /// Ignores differences too small to notice, as produced by different GPUs
+    /// or drivers, but no differing pixels.
+    fn default() -> Self {
+        Tolerance {
+            pixel_threshold: 0.1,
+            max_differing_pixels: 0,
+        }
+    }
+}
+
+/// Replays `capture_path` and asserts that the image selected by `event`
+/// matches `reference_png` within `tolerance`.
+///
+/// Only the color channels are compared, alpha is ignored.
+///
+/// # Panics
+///
+/// Panics if the images differ, if the reference doesn't exist, or if the
+/// capture can't be replayed. For differing images, `<reference>.actual.png`
+/// and `<reference>.diff.png` are written next to the reference.
+pub fn assert_frame_matches<P, Q>(capture_path: P, event: FrameEvent, reference_png: Q, tolerance: Tolerance)
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let (capture_path, reference_png) = (capture_path.as_ref(), reference_png.as_ref());
+    let actual = match render_output(capture_path, event) {
+        Ok(image) => image,
+        Err(msg) => panic!("failed to read the output of {}: {}", capture_path.display(), msg),
+    };
+
+    if env::var_os(UPDATE_ENV_VAR).is_some() {
+        if let Err(err) = write_png(reference_png, &actual) {
+            panic!("failed to write reference {}: {}", reference_png.display(), err);
+        }
+        return;
+    }
+
+    let actual_path = sibling(reference_png, "actual");
+    let reference = match read_png(reference_png) {
+        Ok(image) => image,
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+            let _ = write_png(&actual_path, &actual);
+            panic!(
+                "reference {} does not exist, wrote the actual image to {}; set {}=1 to accept it",
+                reference_png.display(),
+                actual_path.display(),
+                UPDATE_ENV_VAR
+            );
+        }
+        Err(err) => panic!("failed to read reference {}: {}", reference_png.display(), err),
+    };
+
+    let diff = replay::compare_images(&actual, &reference, tolerance.pixel_threshold);
+    if diff.differing_pixels <= tolerance.max_differing_pixels {
+        return;
+    }
+
+    let diff_path = sibling(reference_png, "diff");
+    let _ = write_png(&actual_path, &actual);
+    let _ = write_png(&diff_path, &diff_image(&actual, &reference, tolerance.pixel_threshold));
+    if (actual.width, actual.height) != (reference.width, reference.height) {
+        panic!(
+            "{} is {}x{}, but reference {} is {}x{}; actual image written to {}",
+            capture_path.display(),
+            actual.width,
+            actual.height,
+            reference_png.display(),
+            reference.width,
+            reference.height,
+            actual_path.display()
+        );
+    }
+    panic!(
+        "{} differs from reference {} in {} pixels (at most {} allowed, largest difference {:.3}); \
+         actual image written to {}, differences to {}",
+        capture_path.display(),
+        reference_png.display(),
+        diff.differing_pixels,
+        tolerance.max_differing_pixels,
+        diff.max_difference,
+        actual_path.display(),
+        diff_path.display()
+    );
+}
+
+/// Returns `path` with `suffix` inserted before the extension, e.g.
+/// `shadows.actual.png`.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!("{}.{}.png", stem, suffix))
+}
+
+/// Replays the capture and reads back the image selected by `event`.
+fn render_output(capture_path: &Path, event: FrameEvent) -> Result<Image, String> {
+    let mut replay = CaptureFile::open(capture_path)
+        .and_then(CaptureFile::open_replay)
+        .map_err(|err| err.to_string())?;
+    let (target_event, read_event) = match event {
+        FrameEvent::Event(event_id) => (event_id, event_id),
+        FrameEvent::Final => {
+            let actions = replay.actions().map_err(|err| err.to_string())?;
+            let (last_draw, last_event) = last_events(&actions);
+            (last_draw.ok_or("the capture has no draws")?, last_event)
+        }
+    };
+
+    let texture = output_target(&mut replay, target_event)?;
+    let path = env::temp_dir().join(format!("renderdoc-rs-golden-{}-{}.png", process::id(), read_event));
+    let saved = replay
+        .save_texture(texture, read_event, &path, ImageFormat::Png)
+        .map_err(|err| err.to_string())
+        .and_then(|()| read_png(&path).map_err(|err| err.to_string()));
+    let _ = fs::remove_file(&path);
+    saved
+}
+
+/// Returns the event IDs of the last draw and of the last action.
+fn last_events(actions: &[Action]) -> (Option<u32>, u32) {
+    let (mut last_draw, mut last_event) = (None, 0);
+    for action in actions {
+        action.walk(&mut |a| {
+            last_event = last_event.max(a.event_id);
+            if a.flags.contains(ActionFlags::DRAWCALL) {
+                last_draw = Some(a.event_id);
+            }
+        });
+    }
+    (last_draw, last_event)
+}
+
+fn output_target(replay: &mut ReplayController, event_id: u32) -> Result<replay::ResourceId, String> {
+    let state = replay.pipeline_state(event_id).map_err(|err| err.to_string())?;
+    let target = state.render_targets.iter().flatten().next();
+    target
+        .cloned()
+        .ok_or_else(|| format!("no color target is bound at event {}", event_id))
+}
+
+fn png_error<E: ToString>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+/// Reads a PNG file of any color type, dropping alpha.
+fn read_png(path: &Path) -> io::Result<Image> {
+    let mut decoder = png::Decoder::new(File::open(path)?);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(png_error)?;
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).map_err(png_error)?;
+
+    let samples = info.color_type.samples();
+    let pixels = data[..info.buffer_size()]
+        .chunks(samples)
+        .map(|p| match info.color_type {
+            png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha => [p[0], p[0], p[0]],
+            _ => [p[0], p[1], p[2]],
+        })
+        .collect();
+    Ok(Image {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
+
+fn write_png(path: &Path, image: &Image) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), image.width, image.height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let data: Vec<u8> = image.pixels.iter().flat_map(|p| p.iter().cloned()).collect();
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&data))
+        .map_err(png_error)
+}
+
+/// Returns the actual image faded to gray, with pixels differing from the
+/// reference in red.
+fn diff_image(actual: &Image, reference: &Image, threshold: f64) -> Image {
+    let pixels = actual
+        .pixels
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            let differs = match reference.pixels.get(i) {
+                Some(r) if (actual.width, actual.height) == (reference.width, reference.height) => {
+                    replay::color_difference(a, r) > threshold
+                }
+                _ => true,
+            };
+            if differs {
+                [255, 0, 0]
+            } else {
+                let luma = (u32::from(a[0]) * 299 + u32::from(a[1]) * 587 + u32::from(a[2]) * 114) / 1000;
+                let faded = (255 - (255 - luma) / 4) as u8;
+                [faded, faded, faded]
+            }
+        })
+        .collect();
+    Image {
+        width: actual.width,
+        height: actual.height,
+        pixels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(pixels: Vec<[u8; 3]>) -> Image {
+        Image {
+            width: 2,
+            height: 1,
+            pixels,
+        }
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let path = env::temp_dir().join(format!("renderdoc-rs-testing-{}/frame.png", process::id()));
+        write_png(&path, &image(vec![[255, 0, 255], [10, 20, 30]])).unwrap();
+        let read = read_png(&path).unwrap();
+        assert_eq!((read.width, read.height), (2, 1));
+        assert_eq!(read.pixels, vec![[255, 0, 255], [10, 20, 30]]);
+        assert_eq!(read_png(&sibling(&path, "actual")).unwrap_err().kind(), io::ErrorKind::NotFound);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn highlight_differences() {
+        let actual = image(vec![[0, 0, 0], [255, 255, 255]]);
+        let reference = image(vec![[0, 0, 0], [0, 0, 0]]);
+        let diff = diff_image(&actual, &reference, 0.1);
+        assert_eq!(diff.pixels, vec![[192, 192, 192], [255, 0, 0]]);
+        assert_eq!(sibling(Path::new("golden/shadows.png"), "diff"), Path::new("golden/shadows.diff.png"));
+    }
+}
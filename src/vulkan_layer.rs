@@ -0,0 +1,375 @@
+//! Registration of RenderDoc's Vulkan capture layer.
+//!
+//! RenderDoc captures Vulkan applications through an implicit layer, which
+//! the Vulkan loader only finds if its JSON manifest is registered: through
+//! `implicit_layer.d` directories on Linux, and through the registry on
+//! Windows. A missing or stale registration is the most common reason for
+//! Vulkan captures not working, and is what RenderDoc's UI warns about.
+
+#[cfg(not(windows))]
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of RenderDoc's Vulkan capture layer.
+pub const LAYER_NAME: &str = "VK_LAYER_RENDERDOC_Capture";
+
+/// File name of manifests written by `register()`.
+#[cfg(not(windows))]
+const MANIFEST_NAME: &str = "renderdoc_capture.json";
+#[cfg(windows)]
+const MANIFEST_NAME: &str = "renderdoc.json";
+
+/// Whom a layer registration applies to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Scope {
+    /// Only the current user. Does not require elevated privileges.
+    User,
+    /// All users of the machine.
+    System,
+}
+
+/// A registered manifest of the capture layer.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Registration {
+    /// Whom the registration applies to.
+    pub scope: Scope,
+    /// Path of the JSON manifest.
+    pub manifest: PathBuf,
+    /// Library the manifest points to, resolved relative to the manifest.
+    pub library: Option<PathBuf>,
+}
+
+impl Registration {
+    /// Returns whether the manifest and the library it points to exist.
+    pub fn is_valid(&self) -> bool {
+        self.manifest.is_file() && self.library.as_ref().is_some_and(|lib| lib.is_file())
+    }
+}
+
+/// Returns all registrations of the capture layer, user registrations first.
+pub fn registrations() -> Vec<Registration> {
+    let mut found = Vec::new();
+    for &scope in &[Scope::User, Scope::System] {
+        for manifest in manifests(scope) {
+            let contents = match fs::read_to_string(&manifest) {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+            if !contents.contains(LAYER_NAME) {
+                continue;
+            }
+
+            let library = library_path(&contents).map(|lib| match manifest.parent() {
+                Some(dir) => dir.join(lib),
+                None => PathBuf::from(lib),
+            });
+            found.push(Registration {
+                scope,
+                manifest,
+                library,
+            });
+        }
+    }
+    found
+}
+
+/// Returns whether a valid registration of the capture layer exists.
+pub fn is_registered() -> bool {
+    registrations().iter().any(Registration::is_valid)
+}
+
+/// Registers the capture layer implemented by the RenderDoc `library`.
+///
+/// Returns the path of the written manifest. Registering the `System` scope
+/// usually requires administrator privileges.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use renderdoc::vulkan_layer::{self, Scope};
+///
+/// # fn run() -> std::io::Result<()> {
+/// if !vulkan_layer::is_registered() {
+///     vulkan_layer::register(Scope::User, "/opt/renderdoc/lib/librenderdoc.so")?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn register<P: AsRef<Path>>(scope: Scope, library: P) -> io::Result<PathBuf> {
+    let library = library.as_ref();
+    let manifest = manifest_path(scope, library)?;
+    if let Some(dir) = manifest.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&manifest, manifest_json(library))?;
+    add_registration(scope, &manifest)?;
+    Ok(manifest)
+}
+
+/// Removes all registrations of the capture layer in `scope`.
+///
+/// Returns whether any registration was removed.
+pub fn unregister(scope: Scope) -> io::Result<bool> {
+    let mut removed = false;
+    for registration in registrations().into_iter().filter(|r| r.scope == scope) {
+        remove_registration(scope, &registration.manifest)?;
+        removed = true;
+    }
+    Ok(removed)
+}
+
+/// Builds a manifest in the format RenderDoc itself installs.
+fn manifest_json(library: &Path) -> String {
+    let library = library.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\"");
+    format!(
+        r#"{{
+  "file_format_version": "1.1.2",
+  "layer": {{
+    "name": "{}",
+    "type": "GLOBAL",
+    "library_path": "{}",
+    "api_version": "1.3.131",
+    "implementation_version": "1",
+    "description": "Debugging capture layer for RenderDoc",
+    "functions": {{
+      "vkGetInstanceProcAddr": "VK_LAYER_RENDERDOC_CaptureGetInstanceProcAddr",
+      "vkGetDeviceProcAddr": "VK_LAYER_RENDERDOC_CaptureGetDeviceProcAddr"
+    }},
+    "enable_environment": {{
+      "ENABLE_VULKAN_RENDERDOC_CAPTURE": "1"
+    }},
+    "disable_environment": {{
+      "DISABLE_VULKAN_RENDERDOC_CAPTURE_1_0": "1"
+    }}
+  }}
+}}
+"#,
+        LAYER_NAME, library
+    )
+}
+
+/// Extracts the `library_path` value from a manifest.
+///
+/// Manifests are simple enough that this avoids a JSON dependency.
+fn library_path(manifest: &str) -> Option<String> {
+    let key = manifest.find("\"library_path\"")?;
+    let rest = &manifest[key + "\"library_path\"".len()..];
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start().strip_prefix('"')?;
+
+    let mut value = String::new();
+    let mut chars = rest.chars();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => value.push(chars.next()?),
+            c => value.push(c),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn layer_dirs(scope: Scope) -> Vec<PathBuf> {
+    match scope {
+        Scope::User => {
+            let data_home = env::var_os("XDG_DATA_HOME")
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")));
+            data_home
+                .map(|dir| dir.join("vulkan/implicit_layer.d"))
+                .into_iter()
+                .collect()
+        }
+        Scope::System => vec![
+            PathBuf::from("/etc/vulkan/implicit_layer.d"),
+            PathBuf::from("/usr/local/share/vulkan/implicit_layer.d"),
+            PathBuf::from("/usr/share/vulkan/implicit_layer.d"),
+        ],
+    }
+}
+
+#[cfg(not(windows))]
+fn manifests(scope: Scope) -> Vec<PathBuf> {
+    let mut manifests = Vec::new();
+    for dir in layer_dirs(scope) {
+        if let Ok(entries) = fs::read_dir(dir) {
+            manifests.extend(
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json")),
+            );
+        }
+    }
+    manifests.sort();
+    manifests
+}
+
+#[cfg(not(windows))]
+fn manifest_path(scope: Scope, _library: &Path) -> io::Result<PathBuf> {
+    layer_dirs(scope)
+        .into_iter()
+        .next()
+        .map(|dir| dir.join(MANIFEST_NAME))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))
+}
+
+/// Manifests in `implicit_layer.d` are registered by their presence alone.
+#[cfg(not(windows))]
+fn add_registration(_scope: Scope, _manifest: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn remove_registration(_scope: Scope, manifest: &Path) -> io::Result<()> {
+    fs::remove_file(manifest)
+}
+
+#[cfg(windows)]
+fn manifests(scope: Scope) -> Vec<PathBuf> {
+    unsafe { registry::layer_manifests(scope) }
+}
+
+/// RenderDoc keeps its manifest next to the library on Windows.
+#[cfg(windows)]
+fn manifest_path(_scope: Scope, library: &Path) -> io::Result<PathBuf> {
+    library
+        .parent()
+        .map(|dir| dir.join(MANIFEST_NAME))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "library path has no directory"))
+}
+
+#[cfg(windows)]
+fn add_registration(scope: Scope, manifest: &Path) -> io::Result<()> {
+    unsafe { registry::set_layer(scope, manifest) }
+}
+
+#[cfg(windows)]
+fn remove_registration(scope: Scope, manifest: &Path) -> io::Result<()> {
+    unsafe { registry::delete_layer(scope, manifest) }
+}
+
+/// Access to the loader's `ImplicitLayers` registry keys, which hold one
+/// `DWORD` value per manifest path, zero meaning enabled.
+#[cfg(windows)]
+mod registry {
+    use std::ffi::OsString;
+    use std::io;
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+    use std::path::{Path, PathBuf};
+    use std::ptr;
+
+    use advapi32;
+    use winapi;
+
+    use super::Scope;
+
+    const LAYERS_KEY: &str = r"SOFTWARE\Khronos\Vulkan\ImplicitLayers";
+
+    fn root(scope: Scope) -> winapi::HKEY {
+        match scope {
+            Scope::User => winapi::HKEY_CURRENT_USER,
+            Scope::System => winapi::HKEY_LOCAL_MACHINE,
+        }
+    }
+
+    fn wide<S: AsRef<::std::ffi::OsStr>>(s: S) -> Vec<u16> {
+        s.as_ref().encode_wide().chain(Some(0)).collect()
+    }
+
+    fn check(status: winapi::LONG) -> io::Result<()> {
+        if status == winapi::ERROR_SUCCESS as winapi::LONG {
+            Ok(())
+        } else {
+            Err(io::Error::from_raw_os_error(status))
+        }
+    }
+
+    unsafe fn open(scope: Scope, access: winapi::REGSAM) -> io::Result<winapi::HKEY> {
+        let mut key = ptr::null_mut();
+        check(advapi32::RegCreateKeyExW(
+            root(scope),
+            wide(LAYERS_KEY).as_ptr(),
+            0,
+            ptr::null_mut(),
+            winapi::REG_OPTION_NON_VOLATILE,
+            access,
+            ptr::null_mut(),
+            &mut key,
+            ptr::null_mut(),
+        ))?;
+        Ok(key)
+    }
+
+    pub unsafe fn layer_manifests(scope: Scope) -> Vec<PathBuf> {
+        let key = match open(scope, winapi::KEY_READ) {
+            Ok(key) => key,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut manifests = Vec::new();
+        for index in 0.. {
+            let mut name = [0u16; 1024];
+            let mut len = name.len() as winapi::DWORD;
+            let status = advapi32::RegEnumValueW(
+                key,
+                index,
+                name.as_mut_ptr(),
+                &mut len,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            if status != winapi::ERROR_SUCCESS as winapi::LONG {
+                break;
+            }
+            manifests.push(PathBuf::from(OsString::from_wide(&name[..len as usize])));
+        }
+
+        advapi32::RegCloseKey(key);
+        manifests
+    }
+
+    pub unsafe fn set_layer(scope: Scope, manifest: &Path) -> io::Result<()> {
+        let key = open(scope, winapi::KEY_WRITE)?;
+        let enabled: winapi::DWORD = 0;
+        let status = advapi32::RegSetValueExW(
+            key,
+            wide(manifest).as_ptr(),
+            0,
+            winapi::REG_DWORD,
+            &enabled as *const winapi::DWORD as *const u8,
+            4,
+        );
+        advapi32::RegCloseKey(key);
+        check(status)
+    }
+
+    pub unsafe fn delete_layer(scope: Scope, manifest: &Path) -> io::Result<()> {
+        let key = open(scope, winapi::KEY_WRITE)?;
+        let status = advapi32::RegDeleteValueW(key, wide(manifest).as_ptr());
+        advapi32::RegCloseKey(key);
+        check(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_round_trip() {
+        let json = manifest_json(Path::new(r#"C:\Program Files\Render"Doc\renderdoc.dll"#));
+        assert!(json.contains(LAYER_NAME));
+        assert_eq!(
+            library_path(&json).unwrap(),
+            r#"C:\Program Files\Render"Doc\renderdoc.dll"#
+        );
+    }
+
+    #[test]
+    fn missing_library_path() {
+        assert_eq!(library_path(r#"{"layer": {"name": "VK_LAYER_RENDERDOC_Capture"}}"#), None);
+    }
+}
@@ -0,0 +1,102 @@
+//! Pairing devices with the windows they render to.
+
+#[cfg(feature = "raw-window-handle")]
+use std::os::raw::c_void;
+
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::{HandleError, HasWindowHandle, RawWindowHandle};
+
+use {DevicePointer, WindowHandle};
+
+/// A device together with the window it presents to.
+///
+/// RenderDoc identifies the target of a frame capture by such a pair. Keeping
+/// both in one value avoids mixing up the two raw pointers.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::ptr;
+/// # use renderdoc::WindowBinding;
+/// # use std::os::raw::c_void;
+/// # let (device, window) = (ptr::null::<c_void>(), ptr::null::<c_void>());
+/// let binding = WindowBinding::new(device, window);
+/// assert_eq!(binding, WindowBinding::from((device, window)));
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct WindowBinding {
+    /// The API's root handle, e.g. an `ID3D11Device` or OpenGL context.
+    pub device: DevicePointer,
+    /// The OS window handle.
+    pub window: WindowHandle,
+}
+
+impl WindowBinding {
+    /// Pairs `device` with `window`.
+    pub fn new<D, W>(device: D, window: W) -> Self
+    where
+        D: Into<DevicePointer>,
+        W: Into<WindowHandle>,
+    {
+        WindowBinding {
+            device: device.into(),
+            window: window.into(),
+        }
+    }
+
+    /// Pairs `device` with the native handle of a windowing library's window.
+    ///
+    /// Supports Win32, Xlib, XCB, Wayland, and Android windows, which are the
+    /// window systems RenderDoc can capture from.
+    ///
+    /// Requires the `raw-window-handle` feature.
+    #[cfg(feature = "raw-window-handle")]
+    pub fn from_window<D, W>(device: D, window: &W) -> Result<Self, HandleError>
+    where
+        D: Into<DevicePointer>,
+        W: HasWindowHandle + ?Sized,
+    {
+        let window = match window.window_handle()?.as_raw() {
+            RawWindowHandle::Win32(handle) => handle.hwnd.get() as *const c_void,
+            RawWindowHandle::Xlib(handle) => handle.window as usize as *const c_void,
+            RawWindowHandle::Xcb(handle) => handle.window.get() as usize as *const c_void,
+            RawWindowHandle::Wayland(handle) => handle.surface.as_ptr() as *const c_void,
+            RawWindowHandle::AndroidNdk(handle) => handle.a_native_window.as_ptr() as *const c_void,
+            _ => return Err(HandleError::NotSupported),
+        };
+        Ok(WindowBinding::new(device, window))
+    }
+}
+
+impl<D, W> From<(D, W)> for WindowBinding
+where
+    D: Into<DevicePointer>,
+    W: Into<WindowHandle>,
+{
+    fn from((device, window): (D, W)) -> Self {
+        WindowBinding::new(device, window)
+    }
+}
+
+#[cfg(all(test, feature = "raw-window-handle"))]
+mod tests {
+    use super::*;
+    use raw_window_handle::{WindowHandle as RawHandle, XlibWindowHandle};
+    use std::ptr;
+
+    struct XlibWindow(u32);
+
+    impl HasWindowHandle for XlibWindow {
+        fn window_handle(&self) -> Result<RawHandle<'_>, HandleError> {
+            let raw = RawWindowHandle::Xlib(XlibWindowHandle::new(self.0 as _));
+            Ok(unsafe { RawHandle::borrow_raw(raw) })
+        }
+    }
+
+    #[test]
+    fn bind_xlib_window() {
+        let device = ptr::null::<c_void>();
+        let binding = WindowBinding::from_window(device, &XlibWindow(0x2a)).unwrap();
+        assert_eq!(binding.window.as_raw() as usize, 0x2a);
+    }
+}